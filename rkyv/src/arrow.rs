@@ -0,0 +1,338 @@
+//! Archived Arrow arrays and zero-copy Arrow buffers.
+//!
+//! Arrow's `ScalarBuffer` is archived as an [`ArchivedVec`] and its `PrimitiveArray` as an
+//! [`ArchivedPrimitiveArray`]. Their values are archived with the alignment and padding of
+//! [`ArrowBuffer`], so they can be handed back to Arrow without copying them with
+//! [`scalar_buffer`] and [`ArchivedPrimitiveArray::to_array`].
+//!
+//! Arrow requires little-endian values with the native layout of the primitive type, so the
+//! zero-copy adapters are only available for archived elements that are Arrow native types. This
+//! is the case for primitives on little-endian targets unless `archive_be` or `archive_le` is
+//! enabled.
+//!
+//! # Examples
+//!
+//! ```
+//! use arrow_array::{types::Int32Type, Array, PrimitiveArray};
+//! use rkyv::{archived_root, arrow::scalar_buffer, AlignedVec};
+//! use std::sync::Arc;
+//!
+//! let array = PrimitiveArray::<Int32Type>::from(vec![Some(1), None, Some(3)]);
+//! let bytes = rkyv::to_bytes::<_, 256>(&array).unwrap();
+//!
+//! // Move the archive into an Arc so Arrow can share it
+//! let bytes = Arc::new(bytes);
+//! let archived = unsafe { archived_root::<PrimitiveArray<Int32Type>>(&bytes) };
+//! let view = archived.to_array::<Int32Type>(&bytes);
+//! assert_eq!(view, array);
+//! assert_eq!(view.values().as_ptr(), archived.values().as_ptr());
+//!
+//! let values = scalar_buffer(&bytes, archived.values_vec());
+//! assert_eq!(values.as_ptr(), archived.values().as_ptr());
+//! ```
+
+use crate::{
+    ser::Serializer,
+    util::AlignedVec,
+    vec::{ArchivedVec, VecResolver},
+    with::ArrowBuffer,
+    Serialize,
+};
+use arrow_array::{types::ArrowPrimitiveType, PrimitiveArray};
+use arrow_buffer::{ArrowNativeType, BooleanBuffer, Buffer, NullBuffer, ScalarBuffer};
+use core::{mem, ptr::NonNull};
+use std::sync::Arc;
+
+/// Returns the number of bytes in a validity bitmap for `len` values.
+#[inline]
+fn bitmap_len(len: usize) -> usize {
+    len.div_ceil(8)
+}
+
+/// An archived Arrow `PrimitiveArray`.
+///
+/// The validity bitmap of the array is archived as bytes in the same format as Arrow, with one bit
+/// per value starting at the least significant bit of the first byte. Arrays without a validity
+/// bitmap are archived with an empty one.
+///
+/// Only the values and validity of the array are archived. Deserialized arrays have the default
+/// data type of their primitive type, so parameters like a timestamp's time zone aren't preserved.
+#[cfg_attr(feature = "strict", repr(C))]
+#[derive(Debug)]
+pub struct ArchivedPrimitiveArray<T> {
+    values: ArchivedVec<T>,
+    nulls: ArchivedVec<u8>,
+}
+
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(ArchivedPrimitiveArray<u32> {
+    values: ArchivedVec<u32>,
+    nulls: ArchivedVec<u8>,
+});
+
+impl<T> ArchivedPrimitiveArray<T> {
+    /// Returns the number of values in the array.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns whether the array is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the values of the array, including the ones that are null.
+    #[inline]
+    pub fn values(&self) -> &[T] {
+        self.values.as_slice()
+    }
+
+    /// Returns the archived vec that holds the values of the array.
+    #[inline]
+    pub fn values_vec(&self) -> &ArchivedVec<T> {
+        &self.values
+    }
+
+    /// Returns the validity bitmap of the array, or `None` if the array doesn't have one.
+    #[inline]
+    pub fn null_bitmap(&self) -> Option<&[u8]> {
+        if self.nulls.is_empty() {
+            None
+        } else {
+            Some(self.nulls.as_slice())
+        }
+    }
+
+    /// Returns whether the value at `index` is null.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    pub fn is_null(&self, index: usize) -> bool {
+        assert!(index < self.len(), "index out of bounds");
+        self.null_bitmap()
+            .is_some_and(|nulls| nulls[index / 8] & (1 << (index % 8)) == 0)
+    }
+
+    /// Returns the value at `index`, or `None` if it's null or out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len() && !self.is_null(index) {
+            Some(&self.values[index])
+        } else {
+            None
+        }
+    }
+
+    /// Resolves an archived primitive array from a given length and parameters.
+    ///
+    /// # Safety
+    ///
+    /// - `len` must be the number of values that were serialized
+    /// - `has_nulls` must be whether a validity bitmap was serialized
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing the values and validity bitmap
+    #[inline]
+    pub unsafe fn resolve_from_len(
+        len: usize,
+        has_nulls: bool,
+        pos: usize,
+        resolver: PrimitiveArrayResolver,
+        out: *mut Self,
+    ) {
+        let (fp, fo) = out_field!(out.values);
+        ArchivedVec::resolve_from_len(len, pos + fp, resolver.values, fo);
+        let nulls_len = if has_nulls { bitmap_len(len) } else { 0 };
+        let (fp, fo) = out_field!(out.nulls);
+        ArchivedVec::resolve_from_len(nulls_len, pos + fp, resolver.nulls, fo);
+    }
+
+    /// Serializes the values and validity bitmap of an archived primitive array.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the validity bitmap doesn't have exactly one bit for each value, rounded up to a
+    /// whole byte.
+    pub fn serialize_from_parts<U, S>(
+        values: &[U],
+        nulls: Option<&[u8]>,
+        serializer: &mut S,
+    ) -> Result<PrimitiveArrayResolver, S::Error>
+    where
+        U: Serialize<S, Archived = T, Resolver = ()>,
+        S: Serializer + ?Sized,
+    {
+        let nulls = nulls.unwrap_or(&[]);
+        assert!(
+            nulls.is_empty() || nulls.len() == bitmap_len(values.len()),
+            "the validity bitmap must have one bit for each value"
+        );
+        Ok(PrimitiveArrayResolver {
+            values: ArchivedVec::serialize_aligned_from_slice(
+                values,
+                ArrowBuffer::ALIGNMENT,
+                serializer,
+            )?,
+            nulls: ArchivedVec::serialize_aligned_from_slice(
+                nulls,
+                ArrowBuffer::ALIGNMENT,
+                serializer,
+            )?,
+        })
+    }
+}
+
+impl<T: ArrowNativeType> ArchivedPrimitiveArray<T> {
+    /// Returns a `PrimitiveArray` that shares its values and validity bitmap with the archive.
+    ///
+    /// `archive` must be the archive that contains this array. The returned array keeps it alive.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this array isn't located in `archive`, or if the values aren't aligned for `T`.
+    pub fn to_array<P>(&self, archive: &Arc<AlignedVec>) -> PrimitiveArray<P>
+    where
+        P: ArrowPrimitiveType<Native = T>,
+    {
+        let values = scalar_buffer(archive, &self.values);
+        let nulls = self.null_bitmap().map(|nulls| {
+            NullBuffer::new(BooleanBuffer::new(
+                share_slice(archive, nulls),
+                0,
+                self.len(),
+            ))
+        });
+        PrimitiveArray::new(values, nulls)
+    }
+}
+
+/// The resolver for an [`ArchivedPrimitiveArray`].
+pub struct PrimitiveArrayResolver {
+    values: VecResolver,
+    nulls: VecResolver,
+}
+
+/// Returns a `ScalarBuffer` that shares the elements of an archived vec with the archive.
+///
+/// `archive` must be the archive that contains `vec`. The returned buffer keeps it alive.
+///
+/// # Panics
+///
+/// Panics if `vec` isn't located in `archive`, or if its elements aren't aligned for `T`.
+pub fn scalar_buffer<T: ArrowNativeType>(
+    archive: &Arc<AlignedVec>,
+    vec: &ArchivedVec<T>,
+) -> ScalarBuffer<T> {
+    ScalarBuffer::new(share_slice(archive, vec.as_slice()), 0, vec.len())
+}
+
+fn share_slice<T: ArrowNativeType>(archive: &Arc<AlignedVec>, slice: &[T]) -> Buffer {
+    let len = mem::size_of_val(slice);
+    if len == 0 {
+        // An empty vec of `T` is still aligned for `T`, which scalar buffers require
+        return Buffer::from_vec(Vec::<T>::new());
+    }
+
+    let bytes = archive.as_slice().as_ptr_range();
+    let start = slice.as_ptr().cast::<u8>();
+    assert!(
+        bytes.start <= start && len <= bytes.end as usize - start as usize,
+        "the archived value is not located in the archive"
+    );
+    // SAFETY: The slice is nonempty and located in the archive. The archive can't be mutated or
+    // freed while the buffer holds a reference to it.
+    unsafe {
+        Buffer::from_custom_allocation(
+            NonNull::new_unchecked(start as *mut u8),
+            len,
+            archive.clone(),
+        )
+    }
+}
+
+#[cfg(feature = "validation")]
+pub use self::validation::*;
+
+#[cfg(feature = "validation")]
+mod validation {
+    use super::{bitmap_len, ArchivedPrimitiveArray};
+    use crate::{validation::ArchiveContext, vec::ArchivedVec};
+    use bytecheck::{CheckBytes, Error};
+    use core::{fmt, ptr};
+
+    /// Errors that can occur while checking an archived primitive array.
+    #[derive(Debug)]
+    pub enum PrimitiveArrayError<V, N> {
+        /// An error occurred while checking the values
+        ValuesCheckError(V),
+        /// An error occurred while checking the validity bitmap
+        NullsCheckError(N),
+        /// The validity bitmap doesn't have one bit for each value
+        InvalidNullsLength {
+            /// The length of the validity bitmap in bytes
+            nulls_len: usize,
+            /// The number of values
+            len: usize,
+        },
+    }
+
+    impl<V: fmt::Display, N: fmt::Display> fmt::Display for PrimitiveArrayError<V, N> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                PrimitiveArrayError::ValuesCheckError(e) => write!(f, "values check error: {}", e),
+                PrimitiveArrayError::NullsCheckError(e) => {
+                    write!(f, "validity bitmap check error: {}", e)
+                }
+                PrimitiveArrayError::InvalidNullsLength { nulls_len, len } => write!(
+                    f,
+                    "invalid validity bitmap: {} bytes is the wrong length for {} values",
+                    nulls_len, len
+                ),
+            }
+        }
+    }
+
+    impl<V: Error + 'static, N: Error + 'static> std::error::Error for PrimitiveArrayError<V, N> {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match self {
+                PrimitiveArrayError::ValuesCheckError(e) => Some(e as &dyn std::error::Error),
+                PrimitiveArrayError::NullsCheckError(e) => Some(e as &dyn std::error::Error),
+                PrimitiveArrayError::InvalidNullsLength { .. } => None,
+            }
+        }
+    }
+
+    impl<T, C> CheckBytes<C> for ArchivedPrimitiveArray<T>
+    where
+        T: CheckBytes<C>,
+        C: ArchiveContext + ?Sized,
+        C::Error: Error,
+    {
+        type Error = PrimitiveArrayError<
+            <ArchivedVec<T> as CheckBytes<C>>::Error,
+            <ArchivedVec<u8> as CheckBytes<C>>::Error,
+        >;
+
+        unsafe fn check_bytes<'a>(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<&'a Self, Self::Error> {
+            let values = ArchivedVec::<T>::check_bytes(ptr::addr_of!((*value).values), context)
+                .map_err(PrimitiveArrayError::ValuesCheckError)?;
+            let nulls = ArchivedVec::<u8>::check_bytes(ptr::addr_of!((*value).nulls), context)
+                .map_err(PrimitiveArrayError::NullsCheckError)?;
+
+            if !nulls.is_empty() && nulls.len() != bitmap_len(values.len()) {
+                return Err(PrimitiveArrayError::InvalidNullsLength {
+                    nulls_len: nulls.len(),
+                    len: values.len(),
+                });
+            }
+
+            Ok(&*value)
+        }
+    }
+}
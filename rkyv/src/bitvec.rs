@@ -16,6 +16,13 @@ pub struct ArchivedBitVec<T = Archived<usize>, O = Lsb0> {
     pub(crate) _or: PhantomData<O>,
 }
 
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(ArchivedBitVec {
+    inner: ArchivedVec<Archived<usize>>,
+    bit_len: Archived<usize>,
+    _or: PhantomData<Lsb0>,
+});
+
 impl<T: BitStore, O: BitOrder> Deref for ArchivedBitVec<T, O> {
     type Target = BitSlice<T, O>;
 
@@ -25,6 +25,12 @@ impl<T: ArchivePointee + ?Sized> ArchivedBox<T> {
         unsafe { self.map_unchecked_mut(|s| &mut *s.0.as_mut_ptr()) }
     }
 
+    /// Returns the archived metadata of the value of this archived box.
+    #[inline]
+    pub fn metadata(&self) -> &T::ArchivedMetadata {
+        self.0.metadata()
+    }
+
     /// Resolves an archived box from the given value and parameters.
     ///
     /// # Safety
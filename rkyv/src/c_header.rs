@@ -0,0 +1,673 @@
+//! C header generation for archived types.
+//!
+//! [`CHeader`] emits C definitions that match the layouts of archived types, along with accessor
+//! functions that follow relative pointers. This lets C and C++ code read archives produced by Rust
+//! without a deserialization step.
+//!
+//! [`CType`] is implemented for archived primitives, arrays, strings, vecs, boxes, and options, and
+//! can be derived for archived structs and enums with `#[archive(c_header)]`. Derived structs must
+//! have a `repr(C)` archived type, either with `#[archive_attr(repr(C))]` or the `strict` feature.
+//! The layouts of the built-in archived types are only guaranteed with the `strict` feature, so
+//! every generated definition is checked against the size and alignment of its Rust counterpart
+//! with a static assertion.
+//!
+//! Multibyte values in the archive use the byte order the archive was written with. The generated
+//! accessors do not swap bytes, so archives read from C should use the native byte order of the
+//! reader.
+//!
+//! ## Examples
+//!
+//! ```
+//! use rkyv::{c_header::CHeader, Archive, Archived};
+//!
+//! #[derive(Archive)]
+//! #[archive(c_header)]
+//! #[archive_attr(repr(C))]
+//! struct Point {
+//!     x: f32,
+//!     y: f32,
+//! }
+//!
+//! #[derive(Archive)]
+//! #[archive(c_header)]
+//! #[archive_attr(repr(C))]
+//! struct Polygon {
+//!     name: String,
+//!     points: Vec<Point>,
+//! }
+//!
+//! let header = CHeader::new("POLYGON_H").add::<Archived<Polygon>>().to_string();
+//!
+//! assert!(header.contains("struct ArchivedPoint {\n    float x;\n    float y;\n};"));
+//! assert!(header.contains("static inline const struct ArchivedPoint *ArchivedPolygon_points("));
+//! ```
+
+use crate::{
+    boxed::ArchivedBox, option::ArchivedOption, string::ArchivedString, vec::ArchivedVec,
+    FixedIsize, FixedUsize,
+};
+#[cfg(not(feature = "std"))]
+use ::alloc::{collections::BTreeSet, format, string::String, vec::Vec};
+use core::{
+    fmt::{self, Write},
+    mem::{align_of, size_of},
+    num::{
+        NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU16, NonZeroU32, NonZeroU64,
+        NonZeroU8,
+    },
+};
+#[cfg(feature = "std")]
+use std::collections::BTreeSet;
+
+/// An archived type that has a C representation.
+///
+/// This can be derived for archived structs and enums with `#[archive(c_header)]`.
+pub trait CType {
+    /// Writes a C declaration of `name` with this type.
+    ///
+    /// `name` is a C declarator, so it may be empty or include pointer and function syntax.
+    fn write_declaration<W: Write + ?Sized>(name: &str, out: &mut W) -> fmt::Result;
+
+    /// Writes an identifier for this type, which is used to name the definitions that contain it.
+    fn write_ident<W: Write + ?Sized>(out: &mut W) -> fmt::Result;
+
+    /// Adds the definitions of this type and the types it depends on to a header.
+    #[inline]
+    fn add_definitions(header: &mut CHeader) {
+        let _ = header;
+    }
+
+    /// Adds accessor functions for a member of this type to a header.
+    ///
+    /// `owner` is the C type that contains the member, `prefix` is the name of the accessor, and
+    /// `member` is the path to the member from the owner.
+    #[inline]
+    fn add_accessors(header: &mut CHeader, owner: &str, prefix: &str, member: &str) {
+        let _ = (header, owner, prefix, member);
+    }
+}
+
+/// Writes a C declaration of `name` with a tagged type like `struct Foo`.
+#[inline]
+pub fn write_tagged<W: Write + ?Sized>(
+    out: &mut W,
+    tag: &str,
+    ident: &str,
+    name: &str,
+) -> fmt::Result {
+    write_named(out, &format!("{} {}", tag, ident), name)
+}
+
+fn write_named<W: Write + ?Sized>(out: &mut W, ty: &str, name: &str) -> fmt::Result {
+    out.write_str(ty)?;
+    if !name.is_empty() {
+        out.write_char(' ')?;
+        out.write_str(name)?;
+    }
+    Ok(())
+}
+
+fn declaration<T: CType + ?Sized>(name: &str) -> String {
+    let mut result = String::new();
+    T::write_declaration(name, &mut result).unwrap();
+    result
+}
+
+fn ident<T: CType + ?Sized>() -> String {
+    let mut result = String::new();
+    T::write_ident(&mut result).unwrap();
+    result
+}
+
+/// A field of a struct or enum variant in a C header.
+pub struct CField {
+    name: &'static str,
+    write_declaration: fn(&str, &mut String) -> fmt::Result,
+    add_definitions: fn(&mut CHeader),
+    add_accessors: fn(&mut CHeader, &str, &str, &str),
+}
+
+impl CField {
+    /// Creates a new field with the given name and type.
+    #[inline]
+    pub fn new<T: CType>(name: &'static str) -> Self {
+        Self {
+            name,
+            write_declaration: T::write_declaration::<String>,
+            add_definitions: T::add_definitions,
+            add_accessors: T::add_accessors,
+        }
+    }
+}
+
+/// A variant of an enum in a C header.
+pub struct CVariant<'a> {
+    name: &'static str,
+    fields: &'a [CField],
+}
+
+impl<'a> CVariant<'a> {
+    /// Creates a new variant with the given name and fields.
+    #[inline]
+    pub fn new(name: &'static str, fields: &'a [CField]) -> Self {
+        Self { name, fields }
+    }
+}
+
+/// A builder for C headers that describe archived types.
+pub struct CHeader {
+    guard: String,
+    declared: BTreeSet<String>,
+    definitions: Vec<String>,
+    accessors: Vec<String>,
+}
+
+impl CHeader {
+    /// Creates a new empty header with the given include guard.
+    #[inline]
+    pub fn new(guard: &str) -> Self {
+        Self {
+            guard: guard.into(),
+            declared: BTreeSet::new(),
+            definitions: Vec::new(),
+            accessors: Vec::new(),
+        }
+    }
+
+    /// Adds the definitions for an archived type and the types it depends on.
+    #[inline]
+    pub fn add<T: CType>(&mut self) -> &mut Self {
+        T::add_definitions(self);
+        self
+    }
+
+    /// Declares a definition with the given name.
+    ///
+    /// Returns `false` if the name was already declared, in which case the definition should not
+    /// be added again.
+    #[inline]
+    pub fn declare(&mut self, name: &str) -> bool {
+        self.declared.insert(name.into())
+    }
+
+    /// Adds a definition to the header.
+    ///
+    /// Definitions are emitted in the order they're added, so the definitions of any types used by
+    /// value must be added first.
+    #[inline]
+    pub fn add_definition(&mut self, definition: String) {
+        self.definitions.push(definition);
+    }
+
+    /// Adds an accessor function to the header.
+    ///
+    /// Accessors are emitted after all definitions.
+    #[inline]
+    pub fn add_accessor(&mut self, accessor: String) {
+        self.accessors.push(accessor);
+    }
+
+    /// Adds a struct definition for `T` with the given fields.
+    pub fn add_struct<T>(&mut self, ident: &str, fields: &[CField]) {
+        if !self.declare(ident) {
+            return;
+        }
+
+        for field in fields {
+            (field.add_definitions)(self);
+        }
+
+        let mut definition = format!("struct {} {{\n", ident);
+        write_fields(&mut definition, fields, "    ");
+        definition.push_str("};\n");
+        write_layout_assertions::<T>(&mut definition, &format!("struct {}", ident));
+        self.add_definition(definition);
+
+        let owner = format!("struct {}", ident);
+        for field in fields {
+            let prefix = format!("{}_{}", ident, field.name);
+            (field.add_accessors)(self, &owner, &prefix, field.name);
+        }
+    }
+
+    /// Adds a definition for the enum `T` with tags of type `Tag` and the given variants.
+    ///
+    /// The enum is defined as a union of one struct per variant with fields, each of which starts
+    /// with the tag. The tag of each variant is defined as the constant `<ident>_TAG_<variant>`.
+    pub fn add_enum<T, Tag: CType>(&mut self, ident: &str, variants: &[CVariant<'_>]) {
+        if !self.declare(ident) {
+            return;
+        }
+
+        for variant in variants {
+            for field in variant.fields {
+                (field.add_definitions)(self);
+            }
+        }
+
+        let mut definition = String::new();
+        for (i, variant) in variants.iter().enumerate() {
+            definition.push_str(&format!("#define {}_TAG_{} {}\n", ident, variant.name, i));
+        }
+        for variant in variants.iter().filter(|v| !v.fields.is_empty()) {
+            definition.push_str(&format!("\nstruct {}_{} {{\n", ident, variant.name));
+            definition.push_str(&format!("    {};\n", declaration::<Tag>("tag")));
+            write_fields(&mut definition, variant.fields, "    ");
+            definition.push_str("};\n");
+        }
+        definition.push_str(&format!("\nunion {} {{\n", ident));
+        definition.push_str(&format!("    {};\n", declaration::<Tag>("tag")));
+        for variant in variants.iter().filter(|v| !v.fields.is_empty()) {
+            definition.push_str(&format!(
+                "    struct {}_{} {};\n",
+                ident, variant.name, variant.name
+            ));
+        }
+        definition.push_str("};\n");
+        write_layout_assertions::<T>(&mut definition, &format!("union {}", ident));
+        self.add_definition(definition);
+
+        let owner = format!("union {}", ident);
+        for variant in variants {
+            for field in variant.fields {
+                let prefix = format!("{}_{}_{}", ident, variant.name, field.name);
+                let member = format!("{}.{}", variant.name, field.name);
+                (field.add_accessors)(self, &owner, &prefix, &member);
+            }
+        }
+    }
+}
+
+fn write_fields(out: &mut String, fields: &[CField], indent: &str) {
+    for field in fields {
+        out.push_str(indent);
+        (field.write_declaration)(field.name, out).unwrap();
+        out.push_str(";\n");
+    }
+}
+
+fn write_layout_assertions<T>(out: &mut String, ty: &str) {
+    out.push_str(&format!(
+        "RKYV_STATIC_ASSERT(sizeof({}) == {}, \"size of {} does not match\");\n",
+        ty,
+        size_of::<T>(),
+        ty,
+    ));
+    out.push_str(&format!(
+        "RKYV_STATIC_ASSERT(RKYV_ALIGNOF({}) == {}, \"alignment of {} does not match\");\n",
+        ty,
+        align_of::<T>(),
+        ty,
+    ));
+}
+
+fn int_name(size: usize, signed: bool) -> &'static str {
+    match (size, signed) {
+        (2, true) => "int16_t",
+        (4, true) => "int32_t",
+        (8, true) => "int64_t",
+        (2, false) => "uint16_t",
+        (4, false) => "uint32_t",
+        (8, false) => "uint64_t",
+        _ => unreachable!(),
+    }
+}
+
+fn write_prelude(out: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let byte_order = if cfg!(feature = "archive_be") {
+        "big-endian"
+    } else if cfg!(feature = "archive_le") {
+        "little-endian"
+    } else {
+        "the native"
+    };
+
+    write!(
+        out,
+        r#"#ifndef RKYV_PRELUDE
+#define RKYV_PRELUDE
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+#define RKYV_STATIC_ASSERT(cond, message) static_assert(cond, message)
+#define RKYV_ALIGNOF(type) alignof(type)
+#else
+#define RKYV_STATIC_ASSERT(cond, message) _Static_assert(cond, message)
+#define RKYV_ALIGNOF(type) _Alignof(type)
+#endif
+
+/* Multibyte values are stored in {byte_order} byte order. */
+
+/* An offset from the address of the relative pointer to its target. */
+typedef {rel_ptr} rkyv_rel_ptr;
+/* An archived usize. */
+typedef {size} rkyv_size;
+
+static inline const void *rkyv_rel_ptr_get(const rkyv_rel_ptr *ptr) {{
+    return (const char *)ptr + *ptr;
+}}
+
+/* An archived Vec, boxed slice, or boxed str. */
+struct rkyv_vec {{
+    rkyv_rel_ptr ptr;
+    rkyv_size len;
+}};
+
+static inline const void *rkyv_vec_data(const struct rkyv_vec *vec) {{
+    return rkyv_rel_ptr_get(&vec->ptr);
+}}
+
+/* An archived String. Strings of up to {inline_capacity} bytes are stored inline. */
+struct rkyv_string {{
+    rkyv_size len;
+    uint8_t offset[sizeof(rkyv_rel_ptr)];
+}};
+
+static inline bool rkyv_string_is_inline(const struct rkyv_string *string) {{
+    return (((const uint8_t *)string)[sizeof(struct rkyv_string) - 1] & 0x80) == 0;
+}}
+
+static inline size_t rkyv_string_len(const struct rkyv_string *string) {{
+    if (rkyv_string_is_inline(string)) {{
+        return ((const uint8_t *)string)[sizeof(struct rkyv_string) - 1];
+    }}
+    return string->len;
+}}
+
+static inline const char *rkyv_string_data(const struct rkyv_string *string) {{
+    uint64_t offset = 0;
+    size_t i;
+    if (rkyv_string_is_inline(string)) {{
+        return (const char *)string;
+    }}
+    /* The offset of an out-of-line string is always stored in little-endian byte order */
+    for (i = 0; i < sizeof(string->offset); ++i) {{
+        offset |= (uint64_t)string->offset[i] << (8 * i);
+    }}
+    return (const char *)string + (rkyv_rel_ptr)offset;
+}}
+
+RKYV_STATIC_ASSERT(sizeof(struct rkyv_string) == {string_size}, "rkyv_string size mismatch");
+
+#endif
+"#,
+        byte_order = byte_order,
+        rel_ptr = int_name(size_of::<FixedIsize>(), true),
+        size = int_name(size_of::<FixedUsize>(), false),
+        inline_capacity = crate::string::repr::INLINE_CAPACITY,
+        string_size = size_of::<ArchivedString>(),
+    )
+}
+
+impl fmt::Display for CHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "#ifndef {}", self.guard)?;
+        writeln!(f, "#define {}", self.guard)?;
+        writeln!(f)?;
+        write_prelude(f)?;
+        for definition in self.definitions.iter() {
+            writeln!(f)?;
+            f.write_str(definition)?;
+        }
+        for accessor in self.accessors.iter() {
+            writeln!(f)?;
+            f.write_str(accessor)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "#endif")
+    }
+}
+
+macro_rules! impl_primitive {
+    ($($ty:ty => $name:literal,)*) => {
+        $(
+            impl CType for $ty {
+                #[inline]
+                fn write_declaration<W: Write + ?Sized>(name: &str, out: &mut W) -> fmt::Result {
+                    write_named(out, $name, name)
+                }
+
+                #[inline]
+                fn write_ident<W: Write + ?Sized>(out: &mut W) -> fmt::Result {
+                    out.write_str($name)
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive! {
+    bool => "bool",
+    i8 => "int8_t",
+    i16 => "int16_t",
+    i32 => "int32_t",
+    i64 => "int64_t",
+    u8 => "uint8_t",
+    u16 => "uint16_t",
+    u32 => "uint32_t",
+    u64 => "uint64_t",
+    f32 => "float",
+    f64 => "double",
+    char => "uint32_t",
+    NonZeroI8 => "int8_t",
+    NonZeroI16 => "int16_t",
+    NonZeroI32 => "int32_t",
+    NonZeroI64 => "int64_t",
+    NonZeroU8 => "uint8_t",
+    NonZeroU16 => "uint16_t",
+    NonZeroU32 => "uint32_t",
+    NonZeroU64 => "uint64_t",
+}
+
+#[cfg(feature = "rend")]
+const _: () = {
+    use crate::rend::*;
+
+    impl_primitive! {
+        i16_be => "int16_t",
+        i32_be => "int32_t",
+        i64_be => "int64_t",
+        u16_be => "uint16_t",
+        u32_be => "uint32_t",
+        u64_be => "uint64_t",
+        f32_be => "float",
+        f64_be => "double",
+        char_be => "uint32_t",
+        NonZeroI16_be => "int16_t",
+        NonZeroI32_be => "int32_t",
+        NonZeroI64_be => "int64_t",
+        NonZeroU16_be => "uint16_t",
+        NonZeroU32_be => "uint32_t",
+        NonZeroU64_be => "uint64_t",
+        i16_le => "int16_t",
+        i32_le => "int32_t",
+        i64_le => "int64_t",
+        u16_le => "uint16_t",
+        u32_le => "uint32_t",
+        u64_le => "uint64_t",
+        f32_le => "float",
+        f64_le => "double",
+        char_le => "uint32_t",
+        NonZeroI16_le => "int16_t",
+        NonZeroI32_le => "int32_t",
+        NonZeroI64_le => "int64_t",
+        NonZeroU16_le => "uint16_t",
+        NonZeroU32_le => "uint32_t",
+        NonZeroU64_le => "uint64_t",
+    }
+};
+
+impl<T: CType, const N: usize> CType for [T; N] {
+    #[inline]
+    fn write_declaration<W: Write + ?Sized>(name: &str, out: &mut W) -> fmt::Result {
+        if name.starts_with('*') {
+            T::write_declaration(&format!("({})[{}]", name, N), out)
+        } else {
+            T::write_declaration(&format!("{}[{}]", name, N), out)
+        }
+    }
+
+    #[inline]
+    fn write_ident<W: Write + ?Sized>(out: &mut W) -> fmt::Result {
+        T::write_ident(out)?;
+        write!(out, "_{}", N)
+    }
+
+    #[inline]
+    fn add_definitions(header: &mut CHeader) {
+        T::add_definitions(header);
+    }
+}
+
+fn add_slice_accessors<T: CType + ?Sized>(
+    header: &mut CHeader,
+    owner: &str,
+    prefix: &str,
+    member: &str,
+) {
+    let function = declaration::<T>(&format!("*{}(const {} *value)", prefix, owner));
+    let cast = declaration::<T>("*");
+    header.add_accessor(format!(
+        "static inline const {} {{\n    return (const {})rkyv_vec_data(&value->{});\n}}\n",
+        function, cast, member,
+    ));
+    header.add_accessor(format!(
+        "static inline rkyv_size {}_len(const {} *value) {{\n    return value->{}.len;\n}}\n",
+        prefix, owner, member,
+    ));
+}
+
+impl<T: CType> CType for ArchivedVec<T> {
+    #[inline]
+    fn write_declaration<W: Write + ?Sized>(name: &str, out: &mut W) -> fmt::Result {
+        write_named(out, "struct rkyv_vec", name)
+    }
+
+    #[inline]
+    fn write_ident<W: Write + ?Sized>(out: &mut W) -> fmt::Result {
+        out.write_str("rkyv_vec")
+    }
+
+    #[inline]
+    fn add_definitions(header: &mut CHeader) {
+        T::add_definitions(header);
+    }
+
+    #[inline]
+    fn add_accessors(header: &mut CHeader, owner: &str, prefix: &str, member: &str) {
+        add_slice_accessors::<T>(header, owner, prefix, member);
+    }
+}
+
+impl CType for ArchivedString {
+    #[inline]
+    fn write_declaration<W: Write + ?Sized>(name: &str, out: &mut W) -> fmt::Result {
+        write_named(out, "struct rkyv_string", name)
+    }
+
+    #[inline]
+    fn write_ident<W: Write + ?Sized>(out: &mut W) -> fmt::Result {
+        out.write_str("rkyv_string")
+    }
+
+    fn add_accessors(header: &mut CHeader, owner: &str, prefix: &str, member: &str) {
+        header.add_accessor(format!(
+            "static inline const char *{}(const {} *value) {{\n    \
+                return rkyv_string_data(&value->{});\n}}\n",
+            prefix, owner, member,
+        ));
+        header.add_accessor(format!(
+            "static inline size_t {}_len(const {} *value) {{\n    \
+                return rkyv_string_len(&value->{});\n}}\n",
+            prefix, owner, member,
+        ));
+    }
+}
+
+impl<T: CType> CType for ArchivedBox<T> {
+    #[inline]
+    fn write_declaration<W: Write + ?Sized>(name: &str, out: &mut W) -> fmt::Result {
+        write_named(out, "rkyv_rel_ptr", name)
+    }
+
+    #[inline]
+    fn write_ident<W: Write + ?Sized>(out: &mut W) -> fmt::Result {
+        out.write_str("rkyv_rel_ptr")
+    }
+
+    #[inline]
+    fn add_definitions(header: &mut CHeader) {
+        T::add_definitions(header);
+    }
+
+    fn add_accessors(header: &mut CHeader, owner: &str, prefix: &str, member: &str) {
+        let function = declaration::<T>(&format!("*{}(const {} *value)", prefix, owner));
+        let cast = declaration::<T>("*");
+        header.add_accessor(format!(
+            "static inline const {} {{\n    return (const {})rkyv_rel_ptr_get(&value->{});\n}}\n",
+            function, cast, member,
+        ));
+    }
+}
+
+impl<T: CType> CType for ArchivedBox<[T]> {
+    #[inline]
+    fn write_declaration<W: Write + ?Sized>(name: &str, out: &mut W) -> fmt::Result {
+        write_named(out, "struct rkyv_vec", name)
+    }
+
+    #[inline]
+    fn write_ident<W: Write + ?Sized>(out: &mut W) -> fmt::Result {
+        out.write_str("rkyv_vec")
+    }
+
+    #[inline]
+    fn add_definitions(header: &mut CHeader) {
+        T::add_definitions(header);
+    }
+
+    #[inline]
+    fn add_accessors(header: &mut CHeader, owner: &str, prefix: &str, member: &str) {
+        add_slice_accessors::<T>(header, owner, prefix, member);
+    }
+}
+
+impl<T: CType> CType for ArchivedOption<T> {
+    #[inline]
+    fn write_declaration<W: Write + ?Sized>(name: &str, out: &mut W) -> fmt::Result {
+        write_named(out, &format!("struct {}", ident::<Self>()), name)
+    }
+
+    #[inline]
+    fn write_ident<W: Write + ?Sized>(out: &mut W) -> fmt::Result {
+        out.write_str("rkyv_option_")?;
+        T::write_ident(out)
+    }
+
+    fn add_definitions(header: &mut CHeader) {
+        let ident = ident::<Self>();
+        if !header.declare(&ident) {
+            return;
+        }
+
+        T::add_definitions(header);
+        let mut definition = format!(
+            "struct {} {{\n    uint8_t tag;\n    {};\n}};\n",
+            ident,
+            declaration::<T>("value"),
+        );
+        write_layout_assertions::<Self>(&mut definition, &format!("struct {}", ident));
+        header.add_definition(definition);
+    }
+
+    fn add_accessors(header: &mut CHeader, owner: &str, prefix: &str, member: &str) {
+        let function = declaration::<T>(&format!("*{}(const {} *value)", prefix, owner));
+        header.add_accessor(format!(
+            "static inline const {} {{\n    return value->{}.tag ? &value->{}.value : NULL;\n}}\n",
+            function, member, member,
+        ));
+    }
+}
@@ -0,0 +1,314 @@
+//! Archived bidirectional map implementation.
+//!
+//! An archived bimap stores its pairs once, along with two hash indexes: one for the left values
+//! and one for the right values. This allows pairs to be looked up from either side without
+//! deserializing.
+
+#[cfg(feature = "validation")]
+pub mod validation;
+
+use crate::{
+    collections::{
+        hash_index::{ArchivedHashIndex, HashIndexResolver},
+        util::Entry,
+    },
+    RelPtr,
+};
+use core::{borrow::Borrow, fmt, hash::Hash, iter::FusedIterator, slice};
+
+/// An archived bidirectional map, where both the left and right values of the pairs are unique.
+///
+/// This is the archived type of the [`BiMap`](crate::with::BiMap) wrapper.
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedBiMap<L, R> {
+    left_index: ArchivedHashIndex,
+    right_index: ArchivedHashIndex,
+    entries: RelPtr<Entry<L, R>>,
+}
+
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(ArchivedBiMap<u32, u32> {
+    left_index: ArchivedHashIndex,
+    right_index: ArchivedHashIndex,
+    entries: RelPtr<Entry<u32, u32>>,
+});
+
+impl<L, R> ArchivedBiMap<L, R> {
+    /// Gets the number of pairs in the bimap.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.left_index.len()
+    }
+
+    /// Returns `true` if the bimap contains no pairs.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    fn entries(&self) -> &[Entry<L, R>] {
+        unsafe { slice::from_raw_parts(self.entries.as_ptr(), self.len()) }
+    }
+
+    #[inline]
+    fn find_left<Q>(&self, k: &Q) -> Option<&Entry<L, R>>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let entries = self.entries();
+        self.left_index
+            .find(k, |i| entries[i].key.borrow() == k)
+            .map(|i| &entries[i])
+    }
+
+    #[inline]
+    fn find_right<Q>(&self, k: &Q) -> Option<&Entry<L, R>>
+    where
+        R: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let entries = self.entries();
+        self.right_index
+            .find(k, |i| entries[i].value.borrow() == k)
+            .map(|i| &entries[i])
+    }
+
+    /// Gets the right value paired with the given left value.
+    #[inline]
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&R>
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find_left(left).map(|entry| &entry.value)
+    }
+
+    /// Gets the left value paired with the given right value.
+    #[inline]
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<&L>
+    where
+        R: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find_right(right).map(|entry| &entry.key)
+    }
+
+    /// Returns whether the given left value is present in the bimap.
+    #[inline]
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find_left(left).is_some()
+    }
+
+    /// Returns whether the given right value is present in the bimap.
+    #[inline]
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find_right(right).is_some()
+    }
+
+    /// Gets an iterator over the pairs in the bimap.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, L, R> {
+        Iter {
+            inner: self.entries().iter(),
+        }
+    }
+
+    /// Gets an iterator over the left values in the bimap.
+    #[inline]
+    pub fn left_values(&self) -> LeftValues<'_, L, R> {
+        LeftValues {
+            inner: self.entries().iter(),
+        }
+    }
+
+    /// Gets an iterator over the right values in the bimap.
+    #[inline]
+    pub fn right_values(&self) -> RightValues<'_, L, R> {
+        RightValues {
+            inner: self.entries().iter(),
+        }
+    }
+
+    /// Resolves an archived bimap from a given length and parameters.
+    ///
+    /// # Safety
+    ///
+    /// - `len` must be the number of pairs that were serialized
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing a bimap
+    #[inline]
+    pub unsafe fn resolve_from_len(
+        len: usize,
+        pos: usize,
+        resolver: BiMapResolver,
+        out: *mut Self,
+    ) {
+        let (fp, fo) = out_field!(out.left_index);
+        ArchivedHashIndex::resolve_from_len(len, pos + fp, resolver.left_index, fo);
+
+        let (fp, fo) = out_field!(out.right_index);
+        ArchivedHashIndex::resolve_from_len(len, pos + fp, resolver.right_index, fo);
+
+        let (fp, fo) = out_field!(out.entries);
+        RelPtr::emplace(pos + fp, resolver.entries_pos, fo);
+    }
+}
+
+#[cfg(feature = "alloc")]
+const _: () = {
+    use crate::{
+        ser::{ScratchSpace, Serializer},
+        Serialize,
+    };
+
+    impl<L, R> ArchivedBiMap<L, R> {
+        /// Serializes an iterator of pairs as a bimap.
+        ///
+        /// # Safety
+        ///
+        /// The left values returned by the iterator must be unique, and so must the right values.
+        pub unsafe fn serialize_from_iter<'a, UL, UR, I, S>(
+            iter: I,
+            serializer: &mut S,
+        ) -> Result<BiMapResolver, S::Error>
+        where
+            UL: 'a + Serialize<S, Archived = L> + Hash + Eq,
+            UR: 'a + Serialize<S, Archived = R> + Hash + Eq,
+            I: ExactSizeIterator<Item = (&'a UL, &'a UR)>,
+            S: Serializer + ScratchSpace + ?Sized,
+        {
+            use crate::{collections::hash_index::HashIndexBuilder, ScratchVec};
+
+            let len = iter.len();
+
+            let mut entries = ScratchVec::new(serializer, len)?;
+            let mut left_builder =
+                HashIndexBuilder::new(serializer.hash_algorithm(), len, serializer)?;
+            for (i, (left, right)) in iter.enumerate() {
+                left_builder.insert(left, i);
+                entries.push(Entry {
+                    key: left,
+                    value: right,
+                });
+            }
+            let left_index = left_builder.finish(serializer)?;
+
+            let mut right_builder =
+                HashIndexBuilder::new(serializer.hash_algorithm(), len, serializer)?;
+            for (i, entry) in entries.iter().enumerate() {
+                right_builder.insert(entry.value, i);
+            }
+            let right_index = right_builder.finish(serializer)?;
+
+            let entries_pos = serializer.serialize_iter::<Entry<&UL, &UR>, _, _>(entries.iter())?;
+            entries.free(serializer)?;
+
+            Ok(BiMapResolver {
+                left_index,
+                right_index,
+                entries_pos,
+            })
+        }
+    }
+};
+
+impl<L: fmt::Debug, R: fmt::Debug> fmt::Debug for ArchivedBiMap<L, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<L: Hash + Eq, R: PartialEq> PartialEq for ArchivedBiMap<L, R> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(left, right)| other.get_by_left(left) == Some(right))
+    }
+}
+
+impl<L: Hash + Eq, R: Eq> Eq for ArchivedBiMap<L, R> {}
+
+/// An iterator over the pairs of a bimap.
+pub struct Iter<'a, L, R> {
+    inner: slice::Iter<'a, Entry<L, R>>,
+}
+
+impl<'a, L, R> Iterator for Iter<'a, L, R> {
+    type Item = (&'a L, &'a R);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|entry| (&entry.key, &entry.value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<L, R> ExactSizeIterator for Iter<'_, L, R> {}
+impl<L, R> FusedIterator for Iter<'_, L, R> {}
+
+/// An iterator over the left values of a bimap.
+pub struct LeftValues<'a, L, R> {
+    inner: slice::Iter<'a, Entry<L, R>>,
+}
+
+impl<'a, L, R> Iterator for LeftValues<'a, L, R> {
+    type Item = &'a L;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|entry| &entry.key)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<L, R> ExactSizeIterator for LeftValues<'_, L, R> {}
+impl<L, R> FusedIterator for LeftValues<'_, L, R> {}
+
+/// An iterator over the right values of a bimap.
+pub struct RightValues<'a, L, R> {
+    inner: slice::Iter<'a, Entry<L, R>>,
+}
+
+impl<'a, L, R> Iterator for RightValues<'a, L, R> {
+    type Item = &'a R;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|entry| &entry.value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<L, R> ExactSizeIterator for RightValues<'_, L, R> {}
+impl<L, R> FusedIterator for RightValues<'_, L, R> {}
+
+/// The resolver for archived bimaps.
+pub struct BiMapResolver {
+    left_index: HashIndexResolver,
+    right_index: HashIndexResolver,
+    entries_pos: usize,
+}
@@ -0,0 +1,192 @@
+//! Validation implementation for ArchivedBiMap.
+
+use crate::{
+    collections::{
+        bi_map::ArchivedBiMap,
+        hash_index::validation::HashIndexError,
+        util::{validation::ArchivedEntryError, Entry},
+        ArchivedHashIndex,
+    },
+    validation::ArchiveContext,
+    RelPtr,
+};
+use bytecheck::{CheckBytes, Error, SliceCheckError};
+use core::{
+    alloc::{Layout, LayoutError},
+    convert::Infallible,
+    fmt,
+    hash::Hash,
+    ptr,
+};
+
+/// Errors that can occur while checking an archived bimap.
+#[derive(Debug)]
+pub enum BiMapError<L, R, C> {
+    /// An error occurred while checking one of the hash indexes
+    HashIndexError(HashIndexError<C>),
+    /// The hash indexes have different lengths
+    MismatchedIndexLengths {
+        /// The length of the left hash index
+        left: usize,
+        /// The length of the right hash index
+        right: usize,
+    },
+    /// An error occurred while checking the layouts of entries
+    LayoutError(LayoutError),
+    /// An error occurred while checking the entries
+    CheckEntryError(SliceCheckError<ArchivedEntryError<L, R>>),
+    /// A left value is not located at the correct position
+    ///
+    /// This can either be due to the value being invalid for the left hash index, or the value
+    /// being a duplicate of an earlier left value.
+    InvalidLeftPosition {
+        /// The index of the pair when iterating
+        index: usize,
+    },
+    /// A right value is not located at the correct position
+    ///
+    /// This can either be due to the value being invalid for the right hash index, or the value
+    /// being a duplicate of an earlier right value.
+    InvalidRightPosition {
+        /// The index of the pair when iterating
+        index: usize,
+    },
+    /// A bounds error occurred
+    ContextError(C),
+}
+
+impl<L: fmt::Display, R: fmt::Display, E: fmt::Display> fmt::Display for BiMapError<L, R, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BiMapError::HashIndexError(e) => write!(f, "hash index check error: {}", e),
+            BiMapError::MismatchedIndexLengths { left, right } => write!(
+                f,
+                "mismatched hash index lengths: left index has {} items but right index has {}",
+                left, right
+            ),
+            BiMapError::LayoutError(e) => write!(f, "layout error: {}", e),
+            BiMapError::CheckEntryError(e) => write!(f, "entry check error: {}", e),
+            BiMapError::InvalidLeftPosition { index } => {
+                write!(f, "invalid left value position: at index {}", index)
+            }
+            BiMapError::InvalidRightPosition { index } => {
+                write!(f, "invalid right value position: at index {}", index)
+            }
+            BiMapError::ContextError(e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl<L, R, C> Error for BiMapError<L, R, C>
+    where
+        L: Error + 'static,
+        R: Error + 'static,
+        C: Error + 'static,
+    {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                BiMapError::HashIndexError(e) => Some(e as &dyn Error),
+                BiMapError::MismatchedIndexLengths { .. } => None,
+                BiMapError::LayoutError(e) => Some(e as &dyn Error),
+                BiMapError::CheckEntryError(e) => Some(e as &dyn Error),
+                BiMapError::InvalidLeftPosition { .. } => None,
+                BiMapError::InvalidRightPosition { .. } => None,
+                BiMapError::ContextError(e) => Some(e as &dyn Error),
+            }
+        }
+    }
+};
+
+impl<L, R, C> From<Infallible> for BiMapError<L, R, C> {
+    fn from(_: Infallible) -> Self {
+        unsafe { core::hint::unreachable_unchecked() }
+    }
+}
+
+impl<L, R, C> From<SliceCheckError<Infallible>> for BiMapError<L, R, C> {
+    #[inline]
+    fn from(_: SliceCheckError<Infallible>) -> Self {
+        unsafe { core::hint::unreachable_unchecked() }
+    }
+}
+
+impl<L, R, C> From<HashIndexError<C>> for BiMapError<L, R, C> {
+    #[inline]
+    fn from(e: HashIndexError<C>) -> Self {
+        Self::HashIndexError(e)
+    }
+}
+
+impl<L, R, C> From<LayoutError> for BiMapError<L, R, C> {
+    #[inline]
+    fn from(e: LayoutError) -> Self {
+        Self::LayoutError(e)
+    }
+}
+
+impl<L, R, C> From<SliceCheckError<ArchivedEntryError<L, R>>> for BiMapError<L, R, C> {
+    #[inline]
+    fn from(e: SliceCheckError<ArchivedEntryError<L, R>>) -> Self {
+        Self::CheckEntryError(e)
+    }
+}
+
+impl<L, R, C> CheckBytes<C> for ArchivedBiMap<L, R>
+where
+    L: CheckBytes<C> + Eq + Hash,
+    R: CheckBytes<C> + Eq + Hash,
+    C: ArchiveContext + ?Sized,
+    C::Error: Error,
+{
+    type Error = BiMapError<L::Error, R::Error, C::Error>;
+
+    unsafe fn check_bytes<'a>(
+        value: *const Self,
+        context: &mut C,
+    ) -> Result<&'a Self, Self::Error> {
+        let left_index =
+            ArchivedHashIndex::check_bytes(ptr::addr_of!((*value).left_index), context)?;
+        let right_index =
+            ArchivedHashIndex::check_bytes(ptr::addr_of!((*value).right_index), context)?;
+        if left_index.len() != right_index.len() {
+            return Err(BiMapError::MismatchedIndexLengths {
+                left: left_index.len(),
+                right: right_index.len(),
+            });
+        }
+
+        // Entries
+        Layout::array::<Entry<L, R>>(left_index.len())?;
+        let entries_rel_ptr = RelPtr::manual_check_bytes(ptr::addr_of!((*value).entries), context)?;
+        let entries_ptr = context
+            .check_subtree_ptr::<[Entry<L, R>]>(
+                entries_rel_ptr.base(),
+                entries_rel_ptr.offset(),
+                left_index.len(),
+            )
+            .map_err(BiMapError::ContextError)?;
+
+        let range = context
+            .push_prefix_subtree(entries_ptr)
+            .map_err(BiMapError::ContextError)?;
+        let entries = <[Entry<L, R>]>::check_bytes(entries_ptr, context)?;
+        context
+            .pop_prefix_range(range)
+            .map_err(BiMapError::ContextError)?;
+
+        for (i, entry) in entries.iter().enumerate() {
+            if left_index.find(&entry.key, |j| entries[j].key == entry.key) != Some(i) {
+                return Err(BiMapError::InvalidLeftPosition { index: i });
+            }
+            if right_index.find(&entry.value, |j| entries[j].value == entry.value) != Some(i) {
+                return Err(BiMapError::InvalidRightPosition { index: i });
+            }
+        }
+
+        Ok(&*value)
+    }
+}
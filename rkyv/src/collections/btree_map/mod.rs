@@ -22,12 +22,18 @@ struct InnerNodeEntry<K> {
     key: K,
 }
 
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(InnerNodeEntry<u32> { ptr: RelPtr<NodeHeader>, key: u32 });
+
 #[cfg_attr(feature = "strict", repr(C))]
 struct LeafNodeEntry<K, V> {
     key: K,
     value: V,
 }
 
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(LeafNodeEntry<u8, u32> { key: u8, value: u32 });
+
 impl<'a, UK: Archive, UV: Archive> Archive for LeafNodeEntry<&'a UK, &'a UV> {
     type Archived = LeafNodeEntry<UK::Archived, UV::Archived>;
     type Resolver = (UK::Resolver, UV::Resolver);
@@ -51,6 +57,13 @@ struct NodeHeader {
     ptr: RelPtr<NodeHeader>,
 }
 
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(NodeHeader {
+    meta: Archived<u16>,
+    size: Archived<usize>,
+    ptr: RelPtr<NodeHeader>,
+});
+
 impl NodeHeader {
     #[inline]
     fn is_inner(&self) -> bool {
@@ -89,6 +102,9 @@ struct Node<T: ?Sized> {
     tail: T,
 }
 
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(Node<[u8; 3]> { header: NodeHeader, tail: [u8; 3] });
+
 impl<T> Pointee for Node<[T]> {
     type Metadata = usize;
 }
@@ -191,6 +207,13 @@ pub struct ArchivedBTreeMap<K, V> {
     _phantom: PhantomData<(K, V)>,
 }
 
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(ArchivedBTreeMap<u32, u32> {
+    len: Archived<usize>,
+    root: RelPtr<NodeHeader>,
+    _phantom: PhantomData<(u32, u32)>,
+});
+
 /// The resolver for an [`ArchivedBTreeMap`].
 pub struct BTreeMapResolver {
     root_pos: usize,
@@ -367,189 +390,437 @@ impl<K, V> ArchivedBTreeMap<K, V> {
 
 #[cfg(feature = "alloc")]
 const _: () = {
-    use crate::{ser::Serializer, Serialize};
-    #[cfg(not(feature = "std"))]
-    use alloc::vec::Vec;
-    use core::mem;
+    use crate::{
+        ser::{serializers::AllocScratch, ScratchSpace, Serializer},
+        Fallible, ScratchVec, Serialize,
+    };
+    use core::{alloc::Layout, mem, ops::Deref};
+
+    /// Scratch space for the nodes and resolvers kept while serializing a B-tree.
+    trait BTreeScratch<S: Fallible + ?Sized> {
+        unsafe fn push_scratch(
+            &mut self,
+            serializer: &mut S,
+            layout: Layout,
+        ) -> Result<NonNull<[u8]>, S::Error>;
 
-    impl<K, V> ArchivedBTreeMap<K, V> {
-        /// Serializes an ordered iterator of key-value pairs as a B-tree map.
-        ///
-        /// # Safety
-        ///
-        /// - Keys returned by the iterator must be unique
-        /// - Keys must be in reverse sorted order from last to first
-        pub unsafe fn serialize_from_reverse_iter<'a, UK, UV, S, I>(
-            mut iter: I,
+        unsafe fn pop_scratch(
+            &mut self,
             serializer: &mut S,
-        ) -> Result<BTreeMapResolver, S::Error>
-        where
-            UK: 'a + Serialize<S, Archived = K>,
-            UV: 'a + Serialize<S, Archived = V>,
-            S: Serializer + ?Sized,
-            I: ExactSizeIterator<Item = (&'a UK, &'a UV)>,
-        {
-            if iter.len() == 0 {
-                Ok(BTreeMapResolver { root_pos: 0 })
-            } else {
-                // The memory span of a single node should not exceed 4kb to keep everything within
-                // the distance of a single IO page
-                const MAX_NODE_SIZE: usize = 4096;
-
-                // The nodes that must go in the next level in reverse order (key, node_pos)
-                let mut next_level = Vec::new();
-                let mut resolvers = Vec::new();
-
-                while let Some((key, value)) = iter.next() {
-                    // Start a new block
+            ptr: NonNull<u8>,
+            layout: Layout,
+        ) -> Result<(), S::Error>;
+    }
+
+    /// Keeps the nodes and resolvers in the serializer's own scratch space.
+    struct SerializerScratch;
+
+    impl<S: ScratchSpace + ?Sized> BTreeScratch<S> for SerializerScratch {
+        #[inline]
+        unsafe fn push_scratch(
+            &mut self,
+            serializer: &mut S,
+            layout: Layout,
+        ) -> Result<NonNull<[u8]>, S::Error> {
+            serializer.push_scratch(layout)
+        }
+
+        #[inline]
+        unsafe fn pop_scratch(
+            &mut self,
+            serializer: &mut S,
+            ptr: NonNull<u8>,
+            layout: Layout,
+        ) -> Result<(), S::Error> {
+            serializer.pop_scratch(ptr, layout)
+        }
+    }
+
+    // Scratch space without a limit can only fail when it's popped out of order
+    impl<S: Fallible + ?Sized> BTreeScratch<S> for AllocScratch {
+        #[inline]
+        unsafe fn push_scratch(
+            &mut self,
+            _: &mut S,
+            layout: Layout,
+        ) -> Result<NonNull<[u8]>, S::Error> {
+            Ok(ScratchSpace::push_scratch(self, layout).unwrap())
+        }
+
+        #[inline]
+        unsafe fn pop_scratch(
+            &mut self,
+            _: &mut S,
+            ptr: NonNull<u8>,
+            layout: Layout,
+        ) -> Result<(), S::Error> {
+            ScratchSpace::pop_scratch(self, ptr, layout).unwrap();
+            Ok(())
+        }
+    }
+
+    /// A serializer paired with the scratch space used to serialize a B-tree.
+    struct WithScratch<'a, S: ?Sized, C> {
+        serializer: &'a mut S,
+        scratch: &'a mut C,
+    }
+
+    impl<'a, S: ?Sized, C> WithScratch<'a, S, C> {
+        #[inline]
+        fn new(serializer: &'a mut S, scratch: &'a mut C) -> Self {
+            Self {
+                serializer,
+                scratch,
+            }
+        }
+    }
+
+    impl<S: Fallible + ?Sized, C> Fallible for WithScratch<'_, S, C> {
+        type Error = S::Error;
+    }
+
+    impl<S: Fallible + ?Sized, C: BTreeScratch<S>> ScratchSpace for WithScratch<'_, S, C> {
+        #[inline]
+        unsafe fn push_scratch(&mut self, layout: Layout) -> Result<NonNull<[u8]>, S::Error> {
+            self.scratch.push_scratch(self.serializer, layout)
+        }
+
+        #[inline]
+        unsafe fn pop_scratch(&mut self, ptr: NonNull<u8>, layout: Layout) -> Result<(), S::Error> {
+            self.scratch.pop_scratch(self.serializer, ptr, layout)
+        }
+    }
+
+    /// The first key and position of each node serialized so far.
+    ///
+    /// Each level of the tree takes up a contiguous range of the list, starting with the leaves.
+    /// Scratch space has to be freed in the reverse order it was allocated, so the buffers that the
+    /// list outgrows are kept until the list is freed.
+    struct NodeList<T> {
+        nodes: ScratchVec<T>,
+        outgrown: ScratchVec<ScratchVec<T>>,
+    }
+
+    impl<T: Copy> NodeList<T> {
+        /// Creates a node list with the given capacity that can grow to hold `max_len` nodes.
+        unsafe fn new<C: ScratchSpace + ?Sized>(
+            scratch: &mut C,
+            capacity: usize,
+            max_len: usize,
+        ) -> Result<Self, C::Error> {
+            // The capacity doubles every time the list grows, which bounds the number of buffers
+            // it can outgrow
+            let max_outgrown =
+                8 * mem::size_of::<usize>() - (max_len / capacity).leading_zeros() as usize;
+            let outgrown = ScratchVec::new(scratch, max_outgrown)?;
+            let nodes = ScratchVec::new(scratch, capacity)?;
+            Ok(Self { nodes, outgrown })
+        }
+
+        unsafe fn push<C: ScratchSpace + ?Sized>(
+            &mut self,
+            scratch: &mut C,
+            node: T,
+        ) -> Result<(), C::Error> {
+            if self.nodes.len() == self.nodes.capacity() {
+                let mut nodes = ScratchVec::new(scratch, 2 * self.nodes.capacity())?;
+                nodes.extend_from_slice(&self.nodes);
+                self.outgrown.push(mem::replace(&mut self.nodes, nodes));
+            }
+            self.nodes.push(node);
+            Ok(())
+        }
+
+        unsafe fn free<C: ScratchSpace + ?Sized>(self, scratch: &mut C) -> Result<(), C::Error> {
+            let NodeList {
+                nodes,
+                mut outgrown,
+            } = self;
+            nodes.free(scratch)?;
+            while let Some(nodes) = outgrown.pop() {
+                nodes.free(scratch)?;
+            }
+            outgrown.free(scratch)
+        }
+    }
+
+    impl<T> Deref for NodeList<T> {
+        type Target = [T];
+
+        #[inline]
+        fn deref(&self) -> &Self::Target {
+            &self.nodes
+        }
+    }
+
+    unsafe fn serialize_btree_map<'a, K, V, UK, UV, S, C, I>(
+        mut iter: I,
+        serializer: &mut S,
+        scratch: &mut C,
+    ) -> Result<BTreeMapResolver, S::Error>
+    where
+        UK: 'a + Serialize<S, Archived = K>,
+        UV: 'a + Serialize<S, Archived = V>,
+        S: Serializer + ?Sized,
+        C: BTreeScratch<S>,
+        I: ExactSizeIterator<Item = (&'a UK, &'a UV)>,
+    {
+        let len = iter.len();
+        if len == 0 {
+            Ok(BTreeMapResolver { root_pos: 0 })
+        } else {
+            // The memory span of a single node should not exceed 4kb to keep everything within
+            // the distance of a single IO page
+            const MAX_NODE_SIZE: usize = 4096;
+
+            // Nodes stop taking entries once their entries alone reach the maximum node size,
+            // which bounds the number of resolvers that have to be kept for a single node
+            fn max_node_entries(len: usize, entry_size: usize, min_entries: usize) -> usize {
+                match MAX_NODE_SIZE.checked_div(entry_size) {
+                    Some(max) => usize::min(len, usize::max(min_entries, max + 1)),
+                    None => len,
+                }
+            }
+
+            let max_leaf_entries = max_node_entries(
+                len,
+                mem::size_of::<LeafNodeEntry<K, V>>(),
+                MIN_ENTRIES_PER_LEAF_NODE,
+            );
+            // Inner nodes may take one extra entry to avoid leaving a single entry behind
+            let max_inner_entries = max_node_entries(
+                len,
+                mem::size_of::<InnerNodeEntry<K>>(),
+                MIN_ENTRIES_PER_INNER_NODE,
+            ) + 1;
+
+            // Both sets of resolvers are allocated up front so the node list, which may have to
+            // grow, stays on top of the scratch space
+            let mut leaf_resolvers =
+                ScratchVec::new(&mut WithScratch::new(serializer, scratch), max_leaf_entries)?;
+            let mut inner_resolvers = ScratchVec::new(
+                &mut WithScratch::new(serializer, scratch),
+                max_inner_entries,
+            )?;
+
+            // The nodes of every level in reverse order (key, node_pos)
+            // Each level has at most half as many nodes as the level below it, so the list only
+            // grows when leaves hold fewer than the maximum number of entries
+            let mut nodes = NodeList::new(
+                &mut WithScratch::new(serializer, scratch),
+                2 * (len / max_leaf_entries + 1),
+                2 * len,
+            )?;
+
+            while let Some((key, value)) = iter.next() {
+                // Start a new block
+                let block_start_pos = serializer.pos();
+
+                // Serialize the last entry
+                leaf_resolvers.push((
+                    key,
+                    value,
+                    key.serialize(serializer)?,
+                    value.serialize(serializer)?,
+                ));
+
+                loop {
+                    // This is an estimate of the block size
+                    // It's not exact because there may be padding to align the node and entries
+                    // slice
+                    let estimated_block_size = serializer.pos() - block_start_pos
+                        + mem::size_of::<NodeHeader>()
+                        + leaf_resolvers.len() * mem::size_of::<LeafNodeEntry<K, V>>();
+
+                    // If we've reached or exceeded the maximum node size and have put enough
+                    // entries in this node, then break
+                    if estimated_block_size >= MAX_NODE_SIZE
+                        && leaf_resolvers.len() >= MIN_ENTRIES_PER_LEAF_NODE
+                    {
+                        break;
+                    }
+
+                    if let Some((key, value)) = iter.next() {
+                        // Serialize the next entry
+                        leaf_resolvers.push((
+                            key,
+                            value,
+                            key.serialize(serializer)?,
+                            value.serialize(serializer)?,
+                        ));
+                    } else {
+                        break;
+                    }
+                }
+
+                // Finish the current node
+                serializer.align(usize::max(
+                    mem::align_of::<NodeHeader>(),
+                    mem::align_of::<LeafNodeEntry<K, V>>(),
+                ))?;
+                let raw_node = NodeHeaderData {
+                    meta: combine_meta(false, leaf_resolvers.len()),
+                    size: serializer.pos() - block_start_pos,
+                    // The last node in the list is the next block we're linked to
+                    pos: nodes.last().map(|&(_, pos)| pos),
+                };
+
+                // Add the first key and node position to the next level
+                let node_pos = serializer.resolve_aligned(&raw_node, ())?;
+                nodes.push(
+                    &mut WithScratch::new(serializer, scratch),
+                    (leaf_resolvers.last().unwrap().0, node_pos),
+                )?;
+
+                serializer.align_for::<LeafNodeEntry<K, V>>()?;
+                for (key, value, key_resolver, value_resolver) in leaf_resolvers.drain(..).rev() {
+                    serializer.resolve_aligned(
+                        &LeafNodeEntry { key, value },
+                        (key_resolver, value_resolver),
+                    )?;
+                }
+            }
+
+            // Subsequent levels are populated by serializing node keys from the previous level
+            // When there's only one node left, that's our root
+            let mut level_start = 0;
+            while nodes.len() - level_start > 1 {
+                let level_end = nodes.len();
+                let mut next = level_start;
+                while level_end - next > 1 {
+                    // Start a new inner block
                     let block_start_pos = serializer.pos();
 
-                    // Serialize the last entry
-                    resolvers.push((
-                        key,
-                        value,
-                        key.serialize(serializer)?,
-                        value.serialize(serializer)?,
-                    ));
-
-                    loop {
-                        // This is an estimate of the block size
-                        // It's not exact because there may be padding to align the node and entries
-                        // slice
+                    // When we break, we're guaranteed to have at least one node left
+                    while level_end - next > 1 {
+                        let (key, pos) = nodes[next];
+                        next += 1;
+
+                        // Serialize the next entry
+                        inner_resolvers.push((key, pos, key.serialize(serializer)?));
+
+                        // Estimate the block size
                         let estimated_block_size = serializer.pos() - block_start_pos
                             + mem::size_of::<NodeHeader>()
-                            + resolvers.len() * mem::size_of::<LeafNodeEntry<K, V>>();
+                            + inner_resolvers.len() * mem::size_of::<InnerNodeEntry<K>>();
 
                         // If we've reached or exceeded the maximum node size and have put enough
-                        // entries in this node, then break
+                        // keys in this node, then break
                         if estimated_block_size >= MAX_NODE_SIZE
-                            && resolvers.len() >= MIN_ENTRIES_PER_LEAF_NODE
+                            && inner_resolvers.len() >= MIN_ENTRIES_PER_INNER_NODE
                         {
                             break;
                         }
+                    }
 
-                        if let Some((key, value)) = iter.next() {
-                            // Serialize the next entry
-                            resolvers.push((
-                                key,
-                                value,
-                                key.serialize(serializer)?,
-                                value.serialize(serializer)?,
-                            ));
-                        } else {
-                            break;
-                        }
+                    // Three cases here:
+                    // 1 entry left: use it as the last key
+                    // 2 entries left: serialize the next one and use the last as last to avoid
+                    //   putting only one entry in the final block
+                    // 3+ entries left: use next as last, next block will contain at least two
+                    //   entries
+
+                    if level_end - next == 2 {
+                        let (key, pos) = nodes[next];
+                        next += 1;
+
+                        // Serialize the next entry
+                        inner_resolvers.push((key, pos, key.serialize(serializer)?));
                     }
 
+                    // The next item is the first node
+                    let (first_key, first_pos) = nodes[next];
+                    next += 1;
+
                     // Finish the current node
                     serializer.align(usize::max(
-                        mem::align_of::<NodeHeader>(),
-                        mem::align_of::<LeafNodeEntry<K, V>>(),
+                        mem::align_of::<NodeHeaderData>(),
+                        mem::align_of::<InnerNodeEntry<K>>(),
                     ))?;
-                    let raw_node = NodeHeaderData {
-                        meta: combine_meta(false, resolvers.len()),
+                    let node_header = NodeHeaderData {
+                        meta: combine_meta(true, inner_resolvers.len()),
                         size: serializer.pos() - block_start_pos,
-                        // The last element of next_level is the next block we're linked to
-                        pos: next_level.last().map(|&(_, pos)| pos),
+                        // The pos of the first key is used to make the pointer for inner nodes
+                        pos: Some(first_pos),
                     };
 
-                    // Add the first key and node position to the next level
-                    next_level.push((
-                        resolvers.last().unwrap().0,
-                        serializer.resolve_aligned(&raw_node, ())?,
-                    ));
-
-                    serializer.align_for::<LeafNodeEntry<K, V>>()?;
-                    for (key, value, key_resolver, value_resolver) in resolvers.drain(..).rev() {
-                        serializer.resolve_aligned(
-                            &LeafNodeEntry { key, value },
-                            (key_resolver, value_resolver),
-                        )?;
+                    // Add the second key and node position to the next level
+                    let node_pos = serializer.resolve_aligned(&node_header, ())?;
+                    nodes.push(
+                        &mut WithScratch::new(serializer, scratch),
+                        (first_key, node_pos),
+                    )?;
+
+                    serializer.align_for::<InnerNodeEntry<K>>()?;
+                    for (key, pos, resolver) in inner_resolvers.drain(..).rev() {
+                        let inner_node_data = InnerNodeEntryData::<UK> { key };
+                        serializer.resolve_aligned(&inner_node_data, (pos, resolver))?;
                     }
                 }
 
-                // Subsequent levels are populated by serializing node keys from the previous level
-                // When there's only one node left, that's our root
-                let mut current_level = Vec::new();
-                let mut resolvers = Vec::new();
-                while next_level.len() > 1 {
-                    // Our previous next_level becomes our current level, and current_level is
-                    // guaranteed to be empty at this point
-                    mem::swap(&mut current_level, &mut next_level);
-
-                    let mut iter = current_level.drain(..);
-                    while iter.len() > 1 {
-                        // Start a new inner block
-                        let block_start_pos = serializer.pos();
-
-                        // When we break, we're guaranteed to have at least one node left
-                        while iter.len() > 1 {
-                            let (key, pos) = iter.next().unwrap();
-
-                            // Serialize the next entry
-                            resolvers.push((key, pos, key.serialize(serializer)?));
-
-                            // Estimate the block size
-                            let estimated_block_size = serializer.pos() - block_start_pos
-                                + mem::size_of::<NodeHeader>()
-                                + resolvers.len() * mem::size_of::<InnerNodeEntry<K>>();
-
-                            // If we've reached or exceeded the maximum node size and have put enough
-                            // keys in this node, then break
-                            if estimated_block_size >= MAX_NODE_SIZE
-                                && resolvers.len() >= MIN_ENTRIES_PER_INNER_NODE
-                            {
-                                break;
-                            }
-                        }
-
-                        // Three cases here:
-                        // 1 entry left: use it as the last key
-                        // 2 entries left: serialize the next one and use the last as last to avoid
-                        //   putting only one entry in the final block
-                        // 3+ entries left: use next as last, next block will contain at least two
-                        //   entries
-
-                        if iter.len() == 2 {
-                            let (key, pos) = iter.next().unwrap();
-
-                            // Serialize the next entry
-                            resolvers.push((key, pos, key.serialize(serializer)?));
-                        }
+                debug_assert!(next == level_end);
+                level_start = level_end;
+            }
 
-                        // The next item is the first node
-                        let (first_key, first_pos) = iter.next().unwrap();
-
-                        // Finish the current node
-                        serializer.align(usize::max(
-                            mem::align_of::<NodeHeaderData>(),
-                            mem::align_of::<InnerNodeEntry<K>>(),
-                        ))?;
-                        let node_header = NodeHeaderData {
-                            meta: combine_meta(true, resolvers.len()),
-                            size: serializer.pos() - block_start_pos,
-                            // The pos of the first key is used to make the pointer for inner nodes
-                            pos: Some(first_pos),
-                        };
+            // The root is only node in the final level
+            let root_pos = nodes[level_start].1;
 
-                        // Add the second key and node position to the next level
-                        next_level.push((first_key, serializer.resolve_aligned(&node_header, ())?));
+            // Scratch space has to be freed in the reverse order it was allocated
+            let mut scratch = WithScratch::new(serializer, scratch);
+            nodes.free(&mut scratch)?;
+            inner_resolvers.free(&mut scratch)?;
+            leaf_resolvers.free(&mut scratch)?;
 
-                        serializer.align_for::<InnerNodeEntry<K>>()?;
-                        for (key, pos, resolver) in resolvers.drain(..).rev() {
-                            let inner_node_data = InnerNodeEntryData::<UK> { key };
-                            serializer.resolve_aligned(&inner_node_data, (pos, resolver))?;
-                        }
-                    }
+            Ok(BTreeMapResolver { root_pos })
+        }
+    }
 
-                    debug_assert!(iter.len() == 0);
-                }
+    impl<K, V> ArchivedBTreeMap<K, V> {
+        /// Serializes an ordered iterator of key-value pairs as a B-tree map.
+        ///
+        /// The nodes and resolvers kept while serializing are allocated with the global allocator.
+        /// Use [`serialize_from_reverse_iter_in_scratch`] to keep them in the serializer's scratch
+        /// space instead, or archive fields with the [`InScratch`](crate::with::InScratch) wrapper.
+        ///
+        /// # Safety
+        ///
+        /// - Keys returned by the iterator must be unique
+        /// - Keys must be in reverse sorted order from last to first
+        ///
+        /// [`serialize_from_reverse_iter_in_scratch`]: ArchivedBTreeMap::serialize_from_reverse_iter_in_scratch
+        pub unsafe fn serialize_from_reverse_iter<'a, UK, UV, S, I>(
+            iter: I,
+            serializer: &mut S,
+        ) -> Result<BTreeMapResolver, S::Error>
+        where
+            UK: 'a + Serialize<S, Archived = K>,
+            UV: 'a + Serialize<S, Archived = V>,
+            S: Serializer + ?Sized,
+            I: ExactSizeIterator<Item = (&'a UK, &'a UV)>,
+        {
+            serialize_btree_map(iter, serializer, &mut AllocScratch::new())
+        }
 
-                // The root is only node in the final level
-                Ok(BTreeMapResolver {
-                    root_pos: next_level[0].1,
-                })
-            }
+        /// Serializes an ordered iterator of key-value pairs as a B-tree map.
+        ///
+        /// The nodes and resolvers kept while serializing are allocated in the serializer's
+        /// scratch space, so serializing doesn't allocate.
+        ///
+        /// # Safety
+        ///
+        /// - Keys returned by the iterator must be unique
+        /// - Keys must be in reverse sorted order from last to first
+        pub unsafe fn serialize_from_reverse_iter_in_scratch<'a, UK, UV, S, I>(
+            iter: I,
+            serializer: &mut S,
+        ) -> Result<BTreeMapResolver, S::Error>
+        where
+            UK: 'a + Serialize<S, Archived = K>,
+            UV: 'a + Serialize<S, Archived = V>,
+            S: Serializer + ScratchSpace + ?Sized,
+            I: ExactSizeIterator<Item = (&'a UK, &'a UV)>,
+        {
+            serialize_btree_map(iter, serializer, &mut SerializerScratch)
         }
     }
 };
@@ -74,7 +74,10 @@ impl<K> ArchivedBTreeSet<K> {
 
 #[cfg(feature = "alloc")]
 const _: () = {
-    use crate::{ser::Serializer, Serialize};
+    use crate::{
+        ser::{ScratchSpace, Serializer},
+        Serialize,
+    };
 
     impl<K> ArchivedBTreeSet<K> {
         /// Serializes an ordered iterator of key-value pairs as a B-tree map.
@@ -96,6 +99,32 @@ const _: () = {
                 ArchivedBTreeMap::serialize_from_reverse_iter(iter.map(|x| (x, &())), serializer)?,
             ))
         }
+
+        /// Serializes an ordered iterator of keys as a B-tree set using the serializer's scratch
+        /// space.
+        ///
+        /// See [`ArchivedBTreeMap::serialize_from_reverse_iter_in_scratch`] for details.
+        ///
+        /// # Safety
+        ///
+        /// - Keys returned by the iterator must be unique
+        /// - Keys must be in reverse sorted order from last to first
+        pub unsafe fn serialize_from_reverse_iter_in_scratch<'a, UK, S, I>(
+            iter: I,
+            serializer: &mut S,
+        ) -> Result<BTreeSetResolver, S::Error>
+        where
+            UK: 'a + Serialize<S, Archived = K>,
+            S: Serializer + ScratchSpace + ?Sized,
+            I: ExactSizeIterator<Item = &'a UK>,
+        {
+            Ok(BTreeSetResolver(
+                ArchivedBTreeMap::serialize_from_reverse_iter_in_scratch(
+                    iter.map(|x| (x, &())),
+                    serializer,
+                )?,
+            ))
+        }
     }
 };
 
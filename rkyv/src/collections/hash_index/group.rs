@@ -0,0 +1,260 @@
+//! Groups of slots for archived hash indexes.
+
+use crate::{Archived, FixedUsize};
+
+/// The number of slots in each group.
+pub const GROUP_WIDTH: usize = 16;
+
+/// The control byte for an empty slot. The control bytes for occupied slots always have their top
+/// bit clear.
+pub const EMPTY: u8 = 0xff;
+
+/// Returns the number of groups needed to index `len` items.
+///
+/// The number of groups is always a power of two, and there is always at least one empty slot so
+/// that probing for a missing key terminates.
+#[inline]
+pub const fn group_count(len: usize) -> usize {
+    match checked_group_count(len) {
+        Some(count) => count,
+        None => panic!("too many items for a hash index"),
+    }
+}
+
+/// Returns the number of groups needed to index `len` items, or `None` if the number of groups
+/// overflows a `usize`.
+#[inline]
+pub const fn checked_group_count(len: usize) -> Option<usize> {
+    if len == 0 {
+        return Some(0);
+    }
+    let min_slots = match len.checked_add(len / 7 + 1) {
+        Some(min_slots) => min_slots,
+        None => return None,
+    };
+    ((min_slots - 1) / GROUP_WIDTH + 1).checked_next_power_of_two()
+}
+
+/// Returns the control byte for a hash.
+#[inline]
+pub const fn tag(hash: u64) -> u8 {
+    (hash >> 57) as u8
+}
+
+/// A group of slots in an archived hash index.
+///
+/// Each slot has a control byte, which holds the top seven bits of the key's hash, and the index of
+/// its entry. Keeping both together means that a lookup usually only touches its group and the
+/// matching entry.
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct Group {
+    pub control: [u8; GROUP_WIDTH],
+    pub indices: [Archived<usize>; GROUP_WIDTH],
+}
+
+impl Group {
+    /// Returns a group with all of its slots empty.
+    #[inline]
+    pub fn empty() -> Self {
+        Self {
+            control: [EMPTY; GROUP_WIDTH],
+            indices: [to_archived!(0 as FixedUsize); GROUP_WIDTH],
+        }
+    }
+
+    #[cfg(all(
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "sse2"
+    ))]
+    #[inline]
+    fn match_byte(&self, byte: u8) -> BitMask {
+        #[cfg(target_arch = "x86")]
+        use core::arch::x86::*;
+        #[cfg(target_arch = "x86_64")]
+        use core::arch::x86_64::*;
+
+        unsafe {
+            let control = _mm_loadu_si128(self.control.as_ptr().cast());
+            let cmp = _mm_cmpeq_epi8(control, _mm_set1_epi8(byte as i8));
+            BitMask(_mm_movemask_epi8(cmp) as u16)
+        }
+    }
+
+    #[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+    #[inline]
+    fn match_byte(&self, byte: u8) -> BitMask {
+        use core::arch::aarch64::*;
+
+        unsafe {
+            let control = vld1q_u8(self.control.as_ptr());
+            let cmp = vceqq_u8(control, vdupq_n_u8(byte));
+            // Narrow each byte of the comparison to a nibble, then keep one bit per slot
+            let nibbles = vshrn_n_u16(vreinterpretq_u16_u8(cmp), 4);
+            let bits = vget_lane_u64(vreinterpret_u64_u8(nibbles), 0);
+            BitMask(bits & 0x8888_8888_8888_8888)
+        }
+    }
+
+    #[cfg(not(any(
+        all(
+            any(target_arch = "x86", target_arch = "x86_64"),
+            target_feature = "sse2"
+        ),
+        all(target_arch = "aarch64", target_feature = "neon"),
+    )))]
+    #[inline]
+    fn match_byte(&self, byte: u8) -> BitMask {
+        self.match_byte_generic(byte)
+    }
+
+    #[cfg(any(
+        test,
+        not(any(
+            all(
+                any(target_arch = "x86", target_arch = "x86_64"),
+                target_feature = "sse2"
+            ),
+            all(target_arch = "aarch64", target_feature = "neon"),
+        ))
+    ))]
+    #[inline]
+    fn match_byte_generic(&self, byte: u8) -> BitMask {
+        let mut result = 0;
+        for (i, &control) in self.control.iter().enumerate() {
+            if control == byte {
+                result |= 1 << (i as u32 * BITMASK_STRIDE);
+            }
+        }
+        BitMask(result)
+    }
+
+    /// Returns the slots whose control bytes match the given tag.
+    #[inline]
+    pub fn match_tag(&self, tag: u8) -> BitMask {
+        self.match_byte(tag)
+    }
+
+    /// Returns the empty slots in the group.
+    #[inline]
+    pub fn match_empty(&self) -> BitMask {
+        self.match_byte(EMPTY)
+    }
+
+    /// Returns the entry index of the given slot.
+    #[inline]
+    pub fn index(&self, slot: usize) -> usize {
+        from_archived!(self.indices[slot]) as usize
+    }
+}
+
+#[cfg(not(all(target_arch = "aarch64", target_feature = "neon")))]
+type BitMaskWord = u16;
+#[cfg(not(all(target_arch = "aarch64", target_feature = "neon")))]
+const BITMASK_STRIDE: u32 = 1;
+
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+type BitMaskWord = u64;
+#[cfg(all(target_arch = "aarch64", target_feature = "neon"))]
+const BITMASK_STRIDE: u32 = 4;
+
+/// A set of slots in a group.
+///
+/// Each slot is represented by a single set bit, spaced `BITMASK_STRIDE` bits apart.
+#[derive(Clone, Copy)]
+pub struct BitMask(BitMaskWord);
+
+impl BitMask {
+    /// Returns whether any slots are set.
+    #[inline]
+    pub fn any(self) -> bool {
+        self.0 != 0
+    }
+
+    /// Returns the lowest set slot.
+    #[inline]
+    pub fn lowest(self) -> Option<usize> {
+        if self.0 == 0 {
+            None
+        } else {
+            Some((self.0.trailing_zeros() / BITMASK_STRIDE) as usize)
+        }
+    }
+}
+
+impl Iterator for BitMask {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        let result = self.lowest();
+        self.0 &= self.0.wrapping_sub(1);
+        result
+    }
+}
+
+/// The sequence of groups to probe for a hash.
+///
+/// This uses triangular probing, which visits every group exactly once over the first `group_count`
+/// probes because the number of groups is a power of two.
+pub struct ProbeSeq {
+    pos: usize,
+    stride: usize,
+    mask: usize,
+}
+
+impl ProbeSeq {
+    /// Returns the probe sequence for a hash over the given number of groups.
+    #[inline]
+    pub fn new(hash: u64, group_count: usize) -> Self {
+        let mask = group_count - 1;
+        Self {
+            pos: hash as usize & mask,
+            stride: 0,
+            mask,
+        }
+    }
+
+    /// Returns the index of the current group.
+    #[inline]
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Moves to the next group in the sequence.
+    #[inline]
+    pub fn next_group(&mut self) {
+        self.stride += 1;
+        self.pos = (self.pos + self.stride) & self.mask;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Group, EMPTY, GROUP_WIDTH};
+
+    #[test]
+    fn match_byte() {
+        let mut group = Group::empty();
+        assert!(group.match_empty().eq(0..GROUP_WIDTH));
+        assert!(!group.match_tag(0).any());
+
+        let tags = [0x00, 0x7f, 0x12, EMPTY, 0x12, 0x00, 0x40, EMPTY];
+        for (i, control) in group.control.iter_mut().enumerate() {
+            *control = tags[i % tags.len()];
+        }
+
+        for &byte in [0x00, 0x12, 0x40, 0x7f, 0x55, EMPTY].iter() {
+            let expected = group
+                .control
+                .iter()
+                .enumerate()
+                .filter(|&(_, &control)| control == byte)
+                .map(|(i, _)| i);
+            assert!(group.match_byte(byte).eq(expected.clone()));
+            assert!(group.match_byte_generic(byte).eq(expected));
+        }
+        assert_eq!(group.match_tag(0x12).lowest(), Some(2));
+        assert_eq!(group.match_empty().lowest(), Some(3));
+    }
+}
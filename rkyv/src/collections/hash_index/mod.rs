@@ -1,26 +1,210 @@
-//! A helper type that archives index data for hashed collections using
-//! [compress, hash and displace](http://cmph.sourceforge.net/papers/esa09.pdf).
+//! A helper type that archives index data for hashed collections.
+//!
+//! Archived hash indexes use open addressing with groups of slots, similar to
+//! [hashbrown](https://docs.rs/hashbrown)'s Swiss tables. Each slot has a control byte holding part
+//! of its key's hash, so most lookups only need to examine a single group before comparing keys.
 
+use self::group::{group_count, tag, Group, ProbeSeq};
+#[cfg(feature = "alloc")]
+use crate::{
+    ser::{ScratchSpace, Serializer},
+    FixedUsize, ScratchVec,
+};
 use crate::{Archive, Archived, RelPtr};
+#[cfg(all(feature = "alloc", feature = "rayon", not(feature = "std")))]
+use alloc::vec::Vec;
+#[cfg(all(feature = "alloc", feature = "rayon"))]
+use core::cmp;
+#[cfg(feature = "alloc")]
+use core::mem::size_of;
 use core::{
     fmt,
     hash::{Hash, Hasher},
     slice,
 };
+use seahash::SeaHasher;
 
-/// The hash builder for archived hash indexes.
-pub use seahash::SeaHasher as HashBuilder;
-
+mod group;
 #[cfg(feature = "validation")]
 pub mod validation;
+mod wyhash;
+mod xxh3;
+
+pub use self::{wyhash::WyHasher, xxh3::Xxh3Hasher};
+
+/// The number of groups in each chunk of the table that is filled in parallel.
+///
+/// The chunk size is fixed rather than derived from the number of threads so that the built index
+/// is the same no matter how many threads build it.
+#[cfg(all(feature = "alloc", feature = "rayon"))]
+const PAR_CHUNK_GROUPS: usize = 256;
+
+/// The hash algorithms that may be used by archived hash indexes.
+///
+/// The algorithm and seed used to build a hash index are recorded in the archive, so lookups always
+/// use the matching hasher. New hash indexes are built with the
+/// [algorithm](crate::ser::Serializer::hash_algorithm) and [seed](crate::ser::Serializer::hash_seed)
+/// chosen by the serializer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum HashAlgorithm {
+    /// [SeaHash](https://docs.rs/seahash)
+    SeaHash = 0,
+    /// [wyhash](https://github.com/wangyi-fudan/wyhash), which is considerably faster than SeaHash
+    /// for short keys.
+    WyHash = 1,
+    /// [XXH3](https://github.com/Cyan4973/xxHash), which is also much faster than SeaHash for short
+    /// keys.
+    Xxh3 = 2,
+}
+
+impl HashAlgorithm {
+    /// The algorithm that serializers build new hash indexes with unless they choose another.
+    ///
+    /// This is SeaHash unless the `hash_wyhash` or `hash_xxh3` feature is enabled. If both are
+    /// enabled, XXH3 is used.
+    pub const DEFAULT: Self = if cfg!(feature = "hash_xxh3") {
+        HashAlgorithm::Xxh3
+    } else if cfg!(feature = "hash_wyhash") {
+        HashAlgorithm::WyHash
+    } else {
+        HashAlgorithm::SeaHash
+    };
+
+    /// Returns the algorithm with the given tag, if any.
+    #[inline]
+    pub const fn from_u8(tag: u8) -> Option<Self> {
+        match tag {
+            0 => Some(HashAlgorithm::SeaHash),
+            1 => Some(HashAlgorithm::WyHash),
+            2 => Some(HashAlgorithm::Xxh3),
+            _ => None,
+        }
+    }
+
+    /// Returns a new hasher for this algorithm with the default seed of `0`.
+    #[inline]
+    pub fn hasher(self) -> HashBuilder {
+        self.hasher_with_seed(0)
+    }
+
+    /// Returns a new hasher for this algorithm keyed with the given seed.
+    ///
+    /// The seed is mixed into fixed keys for each algorithm, so the same seed always produces the
+    /// same hasher.
+    #[inline]
+    pub fn hasher_with_seed(self, seed: u64) -> HashBuilder {
+        match self {
+            HashAlgorithm::SeaHash => HashBuilder::SeaHash(sea_hasher(seed)),
+            HashAlgorithm::WyHash => HashBuilder::WyHash(wy_hasher(seed)),
+            HashAlgorithm::Xxh3 => HashBuilder::Xxh3(xxh3_hasher(seed)),
+        }
+    }
+
+    // Hashes with the concrete hasher so that lookups don't build a `HashBuilder`, which is as
+    // large as its largest variant.
+    #[inline]
+    fn hash<K: Hash + ?Sized>(self, seed: u64, k: &K) -> u64 {
+        #[inline]
+        fn hash_with<H: Hasher, K: Hash + ?Sized>(mut hasher: H, k: &K) -> u64 {
+            k.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        match self {
+            HashAlgorithm::SeaHash => hash_with(sea_hasher(seed), k),
+            HashAlgorithm::WyHash => hash_with(wy_hasher(seed), k),
+            HashAlgorithm::Xxh3 => hash_with(xxh3_hasher(seed), k),
+        }
+    }
+}
+
+#[inline]
+fn sea_hasher(seed: u64) -> SeaHasher {
+    SeaHasher::with_seeds(
+        0x08576fb6170b5f5f ^ seed,
+        0x587775eeb84a7e46 ^ seed,
+        0xac701115428ee569 ^ seed,
+        0x910feb91b92bb1cd ^ seed,
+    )
+}
+
+#[inline]
+fn wy_hasher(seed: u64) -> WyHasher {
+    WyHasher::with_seed(0x08576fb6170b5f5f ^ seed)
+}
+
+#[inline]
+fn xxh3_hasher(seed: u64) -> Xxh3Hasher {
+    Xxh3Hasher::with_seed(0x08576fb6170b5f5f ^ seed)
+}
+
+/// The hasher for archived hash indexes.
+// Hashers only live on the stack while hashing a single key, so the XXH3 buffer isn't boxed
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Copy)]
+pub enum HashBuilder {
+    /// A SeaHash hasher
+    SeaHash(SeaHasher),
+    /// A wyhash hasher
+    WyHash(WyHasher),
+    /// An XXH3 hasher
+    Xxh3(Xxh3Hasher),
+}
+
+macro_rules! dispatch_write {
+    ($($name:ident: $ty:ty,)*) => {
+        $(
+            #[inline]
+            fn $name(&mut self, i: $ty) {
+                match self {
+                    HashBuilder::SeaHash(hasher) => hasher.$name(i),
+                    HashBuilder::WyHash(hasher) => hasher.$name(i),
+                    HashBuilder::Xxh3(hasher) => hasher.$name(i),
+                }
+            }
+        )*
+    };
+}
+
+impl Hasher for HashBuilder {
+    #[inline]
+    fn finish(&self) -> u64 {
+        match self {
+            HashBuilder::SeaHash(hasher) => hasher.finish(),
+            HashBuilder::WyHash(hasher) => hasher.finish(),
+            HashBuilder::Xxh3(hasher) => hasher.finish(),
+        }
+    }
+
+    dispatch_write! {
+        write: &[u8],
+        write_u8: u8,
+        write_u16: u16,
+        write_u32: u32,
+        write_u64: u64,
+        write_u128: u128,
+        write_usize: usize,
+    }
+}
 
 /// An archived hash index.
 #[cfg_attr(feature = "strict", repr(C))]
 pub struct ArchivedHashIndex {
     len: Archived<usize>,
-    displace: RelPtr<Archived<u32>>,
+    groups: RelPtr<Group>,
+    seed: Archived<u64>,
+    algorithm: HashAlgorithm,
 }
 
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(ArchivedHashIndex {
+    len: Archived<usize>,
+    groups: RelPtr<Group>,
+    seed: Archived<u64>,
+    algorithm: HashAlgorithm,
+});
+
 impl ArchivedHashIndex {
     /// Gets the number of items in the hash index.
     #[inline]
@@ -28,55 +212,62 @@ impl ArchivedHashIndex {
         from_archived!(self.len) as usize
     }
 
+    /// Gets the hash algorithm that this hash index was built with.
     #[inline]
-    fn make_hasher() -> HashBuilder {
-        HashBuilder::with_seeds(
-            0x08576fb6170b5f5f,
-            0x587775eeb84a7e46,
-            0xac701115428ee569,
-            0x910feb91b92bb1cd,
-        )
+    pub const fn algorithm(&self) -> HashAlgorithm {
+        self.algorithm
     }
 
-    /// Gets the hasher for this hash index. The hasher for all archived hash indexes is the same
-    /// for reproducibility.
+    /// Gets the seed that this hash index was built with.
     #[inline]
-    pub fn hasher(&self) -> HashBuilder {
-        Self::make_hasher()
+    pub const fn seed(&self) -> u64 {
+        from_archived!(self.seed)
     }
 
+    /// Gets the hasher for this hash index.
     #[inline]
-    fn displace_slice(&self) -> &[Archived<u32>] {
-        unsafe { slice::from_raw_parts(self.displace.as_ptr(), self.len()) }
+    pub fn hasher(&self) -> HashBuilder {
+        self.algorithm.hasher_with_seed(self.seed())
     }
 
     #[inline]
-    fn displace(&self, index: usize) -> u32 {
-        from_archived!(self.displace_slice()[index])
+    fn groups(&self) -> &[Group] {
+        unsafe { slice::from_raw_parts(self.groups.as_ptr(), group_count(self.len())) }
     }
 
-    /// Returns the index where a key may be located in the hash index.
+    /// Returns the index of the item matching the given key, if any.
     ///
-    /// The hash index does not have access to the keys used to build it, so the key at the returned
-    /// index must be checked for equality.
-    #[inline]
-    pub fn index<K: Hash + ?Sized>(&self, k: &K) -> Option<usize> {
-        let mut hasher = self.hasher();
-        k.hash(&mut hasher);
-        let displace_index = hasher.finish() % self.len() as u64;
-        let displace = self.displace(displace_index as usize);
-
-        if displace == u32::MAX {
-            None
-        } else if displace & 0x80_00_00_00 == 0 {
-            Some(displace as usize)
-        } else {
-            let mut hasher = self.hasher();
-            displace.hash(&mut hasher);
-            k.hash(&mut hasher);
-            let index = hasher.finish() % self.len() as u64;
-            Some(index as usize)
+    /// The hash index does not have access to the keys used to build it, so `is_match` is called
+    /// with the indices of candidate items until it returns `true`.
+    #[inline]
+    pub fn find<K, F>(&self, k: &K, mut is_match: F) -> Option<usize>
+    where
+        K: Hash + ?Sized,
+        F: FnMut(usize) -> bool,
+    {
+        let groups = self.groups();
+        if groups.is_empty() {
+            return None;
         }
+
+        let hash = self.algorithm.hash(self.seed(), k);
+        let tag = tag(hash);
+        let mut probe = ProbeSeq::new(hash, groups.len());
+        for _ in 0..groups.len() {
+            let group = &groups[probe.pos()];
+            for slot in group.match_tag(tag) {
+                let index = group.index(slot);
+                if is_match(index) {
+                    return Some(index);
+                }
+            }
+            if group.match_empty().any() {
+                return None;
+            }
+            probe.next_group();
+        }
+
+        None
     }
 
     /// Returns whether there are no items in the hash index.
@@ -102,32 +293,31 @@ impl ArchivedHashIndex {
         let (fp, fo) = out_field!(out.len);
         len.resolve(pos + fp, (), fo);
 
-        let (fp, fo) = out_field!(out.displace);
-        RelPtr::emplace(pos + fp, resolver.displace_pos, fo);
+        let (fp, fo) = out_field!(out.groups);
+        RelPtr::emplace(pos + fp, resolver.groups_pos, fo);
+
+        let (fp, fo) = out_field!(out.seed);
+        resolver.seed.resolve(pos + fp, (), fo);
+
+        let (_, fo) = out_field!(out.algorithm);
+        fo.write(resolver.algorithm);
     }
 }
 
 #[cfg(feature = "alloc")]
 const _: () = {
-    use crate::{
-        ser::{ScratchSpace, Serializer},
-        ScratchVec,
-    };
-    #[cfg(not(feature = "std"))]
-    use alloc::vec::Vec;
-    use core::{
-        cmp::Reverse,
-        mem::{size_of, MaybeUninit},
-    };
+    use core::mem::MaybeUninit;
 
     impl ArchivedHashIndex {
-        /// Builds and serializes a hash index from an iterator of key-value pairs.
+        /// Builds and serializes a hash index from an iterator of key-value pairs using the
+        /// serializer's [hash algorithm](Serializer::hash_algorithm).
         ///
         /// # Safety
         ///
         /// - The keys returned by the iterator must be unique.
         /// - `entries` must have a capacity of `iter.len()` entries.
         #[allow(clippy::type_complexity)]
+        #[inline]
         pub unsafe fn build_and_serialize<'a, K, V, S, I>(
             iter: I,
             serializer: &mut S,
@@ -139,113 +329,322 @@ const _: () = {
             S: Serializer + ScratchSpace + ?Sized,
             I: ExactSizeIterator<Item = (&'a K, &'a V)>,
         {
-            let len = iter.len();
+            let algorithm = serializer.hash_algorithm();
+            Self::build_and_serialize_with(algorithm, iter, serializer, entries)
+        }
 
-            let mut bucket_size = ScratchVec::new(serializer, len)?;
-            for _ in 0..len {
-                bucket_size.push(0u32);
+        /// Builds and serializes a hash index from an iterator of key-value pairs using the given
+        /// hash algorithm.
+        ///
+        /// The items of the hash index are indexed in iteration order, and `entries` is filled with
+        /// the key-value pairs in that order.
+        ///
+        /// # Safety
+        ///
+        /// - The keys returned by the iterator must be unique.
+        /// - `entries` must have a capacity of `iter.len()` entries.
+        #[allow(clippy::type_complexity)]
+        pub unsafe fn build_and_serialize_with<'a, K, V, S, I>(
+            algorithm: HashAlgorithm,
+            iter: I,
+            serializer: &mut S,
+            entries: &mut ScratchVec<MaybeUninit<(&'a K, &'a V)>>,
+        ) -> Result<HashIndexResolver, S::Error>
+        where
+            K: 'a + Hash,
+            V: 'a,
+            S: Serializer + ScratchSpace + ?Sized,
+            I: ExactSizeIterator<Item = (&'a K, &'a V)>,
+        {
+            let mut builder = HashIndexBuilder::new(algorithm, iter.len(), serializer)?;
+            for (i, (key, value)) in iter.enumerate() {
+                builder.insert(key, i);
+                entries[i].as_mut_ptr().write((key, value));
             }
+            builder.finish(serializer)
+        }
+    }
+};
 
-            let mut displaces = ScratchVec::new(serializer, len)?;
+#[cfg(all(feature = "alloc", feature = "rayon"))]
+const _: () = {
+    use core::mem::MaybeUninit;
+    use rayon::prelude::*;
 
-            for (key, value) in iter {
-                let mut hasher = Self::make_hasher();
-                key.hash(&mut hasher);
-                let displace = (hasher.finish() % len as u64) as u32;
-                displaces.push((displace, (key, value)));
-                bucket_size[displace as usize] += 1;
-            }
+    impl ArchivedHashIndex {
+        /// Builds and serializes a hash index from an iterator of key-value pairs using the
+        /// serializer's [hash algorithm](Serializer::hash_algorithm), hashing keys and assigning
+        /// buckets on multiple threads.
+        ///
+        /// # Safety
+        ///
+        /// - The keys returned by the iterator must be unique.
+        /// - `entries` must have a capacity of `iter.len()` entries.
+        #[allow(clippy::type_complexity)]
+        #[inline]
+        pub unsafe fn par_build_and_serialize<'a, K, V, S, I>(
+            iter: I,
+            serializer: &mut S,
+            entries: &mut ScratchVec<MaybeUninit<(&'a K, &'a V)>>,
+        ) -> Result<HashIndexResolver, S::Error>
+        where
+            K: 'a + Hash + Sync,
+            V: 'a + Sync,
+            S: Serializer + ScratchSpace + ?Sized,
+            I: ExactSizeIterator<Item = (&'a K, &'a V)>,
+        {
+            let algorithm = serializer.hash_algorithm();
+            Self::par_build_and_serialize_with(algorithm, iter, serializer, entries)
+        }
 
-            displaces
-                .sort_by_key(|&(displace, _)| (Reverse(bucket_size[displace as usize]), displace));
+        /// Builds and serializes a hash index from an iterator of key-value pairs using the given
+        /// hash algorithm, hashing keys and assigning buckets on multiple threads.
+        ///
+        /// Like [`build_and_serialize_with`](ArchivedHashIndex::build_and_serialize_with), the
+        /// items are indexed in iteration order and `entries` is filled in that order. The layout of
+        /// the built index may differ from one built on a single thread, but it is the same
+        /// regardless of how many threads are used.
+        ///
+        /// # Safety
+        ///
+        /// - The keys returned by the iterator must be unique.
+        /// - `entries` must have a capacity of `iter.len()` entries.
+        #[allow(clippy::type_complexity)]
+        pub unsafe fn par_build_and_serialize_with<'a, K, V, S, I>(
+            algorithm: HashAlgorithm,
+            iter: I,
+            serializer: &mut S,
+            entries: &mut ScratchVec<MaybeUninit<(&'a K, &'a V)>>,
+        ) -> Result<HashIndexResolver, S::Error>
+        where
+            K: 'a + Hash + Sync,
+            V: 'a + Sync,
+            S: Serializer + ScratchSpace + ?Sized,
+            I: ExactSizeIterator<Item = (&'a K, &'a V)>,
+        {
+            let len = iter.len();
+            for (i, entry) in iter.enumerate() {
+                entries[i].as_mut_ptr().write(entry);
+            }
+            let entries = slice::from_raw_parts(entries.as_ptr().cast::<(&'a K, &'a V)>(), len);
 
-            let mut occupied = ScratchVec::new(serializer, len)?;
-            for _ in 0..len {
-                occupied.push(false);
+            let mut builder = HashIndexBuilder::new(algorithm, len, serializer)?;
+            let seed = builder.seed();
+            let mut items = ScratchVec::new(serializer, len)?;
+            for i in 0..len {
+                items.push((0, i));
             }
+            items
+                .par_iter_mut()
+                .zip(entries.par_iter())
+                .for_each(|(item, (key, _))| item.0 = algorithm.hash(seed, *key));
+            builder.par_insert_hashed(&mut items);
+            items.free(serializer)?;
+            builder.finish(serializer)
+        }
+    }
+};
 
-            let mut displacements = ScratchVec::new(serializer, len)?;
-            for _ in 0..len {
-                displacements.push(to_archived!(u32::MAX));
+/// An incremental builder for archived hash indexes.
+///
+/// Items are inserted one at a time with their indices, which allows the index to be built in the
+/// same pass that serializes the items.
+#[cfg(feature = "alloc")]
+pub struct HashIndexBuilder {
+    algorithm: HashAlgorithm,
+    seed: u64,
+    groups: ScratchVec<Group>,
+}
+
+#[cfg(feature = "alloc")]
+impl HashIndexBuilder {
+    /// Creates a new builder for a hash index of `len` items, allocating its table from the
+    /// serializer's scratch space.
+    ///
+    /// The hash index is keyed with the serializer's [hash seed](Serializer::hash_seed).
+    ///
+    /// # Safety
+    ///
+    /// The builder must be finished with the same serializer, and any scratch space allocated after
+    /// creating it must be freed before it is finished.
+    #[inline]
+    pub unsafe fn new<S: Serializer + ScratchSpace + ?Sized>(
+        algorithm: HashAlgorithm,
+        len: usize,
+        serializer: &mut S,
+    ) -> Result<Self, S::Error> {
+        let group_count = group_count(len);
+        let mut groups = ScratchVec::new(serializer, group_count)?;
+        for _ in 0..group_count {
+            groups.push(Group::empty());
+        }
+        Ok(Self {
+            algorithm,
+            seed: serializer.hash_seed(),
+            groups,
+        })
+    }
+
+    /// Returns the seed that the hash index is keyed with.
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Inserts a key into the hash index at the given item index.
+    ///
+    /// # Safety
+    ///
+    /// - `index` must be less than the length the builder was created with.
+    /// - No more keys may be inserted than the length the builder was created with.
+    /// - The inserted keys must be unique.
+    #[inline]
+    pub unsafe fn insert<K: Hash + ?Sized>(&mut self, key: &K, index: usize) {
+        self.insert_hashed(self.algorithm.hash(self.seed, key), index);
+    }
+
+    #[inline]
+    unsafe fn insert_hashed(&mut self, hash: u64, index: usize) {
+        let mut probe = ProbeSeq::new(hash, self.groups.len());
+        loop {
+            let group = &mut self.groups[probe.pos()];
+            if let Some(slot) = group.match_empty().lowest() {
+                group.control[slot] = tag(hash);
+                group.indices[slot] = to_archived!(index as FixedUsize);
+                break;
             }
+            probe.next_group();
+        }
+    }
 
-            let mut first_empty = 0;
-            let mut assignments = Vec::with_capacity(8);
-
-            let mut start = 0;
-            while start < displaces.len() {
-                let displace = displaces[start].0;
-                let bucket_size = bucket_size[displace as usize] as usize;
-                let end = start + bucket_size;
-                let bucket = &displaces[start..end];
-                start = end;
-
-                if bucket_size > 1 {
-                    'find_seed: for seed in 0x80_00_00_00u32..=0xFF_FF_FF_FFu32 {
-                        let mut base_hasher = Self::make_hasher();
-                        seed.hash(&mut base_hasher);
-
-                        assignments.clear();
-
-                        for &(_, (key, _)) in bucket.iter() {
-                            let mut hasher = base_hasher;
-                            key.hash(&mut hasher);
-                            let index = (hasher.finish() % len as u64) as u32;
-                            if occupied[index as usize] || assignments.contains(&index) {
-                                continue 'find_seed;
-                            } else {
-                                assignments.push(index);
-                            }
-                        }
+    /// Inserts items with precomputed hashes into the hash index, placing them on multiple threads.
+    ///
+    /// Each item is the hash of a key and its item index. The table is split into fixed-size chunks
+    /// of groups and the items of each chunk are placed in parallel. Items that would probe past the
+    /// end of their chunk are placed afterward on the calling thread. `items` is reordered in the
+    /// process, and the built index does not depend on the number of threads.
+    ///
+    /// # Safety
+    ///
+    /// - The hashes must have been computed with the builder's hash algorithm and seed.
+    /// - The indices must be less than the length the builder was created with.
+    /// - No more items may be inserted than the length the builder was created with.
+    /// - The hashed keys must be unique.
+    #[cfg(feature = "rayon")]
+    pub unsafe fn par_insert_hashed(&mut self, items: &mut [(u64, usize)]) {
+        use rayon::prelude::*;
+
+        let group_count = self.groups.len();
+        if group_count == 0 {
+            return;
+        }
+        let mask = group_count - 1;
+        items.par_sort_unstable_by_key(|&(hash, index)| (hash as usize & mask, index));
+
+        let mut chunks = Vec::with_capacity((group_count - 1) / PAR_CHUNK_GROUPS + 1);
+        let mut groups = &mut self.groups[..];
+        let mut items = items;
+        let mut start = 0;
+        while !groups.is_empty() {
+            let end = start + cmp::min(PAR_CHUNK_GROUPS, groups.len());
+            let (chunk_groups, rest_groups) = groups.split_at_mut(end - start);
+            let split = items.partition_point(|&(hash, _)| (hash as usize & mask) < end);
+            let (chunk_items, rest_items) = items.split_at_mut(split);
+            chunks.push((start, chunk_groups, chunk_items));
+            groups = rest_groups;
+            items = rest_items;
+            start = end;
+        }
 
-                        for i in 0..bucket_size {
-                            occupied[assignments[i] as usize] = true;
-                            entries[assignments[i] as usize]
-                                .as_mut_ptr()
-                                .write(bucket[i].1);
+        // Items that leave their chunk are moved to the front of the chunk's items
+        let deferred = chunks
+            .par_iter_mut()
+            .map(|(start, groups, items)| {
+                let mut deferred = 0;
+                for i in 0..items.len() {
+                    let (hash, index) = items[i];
+                    let mut probe = ProbeSeq::new(hash, group_count);
+                    loop {
+                        let pos = probe.pos().wrapping_sub(*start);
+                        if pos >= groups.len() {
+                            items.swap(deferred, i);
+                            deferred += 1;
+                            break;
+                        }
+                        let group = &mut groups[pos];
+                        if let Some(slot) = group.match_empty().lowest() {
+                            group.control[slot] = tag(hash);
+                            group.indices[slot] = to_archived!(index as FixedUsize);
+                            break;
                         }
-                        displacements[displace as usize] = to_archived!(seed);
-                        break;
+                        probe.next_group();
                     }
-                } else {
-                    let offset = occupied[first_empty..]
-                        .iter()
-                        .position(|value| !value)
-                        .unwrap();
-                    first_empty += offset;
-                    occupied[first_empty] = true;
-                    entries[first_empty].as_mut_ptr().write(bucket[0].1);
-                    displacements[displace as usize] = to_archived!(first_empty as u32);
-                    first_empty += 1;
                 }
-            }
-
-            // Write displacements
-            let displace_pos = serializer.align_for::<Archived<u32>>()?;
-            let displacements_slice = slice::from_raw_parts(
-                displacements.as_ptr().cast::<u8>(),
-                len * size_of::<Archived<u32>>(),
-            );
-            serializer.write(displacements_slice)?;
-
-            // Free scratch vecs
-            displacements.free(serializer)?;
-            occupied.free(serializer)?;
-            displaces.free(serializer)?;
-            bucket_size.free(serializer)?;
-
-            Ok(HashIndexResolver { displace_pos })
+                deferred
+            })
+            .collect::<Vec<_>>();
+
+        let deferred_items = chunks
+            .into_iter()
+            .zip(deferred)
+            .flat_map(|((_, _, items), deferred)| items[..deferred].iter())
+            .copied()
+            .collect::<Vec<_>>();
+        for (hash, index) in deferred_items {
+            self.insert_hashed(hash, index);
         }
     }
-};
+
+    /// Serializes the hash index and frees its scratch space.
+    ///
+    /// # Safety
+    ///
+    /// `serializer` must be the same serializer the builder was created with.
+    #[inline]
+    pub unsafe fn finish<S: Serializer + ScratchSpace + ?Sized>(
+        self,
+        serializer: &mut S,
+    ) -> Result<HashIndexResolver, S::Error> {
+        let groups_pos = serializer.align_for::<Group>()?;
+        let groups_slice = slice::from_raw_parts(
+            self.groups.as_ptr().cast::<u8>(),
+            self.groups.len() * size_of::<Group>(),
+        );
+        serializer.write(groups_slice)?;
+
+        self.groups.free(serializer)?;
+
+        Ok(HashIndexResolver {
+            groups_pos,
+            seed: self.seed,
+            algorithm: self.algorithm,
+        })
+    }
+}
 
 impl fmt::Debug for ArchivedHashIndex {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_list().entries(self.displace_slice()).finish()
+        f.debug_list()
+            .entries(self.groups().iter().flat_map(|group| {
+                group
+                    .control
+                    .iter()
+                    .enumerate()
+                    .map(move |(slot, &control)| {
+                        if control == group::EMPTY {
+                            None
+                        } else {
+                            Some(group.index(slot))
+                        }
+                    })
+            }))
+            .finish()
     }
 }
 
 /// The resolver for an archived hash index.
 pub struct HashIndexResolver {
-    displace_pos: usize,
+    groups_pos: usize,
+    seed: u64,
+    algorithm: HashAlgorithm,
 }
@@ -1,6 +1,18 @@
 //! Validation implementation for ArchivedHashIndex.
 
-use crate::{collections::ArchivedHashIndex, validation::ArchiveContext, Archived, RelPtr};
+use crate::{
+    collections::{
+        hash_index::{
+            group::{checked_group_count, Group, EMPTY, GROUP_WIDTH},
+            HashAlgorithm,
+        },
+        ArchivedHashIndex,
+    },
+    validation::ArchiveContext,
+    Archived, RelPtr,
+};
+#[cfg(not(feature = "std"))]
+use alloc::vec;
 use bytecheck::{CheckBytes, Error, SliceCheckError};
 use core::{
     alloc::{Layout, LayoutError},
@@ -11,14 +23,45 @@ use core::{
 /// Errors that can occur while checking an archived hash index.
 #[derive(Debug)]
 pub enum HashIndexError<C> {
-    /// An error occurred while checking the layouts of displacements or entries
+    /// An error occurred while checking the layouts of groups or entries
     LayoutError(LayoutError),
-    /// A displacement value was invalid
-    InvalidDisplacement {
-        /// The index of the entry with an invalid displacement
-        index: usize,
-        /// The value of the entry at the invalid location
-        value: u32,
+    /// A control byte was invalid
+    InvalidControlByte {
+        /// The index of the slot with an invalid control byte
+        slot: usize,
+        /// The invalid control byte
+        value: u8,
+    },
+    /// An occupied slot had an out-of-bounds entry index
+    InvalidEntryIndex {
+        /// The index of the slot with an invalid entry index
+        slot: usize,
+        /// The invalid entry index
+        value: usize,
+    },
+    /// Two occupied slots had the same entry index
+    DuplicateEntryIndex {
+        /// The index of the second slot with the entry index
+        slot: usize,
+        /// The duplicated entry index
+        value: usize,
+    },
+    /// The length of the hash index was too large to index
+    InvalidLength {
+        /// The invalid length
+        len: usize,
+    },
+    /// The number of occupied slots did not match the length of the hash index
+    InvalidOccupancy {
+        /// The length of the hash index
+        len: usize,
+        /// The number of occupied slots
+        occupied: usize,
+    },
+    /// The hash algorithm tag was invalid
+    InvalidHashAlgorithm {
+        /// The invalid tag value
+        value: u8,
     },
     /// A bounds error occurred
     ContextError(C),
@@ -49,11 +92,26 @@ impl<C: fmt::Display> fmt::Display for HashIndexError<C> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             HashIndexError::LayoutError(e) => write!(f, "layout error: {}", e),
-            HashIndexError::InvalidDisplacement { index, value } => write!(
+            HashIndexError::InvalidControlByte { slot, value } => {
+                write!(f, "invalid control byte: value {} at slot {}", value, slot)
+            }
+            HashIndexError::InvalidEntryIndex { slot, value } => {
+                write!(f, "invalid entry index: value {} at slot {}", value, slot)
+            }
+            HashIndexError::DuplicateEntryIndex { slot, value } => {
+                write!(f, "duplicate entry index: value {} at slot {}", value, slot)
+            }
+            HashIndexError::InvalidLength { len } => {
+                write!(f, "invalid length: {} items is too many to index", len)
+            }
+            HashIndexError::InvalidOccupancy { len, occupied } => write!(
                 f,
-                "invalid displacement: value {} at index {}",
-                value, index,
+                "invalid occupancy: {} occupied slots for {} items",
+                occupied, len,
             ),
+            HashIndexError::InvalidHashAlgorithm { value } => {
+                write!(f, "invalid hash algorithm: {}", value)
+            }
             HashIndexError::ContextError(e) => e.fmt(f),
         }
     }
@@ -67,13 +125,48 @@ const _: () = {
         fn source(&self) -> Option<&(dyn Error + 'static)> {
             match self {
                 HashIndexError::LayoutError(e) => Some(e as &dyn Error),
-                HashIndexError::InvalidDisplacement { .. } => None,
+                HashIndexError::InvalidControlByte { .. } => None,
+                HashIndexError::InvalidEntryIndex { .. } => None,
+                HashIndexError::DuplicateEntryIndex { .. } => None,
+                HashIndexError::InvalidLength { .. } => None,
+                HashIndexError::InvalidOccupancy { .. } => None,
+                HashIndexError::InvalidHashAlgorithm { .. } => None,
                 HashIndexError::ContextError(e) => Some(e as &dyn Error),
             }
         }
     }
 };
 
+impl<C: ?Sized> CheckBytes<C> for Group {
+    type Error = Infallible;
+
+    #[inline]
+    unsafe fn check_bytes<'a>(value: *const Self, _: &mut C) -> Result<&'a Self, Self::Error> {
+        // Groups contain only bytes and integers, so every bit pattern is valid
+        Ok(&*value)
+    }
+}
+
+impl ArchivedHashIndex {
+    /// Checks and returns the length of a hash index without checking the rest of it.
+    ///
+    /// This allows collections to check items that were serialized before their hash index.
+    ///
+    /// # Safety
+    ///
+    /// `value` must be aligned and point to enough bytes to represent an `ArchivedHashIndex`.
+    #[inline]
+    pub(crate) unsafe fn check_len<C: ArchiveContext + ?Sized>(
+        value: *const Self,
+        context: &mut C,
+    ) -> Result<usize, HashIndexError<C::Error>> {
+        Ok(from_archived!(*Archived::<usize>::check_bytes(
+            ptr::addr_of!((*value).len),
+            context,
+        )?) as usize)
+    }
+}
+
 impl<C: ArchiveContext + ?Sized> CheckBytes<C> for ArchivedHashIndex
 where
     C::Error: Error,
@@ -84,36 +177,65 @@ where
         value: *const Self,
         context: &mut C,
     ) -> Result<&'a Self, Self::Error> {
+        let algorithm = *u8::check_bytes(ptr::addr_of!((*value).algorithm).cast(), context)?;
+        if HashAlgorithm::from_u8(algorithm).is_none() {
+            return Err(HashIndexError::InvalidHashAlgorithm { value: algorithm });
+        }
+
         let len = from_archived!(*Archived::<usize>::check_bytes(
             ptr::addr_of!((*value).len),
             context,
         )?) as usize;
-        Layout::array::<Archived<u32>>(len)?;
-
-        let displace_rel_ptr =
-            RelPtr::manual_check_bytes(ptr::addr_of!((*value).displace), context)?;
-        let displace_ptr = context
-            .check_subtree_ptr::<[Archived<u32>]>(
-                displace_rel_ptr.base(),
-                displace_rel_ptr.offset(),
-                len,
+        let group_count = checked_group_count(len).ok_or(HashIndexError::InvalidLength { len })?;
+        Layout::array::<Group>(group_count)?;
+
+        let groups_rel_ptr = RelPtr::manual_check_bytes(ptr::addr_of!((*value).groups), context)?;
+        let groups_ptr = context
+            .check_subtree_ptr::<[Group]>(
+                groups_rel_ptr.base(),
+                groups_rel_ptr.offset(),
+                group_count,
             )
             .map_err(HashIndexError::ContextError)?;
 
         let range = context
-            .push_prefix_subtree(displace_ptr)
+            .push_prefix_subtree(groups_ptr)
             .map_err(HashIndexError::ContextError)?;
-        let displace = <[Archived<u32>]>::check_bytes(displace_ptr, context)?;
+        let groups = <[Group]>::check_bytes(groups_ptr, context)?;
         context
             .pop_prefix_range(range)
             .map_err(HashIndexError::ContextError)?;
 
-        for (i, &d) in displace.iter().enumerate() {
-            let d = from_archived!(d);
-            if d as usize >= len && d < 0x80_00_00_00 {
-                return Err(HashIndexError::InvalidDisplacement { index: i, value: d });
+        // Each entry index may only be used by one slot, so seen indices are tracked in a bitset
+        let mut seen = vec![0u64; len / 64 + 1];
+        let mut occupied = 0;
+        for (i, group) in groups.iter().enumerate() {
+            for (j, &control) in group.control.iter().enumerate() {
+                let slot = i * GROUP_WIDTH + j;
+                if control == EMPTY {
+                    continue;
+                } else if control & 0x80 != 0 {
+                    return Err(HashIndexError::InvalidControlByte {
+                        slot,
+                        value: control,
+                    });
+                }
+
+                let index = group.index(j);
+                if index >= len {
+                    return Err(HashIndexError::InvalidEntryIndex { slot, value: index });
+                }
+                let (word, bit) = (index / 64, 1 << (index % 64));
+                if seen[word] & bit != 0 {
+                    return Err(HashIndexError::DuplicateEntryIndex { slot, value: index });
+                }
+                seen[word] |= bit;
+                occupied += 1;
             }
         }
+        if occupied != len {
+            return Err(HashIndexError::InvalidOccupancy { len, occupied });
+        }
 
         Ok(&*value)
     }
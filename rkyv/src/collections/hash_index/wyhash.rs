@@ -0,0 +1,242 @@
+//! A streaming implementation of [wyhash](https://github.com/wangyi-fudan/wyhash) final version 4.
+
+use core::{convert::TryInto, hash::Hasher};
+
+const SECRET: [u64; 4] = [
+    0x2d358dccaa6c78a5,
+    0x8bb84b93962eacc9,
+    0x4b33a62ed433d4a3,
+    0x4d5a2da51de1aa47,
+];
+
+const BLOCK_LEN: usize = 48;
+
+#[inline]
+const fn mum(a: u64, b: u64) -> (u64, u64) {
+    let r = a as u128 * b as u128;
+    (r as u64, (r >> 64) as u64)
+}
+
+#[inline]
+const fn mix(a: u64, b: u64) -> u64 {
+    let (a, b) = mum(a, b);
+    a ^ b
+}
+
+#[inline]
+fn read8(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes[..8].try_into().unwrap())
+}
+
+#[inline]
+fn read4(bytes: &[u8]) -> u64 {
+    u32::from_le_bytes(bytes[..4].try_into().unwrap()) as u64
+}
+
+#[inline]
+fn read3(bytes: &[u8]) -> u64 {
+    let len = bytes.len();
+    (bytes[0] as u64) << 16 | (bytes[len >> 1] as u64) << 8 | bytes[len - 1] as u64
+}
+
+/// A streaming wyhash hasher.
+///
+/// Written bytes are buffered and hashed as a single input, so the finished hash is the same as
+/// the wyhash of all of the written bytes with the hasher's seed and the default secret. Integers
+/// are always hashed in little-endian order with `usize` and `isize` widened to 64 bits, so hashes
+/// are the same on every platform.
+#[derive(Clone, Copy, Debug)]
+pub struct WyHasher {
+    seed: u64,
+    see1: u64,
+    see2: u64,
+    len: u64,
+    buffer: [u8; BLOCK_LEN],
+    buffered: usize,
+    // The end of the last consumed block, which is read again if fewer than 16 bytes follow it
+    last: [u8; 16],
+}
+
+impl WyHasher {
+    /// Creates a new `WyHasher` with the given seed.
+    #[inline]
+    pub const fn with_seed(seed: u64) -> Self {
+        let seed = seed ^ mix(seed ^ SECRET[0], SECRET[1]);
+        Self {
+            seed,
+            see1: seed,
+            see2: seed,
+            len: 0,
+            buffer: [0; BLOCK_LEN],
+            buffered: 0,
+            last: [0; 16],
+        }
+    }
+
+    #[inline]
+    fn consume(&mut self, block: &[u8]) {
+        self.seed = mix(read8(block) ^ SECRET[1], read8(&block[8..]) ^ self.seed);
+        self.see1 = mix(
+            read8(&block[16..]) ^ SECRET[2],
+            read8(&block[24..]) ^ self.see1,
+        );
+        self.see2 = mix(
+            read8(&block[32..]) ^ SECRET[3],
+            read8(&block[40..]) ^ self.see2,
+        );
+        self.last.copy_from_slice(&block[BLOCK_LEN - 16..]);
+    }
+}
+
+impl Hasher for WyHasher {
+    fn finish(&self) -> u64 {
+        let bytes = &self.buffer[..self.buffered];
+        let mut seed = self.seed;
+
+        let (a, b) = if self.len <= 16 {
+            let len = bytes.len();
+            if len >= 4 {
+                let offset = (len >> 3) << 2;
+                (
+                    read4(bytes) << 32 | read4(&bytes[offset..]),
+                    read4(&bytes[len - 4..]) << 32 | read4(&bytes[len - 4 - offset..]),
+                )
+            } else if len > 0 {
+                (read3(bytes), 0)
+            } else {
+                (0, 0)
+            }
+        } else {
+            if self.len > BLOCK_LEN as u64 {
+                seed ^= self.see1 ^ self.see2;
+            }
+            let mut rest = bytes;
+            while rest.len() > 16 {
+                seed = mix(read8(rest) ^ SECRET[1], read8(&rest[8..]) ^ seed);
+                rest = &rest[16..];
+            }
+
+            let mut tail = [0u8; 16];
+            if bytes.len() >= 16 {
+                tail.copy_from_slice(&bytes[bytes.len() - 16..]);
+            } else {
+                let carried = 16 - bytes.len();
+                tail[..carried].copy_from_slice(&self.last[16 - carried..]);
+                tail[carried..].copy_from_slice(bytes);
+            }
+            (read8(&tail), read8(&tail[8..]))
+        };
+
+        let (a, b) = mum(a ^ SECRET[1], b ^ seed);
+        mix(a ^ SECRET[0] ^ self.len, b ^ SECRET[1])
+    }
+
+    fn write(&mut self, mut bytes: &[u8]) {
+        self.len += bytes.len() as u64;
+        if self.buffered + bytes.len() <= BLOCK_LEN {
+            self.buffer[self.buffered..self.buffered + bytes.len()].copy_from_slice(bytes);
+            self.buffered += bytes.len();
+            return;
+        }
+
+        // Blocks are only consumed once more bytes follow them, since the end of the input is
+        // mixed differently
+        if self.buffered > 0 {
+            let fill = BLOCK_LEN - self.buffered;
+            self.buffer[self.buffered..].copy_from_slice(&bytes[..fill]);
+            bytes = &bytes[fill..];
+            let block = self.buffer;
+            self.consume(&block);
+        }
+        while bytes.len() > BLOCK_LEN {
+            self.consume(&bytes[..BLOCK_LEN]);
+            bytes = &bytes[BLOCK_LEN..];
+        }
+        self.buffer[..bytes.len()].copy_from_slice(bytes);
+        self.buffered = bytes.len();
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.write(&[i]);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WyHasher;
+    use core::hash::Hasher;
+
+    // The test vectors of the reference implementation
+    const VECTORS: [(&str, u64, u64); 7] = [
+        ("", 0, 0x93228a4de0eec5a2),
+        ("a", 1, 0xc5bac3db178713c4),
+        ("abc", 2, 0xa97f2f7b1d9b3314),
+        ("message digest", 3, 0x786d1f1df3801df4),
+        ("abcdefghijklmnopqrstuvwxyz", 4, 0xdca5a8138ad37c87),
+        (
+            "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+            5,
+            0xb9e734f117cfaf70,
+        ),
+        (
+            "12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+            6,
+            0x6cc5eab49a92d617,
+        ),
+    ];
+
+    #[test]
+    fn reference_vectors() {
+        for &(input, seed, expected) in VECTORS.iter() {
+            let mut hasher = WyHasher::with_seed(seed);
+            hasher.write(input.as_bytes());
+            assert_eq!(hasher.finish(), expected, "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn split_writes() {
+        let mut bytes = [0u8; 300];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = (i * 7 + 3) as u8;
+        }
+        for &len in [0, 3, 16, 17, 48, 49, 64, 96, 97, 300].iter() {
+            let mut hasher = WyHasher::with_seed(42);
+            hasher.write(&bytes[..len]);
+            let expected = hasher.finish();
+            for &chunk in [1, 5, 16, 47, 48, 49].iter() {
+                let mut hasher = WyHasher::with_seed(42);
+                for bytes in bytes[..len].chunks(chunk) {
+                    hasher.write(bytes);
+                }
+                assert_eq!(hasher.finish(), expected, "len {} chunk {}", len, chunk);
+            }
+        }
+    }
+}
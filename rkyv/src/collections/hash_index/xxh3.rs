@@ -0,0 +1,417 @@
+//! A streaming implementation of the 64-bit variant of [XXH3](https://github.com/Cyan4973/xxHash).
+
+use core::{convert::TryInto, hash::Hasher};
+
+const SECRET: [u8; 192] = [
+    0xb8, 0xfe, 0x6c, 0x39, 0x23, 0xa4, 0x4b, 0xbe, 0x7c, 0x01, 0x81, 0x2c, 0xf7, 0x21, 0xad, 0x1c,
+    0xde, 0xd4, 0x6d, 0xe9, 0x83, 0x90, 0x97, 0xdb, 0x72, 0x40, 0xa4, 0xa4, 0xb7, 0xb3, 0x67, 0x1f,
+    0xcb, 0x79, 0xe6, 0x4e, 0xcc, 0xc0, 0xe5, 0x78, 0x82, 0x5a, 0xd0, 0x7d, 0xcc, 0xff, 0x72, 0x21,
+    0xb8, 0x08, 0x46, 0x74, 0xf7, 0x43, 0x24, 0x8e, 0xe0, 0x35, 0x90, 0xe6, 0x81, 0x3a, 0x26, 0x4c,
+    0x3c, 0x28, 0x52, 0xbb, 0x91, 0xc3, 0x00, 0xcb, 0x88, 0xd0, 0x65, 0x8b, 0x1b, 0x53, 0x2e, 0xa3,
+    0x71, 0x64, 0x48, 0x97, 0xa2, 0x0d, 0xf9, 0x4e, 0x38, 0x19, 0xef, 0x46, 0xa9, 0xde, 0xac, 0xd8,
+    0xa8, 0xfa, 0x76, 0x3f, 0xe3, 0x9c, 0x34, 0x3f, 0xf9, 0xdc, 0xbb, 0xc7, 0xc7, 0x0b, 0x4f, 0x1d,
+    0x8a, 0x51, 0xe0, 0x4b, 0xcd, 0xb4, 0x59, 0x31, 0xc8, 0x9f, 0x7e, 0xc9, 0xd9, 0x78, 0x73, 0x64,
+    0xea, 0xc5, 0xac, 0x83, 0x34, 0xd3, 0xeb, 0xc3, 0xc5, 0x81, 0xa0, 0xff, 0xfa, 0x13, 0x63, 0xeb,
+    0x17, 0x0d, 0xdd, 0x51, 0xb7, 0xf0, 0xda, 0x49, 0xd3, 0x16, 0x55, 0x26, 0x29, 0xd4, 0x68, 0x9e,
+    0x2b, 0x16, 0xbe, 0x58, 0x7d, 0x47, 0xa1, 0xfc, 0x8f, 0xf8, 0xb8, 0xd1, 0x7a, 0xd0, 0x31, 0xce,
+    0x45, 0xcb, 0x3a, 0x8f, 0x95, 0x16, 0x04, 0x28, 0xaf, 0xd7, 0xfb, 0xca, 0xbb, 0x4b, 0x40, 0x7e,
+];
+
+const PRIME32_1: u64 = 0x9e3779b1;
+const PRIME32_2: u64 = 0x85ebca77;
+const PRIME32_3: u64 = 0xc2b2ae3d;
+const PRIME64_1: u64 = 0x9e3779b185ebca87;
+const PRIME64_2: u64 = 0xc2b2ae3d27d4eb4f;
+const PRIME64_3: u64 = 0x165667b19e3779f9;
+const PRIME64_4: u64 = 0x85ebca77c2b2ae63;
+const PRIME64_5: u64 = 0x27d4eb2f165667c5;
+const PRIME_MX1: u64 = 0x165667919e3779f9;
+const PRIME_MX2: u64 = 0x9fb21c651e98df25;
+
+const INITIAL_ACC: [u64; 8] = [
+    PRIME32_3, PRIME64_1, PRIME64_2, PRIME64_3, PRIME64_4, PRIME32_2, PRIME64_5, PRIME32_1,
+];
+
+const STRIPE_LEN: usize = 64;
+const SECRET_CONSUME_RATE: usize = 8;
+const STRIPES_PER_BLOCK: usize = (SECRET.len() - STRIPE_LEN) / SECRET_CONSUME_RATE;
+const BUFFER_LEN: usize = 256;
+const BUFFER_STRIPES: usize = BUFFER_LEN / STRIPE_LEN;
+const MIDSIZE_MAX: u64 = 240;
+const LAST_STRIPE_OFFSET: usize = SECRET.len() - STRIPE_LEN - 7;
+const MERGE_OFFSET: usize = 11;
+const MIDSIZE_START_OFFSET: usize = 3;
+const MIDSIZE_LAST_OFFSET: usize = 136 - 17;
+
+#[inline]
+fn read8(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes[..8].try_into().unwrap())
+}
+
+#[inline]
+fn read4(bytes: &[u8]) -> u64 {
+    u32::from_le_bytes(bytes[..4].try_into().unwrap()) as u64
+}
+
+#[inline]
+fn mul_fold(a: u64, b: u64) -> u64 {
+    let r = a as u128 * b as u128;
+    r as u64 ^ (r >> 64) as u64
+}
+
+#[inline]
+fn xxh64_avalanche(mut h: u64) -> u64 {
+    h ^= h >> 33;
+    h = h.wrapping_mul(PRIME64_2);
+    h ^= h >> 29;
+    h = h.wrapping_mul(PRIME64_3);
+    h ^ (h >> 32)
+}
+
+#[inline]
+fn avalanche(mut h: u64) -> u64 {
+    h ^= h >> 37;
+    h = h.wrapping_mul(PRIME_MX1);
+    h ^ (h >> 32)
+}
+
+#[inline]
+fn rrmxmx(mut h: u64, len: u64) -> u64 {
+    h ^= h.rotate_left(49) ^ h.rotate_left(24);
+    h = h.wrapping_mul(PRIME_MX2);
+    h ^= (h >> 35).wrapping_add(len);
+    h = h.wrapping_mul(PRIME_MX2);
+    h ^ (h >> 28)
+}
+
+#[inline]
+fn mix16(bytes: &[u8], secret: &[u8], seed: u64) -> u64 {
+    mul_fold(
+        read8(bytes) ^ read8(secret).wrapping_add(seed),
+        read8(&bytes[8..]) ^ read8(&secret[8..]).wrapping_sub(seed),
+    )
+}
+
+#[inline]
+fn accumulate_stripe(acc: &mut [u64; 8], stripe: &[u8], secret: &[u8]) {
+    for i in 0..acc.len() {
+        let value = read8(&stripe[8 * i..]);
+        let key = value ^ read8(&secret[8 * i..]);
+        acc[i ^ 1] = acc[i ^ 1].wrapping_add(value);
+        acc[i] = acc[i].wrapping_add((key & 0xffffffff).wrapping_mul(key >> 32));
+    }
+}
+
+#[inline]
+fn scramble(acc: &mut [u64; 8], secret: &[u8]) {
+    for (i, acc) in acc.iter_mut().enumerate() {
+        *acc = (*acc ^ (*acc >> 47) ^ read8(&secret[8 * i..])).wrapping_mul(PRIME32_1);
+    }
+}
+
+/// Derives the secret used for inputs longer than 240 bytes from a seed.
+fn derive_secret(seed: u64) -> [u8; SECRET.len()] {
+    let mut secret = [0u8; SECRET.len()];
+    for i in (0..SECRET.len()).step_by(16) {
+        let lo = read8(&SECRET[i..]).wrapping_add(seed);
+        let hi = read8(&SECRET[i + 8..]).wrapping_sub(seed);
+        secret[i..i + 8].copy_from_slice(&lo.to_le_bytes());
+        secret[i + 8..i + 16].copy_from_slice(&hi.to_le_bytes());
+    }
+    secret
+}
+
+/// Accumulates `count` stripes of `bytes`, scrambling the accumulators after each full block.
+#[inline]
+fn consume_stripes(
+    acc: &mut [u64; 8],
+    stripes: &mut usize,
+    bytes: &[u8],
+    count: usize,
+    secret: &[u8],
+) {
+    for stripe in bytes.chunks_exact(STRIPE_LEN).take(count) {
+        accumulate_stripe(acc, stripe, &secret[*stripes * SECRET_CONSUME_RATE..]);
+        *stripes += 1;
+        if *stripes == STRIPES_PER_BLOCK {
+            scramble(acc, &secret[SECRET.len() - STRIPE_LEN..]);
+            *stripes = 0;
+        }
+    }
+}
+
+#[inline]
+fn merge(acc: &[u64; 8], secret: &[u8], len: u64) -> u64 {
+    let mut result = len.wrapping_mul(PRIME64_1);
+    for i in 0..4 {
+        let secret = &secret[MERGE_OFFSET + 16 * i..];
+        result = result.wrapping_add(mul_fold(
+            acc[2 * i] ^ read8(secret),
+            acc[2 * i + 1] ^ read8(&secret[8..]),
+        ));
+    }
+    avalanche(result)
+}
+
+/// Hashes `bytes`, which must be at most 240 bytes long, with the given seed.
+fn hash_short(bytes: &[u8], seed: u64) -> u64 {
+    let len = bytes.len();
+
+    if len == 0 {
+        xxh64_avalanche(seed ^ read8(&SECRET[56..]) ^ read8(&SECRET[64..]))
+    } else if len <= 3 {
+        let combined = (bytes[0] as u64) << 16
+            | (bytes[len >> 1] as u64) << 24
+            | bytes[len - 1] as u64
+            | (len as u64) << 8;
+        let bitflip = (read4(&SECRET) ^ read4(&SECRET[4..])).wrapping_add(seed);
+        xxh64_avalanche(combined ^ bitflip)
+    } else if len <= 8 {
+        let seed = seed ^ ((seed as u32).swap_bytes() as u64) << 32;
+        let bitflip = (read8(&SECRET[8..]) ^ read8(&SECRET[16..])).wrapping_sub(seed);
+        let input = read4(&bytes[len - 4..]).wrapping_add(read4(bytes) << 32);
+        rrmxmx(input ^ bitflip, len as u64)
+    } else if len <= 16 {
+        let bitflip_lo = (read8(&SECRET[24..]) ^ read8(&SECRET[32..])).wrapping_add(seed);
+        let bitflip_hi = (read8(&SECRET[40..]) ^ read8(&SECRET[48..])).wrapping_sub(seed);
+        let lo = read8(bytes) ^ bitflip_lo;
+        let hi = read8(&bytes[len - 8..]) ^ bitflip_hi;
+        avalanche(
+            (len as u64)
+                .wrapping_add(lo.swap_bytes())
+                .wrapping_add(hi)
+                .wrapping_add(mul_fold(lo, hi)),
+        )
+    } else if len <= 128 {
+        let mut acc = (len as u64).wrapping_mul(PRIME64_1);
+        // Mix pairs of 16-byte chunks working inward from both ends
+        for i in 0..(len - 1) / 32 + 1 {
+            acc = acc
+                .wrapping_add(mix16(&bytes[16 * i..], &SECRET[32 * i..], seed))
+                .wrapping_add(mix16(
+                    &bytes[len - 16 * (i + 1)..],
+                    &SECRET[32 * i + 16..],
+                    seed,
+                ));
+        }
+        avalanche(acc)
+    } else {
+        let mut acc = (len as u64).wrapping_mul(PRIME64_1);
+        for i in 0..8 {
+            acc = acc.wrapping_add(mix16(&bytes[16 * i..], &SECRET[16 * i..], seed));
+        }
+        acc = avalanche(acc);
+        let mut acc_end = mix16(&bytes[len - 16..], &SECRET[MIDSIZE_LAST_OFFSET..], seed);
+        for i in 8..len / 16 {
+            acc_end = acc_end.wrapping_add(mix16(
+                &bytes[16 * i..],
+                &SECRET[16 * (i - 8) + MIDSIZE_START_OFFSET..],
+                seed,
+            ));
+        }
+        avalanche(acc.wrapping_add(acc_end))
+    }
+}
+
+/// A streaming XXH3 hasher.
+///
+/// Written bytes are buffered and hashed as a single input, so the finished hash is the same as
+/// the 64-bit XXH3 hash of all of the written bytes with the hasher's seed. Integers are always
+/// hashed in little-endian order with `usize` and `isize` widened to 64 bits, so hashes are the
+/// same on every platform.
+#[derive(Clone, Copy, Debug)]
+pub struct Xxh3Hasher {
+    seed: u64,
+    len: u64,
+    acc: [u64; 8],
+    stripes: usize,
+    secret: [u8; SECRET.len()],
+    buffer: [u8; BUFFER_LEN],
+    buffered: usize,
+}
+
+impl Xxh3Hasher {
+    /// Creates a new `Xxh3Hasher` with the given seed.
+    #[inline]
+    pub const fn with_seed(seed: u64) -> Self {
+        Self {
+            seed,
+            len: 0,
+            acc: INITIAL_ACC,
+            stripes: 0,
+            // The secret is only needed for long inputs, so it's derived once the buffer fills
+            secret: [0; SECRET.len()],
+            buffer: [0; BUFFER_LEN],
+            buffered: 0,
+        }
+    }
+}
+
+impl Hasher for Xxh3Hasher {
+    fn finish(&self) -> u64 {
+        if self.len <= MIDSIZE_MAX {
+            return hash_short(&self.buffer[..self.buffered], self.seed);
+        }
+
+        let derived;
+        let secret = if self.len > BUFFER_LEN as u64 {
+            &self.secret
+        } else {
+            derived = derive_secret(self.seed);
+            &derived
+        };
+
+        let mut acc = self.acc;
+        let mut stripes = self.stripes;
+        let mut last = [0u8; STRIPE_LEN];
+        if self.buffered >= STRIPE_LEN {
+            let count = (self.buffered - 1) / STRIPE_LEN;
+            consume_stripes(&mut acc, &mut stripes, &self.buffer, count, secret);
+            last.copy_from_slice(&self.buffer[self.buffered - STRIPE_LEN..self.buffered]);
+        } else {
+            // The rest of the last stripe is the end of the previously consumed bytes
+            let carried = STRIPE_LEN - self.buffered;
+            last[..carried].copy_from_slice(&self.buffer[BUFFER_LEN - carried..]);
+            last[carried..].copy_from_slice(&self.buffer[..self.buffered]);
+        }
+        accumulate_stripe(&mut acc, &last, &secret[LAST_STRIPE_OFFSET..]);
+        merge(&acc, secret, self.len)
+    }
+
+    fn write(&mut self, mut bytes: &[u8]) {
+        if self.buffered + bytes.len() <= BUFFER_LEN {
+            self.buffer[self.buffered..self.buffered + bytes.len()].copy_from_slice(bytes);
+            self.buffered += bytes.len();
+            self.len += bytes.len() as u64;
+            return;
+        }
+
+        if self.len <= BUFFER_LEN as u64 {
+            self.secret = derive_secret(self.seed);
+        }
+        self.len += bytes.len() as u64;
+
+        // Stripes are only consumed once more bytes follow them, since the last stripe is
+        // accumulated differently
+        if self.buffered > 0 {
+            let fill = BUFFER_LEN - self.buffered;
+            self.buffer[self.buffered..].copy_from_slice(&bytes[..fill]);
+            bytes = &bytes[fill..];
+            consume_stripes(
+                &mut self.acc,
+                &mut self.stripes,
+                &self.buffer,
+                BUFFER_STRIPES,
+                &self.secret,
+            );
+            self.buffered = 0;
+        }
+        if bytes.len() > BUFFER_LEN {
+            let count = (bytes.len() - 1) / STRIPE_LEN;
+            consume_stripes(&mut self.acc, &mut self.stripes, bytes, count, &self.secret);
+            let consumed = count * STRIPE_LEN;
+            self.buffer[BUFFER_LEN - STRIPE_LEN..]
+                .copy_from_slice(&bytes[consumed - STRIPE_LEN..consumed]);
+            bytes = &bytes[consumed..];
+        }
+        self.buffer[..bytes.len()].copy_from_slice(bytes);
+        self.buffered = bytes.len();
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.write(&[i]);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Xxh3Hasher;
+    use core::hash::Hasher;
+
+    /// Fills a buffer with the same bytes as the xxHash sanity checks.
+    fn sanity_buffer() -> [u8; 2048] {
+        let mut buffer = [0u8; 2048];
+        let mut byte_gen = 2654435761u64;
+        for byte in buffer.iter_mut() {
+            *byte = (byte_gen >> 56) as u8;
+            byte_gen = byte_gen.wrapping_mul(11400714785074694797);
+        }
+        buffer
+    }
+
+    // Hashes of the sanity buffer from the reference implementation
+    const VECTORS: [(usize, u64, u64); 22] = [
+        (0, 0, 0x2d06800538d394c2),
+        (0, 2654435761, 0xf702ca3814de2125),
+        (1, 0, 0xc44bdff4074eecdb),
+        (1, 2654435761, 0xb53d5557e7f76f8d),
+        (6, 0, 0x27b56a84cd2d7325),
+        (6, 2654435761, 0xfd7c7af0cde06034),
+        (12, 0, 0xa713daf0dfbb77e7),
+        (12, 2654435761, 0x8922341eca861f0e),
+        (24, 0, 0xa3fe70bf9d3510eb),
+        (24, 2654435761, 0x84a011695f3489d5),
+        (48, 0, 0x397da259ecba1f11),
+        (48, 2654435761, 0x11f47041a93c3ebc),
+        (80, 0, 0xbcdefbbb2c47c90a),
+        (80, 2654435761, 0x5a5dfdf2afc5bf47),
+        (195, 0, 0xcd94217ee362ec3a),
+        (195, 2654435761, 0x55efefea17d17c4a),
+        (403, 0, 0xcdeb804d65c6dea4),
+        (403, 2654435761, 0x1fef87bd75dbe404),
+        (512, 0, 0x617e49599013cb6b),
+        (512, 2654435761, 0x545f610e9f5a78ec),
+        (2048, 0, 0xdd59e2c3a5f038e0),
+        (2048, 2654435761, 0x230d43f30206260b),
+    ];
+
+    #[test]
+    fn reference_vectors() {
+        let buffer = sanity_buffer();
+        for &(len, seed, expected) in VECTORS.iter() {
+            let mut hasher = Xxh3Hasher::with_seed(seed);
+            hasher.write(&buffer[..len]);
+            assert_eq!(hasher.finish(), expected, "len {} seed {}", len, seed);
+        }
+    }
+
+    #[test]
+    fn split_writes() {
+        let buffer = sanity_buffer();
+        for &(len, seed, expected) in VECTORS.iter() {
+            for &chunk in [1, 7, 64, 100, 256, 257].iter() {
+                let mut hasher = Xxh3Hasher::with_seed(seed);
+                for bytes in buffer[..len].chunks(chunk) {
+                    hasher.write(bytes);
+                }
+                assert_eq!(hasher.finish(), expected, "len {} chunk {}", len, chunk);
+            }
+        }
+    }
+}
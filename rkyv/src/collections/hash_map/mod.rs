@@ -1,14 +1,14 @@
 //! Archived hash map implementation.
 //!
-//! During archiving, hashmaps are built into minimal perfect hashmaps using
-//! [compress, hash and displace](http://cmph.sourceforge.net/papers/esa09.pdf).
+//! During archiving, hashmaps are built into open-addressed tables with groups of control bytes,
+//! similar to [hashbrown](https://docs.rs/hashbrown)'s Swiss tables.
 
 #[cfg(feature = "validation")]
 pub mod validation;
 
 use crate::{
     collections::{
-        hash_index::{ArchivedHashIndex, HashIndexResolver},
+        hash_index::{ArchivedHashIndex, HashBuilder, HashIndexResolver},
         util::Entry,
     },
     RelPtr,
@@ -29,6 +29,12 @@ pub struct ArchivedHashMap<K, V> {
     entries: RelPtr<Entry<K, V>>,
 }
 
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(ArchivedHashMap<u32, u32> {
+    index: ArchivedHashIndex,
+    entries: RelPtr<Entry<u32, u32>>,
+});
+
 impl<K, V> ArchivedHashMap<K, V> {
     /// Gets the number of items in the hash map.
     #[inline]
@@ -36,10 +42,9 @@ impl<K, V> ArchivedHashMap<K, V> {
         self.index.len()
     }
 
-    /// Gets the hasher for this hashmap. The hasher for all archived hashmaps is the same for
-    /// reproducibility.
+    /// Gets the hasher for this hashmap.
     #[inline]
-    pub fn hasher(&self) -> seahash::SeaHasher {
+    pub fn hasher(&self) -> HashBuilder {
         self.index.hasher()
     }
 
@@ -59,14 +64,8 @@ impl<K, V> ArchivedHashMap<K, V> {
         K: Borrow<Q>,
         Q: Hash + Eq,
     {
-        self.index.index(k).and_then(|i| {
-            let entry = unsafe { self.entry(i) };
-            if entry.key.borrow() == k {
-                Some(i)
-            } else {
-                None
-            }
-        })
+        self.index
+            .find(k, |i| unsafe { self.entry(i).key.borrow() == k })
     }
 
     /// Finds the key-value entry for a key.
@@ -241,24 +240,49 @@ const _: () = {
             entries.set_len(len);
             let index_resolver =
                 ArchivedHashIndex::build_and_serialize(iter, serializer, &mut entries)?;
-            let mut entries = entries.assume_init();
+            let entries = entries.assume_init();
 
-            // Serialize entries
-            let mut resolvers = ScratchVec::new(serializer, len)?;
-            for (key, value) in entries.iter() {
-                resolvers.push((key.serialize(serializer)?, value.serialize(serializer)?));
-            }
+            let entries_pos = serializer.serialize_iter::<Entry<&KU, &VU>, _, _>(
+                entries.iter().map(|&(key, value)| Entry { key, value }),
+            )?;
+            entries.free(serializer)?;
 
-            let entries_pos = serializer.align_for::<Entry<K, V>>()?;
-            for ((key, value), (key_resolver, value_resolver)) in
-                entries.drain(..).zip(resolvers.drain(..))
-            {
-                serializer
-                    .resolve_aligned(&Entry { key, value }, (key_resolver, value_resolver))?;
-            }
+            Ok(HashMapResolver {
+                index_resolver,
+                entries_pos,
+            })
+        }
+
+        /// Serializes an iterator of key-value pairs as a hash map, building its hash index on
+        /// multiple threads.
+        ///
+        /// # Safety
+        ///
+        /// The keys returned by the iterator must be unique.
+        #[cfg(feature = "rayon")]
+        pub unsafe fn par_serialize_from_iter<'a, KU, VU, S, I>(
+            iter: I,
+            serializer: &mut S,
+        ) -> Result<HashMapResolver, S::Error>
+        where
+            KU: 'a + Serialize<S, Archived = K> + Hash + Eq + Sync,
+            VU: 'a + Serialize<S, Archived = V> + Sync,
+            S: Serializer + ScratchSpace + ?Sized,
+            I: ExactSizeIterator<Item = (&'a KU, &'a VU)>,
+        {
+            use crate::ScratchVec;
+
+            let len = iter.len();
+
+            let mut entries = ScratchVec::new(serializer, len)?;
+            entries.set_len(len);
+            let index_resolver =
+                ArchivedHashIndex::par_build_and_serialize(iter, serializer, &mut entries)?;
+            let entries = entries.assume_init();
 
-            // Free scratch vecs
-            resolvers.free(serializer)?;
+            let entries_pos = serializer.serialize_iter::<Entry<&KU, &VU>, _, _>(
+                entries.iter().map(|&(key, value)| Entry { key, value }),
+            )?;
             entries.free(serializer)?;
 
             Ok(HashMapResolver {
@@ -141,7 +141,7 @@ where
             .map_err(HashMapError::ContextError)?;
 
         for (i, entry) in entries.iter().enumerate() {
-            if index.index(&entry.key) != Some(i) {
+            if index.find(&entry.key, |j| entries[j].key == entry.key) != Some(i) {
                 return Err(HashMapError::InvalidKeyPosition { index: i });
             }
         }
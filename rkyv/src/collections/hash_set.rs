@@ -1,11 +1,12 @@
 //! Archived hash set implementation.
 //!
-//! During archiving, hashsets are built into minimal perfect hashsets using
-//! [compress, hash and displace](http://cmph.sourceforge.net/papers/esa09.pdf).
+//! During archiving, hashsets are built into open-addressed tables with groups of control bytes,
+//! similar to [hashbrown](https://docs.rs/hashbrown)'s Swiss tables.
 
 use crate::collections::hash_map::{ArchivedHashMap, HashMapResolver, Keys};
 #[cfg(feature = "alloc")]
 use crate::{
+    collections::hash_index::HashBuilder,
     ser::{ScratchSpace, Serializer},
     Serialize,
 };
@@ -47,7 +48,7 @@ impl<K> ArchivedHashSet<K> {
     /// Gets the hasher for the underlying hash map.
     #[cfg(feature = "alloc")]
     #[inline]
-    pub fn hasher(&self) -> seahash::SeaHasher {
+    pub fn hasher(&self) -> HashBuilder {
         self.0.hasher()
     }
 
@@ -102,6 +103,28 @@ impl<K> ArchivedHashSet<K> {
             serializer,
         )?))
     }
+
+    /// Serializes an iterator of keys as a hash set, building its hash index on multiple threads.
+    ///
+    /// # Safety
+    ///
+    /// The keys returned by the iterator must be unique.
+    #[cfg(all(feature = "alloc", feature = "rayon"))]
+    #[inline]
+    pub unsafe fn par_serialize_from_iter<'a, KU, S, I>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<HashSetResolver, S::Error>
+    where
+        KU: 'a + Serialize<S, Archived = K> + Hash + Eq + Sync,
+        S: Serializer + ScratchSpace + ?Sized,
+        I: ExactSizeIterator<Item = &'a KU>,
+    {
+        Ok(HashSetResolver(ArchivedHashMap::par_serialize_from_iter(
+            iter.map(|x| (x, &())),
+            serializer,
+        )?))
+    }
 }
 
 impl<K: fmt::Debug> fmt::Debug for ArchivedHashSet<K> {
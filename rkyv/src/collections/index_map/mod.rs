@@ -1,34 +1,40 @@
 //! Archived index map implementation.
 //!
-//! During archiving, hashmaps are built into minimal perfect hashmaps using
-//! [compress, hash and displace](http://cmph.sourceforge.net/papers/esa09.pdf).
+//! During archiving, index maps are built into open-addressed tables with groups of control bytes,
+//! similar to [hashbrown](https://docs.rs/hashbrown)'s Swiss tables. The entries are stored in
+//! order, and the index points directly to them.
 
+mod slice;
 #[cfg(feature = "validation")]
 pub mod validation;
 
+pub use self::slice::ArchivedIndexMapSlice;
+
 use crate::{
     collections::{
         hash_index::{ArchivedHashIndex, HashBuilder, HashIndexResolver},
         util::Entry,
     },
-    out_field, Archived, RelPtr,
+    out_field, RelPtr,
+};
+use core::{
+    borrow::Borrow, fmt, hash::Hash, iter::FusedIterator, marker::PhantomData, ops::RangeBounds,
 };
-use core::{borrow::Borrow, fmt, hash::Hash, iter::FusedIterator, marker::PhantomData};
 
 /// An archived `IndexMap`.
 #[cfg_attr(feature = "strict", repr(C))]
 pub struct ArchivedIndexMap<K, V> {
     index: ArchivedHashIndex,
-    pivots: RelPtr<Archived<usize>>,
     entries: RelPtr<Entry<K, V>>,
 }
 
-impl<K, V> ArchivedIndexMap<K, V> {
-    #[inline]
-    unsafe fn pivot(&self, index: usize) -> usize {
-        from_archived!(*self.pivots.as_ptr().add(index)) as usize
-    }
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(ArchivedIndexMap<u32, u32> {
+    index: ArchivedHashIndex,
+    entries: RelPtr<Entry<u32, u32>>,
+});
 
+impl<K, V> ArchivedIndexMap<K, V> {
     #[inline]
     unsafe fn entry(&self, index: usize) -> &Entry<K, V> {
         &*self.entries.as_ptr().add(index)
@@ -40,15 +46,15 @@ impl<K, V> ArchivedIndexMap<K, V> {
         K: Borrow<Q>,
         Q: Hash + Eq,
     {
-        self.index.index(k).and_then(|pivot_index| {
-            let index = unsafe { self.pivot(pivot_index) };
-            let entry = unsafe { self.entry(index) };
-            if entry.key.borrow() == k {
-                Some(index)
-            } else {
-                None
-            }
-        })
+        self.index
+            .find(k, |i| unsafe { self.entry(i).key.borrow() == k })
+    }
+
+    /// Returns a slice of all the entries in the map.
+    #[inline]
+    pub fn as_slice(&self) -> &ArchivedIndexMapSlice<K, V> {
+        let entries = unsafe { core::slice::from_raw_parts(self.entries.as_ptr(), self.len()) };
+        ArchivedIndexMapSlice::from_entries(entries)
     }
 
     /// Returns whether a key is present in the hash map.
@@ -107,6 +113,16 @@ impl<K, V> ArchivedIndexMap<K, V> {
         }
     }
 
+    /// Returns a slice of the entries in the given range of indices, or `None` if it is out of
+    /// bounds.
+    #[inline]
+    pub fn get_range<R: RangeBounds<usize>>(
+        &self,
+        range: R,
+    ) -> Option<&ArchivedIndexMapSlice<K, V>> {
+        self.as_slice().get_range(range)
+    }
+
     /// Gets the index of a key if it exists in the map.
     #[inline]
     pub fn get_index_of<Q: ?Sized>(&self, key: &Q) -> Option<usize>
@@ -204,9 +220,6 @@ impl<K, V> ArchivedIndexMap<K, V> {
         let (fp, fo) = out_field!(out.index);
         ArchivedHashIndex::resolve_from_len(len, pos + fp, resolver.index_resolver, fo);
 
-        let (fp, fo) = out_field!(out.pivots);
-        RelPtr::emplace(pos + fp, resolver.pivots_pos, fo);
-
         let (fp, fo) = out_field!(out.entries);
         RelPtr::emplace(pos + fp, resolver.entries_pos, fo);
     }
@@ -224,55 +237,35 @@ const _: () = {
         ///
         /// # Safety
         ///
-        /// - The keys returned by the iterator must be unique
-        /// - The index function must return the index of the given key within the iterator
-        pub unsafe fn serialize_from_iter_index<'a, UK, UV, I, F, S>(
+        /// The keys returned by the iterator must be unique.
+        pub unsafe fn serialize_from_iter<'a, UK, UV, I, S>(
             iter: I,
-            index: F,
             serializer: &mut S,
         ) -> Result<IndexMapResolver, S::Error>
         where
             UK: 'a + Serialize<S, Archived = K> + Hash + Eq,
             UV: 'a + Serialize<S, Archived = V>,
-            I: Clone + ExactSizeIterator<Item = (&'a UK, &'a UV)>,
-            F: Fn(&UK) -> usize,
+            I: ExactSizeIterator<Item = (&'a UK, &'a UV)>,
             S: Serializer + ScratchSpace + ?Sized,
         {
-            use crate::ScratchVec;
+            use crate::{collections::hash_index::HashIndexBuilder, ScratchVec};
 
             let len = iter.len();
 
-            let mut entries = ScratchVec::new(serializer, iter.len())?;
-            entries.set_len(len);
-            let index_resolver =
-                ArchivedHashIndex::build_and_serialize(iter.clone(), serializer, &mut entries)?;
-            let mut entries = entries.assume_init();
-
-            // Serialize entries
-            let mut resolvers = ScratchVec::new(serializer, iter.len())?;
-            for (key, value) in iter.clone() {
-                resolvers.push((key.serialize(serializer)?, value.serialize(serializer)?));
+            let mut index_builder =
+                HashIndexBuilder::new(serializer.hash_algorithm(), len, serializer)?;
+            let mut entries = ScratchVec::new(serializer, len)?;
+            for (i, (key, value)) in iter.enumerate() {
+                index_builder.insert(key, i);
+                entries.push(Entry { key, value });
             }
-
-            let entries_pos = serializer.align_for::<Entry<K, V>>()?;
-            for ((key, value), (key_resolver, value_resolver)) in iter.zip(resolvers.drain(..)) {
-                serializer
-                    .resolve_aligned(&Entry { key, value }, (key_resolver, value_resolver))?;
-            }
-
-            // Serialize pivots
-            let pivots_pos = serializer.align_for::<Archived<usize>>()?;
-            for (key, _) in entries.drain(..) {
-                serializer.resolve_aligned(&index(key), ())?;
-            }
-
-            // Free scratch vecs
-            resolvers.free(serializer)?;
+            let entries_pos = serializer.serialize_iter::<Entry<&UK, &UV>, _, _>(entries.iter())?;
             entries.free(serializer)?;
 
+            let index_resolver = index_builder.finish(serializer)?;
+
             Ok(IndexMapResolver {
                 index_resolver,
-                pivots_pos,
                 entries_pos,
             })
         }
@@ -409,6 +402,5 @@ impl<K, V> FusedIterator for Values<'_, K, V> {}
 /// The resolver for an `IndexMap`.
 pub struct IndexMapResolver {
     index_resolver: HashIndexResolver,
-    pivots_pos: usize,
     entries_pos: usize,
 }
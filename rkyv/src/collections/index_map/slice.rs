@@ -0,0 +1,148 @@
+use super::{Iter, Keys, RawIter, Values};
+use crate::collections::util::Entry;
+use core::{
+    fmt,
+    ops::{Bound, Index, RangeBounds},
+};
+
+/// A borrowed view of a contiguous range of the entries of an
+/// [`ArchivedIndexMap`](super::ArchivedIndexMap).
+///
+/// Slices are returned from [`ArchivedIndexMap::get_range`](super::ArchivedIndexMap::get_range) and
+/// can be subsliced further. They can't look up entries by key because they don't have access to
+/// the hash index, but they can be handed out without copying any keys or values.
+#[repr(transparent)]
+pub struct ArchivedIndexMapSlice<K, V> {
+    entries: [Entry<K, V>],
+}
+
+impl<K, V> ArchivedIndexMapSlice<K, V> {
+    #[inline]
+    pub(super) fn from_entries(entries: &[Entry<K, V>]) -> &Self {
+        // SAFETY: `ArchivedIndexMapSlice` is a transparent wrapper around a slice of entries.
+        unsafe { &*(entries as *const [Entry<K, V>] as *const Self) }
+    }
+
+    /// Gets the number of entries in the slice.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the slice contains no entries.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Gets a key-value pair by its index in the slice.
+    #[inline]
+    pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        self.entries
+            .get(index)
+            .map(|entry| (&entry.key, &entry.value))
+    }
+
+    /// Returns a slice of the entries in the given range of indices, or `None` if it is out of
+    /// bounds.
+    #[inline]
+    pub fn get_range<R: RangeBounds<usize>>(&self, range: R) -> Option<&Self> {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.checked_add(1)?,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.checked_add(1)?,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len(),
+        };
+        self.entries.get(start..end).map(Self::from_entries)
+    }
+
+    /// Returns the first key-value pair.
+    #[inline]
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.entries.first().map(|entry| (&entry.key, &entry.value))
+    }
+
+    /// Returns the last key-value pair.
+    #[inline]
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.entries.last().map(|entry| (&entry.key, &entry.value))
+    }
+
+    /// Divides the slice into two at an index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`.
+    #[inline]
+    pub fn split_at(&self, index: usize) -> (&Self, &Self) {
+        let (first, second) = self.entries.split_at(index);
+        (Self::from_entries(first), Self::from_entries(second))
+    }
+
+    #[inline]
+    fn raw_iter(&self) -> RawIter<'_, K, V> {
+        RawIter::new(self.entries.as_ptr(), self.len())
+    }
+
+    /// Returns an iterator over the key-value pairs of the slice in order.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.raw_iter(),
+        }
+    }
+
+    /// Returns an iterator over the keys of the slice in order.
+    #[inline]
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys {
+            inner: self.raw_iter(),
+        }
+    }
+
+    /// Returns an iterator over the values of the slice in order.
+    #[inline]
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values {
+            inner: self.raw_iter(),
+        }
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for ArchivedIndexMapSlice<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V> Index<usize> for ArchivedIndexMapSlice<K, V> {
+    type Output = V;
+
+    #[inline]
+    fn index(&self, index: usize) -> &V {
+        &self.entries[index].value
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a ArchivedIndexMapSlice<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for ArchivedIndexMapSlice<K, V> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for ArchivedIndexMapSlice<K, V> {}
@@ -8,7 +8,7 @@ use crate::{
         ArchivedHashIndex,
     },
     validation::ArchiveContext,
-    Archived, RelPtr,
+    RelPtr,
 };
 use bytecheck::{CheckBytes, Error, SliceCheckError};
 use core::{
@@ -24,21 +24,14 @@ use core::{
 pub enum IndexMapError<K, V, C> {
     /// An error occurred while checking the hash index
     HashIndexError(HashIndexError<C>),
-    /// An error occurred while checking the layouts of displacements or entries
+    /// An error occurred while checking the layouts of entries
     LayoutError(LayoutError),
-    /// A pivot indexes outside of the entries array
-    PivotOutOfBounds {
-        /// The index of the pivot when iterating
-        index: usize,
-        /// The pivot value that was invalid
-        pivot: usize,
-    },
     /// An error occurred while checking the entries
     CheckEntryError(SliceCheckError<ArchivedEntryError<K, V>>),
     /// A key is not located at the correct position
     ///
-    /// This can either be due to the key being invalid for the hash index, or the pivot for the key
-    /// not pointing to it.
+    /// This can either be due to the key being invalid for the hash index, or the key being a
+    /// duplicate of an earlier key.
     InvalidKeyPosition {
         /// The index of the key when iterating
         index: usize,
@@ -52,9 +45,6 @@ impl<K: fmt::Display, V: fmt::Display, E: fmt::Display> fmt::Display for IndexMa
         match self {
             IndexMapError::HashIndexError(e) => write!(f, "hash index check error: {}", e),
             IndexMapError::LayoutError(e) => write!(f, "layout error: {}", e),
-            IndexMapError::PivotOutOfBounds { index, pivot } => {
-                write!(f, "pivot out of bounds: {} at index {}", pivot, index)
-            }
             IndexMapError::CheckEntryError(e) => write!(f, "entry check error: {}", e),
             IndexMapError::InvalidKeyPosition { index } => {
                 write!(f, "invalid key position: at index {}", index)
@@ -78,7 +68,6 @@ const _: () = {
             match self {
                 IndexMapError::HashIndexError(e) => Some(e as &dyn Error),
                 IndexMapError::LayoutError(e) => Some(e as &dyn Error),
-                IndexMapError::PivotOutOfBounds { .. } => None,
                 IndexMapError::CheckEntryError(e) => Some(e as &dyn Error),
                 IndexMapError::InvalidKeyPosition { .. } => None,
                 IndexMapError::ContextError(e) => Some(e as &dyn Error),
@@ -134,16 +123,15 @@ where
         value: *const Self,
         context: &mut C,
     ) -> Result<&'a Self, Self::Error> {
-        let index = ArchivedHashIndex::check_bytes(ptr::addr_of!((*value).index), context)?;
-
-        // Entries
-        Layout::array::<Entry<K, V>>(index.len())?;
+        // Entries are serialized before the hash index, so they have to be checked first
+        let len = ArchivedHashIndex::check_len(ptr::addr_of!((*value).index), context)?;
+        Layout::array::<Entry<K, V>>(len)?;
         let entries_rel_ptr = RelPtr::manual_check_bytes(ptr::addr_of!((*value).entries), context)?;
         let entries_ptr = context
             .check_subtree_ptr::<[Entry<K, V>]>(
                 entries_rel_ptr.base(),
                 entries_rel_ptr.offset(),
-                index.len(),
+                len,
             )
             .map_err(IndexMapError::ContextError)?;
 
@@ -155,39 +143,10 @@ where
             .pop_prefix_range(range)
             .map_err(IndexMapError::ContextError)?;
 
-        // Pivots
-        Layout::array::<Archived<usize>>(index.len())?;
-        let pivots_rel_ptr = RelPtr::manual_check_bytes(ptr::addr_of!((*value).pivots), context)?;
-        let pivots_ptr = context
-            .check_subtree_ptr::<[Archived<usize>]>(
-                pivots_rel_ptr.base(),
-                pivots_rel_ptr.offset(),
-                index.len(),
-            )
-            .map_err(IndexMapError::ContextError)?;
-
-        let range = context
-            .push_prefix_subtree(pivots_ptr)
-            .map_err(IndexMapError::ContextError)?;
-        let pivots = <[Archived<usize>]>::check_bytes(pivots_ptr, context)?;
-        context
-            .pop_prefix_range(range)
-            .map_err(IndexMapError::ContextError)?;
-
-        for (i, pivot) in pivots.iter().enumerate() {
-            let pivot = from_archived!(*pivot) as usize;
-            if pivot >= index.len() {
-                return Err(IndexMapError::PivotOutOfBounds { index: i, pivot });
-            }
-        }
+        let index = ArchivedHashIndex::check_bytes(ptr::addr_of!((*value).index), context)?;
 
         for (i, entry) in entries.iter().enumerate() {
-            if let Some(pivot_index) = index.index(&entry.key) {
-                let pivot = from_archived!(pivots[pivot_index]) as usize;
-                if pivot != i {
-                    return Err(IndexMapError::InvalidKeyPosition { index: i });
-                }
-            } else {
+            if index.find(&entry.key, |j| entries[j].key == entry.key) != Some(i) {
                 return Err(IndexMapError::InvalidKeyPosition { index: i });
             }
         }
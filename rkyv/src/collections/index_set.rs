@@ -1,7 +1,7 @@
 //! Archived index set implementation.
 //!
-//! During archiving, index sets are built into minimal perfect index sets using
-//! [compress, hash and displace](http://cmph.sourceforge.net/papers/esa09.pdf).
+//! During archiving, index sets are built into open-addressed tables with groups of control bytes,
+//! similar to [hashbrown](https://docs.rs/hashbrown)'s Swiss tables.
 
 use crate::{
     collections::{
@@ -133,27 +133,21 @@ const _: () = {
         ///
         /// # Safety
         ///
-        /// - The keys returned by the iterator must be unique
-        /// - The index function must return the index of the given key within the iterator
+        /// The keys returned by the iterator must be unique.
         #[inline]
-        pub unsafe fn serialize_from_iter_index<'a, UK, I, F, S>(
+        pub unsafe fn serialize_from_iter<'a, UK, I, S>(
             iter: I,
-            index: F,
             serializer: &mut S,
         ) -> Result<IndexSetResolver, S::Error>
         where
             UK: 'a + Hash + Eq + Serialize<S, Archived = K>,
-            I: Clone + ExactSizeIterator<Item = &'a UK>,
-            F: Fn(&UK) -> usize,
+            I: ExactSizeIterator<Item = &'a UK>,
             S: ScratchSpace + Serializer + ?Sized,
         {
-            Ok(IndexSetResolver(
-                ArchivedIndexMap::serialize_from_iter_index(
-                    iter.map(|k| (k, &())),
-                    index,
-                    serializer,
-                )?,
-            ))
+            Ok(IndexSetResolver(ArchivedIndexMap::serialize_from_iter(
+                iter.map(|k| (k, &())),
+                serializer,
+            )?))
         }
     }
 };
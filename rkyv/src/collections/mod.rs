@@ -1,5 +1,6 @@
 //! Archived versions of standard library containers.
 
+pub mod bi_map;
 pub mod btree_map;
 pub mod btree_set;
 pub mod hash_index;
@@ -8,8 +9,11 @@ pub mod hash_set;
 // TODO: move these into a separate crate when indexmap adds rkyv support
 pub mod index_map;
 pub mod index_set;
+pub mod multi_map;
+pub mod range_map;
 pub mod util;
 
+pub use self::bi_map::ArchivedBiMap;
 pub use self::btree_map::ArchivedBTreeMap;
 pub use self::hash_index::ArchivedHashIndex;
 pub use self::hash_map::ArchivedHashMap;
@@ -17,3 +21,5 @@ pub use self::hash_set::ArchivedHashSet;
 // TODO: move these into a separate crate when indexmap adds rkyv support
 pub use self::index_map::ArchivedIndexMap;
 pub use self::index_set::ArchivedIndexSet;
+pub use self::multi_map::ArchivedMultiMap;
+pub use self::range_map::{ArchivedRangeInclusiveMap, ArchivedRangeMap};
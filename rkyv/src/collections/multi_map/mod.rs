@@ -0,0 +1,270 @@
+//! Archived multimap implementation.
+//!
+//! An archived multimap is a hash map from each key to a range of a single vector of values. The
+//! values for each key are stored next to each other, so looking up a key returns them as a slice
+//! without any extra indirection.
+
+#[cfg(feature = "validation")]
+pub mod validation;
+
+use crate::{
+    collections::hash_map::{self, ArchivedHashMap, HashMapResolver},
+    ops::ArchivedRange,
+    vec::{ArchivedVec, VecResolver},
+    Archived,
+};
+#[cfg(feature = "alloc")]
+use crate::{
+    ser::{ScratchSpace, Serializer},
+    Serialize,
+};
+use core::{borrow::Borrow, fmt, hash::Hash, iter::FusedIterator, ops::Index};
+
+/// An archived multimap, which associates each key with a slice of values.
+///
+/// This is the archived type of the [`MultiMap`](crate::with::MultiMap) wrapper.
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedMultiMap<K, V> {
+    ranges: ArchivedHashMap<K, ArchivedRange<Archived<usize>>>,
+    values: ArchivedVec<V>,
+}
+
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(ArchivedMultiMap<u32, u32> {
+    ranges: ArchivedHashMap<u32, ArchivedRange<Archived<usize>>>,
+    values: ArchivedVec<u32>,
+});
+
+impl<K, V> ArchivedMultiMap<K, V> {
+    /// Gets the number of keys in the multimap.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    /// Returns `true` if the multimap contains no keys.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Gets the total number of values in the multimap.
+    #[inline]
+    pub fn values_len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Gets the values of every key in the multimap, grouped by key.
+    #[inline]
+    pub fn all_values(&self) -> &[V] {
+        self.values.as_slice()
+    }
+
+    #[inline]
+    fn slice(&self, range: &ArchivedRange<Archived<usize>>) -> &[V] {
+        let start = from_archived!(range.start) as usize;
+        let end = from_archived!(range.end) as usize;
+        // Ranges are checked against the values during validation
+        &self.values[start..end]
+    }
+
+    /// Finds the key and values for a key.
+    #[inline]
+    pub fn get_key_value<Q>(&self, k: &Q) -> Option<(&K, &[V])>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.ranges
+            .get_key_value(k)
+            .map(|(key, range)| (key, self.slice(range)))
+    }
+
+    /// Gets the values associated with the given key.
+    #[inline]
+    pub fn get<Q>(&self, k: &Q) -> Option<&[V]>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.ranges.get(k).map(|range| self.slice(range))
+    }
+
+    /// Returns whether a key is present in the multimap.
+    #[inline]
+    pub fn contains_key<Q>(&self, k: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.ranges.contains_key(k)
+    }
+
+    /// Gets an iterator over the keys and values in the multimap.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.ranges.iter(),
+            map: self,
+        }
+    }
+
+    /// Gets an iterator over the keys in the multimap.
+    #[inline]
+    pub fn keys(&self) -> hash_map::Keys<'_, K, ArchivedRange<Archived<usize>>> {
+        self.ranges.keys()
+    }
+
+    /// Resolves an archived multimap from the given lengths and parameters.
+    ///
+    /// # Safety
+    ///
+    /// - `len` must be the number of keys that were serialized
+    /// - `values_len` must be the total number of values that were serialized
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing a multimap
+    #[inline]
+    pub unsafe fn resolve_from_len(
+        len: usize,
+        values_len: usize,
+        pos: usize,
+        resolver: MultiMapResolver,
+        out: *mut Self,
+    ) {
+        let (fp, fo) = out_field!(out.ranges);
+        ArchivedHashMap::resolve_from_len(len, pos + fp, resolver.ranges, fo);
+        let (fp, fo) = out_field!(out.values);
+        ArchivedVec::resolve_from_len(values_len, pos + fp, resolver.values, fo);
+    }
+}
+
+#[cfg(feature = "alloc")]
+const _: () = {
+    use core::ops::Range;
+
+    impl<K, V> ArchivedMultiMap<K, V> {
+        /// Serializes an iterator of keys and their values as a multimap.
+        ///
+        /// # Safety
+        ///
+        /// The keys returned by the iterator must be unique.
+        pub unsafe fn serialize_from_iter<'a, KU, VU, S, I>(
+            iter: I,
+            serializer: &mut S,
+        ) -> Result<MultiMapResolver, S::Error>
+        where
+            KU: 'a + Serialize<S, Archived = K> + Hash + Eq,
+            VU: 'a + Serialize<S, Archived = V>,
+            S: Serializer + ScratchSpace + ?Sized,
+            I: ExactSizeIterator<Item = (&'a KU, &'a [VU])>,
+        {
+            use crate::ScratchVec;
+
+            let mut entries = ScratchVec::new(serializer, iter.len())?;
+            let mut values_len = 0;
+            for (key, values) in iter {
+                entries.push((key, values));
+                values_len += values.len();
+            }
+
+            let mut ranges = ScratchVec::new(serializer, entries.len())?;
+            let mut values = ScratchVec::<&VU>::new(serializer, values_len)?;
+            for (_, entry_values) in entries.iter() {
+                let start = values.len();
+                for value in entry_values.iter() {
+                    values.push(value);
+                }
+                ranges.push(Range {
+                    start,
+                    end: values.len(),
+                });
+            }
+
+            let ranges_resolver = ArchivedHashMap::serialize_from_iter(
+                entries
+                    .iter()
+                    .zip(ranges.iter())
+                    .map(|(&(key, _), range)| (key, range)),
+                serializer,
+            )?;
+            let values_resolver = ArchivedVec::serialize_from_iter::<VU, _, _, _>(
+                values.iter().copied(),
+                serializer,
+            )?;
+
+            values.free(serializer)?;
+            ranges.free(serializer)?;
+            entries.free(serializer)?;
+
+            Ok(MultiMapResolver {
+                ranges: ranges_resolver,
+                values: values_resolver,
+            })
+        }
+    }
+};
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for ArchivedMultiMap<K, V> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, Q, V> Index<&'_ Q> for ArchivedMultiMap<K, V>
+where
+    K: Eq + Hash + Borrow<Q>,
+    Q: Eq + Hash + ?Sized,
+{
+    type Output = [V];
+
+    #[inline]
+    fn index(&self, key: &Q) -> &[V] {
+        self.get(key).unwrap()
+    }
+}
+
+impl<K: Hash + Eq, V: PartialEq> PartialEq for ArchivedMultiMap<K, V> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        if self.len() != other.len() {
+            false
+        } else {
+            self.iter()
+                .all(|(key, values)| other.get(key) == Some(values))
+        }
+    }
+}
+
+impl<K: Hash + Eq, V: Eq> Eq for ArchivedMultiMap<K, V> {}
+
+/// An iterator over the keys and values of a multimap.
+pub struct Iter<'a, K, V> {
+    inner: hash_map::Iter<'a, K, ArchivedRange<Archived<usize>>>,
+    map: &'a ArchivedMultiMap<K, V>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a [V]);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|(key, range)| (key, self.map.slice(range)))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+/// The resolver for archived multimaps.
+pub struct MultiMapResolver {
+    ranges: HashMapResolver,
+    values: VecResolver,
+}
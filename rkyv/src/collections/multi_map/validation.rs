@@ -0,0 +1,95 @@
+//! Validation implementation for ArchivedMultiMap.
+
+use crate::{
+    collections::{hash_map::ArchivedHashMap, multi_map::ArchivedMultiMap},
+    ops::ArchivedRange,
+    validation::ArchiveContext,
+    vec::ArchivedVec,
+    Archived,
+};
+use bytecheck::{CheckBytes, Error};
+use core::{fmt, hash::Hash, ptr};
+
+/// Errors that can occur while checking an archived multimap.
+#[derive(Debug)]
+pub enum MultiMapError<R, V> {
+    /// An error occurred while checking the ranges of the keys
+    RangesCheckError(R),
+    /// An error occurred while checking the values
+    ValuesCheckError(V),
+    /// The range of a key is out of bounds of the values
+    InvalidRange {
+        /// The start of the range
+        start: usize,
+        /// The end of the range
+        end: usize,
+        /// The number of values
+        len: usize,
+    },
+}
+
+impl<R: fmt::Display, V: fmt::Display> fmt::Display for MultiMapError<R, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MultiMapError::RangesCheckError(e) => write!(f, "ranges check error: {}", e),
+            MultiMapError::ValuesCheckError(e) => write!(f, "values check error: {}", e),
+            MultiMapError::InvalidRange { start, end, len } => write!(
+                f,
+                "invalid range: {}..{} is out of bounds of {} values",
+                start, end, len
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl<R: Error + 'static, V: Error + 'static> Error for MultiMapError<R, V> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                MultiMapError::RangesCheckError(e) => Some(e as &dyn Error),
+                MultiMapError::ValuesCheckError(e) => Some(e as &dyn Error),
+                MultiMapError::InvalidRange { .. } => None,
+            }
+        }
+    }
+};
+
+impl<K, V, C> CheckBytes<C> for ArchivedMultiMap<K, V>
+where
+    K: CheckBytes<C> + Eq + Hash,
+    V: CheckBytes<C>,
+    C: ArchiveContext + ?Sized,
+    C::Error: Error,
+{
+    type Error = MultiMapError<
+        <ArchivedHashMap<K, ArchivedRange<Archived<usize>>> as CheckBytes<C>>::Error,
+        <ArchivedVec<V> as CheckBytes<C>>::Error,
+    >;
+
+    unsafe fn check_bytes<'a>(
+        value: *const Self,
+        context: &mut C,
+    ) -> Result<&'a Self, Self::Error> {
+        let ranges = ArchivedHashMap::check_bytes(ptr::addr_of!((*value).ranges), context)
+            .map_err(MultiMapError::RangesCheckError)?;
+        let values = ArchivedVec::<V>::check_bytes(ptr::addr_of!((*value).values), context)
+            .map_err(MultiMapError::ValuesCheckError)?;
+
+        for range in ranges.values() {
+            let start = from_archived!(range.start) as usize;
+            let end = from_archived!(range.end) as usize;
+            if start > end || end > values.len() {
+                return Err(MultiMapError::InvalidRange {
+                    start,
+                    end,
+                    len: values.len(),
+                });
+            }
+        }
+
+        Ok(&*value)
+    }
+}
@@ -0,0 +1,296 @@
+//! Archived range map implementation.
+//!
+//! An archived range map stores non-overlapping ranges sorted by their starts, each with a value.
+//! Point queries find the range containing a key with a binary search.
+
+#[cfg(feature = "validation")]
+pub mod validation;
+
+use crate::{
+    collections::util::Entry,
+    ops::{ArchivedRange, ArchivedRangeInclusive},
+    vec::{ArchivedVec, VecResolver},
+};
+use core::{
+    fmt,
+    iter::FusedIterator,
+    marker::PhantomData,
+    ops::{Range, RangeInclusive},
+    slice,
+};
+
+/// A range that can be used as a key of an [`ArchivedRangeMap`].
+///
+/// This is implemented for half-open and inclusive ranges, both archived and unarchived.
+pub trait MapRange<K> {
+    /// Whether the range contains its end.
+    const INCLUSIVE: bool;
+
+    /// Returns the start of the range.
+    fn start(&self) -> &K;
+
+    /// Returns the end of the range.
+    fn end(&self) -> &K;
+}
+
+impl<K> MapRange<K> for Range<K> {
+    const INCLUSIVE: bool = false;
+
+    #[inline]
+    fn start(&self) -> &K {
+        &self.start
+    }
+
+    #[inline]
+    fn end(&self) -> &K {
+        &self.end
+    }
+}
+
+impl<K> MapRange<K> for RangeInclusive<K> {
+    const INCLUSIVE: bool = true;
+
+    #[inline]
+    fn start(&self) -> &K {
+        RangeInclusive::start(self)
+    }
+
+    #[inline]
+    fn end(&self) -> &K {
+        RangeInclusive::end(self)
+    }
+}
+
+impl<K> MapRange<K> for ArchivedRange<K> {
+    const INCLUSIVE: bool = false;
+
+    #[inline]
+    fn start(&self) -> &K {
+        &self.start
+    }
+
+    #[inline]
+    fn end(&self) -> &K {
+        &self.end
+    }
+}
+
+impl<K> MapRange<K> for ArchivedRangeInclusive<K> {
+    const INCLUSIVE: bool = true;
+
+    #[inline]
+    fn start(&self) -> &K {
+        &self.start
+    }
+
+    #[inline]
+    fn end(&self) -> &K {
+        &self.end
+    }
+}
+
+/// Returns whether `range` contains keys after `key`, or `key` itself if it's inclusive.
+#[inline]
+fn ends_after<K: PartialOrd<Q>, Q: ?Sized, R: MapRange<K>>(range: &R, key: &Q) -> bool {
+    if R::INCLUSIVE {
+        range.end().ge(key)
+    } else {
+        range.end().gt(key)
+    }
+}
+
+/// Returns whether `range` contains no keys.
+#[inline]
+pub(crate) fn is_empty<K: PartialOrd, R: MapRange<K>>(range: &R) -> bool {
+    if R::INCLUSIVE {
+        !range.start().le(range.end())
+    } else {
+        !range.start().lt(range.end())
+    }
+}
+
+/// Returns whether every key in `range` is before `key`.
+#[inline]
+pub(crate) fn ends_before<K: PartialOrd, R: MapRange<K>>(range: &R, key: &K) -> bool {
+    if R::INCLUSIVE {
+        range.end().lt(key)
+    } else {
+        range.end().le(key)
+    }
+}
+
+/// An archived map from non-overlapping ranges to values.
+///
+/// The ranges are half-open by default. An [`ArchivedRangeInclusiveMap`] uses inclusive ranges
+/// instead.
+///
+/// This is the archived type of the [`RangeMap`](crate::with::RangeMap) wrapper, and of the maps
+/// from the `rangemap` crate.
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedRangeMap<K, V, R = ArchivedRange<K>> {
+    entries: ArchivedVec<Entry<R, V>>,
+    _phantom: PhantomData<K>,
+}
+
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(ArchivedRangeMap<u32, u32> {
+    entries: ArchivedVec<Entry<ArchivedRange<u32>, u32>>,
+    _phantom: PhantomData<u32>,
+});
+
+/// An archived map from non-overlapping inclusive ranges to values.
+pub type ArchivedRangeInclusiveMap<K, V> = ArchivedRangeMap<K, V, ArchivedRangeInclusive<K>>;
+
+impl<K, V, R> ArchivedRangeMap<K, V, R> {
+    /// Gets the number of ranges in the range map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the range map contains no ranges.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Gets an iterator over the ranges and values in the range map, sorted by range.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, R, V> {
+        Iter {
+            inner: self.entries.iter(),
+        }
+    }
+
+    /// Resolves an archived range map from a given length and parameters.
+    ///
+    /// # Safety
+    ///
+    /// - `len` must be the number of ranges that were serialized
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing a range map
+    #[inline]
+    pub unsafe fn resolve_from_len(
+        len: usize,
+        pos: usize,
+        resolver: RangeMapResolver,
+        out: *mut Self,
+    ) {
+        let (fp, fo) = out_field!(out.entries);
+        ArchivedVec::resolve_from_len(len, pos + fp, resolver.0, fo);
+    }
+}
+
+impl<K, V, R: MapRange<K>> ArchivedRangeMap<K, V, R> {
+    /// Finds the range containing the given key and its value.
+    #[inline]
+    pub fn get_key_value<Q: ?Sized>(&self, key: &Q) -> Option<(&R, &V)>
+    where
+        K: PartialOrd<Q>,
+    {
+        let index = self
+            .entries
+            .partition_point(|entry| entry.key.start().le(key));
+        let entry = self.entries.get(index.checked_sub(1)?)?;
+        if ends_after(&entry.key, key) {
+            Some((&entry.key, &entry.value))
+        } else {
+            None
+        }
+    }
+
+    /// Gets the value of the range containing the given key.
+    #[inline]
+    pub fn get<Q: ?Sized>(&self, key: &Q) -> Option<&V>
+    where
+        K: PartialOrd<Q>,
+    {
+        self.get_key_value(key).map(|(_, value)| value)
+    }
+
+    /// Returns whether any range in the range map contains the given key.
+    #[inline]
+    pub fn contains_key<Q: ?Sized>(&self, key: &Q) -> bool
+    where
+        K: PartialOrd<Q>,
+    {
+        self.get_key_value(key).is_some()
+    }
+}
+
+#[cfg(feature = "alloc")]
+const _: () = {
+    use crate::{
+        ser::{ScratchSpace, Serializer},
+        Serialize,
+    };
+
+    impl<K, V, R> ArchivedRangeMap<K, V, R> {
+        /// Serializes an iterator of ranges and values as a range map.
+        ///
+        /// # Safety
+        ///
+        /// The ranges returned by the iterator must be sorted by their starts and must not
+        /// overlap.
+        pub unsafe fn serialize_from_iter<'a, UR, UV, I, S>(
+            iter: I,
+            serializer: &mut S,
+        ) -> Result<RangeMapResolver, S::Error>
+        where
+            UR: 'a + Serialize<S, Archived = R>,
+            UV: 'a + Serialize<S, Archived = V>,
+            I: ExactSizeIterator<Item = (&'a UR, &'a UV)>,
+            S: Serializer + ScratchSpace + ?Sized,
+        {
+            Ok(RangeMapResolver(ArchivedVec::serialize_from_iter::<
+                Entry<&UR, &UV>,
+                _,
+                _,
+                _,
+            >(
+                iter.map(|(key, value)| Entry { key, value }),
+                serializer,
+            )?))
+        }
+    }
+};
+
+impl<K, V: fmt::Debug, R: fmt::Debug> fmt::Debug for ArchivedRangeMap<K, V, R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V: PartialEq, R: PartialEq> PartialEq for ArchivedRangeMap<K, V, R> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<K, V: Eq, R: Eq> Eq for ArchivedRangeMap<K, V, R> {}
+
+/// An iterator over the ranges and values of a range map.
+pub struct Iter<'a, R, V> {
+    inner: slice::Iter<'a, Entry<R, V>>,
+}
+
+impl<'a, R, V> Iterator for Iter<'a, R, V> {
+    type Item = (&'a R, &'a V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|entry| (&entry.key, &entry.value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<R, V> ExactSizeIterator for Iter<'_, R, V> {}
+impl<R, V> FusedIterator for Iter<'_, R, V> {}
+
+/// The resolver for archived range maps.
+pub struct RangeMapResolver(VecResolver);
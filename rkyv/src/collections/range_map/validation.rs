@@ -0,0 +1,96 @@
+//! Validation implementation for ArchivedRangeMap.
+
+use crate::{
+    collections::{
+        range_map::{ends_before, is_empty, ArchivedRangeMap, MapRange},
+        util::Entry,
+    },
+    validation::ArchiveContext,
+    vec::ArchivedVec,
+};
+use bytecheck::{CheckBytes, Error};
+use core::{fmt, ptr};
+
+/// Errors that can occur while checking an archived range map.
+#[derive(Debug)]
+pub enum ArchivedRangeMapError<E> {
+    /// An error occurred while checking the entries
+    EntriesCheckError(E),
+    /// A range started before the range preceding it
+    UnsortedRanges {
+        /// The index of the range
+        index: usize,
+    },
+    /// A range overlapped the range preceding it
+    OverlappingRanges {
+        /// The index of the range
+        index: usize,
+    },
+}
+
+impl<E: fmt::Display> fmt::Display for ArchivedRangeMapError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArchivedRangeMapError::EntriesCheckError(e) => write!(f, "entries check error: {}", e),
+            ArchivedRangeMapError::UnsortedRanges { index } => write!(
+                f,
+                "unsorted ranges: range {} starts before the range preceding it",
+                index
+            ),
+            ArchivedRangeMapError::OverlappingRanges { index } => write!(
+                f,
+                "overlapping ranges: range {} overlaps the range preceding it",
+                index
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::error::Error;
+
+    impl<E: Error + 'static> Error for ArchivedRangeMapError<E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                ArchivedRangeMapError::EntriesCheckError(e) => Some(e as &dyn Error),
+                ArchivedRangeMapError::UnsortedRanges { .. }
+                | ArchivedRangeMapError::OverlappingRanges { .. } => None,
+            }
+        }
+    }
+};
+
+impl<K, V, R, C> CheckBytes<C> for ArchivedRangeMap<K, V, R>
+where
+    K: PartialOrd,
+    V: CheckBytes<C>,
+    R: CheckBytes<C> + MapRange<K>,
+    C: ArchiveContext + ?Sized,
+    C::Error: Error,
+{
+    type Error = ArchivedRangeMapError<<ArchivedVec<Entry<R, V>> as CheckBytes<C>>::Error>;
+
+    unsafe fn check_bytes<'a>(
+        value: *const Self,
+        context: &mut C,
+    ) -> Result<&'a Self, Self::Error> {
+        let entries =
+            ArchivedVec::<Entry<R, V>>::check_bytes(ptr::addr_of!((*value).entries), context)
+                .map_err(ArchivedRangeMapError::EntriesCheckError)?;
+
+        // Lookups only check the last range that starts at or before a key, so every range that
+        // isn't empty has to end before the next one starts
+        for (i, w) in entries.windows(2).enumerate() {
+            let (prev, next) = (&w[0].key, &w[1].key);
+            if !prev.start().le(next.start()) {
+                return Err(ArchivedRangeMapError::UnsortedRanges { index: i + 1 });
+            }
+            if !is_empty(prev) && !ends_before(prev, next.start()) {
+                return Err(ArchivedRangeMapError::OverlappingRanges { index: i + 1 });
+            }
+        }
+
+        Ok(&*value)
+    }
+}
@@ -17,6 +17,9 @@ pub struct Entry<K, V> {
     pub value: V,
 }
 
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(Entry<u8, u32> { key: u8, value: u32 });
+
 impl<K: Archive, V: Archive> Archive for Entry<&'_ K, &'_ V> {
     type Archived = Entry<K::Archived, V::Archived>;
     type Resolver = (K::Resolver, V::Resolver);
@@ -0,0 +1,71 @@
+//! Optimization primitives for copyable types.
+//!
+//! Types opt into bulk copying with their [`COPY_OPTIMIZATION`](crate::Archive::COPY_OPTIMIZATION),
+//! which works on stable. With the `copy` feature, types that are `ArchiveCopyOptimize` are also
+//! bulk copied through specialization.
+
+#[cfg(feature = "copy")]
+mod specialization;
+
+use crate::FixedUsize;
+use core::{marker::PhantomData, mem::size_of};
+
+#[doc(inline)]
+#[cfg(feature = "copy")]
+pub use self::specialization::*;
+
+/// Whether multibyte primitives are archived with the endianness of the target, and so have the
+/// same byte representation as their archived versions.
+pub(crate) const NATIVE_ENDIAN: bool = cfg!(not(any(
+    all(target_endian = "little", feature = "archive_be"),
+    all(target_endian = "big", feature = "archive_le"),
+)));
+
+/// Whether pointer-sized integers are archived with the pointer width of the target.
+pub(crate) const NATIVE_SIZE: bool = size_of::<usize>() == size_of::<FixedUsize>();
+
+/// A flag that indicates whether a type may be serialized and deserialized with a bulk copy.
+///
+/// When enabled, slices and `Vec`s of the type are written to the serializer with a single `memcpy`
+/// instead of serializing and resolving each element, and `ArchivedVec`s are deserialized the same
+/// way.
+pub struct CopyOptimization<T: ?Sized>(bool, PhantomData<T>);
+
+impl<T: ?Sized> CopyOptimization<T> {
+    /// Returns a `CopyOptimization` that is disabled.
+    #[inline]
+    pub const fn disable() -> Self {
+        Self(false, PhantomData)
+    }
+
+    /// Returns a `CopyOptimization` that is enabled.
+    ///
+    /// # Safety
+    ///
+    /// `T` must implement [`Archive`](crate::Archive) such that:
+    ///
+    /// - `T` and `T::Archived` have the same size, alignment, and byte representation
+    /// - `T` contains no padding bytes
+    /// - `T::Resolver` carries no information and serializing `T` has no effect on the serializer
+    /// - every value of `T::Archived` deserializes to the `T` with the same bytes
+    #[inline]
+    pub const unsafe fn enable() -> Self {
+        Self(true, PhantomData)
+    }
+
+    /// Returns a `CopyOptimization` that is enabled if `value` is `true`.
+    ///
+    /// # Safety
+    ///
+    /// If `value` is `true`, the requirements of [`enable`](CopyOptimization::enable) must be met.
+    #[inline]
+    pub const unsafe fn enable_if(value: bool) -> Self {
+        Self(value, PhantomData)
+    }
+
+    /// Returns whether the copy optimization is enabled.
+    #[inline]
+    pub const fn is_enabled(&self) -> bool {
+        self.0
+    }
+}
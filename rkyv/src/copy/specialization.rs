@@ -1,4 +1,4 @@
-//! Optimization primitives for copyable types.
+//! Copy optimization through specialization, which requires nightly.
 
 #[cfg(has_atomics)]
 use core::sync::atomic::{
@@ -158,7 +158,7 @@ macro_rules! impl_tuple {
     };
 }
 
-impl_tuple!(T, T, T, T, T, T, T, T, T, T, T,);
+impl_tuple!(T, T, T, T, T, T, T, T, T, T, T, T, T, T, T, T,);
 
 unsafe impl<T: ArchiveCopySafe, const N: usize> ArchiveCopySafe for [T; N] {}
 
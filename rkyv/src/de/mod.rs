@@ -1,6 +1,8 @@
 //! Deserialization traits, deserializers, and adapters.
 
 pub mod deserializers;
+#[cfg(feature = "serde")]
+pub mod serde;
 
 #[cfg(feature = "alloc")]
 use crate::{ArchiveUnsized, DeserializeUnsized, Fallible};
@@ -0,0 +1,784 @@
+//! A [`serde::Deserializer`] driven by archived values.
+//!
+//! [`ArchiveDeserializer`] walks an archived value and feeds its contents to a serde visitor. This
+//! makes it possible to transcode archives into any serde format, and to deserialize archives into
+//! types that only implement [`serde::Deserialize`]. Strings are borrowed from the archive, so types
+//! with `&str` fields can be deserialized without copying.
+//!
+//! Archived types take part by implementing [`VisitArchived`]. It's implemented for archived
+//! primitives, strings, boxes, vecs, options, results, tuples, arrays, and collections, and it can
+//! be derived for archived structs and enums with `#[archive(serde)]`.
+//!
+//! ## Examples
+//!
+//! ```
+//! use rkyv::{
+//!     de::serde::from_archived,
+//!     ser::{serializers::AllocSerializer, Serializer},
+//!     unchecked::archived_root,
+//!     Archive, Serialize,
+//! };
+//!
+//! #[derive(Archive, Serialize)]
+//! #[archive(serde)]
+//! struct Label(String, u16, Option<u16>);
+//!
+//! let value = Label("server".to_string(), 80, None);
+//!
+//! let mut serializer = AllocSerializer::<256>::default();
+//! serializer.serialize_value(&value).unwrap();
+//! let bytes = serializer.into_serializer().into_inner();
+//! let archived = unsafe { archived_root::<Label>(&bytes[..]) };
+//!
+//! // The name is borrowed from the archive
+//! let (name, port, fallback): (&str, u16, Option<u16>) = from_archived(archived).unwrap();
+//! assert_eq!(name, "server");
+//! assert_eq!(port, 80);
+//! assert_eq!(fallback, None);
+//! ```
+
+use crate::{
+    boxed::ArchivedBox,
+    collections::{
+        btree_set::ArchivedBTreeSet, ArchivedBTreeMap, ArchivedHashMap, ArchivedHashSet,
+        ArchivedIndexMap, ArchivedIndexSet,
+    },
+    option::ArchivedOption,
+    result::ArchivedResult,
+    string::ArchivedString,
+    vec::{ArchivedInlineVec, ArchivedVec},
+    ArchivePointee,
+};
+use ::serde::{
+    de::{
+        self, value::BorrowedStrDeserializer, DeserializeSeed, Deserializer, EnumAccess, MapAccess,
+        SeqAccess, VariantAccess, Visitor,
+    },
+    forward_to_deserialize_any,
+};
+use core::{
+    marker::PhantomData,
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroU128, NonZeroU16,
+        NonZeroU32, NonZeroU64, NonZeroU8,
+    },
+};
+
+/// An archived type that can drive a serde [`Visitor`].
+///
+/// This can be derived for archived structs and enums with `#[archive(serde)]`.
+pub trait VisitArchived {
+    /// Feeds the contents of this archived value to the given visitor.
+    fn visit<'de, V: Visitor<'de>, E: de::Error>(&'de self, visitor: V) -> Result<V::Value, E>;
+}
+
+/// An archived struct or enum whose fields can be visited one at a time.
+///
+/// This is implemented by `#[archive(serde)]` and used with [`visit_struct`] and [`visit_enum`] to
+/// implement [`VisitArchived`].
+pub trait VisitFields {
+    /// Returns the name of the variant of this archived enum, or `None` if it's a struct.
+    #[inline]
+    fn variant(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Returns the names of the fields, or an empty slice if the fields are unnamed.
+    fn field_names(&self) -> &'static [&'static str];
+
+    /// Returns the number of fields.
+    fn field_count(&self) -> usize;
+
+    /// Deserializes the field at the given index with a seed.
+    fn visit_field<'de, S: DeserializeSeed<'de>, E: de::Error>(
+        &'de self,
+        index: usize,
+        seed: S,
+    ) -> Result<S::Value, E>;
+}
+
+/// Feeds an archived struct to a visitor.
+///
+/// Structs with named fields are visited as maps, structs with one unnamed field are visited as
+/// newtype structs, and other structs are visited as sequences.
+#[inline]
+pub fn visit_struct<'de, T, V, E>(value: &'de T, visitor: V) -> Result<V::Value, E>
+where
+    T: VisitFields + ?Sized,
+    V: Visitor<'de>,
+    E: de::Error,
+{
+    if !value.field_names().is_empty() {
+        visitor.visit_map(FieldsAccess::new(value))
+    } else {
+        match value.field_count() {
+            0 => visitor.visit_unit(),
+            1 => visitor.visit_newtype_struct(FieldDeserializer::new(value, 0)),
+            _ => visitor.visit_seq(FieldsAccess::new(value)),
+        }
+    }
+}
+
+/// Feeds an archived enum to a visitor.
+///
+/// # Panics
+///
+/// Panics if `value` is not an enum.
+#[inline]
+pub fn visit_enum<'de, T, V, E>(value: &'de T, visitor: V) -> Result<V::Value, E>
+where
+    T: VisitFields + ?Sized,
+    V: Visitor<'de>,
+    E: de::Error,
+{
+    visitor.visit_enum(FieldsAccess::new(value))
+}
+
+/// A [`serde::Deserializer`] for an archived value.
+pub struct ArchiveDeserializer<'de, T: ?Sized, E = de::value::Error> {
+    value: &'de T,
+    _phantom: PhantomData<E>,
+}
+
+impl<'de, T: ?Sized, E> ArchiveDeserializer<'de, T, E> {
+    /// Creates a new deserializer for the given archived value.
+    #[inline]
+    pub fn new(value: &'de T) -> Self {
+        Self {
+            value,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized, E> Clone for ArchiveDeserializer<'_, T, E> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized, E> Copy for ArchiveDeserializer<'_, T, E> {}
+
+impl<'de, T: VisitArchived + ?Sized, E: de::Error> Deserializer<'de>
+    for ArchiveDeserializer<'de, T, E>
+{
+    type Error = E;
+
+    #[inline]
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+        self.value.visit(visitor)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option
+        unit unit_struct newtype_struct seq tuple tuple_struct map struct enum identifier
+        ignored_any
+    }
+}
+
+/// Deserializes a serde type from an archived value.
+#[inline]
+pub fn from_archived<'de, T, U>(value: &'de T) -> Result<U, de::value::Error>
+where
+    T: VisitArchived + ?Sized,
+    U: de::Deserialize<'de>,
+{
+    U::deserialize(ArchiveDeserializer::new(value))
+}
+
+struct SeqIter<I, E> {
+    iter: I,
+    _phantom: PhantomData<E>,
+}
+
+impl<I, E> SeqIter<I, E> {
+    #[inline]
+    fn new(iter: I) -> Self {
+        Self {
+            iter,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'de, T, I, E> SeqAccess<'de> for SeqIter<I, E>
+where
+    T: 'de + VisitArchived + ?Sized,
+    I: Iterator<Item = &'de T>,
+    E: de::Error,
+{
+    type Error = E;
+
+    #[inline]
+    fn next_element_seed<S: DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> Result<Option<S::Value>, E> {
+        self.iter
+            .next()
+            .map(|value| seed.deserialize(ArchiveDeserializer::new(value)))
+            .transpose()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        match self.iter.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(lower),
+            _ => None,
+        }
+    }
+}
+
+struct MapIter<'de, I, V: ?Sized, E> {
+    iter: I,
+    value: Option<&'de V>,
+    _phantom: PhantomData<E>,
+}
+
+impl<I, V: ?Sized, E> MapIter<'_, I, V, E> {
+    #[inline]
+    fn new(iter: I) -> Self {
+        Self {
+            iter,
+            value: None,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'de, K, V, I, E> MapAccess<'de> for MapIter<'de, I, V, E>
+where
+    K: 'de + VisitArchived + ?Sized,
+    V: 'de + VisitArchived + ?Sized,
+    I: Iterator<Item = (&'de K, &'de V)>,
+    E: de::Error,
+{
+    type Error = E;
+
+    #[inline]
+    fn next_key_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<Option<S::Value>, E> {
+        match self.iter.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(ArchiveDeserializer::new(key)).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    #[inline]
+    fn next_value_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<S::Value, E> {
+        let value = self
+            .value
+            .take()
+            .expect("MapAccess::next_value called before next_key");
+        seed.deserialize(ArchiveDeserializer::new(value))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        match self.iter.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(lower),
+            _ => None,
+        }
+    }
+}
+
+struct FieldsAccess<'de, T: ?Sized, E> {
+    value: &'de T,
+    index: usize,
+    _phantom: PhantomData<E>,
+}
+
+impl<'de, T: ?Sized, E> FieldsAccess<'de, T, E> {
+    #[inline]
+    fn new(value: &'de T) -> Self {
+        Self {
+            value,
+            index: 0,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'de, T: VisitFields + ?Sized, E: de::Error> SeqAccess<'de> for FieldsAccess<'de, T, E> {
+    type Error = E;
+
+    #[inline]
+    fn next_element_seed<S: DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> Result<Option<S::Value>, E> {
+        if self.index < self.value.field_count() {
+            let result = self.value.visit_field(self.index, seed)?;
+            self.index += 1;
+            Ok(Some(result))
+        } else {
+            Ok(None)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.value.field_count() - self.index)
+    }
+}
+
+impl<'de, T: VisitFields + ?Sized, E: de::Error> MapAccess<'de> for FieldsAccess<'de, T, E> {
+    type Error = E;
+
+    #[inline]
+    fn next_key_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<Option<S::Value>, E> {
+        match self.value.field_names().get(self.index) {
+            Some(&name) => seed
+                .deserialize(BorrowedStrDeserializer::new(name))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+
+    #[inline]
+    fn next_value_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<S::Value, E> {
+        let result = self.value.visit_field(self.index, seed)?;
+        self.index += 1;
+        Ok(result)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.value.field_count() - self.index)
+    }
+}
+
+impl<'de, T: VisitFields + ?Sized, E: de::Error> EnumAccess<'de> for FieldsAccess<'de, T, E> {
+    type Error = E;
+    type Variant = Self;
+
+    #[inline]
+    fn variant_seed<S: DeserializeSeed<'de>>(self, seed: S) -> Result<(S::Value, Self), E> {
+        let name = self
+            .value
+            .variant()
+            .expect("visit_enum called with a value that is not an enum");
+        let variant = seed.deserialize(BorrowedStrDeserializer::new(name))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, T: VisitFields + ?Sized, E: de::Error> VariantAccess<'de> for FieldsAccess<'de, T, E> {
+    type Error = E;
+
+    #[inline]
+    fn unit_variant(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    #[inline]
+    fn newtype_variant_seed<S: DeserializeSeed<'de>>(self, seed: S) -> Result<S::Value, E> {
+        self.value.visit_field(0, seed)
+    }
+
+    #[inline]
+    fn tuple_variant<V: Visitor<'de>>(self, _: usize, visitor: V) -> Result<V::Value, E> {
+        visitor.visit_seq(self)
+    }
+
+    #[inline]
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, E> {
+        visitor.visit_map(self)
+    }
+}
+
+struct FieldDeserializer<'de, T: ?Sized, E> {
+    value: &'de T,
+    index: usize,
+    _phantom: PhantomData<E>,
+}
+
+impl<'de, T: ?Sized, E> FieldDeserializer<'de, T, E> {
+    #[inline]
+    fn new(value: &'de T, index: usize) -> Self {
+        Self {
+            value,
+            index,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'de, T: VisitFields + ?Sized, E: de::Error> Deserializer<'de>
+    for FieldDeserializer<'de, T, E>
+{
+    type Error = E;
+
+    #[inline]
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, E> {
+        self.value.visit_field(self.index, VisitorSeed(visitor))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf option
+        unit unit_struct newtype_struct seq tuple tuple_struct map struct enum identifier
+        ignored_any
+    }
+}
+
+struct VisitorSeed<V>(V);
+
+impl<'de, V: Visitor<'de>> DeserializeSeed<'de> for VisitorSeed<V> {
+    type Value = V::Value;
+
+    #[inline]
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<V::Value, D::Error> {
+        deserializer.deserialize_any(self.0)
+    }
+}
+
+macro_rules! impl_visit_primitive {
+    ($($ty:ty => $visit:ident,)*) => {
+        $(
+            impl VisitArchived for $ty {
+                #[inline]
+                fn visit<'de, V: Visitor<'de>, E: de::Error>(
+                    &'de self,
+                    visitor: V,
+                ) -> Result<V::Value, E> {
+                    visitor.$visit(*self)
+                }
+            }
+        )*
+    };
+}
+
+impl_visit_primitive! {
+    bool => visit_bool,
+    i8 => visit_i8,
+    i16 => visit_i16,
+    i32 => visit_i32,
+    i64 => visit_i64,
+    i128 => visit_i128,
+    u8 => visit_u8,
+    u16 => visit_u16,
+    u32 => visit_u32,
+    u64 => visit_u64,
+    u128 => visit_u128,
+    f32 => visit_f32,
+    f64 => visit_f64,
+    char => visit_char,
+}
+
+macro_rules! impl_visit_nonzero {
+    ($($ty:ty => $visit:ident,)*) => {
+        $(
+            impl VisitArchived for $ty {
+                #[inline]
+                fn visit<'de, V: Visitor<'de>, E: de::Error>(
+                    &'de self,
+                    visitor: V,
+                ) -> Result<V::Value, E> {
+                    visitor.$visit(self.get())
+                }
+            }
+        )*
+    };
+}
+
+impl_visit_nonzero! {
+    NonZeroI8 => visit_i8,
+    NonZeroI16 => visit_i16,
+    NonZeroI32 => visit_i32,
+    NonZeroI64 => visit_i64,
+    NonZeroI128 => visit_i128,
+    NonZeroU8 => visit_u8,
+    NonZeroU16 => visit_u16,
+    NonZeroU32 => visit_u32,
+    NonZeroU64 => visit_u64,
+    NonZeroU128 => visit_u128,
+}
+
+#[cfg(feature = "rend")]
+const _: () = {
+    use crate::rend::*;
+
+    macro_rules! impl_visit_rend {
+        ($($ty:ty => $visit:ident,)*) => {
+            $(
+                impl VisitArchived for $ty {
+                    #[inline]
+                    fn visit<'de, V: Visitor<'de>, E: de::Error>(
+                        &'de self,
+                        visitor: V,
+                    ) -> Result<V::Value, E> {
+                        visitor.$visit(self.value())
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_visit_rend! {
+        i16_be => visit_i16,
+        i32_be => visit_i32,
+        i64_be => visit_i64,
+        i128_be => visit_i128,
+        u16_be => visit_u16,
+        u32_be => visit_u32,
+        u64_be => visit_u64,
+        u128_be => visit_u128,
+        f32_be => visit_f32,
+        f64_be => visit_f64,
+        char_be => visit_char,
+        i16_le => visit_i16,
+        i32_le => visit_i32,
+        i64_le => visit_i64,
+        i128_le => visit_i128,
+        u16_le => visit_u16,
+        u32_le => visit_u32,
+        u64_le => visit_u64,
+        u128_le => visit_u128,
+        f32_le => visit_f32,
+        f64_le => visit_f64,
+        char_le => visit_char,
+    }
+
+    macro_rules! impl_visit_rend_nonzero {
+        ($($ty:ty => $visit:ident,)*) => {
+            $(
+                impl VisitArchived for $ty {
+                    #[inline]
+                    fn visit<'de, V: Visitor<'de>, E: de::Error>(
+                        &'de self,
+                        visitor: V,
+                    ) -> Result<V::Value, E> {
+                        visitor.$visit(self.value().get())
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_visit_rend_nonzero! {
+        NonZeroI16_be => visit_i16,
+        NonZeroI32_be => visit_i32,
+        NonZeroI64_be => visit_i64,
+        NonZeroI128_be => visit_i128,
+        NonZeroU16_be => visit_u16,
+        NonZeroU32_be => visit_u32,
+        NonZeroU64_be => visit_u64,
+        NonZeroU128_be => visit_u128,
+        NonZeroI16_le => visit_i16,
+        NonZeroI32_le => visit_i32,
+        NonZeroI64_le => visit_i64,
+        NonZeroI128_le => visit_i128,
+        NonZeroU16_le => visit_u16,
+        NonZeroU32_le => visit_u32,
+        NonZeroU64_le => visit_u64,
+        NonZeroU128_le => visit_u128,
+    }
+};
+
+impl VisitArchived for () {
+    #[inline]
+    fn visit<'de, V: Visitor<'de>, E: de::Error>(&'de self, visitor: V) -> Result<V::Value, E> {
+        visitor.visit_unit()
+    }
+}
+
+impl VisitArchived for str {
+    #[inline]
+    fn visit<'de, V: Visitor<'de>, E: de::Error>(&'de self, visitor: V) -> Result<V::Value, E> {
+        visitor.visit_borrowed_str(self)
+    }
+}
+
+impl VisitArchived for ArchivedString {
+    #[inline]
+    fn visit<'de, V: Visitor<'de>, E: de::Error>(&'de self, visitor: V) -> Result<V::Value, E> {
+        visitor.visit_borrowed_str(self.as_str())
+    }
+}
+
+impl<T: VisitArchived> VisitArchived for [T] {
+    #[inline]
+    fn visit<'de, V: Visitor<'de>, E: de::Error>(&'de self, visitor: V) -> Result<V::Value, E> {
+        visitor.visit_seq(SeqIter::new(self.iter()))
+    }
+}
+
+impl<T: VisitArchived, const N: usize> VisitArchived for [T; N] {
+    #[inline]
+    fn visit<'de, V: Visitor<'de>, E: de::Error>(&'de self, visitor: V) -> Result<V::Value, E> {
+        visitor.visit_seq(SeqIter::new(self.iter()))
+    }
+}
+
+impl<T: VisitArchived> VisitArchived for ArchivedVec<T> {
+    #[inline]
+    fn visit<'de, V: Visitor<'de>, E: de::Error>(&'de self, visitor: V) -> Result<V::Value, E> {
+        self.as_slice().visit(visitor)
+    }
+}
+
+impl<T: VisitArchived, const N: usize> VisitArchived for ArchivedInlineVec<T, N> {
+    #[inline]
+    fn visit<'de, V: Visitor<'de>, E: de::Error>(&'de self, visitor: V) -> Result<V::Value, E> {
+        self.as_slice().visit(visitor)
+    }
+}
+
+impl<T: ArchivePointee + VisitArchived + ?Sized> VisitArchived for ArchivedBox<T> {
+    #[inline]
+    fn visit<'de, V: Visitor<'de>, E: de::Error>(&'de self, visitor: V) -> Result<V::Value, E> {
+        self.get().visit(visitor)
+    }
+}
+
+impl<T: VisitArchived> VisitArchived for ArchivedOption<T> {
+    #[inline]
+    fn visit<'de, V: Visitor<'de>, E: de::Error>(&'de self, visitor: V) -> Result<V::Value, E> {
+        match self {
+            ArchivedOption::None => visitor.visit_none(),
+            ArchivedOption::Some(value) => visitor.visit_some(ArchiveDeserializer::new(value)),
+        }
+    }
+}
+
+impl<T: VisitArchived, U: VisitArchived> VisitFields for ArchivedResult<T, U> {
+    #[inline]
+    fn variant(&self) -> Option<&'static str> {
+        match self {
+            ArchivedResult::Ok(_) => Some("Ok"),
+            ArchivedResult::Err(_) => Some("Err"),
+        }
+    }
+
+    #[inline]
+    fn field_names(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    #[inline]
+    fn field_count(&self) -> usize {
+        1
+    }
+
+    #[inline]
+    fn visit_field<'de, S: DeserializeSeed<'de>, E: de::Error>(
+        &'de self,
+        _: usize,
+        seed: S,
+    ) -> Result<S::Value, E> {
+        match self {
+            ArchivedResult::Ok(value) => seed.deserialize(ArchiveDeserializer::new(value)),
+            ArchivedResult::Err(error) => seed.deserialize(ArchiveDeserializer::new(error)),
+        }
+    }
+}
+
+impl<T: VisitArchived, U: VisitArchived> VisitArchived for ArchivedResult<T, U> {
+    #[inline]
+    fn visit<'de, V: Visitor<'de>, E: de::Error>(&'de self, visitor: V) -> Result<V::Value, E> {
+        visit_enum(self, visitor)
+    }
+}
+
+macro_rules! impl_visit_map {
+    ($($map:ident,)*) => {
+        $(
+            impl<K: VisitArchived, V: VisitArchived> VisitArchived for $map<K, V> {
+                #[inline]
+                fn visit<'de, W: Visitor<'de>, E: de::Error>(
+                    &'de self,
+                    visitor: W,
+                ) -> Result<W::Value, E> {
+                    visitor.visit_map(MapIter::new(self.iter()))
+                }
+            }
+        )*
+    };
+}
+
+impl_visit_map! {
+    ArchivedBTreeMap,
+    ArchivedHashMap,
+    ArchivedIndexMap,
+}
+
+macro_rules! impl_visit_set {
+    ($($set:ident,)*) => {
+        $(
+            impl<K: VisitArchived> VisitArchived for $set<K> {
+                #[inline]
+                fn visit<'de, V: Visitor<'de>, E: de::Error>(
+                    &'de self,
+                    visitor: V,
+                ) -> Result<V::Value, E> {
+                    visitor.visit_seq(SeqIter::new(self.iter()))
+                }
+            }
+        )*
+    };
+}
+
+impl_visit_set! {
+    ArchivedBTreeSet,
+    ArchivedHashSet,
+    ArchivedIndexSet,
+}
+
+#[cfg(not(feature = "strict"))]
+macro_rules! peel_tuple {
+    ($type:ident $index:tt, $($type_rest:ident $index_rest:tt,)*) => {
+        impl_visit_tuple! { $($type_rest $index_rest,)* }
+    };
+}
+
+#[cfg(not(feature = "strict"))]
+macro_rules! impl_visit_tuple {
+    () => ();
+    ($($type:ident $index:tt,)+) => {
+        impl<$($type: VisitArchived),+> VisitFields for ($($type,)+) {
+            #[inline]
+            fn field_names(&self) -> &'static [&'static str] {
+                &[]
+            }
+
+            #[inline]
+            fn field_count(&self) -> usize {
+                [$($index,)+].len()
+            }
+
+            #[inline]
+            fn visit_field<'de, S: DeserializeSeed<'de>, E: de::Error>(
+                &'de self,
+                index: usize,
+                seed: S,
+            ) -> Result<S::Value, E> {
+                match index {
+                    $($index => seed.deserialize(ArchiveDeserializer::new(&self.$index)),)+
+                    _ => Err(E::invalid_length(index, &"a tuple index")),
+                }
+            }
+        }
+
+        impl<$($type: VisitArchived),+> VisitArchived for ($($type,)+) {
+            #[inline]
+            fn visit<'de, V: Visitor<'de>, E: de::Error>(
+                &'de self,
+                visitor: V,
+            ) -> Result<V::Value, E> {
+                visitor.visit_seq(FieldsAccess::new(self))
+            }
+        }
+
+        peel_tuple! { $($type $index,)+ }
+    };
+}
+
+#[cfg(not(feature = "strict"))]
+impl_visit_tuple! { T11 11, T10 10, T9 9, T8 8, T7 7, T6 6, T5 5, T4 4, T3 3, T2 2, T1 1, T0 0, }
@@ -3,7 +3,13 @@ use crate::{
     Archive, Deserialize, DeserializeUnsized, Fallible, Serialize, SerializeUnsized,
 };
 #[cfg(not(feature = "std"))]
-use ::alloc::string::{String, ToString};
+use ::alloc::{
+    borrow::Cow,
+    string::{String, ToString},
+};
+use core::cmp;
+#[cfg(feature = "std")]
+use std::borrow::Cow;
 
 impl Archive for String {
     type Archived = ArchivedString;
@@ -48,3 +54,45 @@ impl PartialEq<ArchivedString> for String {
         PartialEq::eq(other.as_str(), self.as_str())
     }
 }
+
+impl PartialOrd<String> for ArchivedString {
+    #[inline]
+    fn partial_cmp(&self, other: &String) -> Option<cmp::Ordering> {
+        PartialOrd::partial_cmp(self.as_str(), other.as_str())
+    }
+}
+
+impl PartialOrd<ArchivedString> for String {
+    #[inline]
+    fn partial_cmp(&self, other: &ArchivedString) -> Option<cmp::Ordering> {
+        PartialOrd::partial_cmp(self.as_str(), other.as_str())
+    }
+}
+
+impl PartialEq<Cow<'_, str>> for ArchivedString {
+    #[inline]
+    fn eq(&self, other: &Cow<'_, str>) -> bool {
+        PartialEq::eq(self.as_str(), other.as_ref())
+    }
+}
+
+impl PartialEq<ArchivedString> for Cow<'_, str> {
+    #[inline]
+    fn eq(&self, other: &ArchivedString) -> bool {
+        PartialEq::eq(other.as_str(), self.as_ref())
+    }
+}
+
+impl PartialOrd<Cow<'_, str>> for ArchivedString {
+    #[inline]
+    fn partial_cmp(&self, other: &Cow<'_, str>) -> Option<cmp::Ordering> {
+        PartialOrd::partial_cmp(self.as_str(), other.as_ref())
+    }
+}
+
+impl PartialOrd<ArchivedString> for Cow<'_, str> {
+    #[inline]
+    fn partial_cmp(&self, other: &ArchivedString) -> Option<cmp::Ordering> {
+        PartialOrd::partial_cmp(self.as_ref(), other.as_str())
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
 };
 #[cfg(not(feature = "std"))]
 use ::alloc::{alloc, boxed::Box, vec::Vec};
-use ::core::cmp;
+use ::core::{cmp, ptr};
 #[cfg(feature = "std")]
 use ::std::alloc;
 
@@ -61,6 +61,17 @@ where
     #[inline]
     fn deserialize(&self, deserializer: &mut D) -> Result<Vec<T>, D::Error> {
         unsafe {
+            if T::COPY_OPTIMIZATION.is_enabled() {
+                let mut result = Vec::with_capacity(self.len());
+                ptr::copy_nonoverlapping(
+                    self.as_ptr().cast::<T>(),
+                    result.as_mut_ptr(),
+                    self.len(),
+                );
+                result.set_len(self.len());
+                return Ok(result);
+            }
+
             let data_address = self
                 .as_slice()
                 .deserialize_unsized(deserializer, |layout| alloc::alloc(layout))?;
@@ -0,0 +1,106 @@
+use crate::{
+    arrow::{ArchivedPrimitiveArray, PrimitiveArrayResolver},
+    ser::Serializer,
+    vec::{ArchivedVec, VecResolver},
+    with::ArrowBuffer,
+    Archive, Archived, Deserialize, Fallible, Serialize,
+};
+use arrow_array::{types::ArrowPrimitiveType, Array, PrimitiveArray};
+use arrow_buffer::{ArrowNativeType, BooleanBuffer, Buffer, NullBuffer, ScalarBuffer};
+
+impl<T: ArrowNativeType + Archive> Archive for ScalarBuffer<T> {
+    type Archived = ArchivedVec<T::Archived>;
+    type Resolver = VecResolver;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        ArchivedVec::resolve_from_len(self.len(), pos, resolver, out);
+    }
+}
+
+impl<T, S> Serialize<S> for ScalarBuffer<T>
+where
+    T: ArrowNativeType + Serialize<S, Resolver = ()>,
+    S: Serializer + ?Sized,
+{
+    #[inline]
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedVec::serialize_aligned_from_slice(self.as_ref(), ArrowBuffer::ALIGNMENT, serializer)
+    }
+}
+
+impl<T, D> Deserialize<ScalarBuffer<T>, D> for ArchivedVec<T::Archived>
+where
+    T: ArrowNativeType + Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize(&self, deserializer: &mut D) -> Result<ScalarBuffer<T>, D::Error> {
+        let values = self
+            .iter()
+            .map(|value| value.deserialize(deserializer))
+            .collect::<Result<Vec<T>, _>>()?;
+        Ok(ScalarBuffer::from(values))
+    }
+}
+
+impl<P: ArrowPrimitiveType> Archive for PrimitiveArray<P>
+where
+    P::Native: Archive,
+{
+    type Archived = ArchivedPrimitiveArray<Archived<P::Native>>;
+    type Resolver = PrimitiveArrayResolver;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        ArchivedPrimitiveArray::resolve_from_len(
+            self.len(),
+            self.nulls().is_some(),
+            pos,
+            resolver,
+            out,
+        );
+    }
+}
+
+impl<P, S> Serialize<S> for PrimitiveArray<P>
+where
+    P: ArrowPrimitiveType,
+    P::Native: Serialize<S, Resolver = ()>,
+    S: Serializer + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        // Sliced arrays may have a validity bitmap that doesn't start at the first bit
+        let nulls = self.nulls().map(|nulls| nulls.inner().sliced());
+        ArchivedPrimitiveArray::serialize_from_parts(
+            self.values().as_ref(),
+            nulls.as_ref().map(|nulls| nulls.as_slice()),
+            serializer,
+        )
+    }
+}
+
+impl<P, D> Deserialize<PrimitiveArray<P>, D> for ArchivedPrimitiveArray<Archived<P::Native>>
+where
+    P: ArrowPrimitiveType,
+    P::Native: Archive,
+    Archived<P::Native>: Deserialize<P::Native, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<PrimitiveArray<P>, D::Error> {
+        let values = self
+            .values()
+            .iter()
+            .map(|value| value.deserialize(deserializer))
+            .collect::<Result<Vec<P::Native>, _>>()?;
+        let nulls = self.null_bitmap().map(|nulls| {
+            NullBuffer::new(BooleanBuffer::new(
+                Buffer::from_vec(nulls.to_vec()),
+                0,
+                self.len(),
+            ))
+        });
+        Ok(PrimitiveArray::new(ScalarBuffer::from(values), nulls))
+    }
+}
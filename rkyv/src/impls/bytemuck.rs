@@ -0,0 +1,46 @@
+use crate::net::{ArchivedIpv4Addr, ArchivedIpv6Addr};
+use bytemuck::{Pod, Zeroable};
+
+// Safety: ArchivedIpv4Addr is a transparent wrapper around an array of bytes, so all bit patterns
+// are valid and it contains no padding
+unsafe impl Zeroable for ArchivedIpv4Addr {}
+unsafe impl Pod for ArchivedIpv4Addr {}
+
+// Safety: ArchivedIpv6Addr is a transparent wrapper around an array of bytes, so all bit patterns
+// are valid and it contains no padding
+unsafe impl Zeroable for ArchivedIpv6Addr {}
+unsafe impl Pod for ArchivedIpv6Addr {}
+
+#[cfg(test)]
+mod rkyv_tests {
+    use crate::{archived_root, net::ArchivedIpv4Addr, Archived};
+    use std::{net::Ipv4Addr, vec, vec::Vec};
+
+    #[test]
+    fn cast_archived_primitives() {
+        let value: Vec<u32> = vec![1, 2, 0x01020304, u32::MAX];
+
+        let buf = crate::to_bytes::<_, 256>(&value).expect("failed to archive vec");
+        let archived = unsafe { archived_root::<Vec<u32>>(buf.as_ref()) };
+
+        let bytes = bytemuck::cast_slice::<Archived<u32>, u8>(archived.as_slice());
+        assert_eq!(bytes.len(), 16);
+        let words = bytemuck::cast_slice::<u8, Archived<u32>>(bytes);
+        assert_eq!(words, archived.as_slice());
+    }
+
+    #[test]
+    fn cast_archived_ip_addrs() {
+        let value: Vec<Ipv4Addr> = vec![Ipv4Addr::new(127, 0, 0, 1), Ipv4Addr::new(10, 1, 2, 3)];
+
+        let buf = crate::to_bytes::<_, 256>(&value).expect("failed to archive vec");
+        let archived = unsafe { archived_root::<Vec<Ipv4Addr>>(buf.as_ref()) };
+
+        let bytes = bytemuck::cast_slice::<ArchivedIpv4Addr, u8>(archived.as_slice());
+        assert_eq!(bytes, &[127, 0, 0, 1, 10, 1, 2, 3]);
+        assert_eq!(
+            bytemuck::from_bytes::<ArchivedIpv4Addr>(&bytes[4..]).octets(),
+            [10, 1, 2, 3],
+        );
+    }
+}
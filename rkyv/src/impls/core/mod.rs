@@ -2,8 +2,8 @@
 use crate::copy::ArchiveCopyOptimize;
 use crate::{
     ser::{ScratchSpace, Serializer},
-    Archive, ArchivePointee, ArchiveUnsized, Archived, ArchivedMetadata, Deserialize,
-    DeserializeUnsized, Fallible, FixedUsize, Serialize, SerializeUnsized,
+    Archive, ArchivePointee, ArchiveUnsized, Archived, ArchivedMetadata, CopyOptimization,
+    Deserialize, DeserializeUnsized, Fallible, FixedUsize, Serialize, SerializeUnsized,
 };
 use core::{alloc::Layout, ptr, str};
 use ptr_meta::Pointee;
@@ -122,13 +122,22 @@ macro_rules! impl_tuple {
     };
 }
 
+// `CheckBytes` is implemented for tuples by bytecheck, which only goes up to 12 elements. Tuples
+// with 13 to 16 elements can be archived, but not validated. This macro isn't exported because
+// orphan rules would keep other crates from using it for tuples anyway.
 #[cfg(not(feature = "strict"))]
-impl_tuple! { T11 11, T10 10, T9 9, T8 8, T7 7, T6 6, T5 5, T4 4, T3 3, T2 2, T1 1, T0 0, }
+impl_tuple! {
+    T15 15, T14 14, T13 13, T12 12, T11 11, T10 10, T9 9, T8 8, T7 7, T6 6, T5 5, T4 4, T3 3, T2 2,
+    T1 1, T0 0,
+}
 
 impl<T: Archive, const N: usize> Archive for [T; N] {
     type Archived = [T::Archived; N];
     type Resolver = [T::Resolver; N];
 
+    const COPY_OPTIMIZATION: CopyOptimization<Self> =
+        unsafe { CopyOptimization::enable_if(T::COPY_OPTIMIZATION.is_enabled()) };
+
     #[inline]
     unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
         let mut resolvers = core::mem::MaybeUninit::new(resolver);
@@ -202,24 +211,9 @@ impl<T> ArchivePointee for [T] {
 
 impl<T: Serialize<S>, S: ScratchSpace + Serializer + ?Sized> SerializeUnsized<S> for [T] {
     default! {
+        #[inline]
         fn serialize_unsized(&self, serializer: &mut S) -> Result<usize, S::Error> {
-            use crate::ScratchVec;
-
-            unsafe {
-                let mut resolvers = ScratchVec::new(serializer, self.len())?;
-
-                for value in self.iter() {
-                    resolvers.push(value.serialize(serializer)?);
-                }
-                let result = serializer.align_for::<T::Archived>()?;
-                for (value, resolver) in self.iter().zip(resolvers.drain(..)) {
-                    serializer.resolve_aligned(value, resolver)?;
-                }
-
-                resolvers.free(serializer)?;
-
-                Ok(result)
-            }
+            serializer.serialize_slice(self)
         }
     }
 
@@ -1,4 +1,7 @@
-use crate::{Archive, Archived, Deserialize, Fallible, FixedIsize, FixedUsize, Serialize};
+use crate::{
+    copy::{CopyOptimization, NATIVE_ENDIAN, NATIVE_SIZE},
+    Archive, Archived, Deserialize, Fallible, FixedIsize, FixedUsize, Serialize,
+};
 #[cfg(has_atomics)]
 use core::sync::atomic::{
     AtomicBool, AtomicI16, AtomicI32, AtomicI8, AtomicIsize, AtomicU16, AtomicU32, AtomicU8,
@@ -28,6 +31,8 @@ macro_rules! impl_primitive {
             type Archived = Self;
             type Resolver = ();
 
+            const COPY_OPTIMIZATION: CopyOptimization<Self> = unsafe { CopyOptimization::enable() };
+
             #[inline]
             unsafe fn resolve(&self, _: usize, _: Self::Resolver, out: *mut Self::Archived) {
                 out.write(*self);
@@ -56,6 +61,9 @@ macro_rules! impl_primitive {
                 type Archived = Archived;
                 type Resolver = ();
 
+                const COPY_OPTIMIZATION: CopyOptimization<Self> =
+                    unsafe { CopyOptimization::enable_if(NATIVE_ENDIAN) };
+
                 #[inline]
                 unsafe fn resolve(&self, _: usize, _: Self::Resolver, out: *mut Self::Archived) {
                     out.write(to_archived!(*self as Self));
@@ -234,6 +242,9 @@ impl Archive for usize {
     type Archived = Archived<FixedUsize>;
     type Resolver = ();
 
+    const COPY_OPTIMIZATION: CopyOptimization<Self> =
+        unsafe { CopyOptimization::enable_if(NATIVE_ENDIAN && NATIVE_SIZE) };
+
     #[inline]
     unsafe fn resolve(&self, _: usize, _: Self::Resolver, out: *mut Self::Archived) {
         out.write(to_archived!(*self as FixedUsize));
@@ -260,6 +271,9 @@ impl Archive for isize {
     type Archived = Archived<FixedIsize>;
     type Resolver = ();
 
+    const COPY_OPTIMIZATION: CopyOptimization<Self> =
+        unsafe { CopyOptimization::enable_if(NATIVE_ENDIAN && NATIVE_SIZE) };
+
     #[inline]
     unsafe fn resolve(&self, _: usize, _: Self::Resolver, out: *mut Self::Archived) {
         out.write(to_archived!(*self as FixedIsize));
@@ -22,13 +22,7 @@ where
     S: ScratchSpace + Serializer + ?Sized,
 {
     fn serialize(&self, serializer: &mut S) -> Result<IndexMapResolver, S::Error> {
-        unsafe {
-            ArchivedIndexMap::serialize_from_iter_index(
-                self.iter(),
-                |k| self.get_index_of(k).unwrap(),
-                serializer,
-            )
-        }
+        unsafe { ArchivedIndexMap::serialize_from_iter(self.iter(), serializer) }
     }
 }
 
@@ -21,13 +21,7 @@ where
     S: ScratchSpace + Serializer + ?Sized,
 {
     fn serialize(&self, serializer: &mut S) -> Result<IndexSetResolver, S::Error> {
-        unsafe {
-            ArchivedIndexSet::serialize_from_iter_index(
-                self.iter(),
-                |k| self.get_index_of(k).unwrap(),
-                serializer,
-            )
-        }
+        unsafe { ArchivedIndexSet::serialize_from_iter(self.iter(), serializer) }
     }
 }
 
@@ -0,0 +1,149 @@
+use crate::{
+    collections::index_map::ArchivedIndexMap,
+    lru::{ArchivedLruCache, LruCacheResolver},
+    ser::{ScratchSpace, Serializer},
+    Archive, Deserialize, Fallible, Serialize,
+};
+use core::hash::{BuildHasher, Hash};
+use lru::LruCache;
+
+impl<K: Archive + Hash + Eq, V: Archive, S: BuildHasher> Archive for LruCache<K, V, S> {
+    type Archived = ArchivedLruCache<K::Archived, V::Archived>;
+    type Resolver = LruCacheResolver;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        ArchivedLruCache::resolve_from_len(self.len(), self.cap(), pos, resolver, out);
+    }
+}
+
+impl<K, V, S, RandomState> Serialize<S> for LruCache<K, V, RandomState>
+where
+    K: Hash + Eq + Serialize<S>,
+    V: Serialize<S>,
+    S: ScratchSpace + Serializer + ?Sized,
+    RandomState: BuildHasher,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        // LruCache iterates from the most recently used entry to the least recently used one
+        let entries_resolver =
+            unsafe { ArchivedIndexMap::serialize_from_iter(self.iter(), serializer)? };
+        Ok(LruCacheResolver { entries_resolver })
+    }
+}
+
+impl<K, V, D, S> Deserialize<LruCache<K, V, S>, D> for ArchivedLruCache<K::Archived, V::Archived>
+where
+    K: Archive + Hash + Eq,
+    K::Archived: Deserialize<K, D>,
+    V: Archive,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+    S: Default + BuildHasher,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<LruCache<K, V, S>, D::Error> {
+        let mut result = LruCache::with_hasher(self.cap(), S::default());
+        // Insert from least to most recently used so that the recency order is restored
+        for i in (0..self.len()).rev() {
+            let (k, v) = self.get_index(i).unwrap();
+            result.put(k.deserialize(deserializer)?, v.deserialize(deserializer)?);
+        }
+        Ok(result)
+    }
+}
+
+impl<UK, K, UV, V, S> PartialEq<LruCache<UK, UV, S>> for ArchivedLruCache<K, V>
+where
+    K: PartialEq<UK>,
+    V: PartialEq<UV>,
+    UK: Hash + Eq,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &LruCache<UK, UV, S>) -> bool {
+        self.cap() == other.cap()
+            && self.len() == other.len()
+            && self
+                .iter()
+                .zip(other.iter())
+                .all(|((ak, av), (bk, bv))| ak == bk && av == bv)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        archived_root,
+        ser::{serializers::AllocSerializer, Serializer},
+        Deserialize, Infallible,
+    };
+    use core::num::NonZeroUsize;
+    use lru::LruCache;
+
+    fn make_cache() -> LruCache<String, i32> {
+        let mut value = LruCache::new(NonZeroUsize::new(8).unwrap());
+        value.put(String::from("foo"), 10);
+        value.put(String::from("bar"), 20);
+        value.put(String::from("baz"), 40);
+        value.put(String::from("bat"), 80);
+        value.get("bar");
+        value
+    }
+
+    #[test]
+    fn lru_cache() {
+        let value = make_cache();
+
+        let mut serializer = AllocSerializer::<4096>::default();
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<LruCache<String, i32>>(result.as_ref()) };
+
+        assert_eq!(archived.cap().get(), 8);
+        assert_eq!(archived.len(), 4);
+        let keys = archived.keys().map(|k| k.as_str()).collect::<Vec<_>>();
+        assert_eq!(keys, ["bar", "bat", "baz", "foo"]);
+        assert_eq!(*archived.get("baz").unwrap(), 40);
+        assert_eq!(archived, &value);
+
+        let mut deserialized: LruCache<String, i32> =
+            archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized.cap(), value.cap());
+        assert!(deserialized.iter().eq(value.iter()));
+
+        deserialized.put(String::from("qux"), 160);
+        deserialized.put(String::from("quux"), 320);
+        deserialized.put(String::from("corge"), 640);
+        deserialized.put(String::from("grault"), 1280);
+        deserialized.put(String::from("garply"), 2560);
+        assert!(!deserialized.contains("foo"));
+        assert!(deserialized.contains("bar"));
+    }
+
+    #[test]
+    fn unbounded_lru_cache() {
+        let mut value = LruCache::<String, i32>::unbounded();
+        value.put(String::from("foo"), 10);
+
+        let mut serializer = AllocSerializer::<4096>::default();
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<LruCache<String, i32>>(result.as_ref()) };
+
+        assert_eq!(archived.cap().get(), crate::FixedUsize::MAX as usize);
+        assert_eq!(*archived.get("foo").unwrap(), 10);
+    }
+
+    #[cfg(feature = "validation")]
+    #[test]
+    fn validate_lru_cache() {
+        use crate::check_archived_root;
+
+        let value = make_cache();
+
+        let mut serializer = AllocSerializer::<4096>::default();
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_serializer().into_inner();
+        check_archived_root::<LruCache<String, i32>>(result.as_ref())
+            .expect("failed to validate archived LRU cache");
+    }
+}
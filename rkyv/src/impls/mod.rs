@@ -13,12 +13,22 @@ mod std;
 // implementations should be moved into their respective crates over time. Before adding support for
 // another crate, please consider getting rkyv support in the crate instead.
 
+#[cfg(feature = "arrow")]
+mod arrow;
 #[cfg(feature = "bitvec")]
 mod bitvec;
+#[cfg(feature = "bytemuck")]
+mod bytemuck;
 #[cfg(feature = "hashbrown")]
 mod hashbrown;
 #[cfg(feature = "indexmap")]
 mod indexmap;
+#[cfg(all(feature = "alloc", feature = "lru"))]
+mod lru;
+#[cfg(all(feature = "alloc", feature = "rangemap"))]
+mod rangemap;
+#[cfg(feature = "serde")]
+mod serde;
 #[cfg(feature = "smallvec")]
 mod smallvec;
 #[cfg(feature = "tinyvec")]
@@ -0,0 +1,161 @@
+use crate::{
+    collections::range_map::{ArchivedRangeInclusiveMap, ArchivedRangeMap, RangeMapResolver},
+    ser::{ScratchSpace, Serializer},
+    Archive, Deserialize, Fallible, Serialize,
+};
+use rangemap::{RangeInclusiveMap, RangeMap, StepFns};
+
+/// Marks the iterators of range maps as exact size.
+///
+/// The iterators of `rangemap` don't implement `ExactSizeIterator`, but they forward the exact size
+/// hints of the B-tree maps they wrap.
+struct ExactSize<I>(I);
+
+impl<I: Iterator> Iterator for ExactSize<I> {
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<I: Iterator> ExactSizeIterator for ExactSize<I> {}
+
+// RangeMap
+
+impl<K: Archive, V: Archive> Archive for RangeMap<K, V> {
+    type Archived = ArchivedRangeMap<K::Archived, V::Archived>;
+    type Resolver = RangeMapResolver;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        ArchivedRangeMap::resolve_from_len(self.len(), pos, resolver, out);
+    }
+}
+
+impl<K, V, S> Serialize<S> for RangeMap<K, V>
+where
+    K: Serialize<S>,
+    V: Serialize<S>,
+    S: ScratchSpace + Serializer + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        // Range maps iterate over their ranges in order and never contain overlapping ranges
+        unsafe {
+            ArchivedRangeMap::<K::Archived, V::Archived>::serialize_from_iter(
+                ExactSize(self.iter()),
+                serializer,
+            )
+        }
+    }
+}
+
+impl<K, V, D> Deserialize<RangeMap<K, V>, D> for ArchivedRangeMap<K::Archived, V::Archived>
+where
+    K: Archive + Ord + Clone,
+    K::Archived: Deserialize<K, D>,
+    V: Archive + PartialEq + Clone,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<RangeMap<K, V>, D::Error> {
+        let mut result = RangeMap::new();
+        for (range, value) in self.iter() {
+            result.insert(
+                range.start.deserialize(deserializer)?..range.end.deserialize(deserializer)?,
+                value.deserialize(deserializer)?,
+            );
+        }
+        Ok(result)
+    }
+}
+
+impl<K, V, UK, UV> PartialEq<RangeMap<UK, UV>> for ArchivedRangeMap<K, V>
+where
+    K: PartialEq<UK>,
+    V: PartialEq<UV>,
+{
+    fn eq(&self, other: &RangeMap<UK, UV>) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .zip(other.iter())
+                .all(|((ar, av), (br, bv))| ar.start == br.start && ar.end == br.end && av == bv)
+    }
+}
+
+// RangeInclusiveMap
+
+impl<K, V, F> Archive for RangeInclusiveMap<K, V, F>
+where
+    K: Archive + Ord + Clone,
+    V: Archive + PartialEq + Clone,
+    F: StepFns<K>,
+{
+    type Archived = ArchivedRangeInclusiveMap<K::Archived, V::Archived>;
+    type Resolver = RangeMapResolver;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        ArchivedRangeMap::resolve_from_len(self.len(), pos, resolver, out);
+    }
+}
+
+impl<K, V, F, S> Serialize<S> for RangeInclusiveMap<K, V, F>
+where
+    K: Serialize<S> + Ord + Clone,
+    V: Serialize<S> + PartialEq + Clone,
+    F: StepFns<K>,
+    S: ScratchSpace + Serializer + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        // Range maps iterate over their ranges in order and never contain overlapping ranges
+        unsafe {
+            ArchivedRangeInclusiveMap::<K::Archived, V::Archived>::serialize_from_iter(
+                ExactSize(self.iter()),
+                serializer,
+            )
+        }
+    }
+}
+
+impl<K, V, F, D> Deserialize<RangeInclusiveMap<K, V, F>, D>
+    for ArchivedRangeInclusiveMap<K::Archived, V::Archived>
+where
+    K: Archive + Ord + Clone,
+    K::Archived: Deserialize<K, D>,
+    V: Archive + PartialEq + Clone,
+    V::Archived: Deserialize<V, D>,
+    F: StepFns<K>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<RangeInclusiveMap<K, V, F>, D::Error> {
+        let mut result = RangeInclusiveMap::new_with_step_fns();
+        for (range, value) in self.iter() {
+            result.insert(
+                range.start.deserialize(deserializer)?..=range.end.deserialize(deserializer)?,
+                value.deserialize(deserializer)?,
+            );
+        }
+        Ok(result)
+    }
+}
+
+impl<K, V, UK, UV, F> PartialEq<RangeInclusiveMap<UK, UV, F>> for ArchivedRangeInclusiveMap<K, V>
+where
+    K: PartialEq<UK>,
+    V: PartialEq<UV>,
+{
+    fn eq(&self, other: &RangeInclusiveMap<UK, UV, F>) -> bool {
+        self.iter().len() == other.iter().count()
+            && self.iter().zip(other.iter()).all(|((ar, av), (br, bv))| {
+                ar.start == *br.start() && ar.end == *br.end() && av == bv
+            })
+    }
+}
@@ -0,0 +1,119 @@
+//! [`serde::Serialize`] implementations for archived types.
+//!
+//! These make it possible to re-encode an archived value with any serde format without
+//! deserializing it first. Archived primitives are their unarchived types unless `archive_le` or
+//! `archive_be` is enabled, in which case they are endian-aware types from `rend` which do not
+//! implement `serde::Serialize`.
+//!
+//! Because neither those types nor `serde::Serialize` belong to this crate, the orphan rules don't
+//! allow implementing it for them here. With `archive_le` or `archive_be`, only archived types that
+//! don't contain multibyte primitives (such as strings, `bool`s, `u8`s, and collections of them)
+//! can be serialized with serde.
+
+use crate::{
+    boxed::ArchivedBox,
+    collections::{
+        btree_set::ArchivedBTreeSet, ArchivedBTreeMap, ArchivedHashMap, ArchivedHashSet,
+        ArchivedIndexMap, ArchivedIndexSet,
+    },
+    option::ArchivedOption,
+    result::ArchivedResult,
+    string::ArchivedString,
+    vec::{ArchivedInlineVec, ArchivedVec},
+    ArchivePointee,
+};
+use serde::{Serialize, Serializer};
+
+impl Serialize for ArchivedString {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<T: Serialize> Serialize for ArchivedVec<T> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_slice().serialize(serializer)
+    }
+}
+
+impl<T: Serialize, const N: usize> Serialize for ArchivedInlineVec<T, N> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_slice().serialize(serializer)
+    }
+}
+
+impl<T: Serialize> Serialize for ArchivedOption<T> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ArchivedOption::None => serializer.serialize_none(),
+            ArchivedOption::Some(value) => serializer.serialize_some(value),
+        }
+    }
+}
+
+impl<T: Serialize, E: Serialize> Serialize for ArchivedResult<T, E> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ArchivedResult::Ok(value) => {
+                serializer.serialize_newtype_variant("Result", 0, "Ok", value)
+            }
+            ArchivedResult::Err(error) => {
+                serializer.serialize_newtype_variant("Result", 1, "Err", error)
+            }
+        }
+    }
+}
+
+impl<T: ArchivePointee + Serialize + ?Sized> Serialize for ArchivedBox<T> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.get().serialize(serializer)
+    }
+}
+
+impl<K: Serialize, V: Serialize> Serialize for ArchivedHashMap<K, V> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+impl<K: Serialize> Serialize for ArchivedHashSet<K> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<K: Serialize, V: Serialize> Serialize for ArchivedBTreeMap<K, V> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+impl<K: Serialize> Serialize for ArchivedBTreeSet<K> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<K: Serialize, V: Serialize> Serialize for ArchivedIndexMap<K, V> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+impl<K: Serialize> Serialize for ArchivedIndexSet<K> {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
@@ -0,0 +1,106 @@
+//! Layout assertions for archived types.
+//!
+//! With the `strict` feature, every archived type has a `repr(C)` layout. The layout of a `repr(C)`
+//! type only depends on the sizes and alignments of its fields, so archives built with `strict`
+//! have the same format across compiler versions and can be read across FFI boundaries.
+//!
+//! [`ReprC`] computes the layout that the `repr(C)` rules give a list of fields in a `const`
+//! context, and [`assert_repr_c`] checks a type against it at compile time.
+//! With `strict`, the derive and the built-in archived types assert their layouts this way, so any
+//! change to an archived layout is a compile error instead of a silent format change.
+
+use core::mem::{align_of, size_of};
+
+/// The layout of a `repr(C)` struct, built up one field at a time.
+///
+/// # Examples
+///
+/// ```
+/// use core::mem::{align_of, size_of};
+/// use rkyv::layout::ReprC;
+///
+/// #[repr(C)]
+/// struct Example {
+///     a: u8,
+///     b: u32,
+///     c: u16,
+/// }
+///
+/// const LAYOUT: ReprC = ReprC::new().field::<u8>().field::<u32>().field::<u16>();
+/// assert_eq!(ReprC::new().field::<u8>().offset::<u32>(), 4);
+/// assert_eq!(LAYOUT.size(), size_of::<Example>());
+/// assert_eq!(LAYOUT.align(), align_of::<Example>());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReprC {
+    end: usize,
+    align: usize,
+}
+
+impl Default for ReprC {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReprC {
+    /// Returns the layout of a struct with no fields.
+    #[inline]
+    pub const fn new() -> Self {
+        Self { end: 0, align: 1 }
+    }
+
+    /// Returns the offset that the next field would have if it were of type `T`.
+    #[inline]
+    pub const fn offset<T>(&self) -> usize {
+        round_up(self.end, align_of::<T>())
+    }
+
+    /// Returns the layout with a field of type `T` added to the end.
+    #[inline]
+    pub const fn field<T>(self) -> Self {
+        Self {
+            end: self.offset::<T>() + size_of::<T>(),
+            align: max(self.align, align_of::<T>()),
+        }
+    }
+
+    /// Returns the layout of a `repr(C)` union of this layout and another.
+    ///
+    /// Enums with a primitive representation are laid out as a union of `repr(C)` structs, one
+    /// for each variant, that each start with the tag.
+    #[inline]
+    pub const fn union(self, other: Self) -> Self {
+        Self {
+            end: max(self.end, other.end),
+            align: max(self.align, other.align),
+        }
+    }
+
+    /// Returns the size of the layout, including trailing padding.
+    #[inline]
+    pub const fn size(&self) -> usize {
+        round_up(self.end, self.align)
+    }
+
+    /// Returns the alignment of the layout.
+    #[inline]
+    pub const fn align(&self) -> usize {
+        self.align
+    }
+}
+
+#[inline]
+const fn round_up(value: usize, align: usize) -> usize {
+    (value + align - 1) & !(align - 1)
+}
+
+#[inline]
+const fn max(a: usize, b: usize) -> usize {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
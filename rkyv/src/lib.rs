@@ -28,9 +28,9 @@
 //! ## Type support
 //!
 //! rkyv has a hashmap implementation that is built for zero-copy deserialization, so you can
-//! serialize your hashmaps with abandon. The implementation performs perfect hashing with the
-//! compress, hash and displace algorithm to use as little memory as possible while still performing
-//! fast lookups.
+//! serialize your hashmaps with abandon. The implementation uses open addressing with groups of
+//! control bytes, similar to hashbrown's Swiss tables, so most lookups only touch one group of the
+//! index before comparing keys.
 //!
 //! It also comes with a B+ tree implementation that is built for maximum performance by splitting
 //! data into easily-pageable 4KB segments. This makes it perfect for building immutable databases
@@ -63,6 +63,25 @@
 //! - `copy_unsafe`: Automatically opts all potentially copyable types into copy optimization. This
 //!   broadly improves performance but may cause uninitialized bytes to be copied to the output.
 //!   Requires nightly.
+//! - `hash_wyhash`: Builds archived hash maps and sets with wyhash instead of SeaHash by default.
+//!   wyhash is considerably faster for short keys. The hash algorithm is recorded in each archive,
+//!   so archives built with any algorithm can be read regardless of this feature. Serializers can
+//!   also choose an algorithm with `Serializer::hash_algorithm`.
+//! - `hash_xxh3`: Builds archived hash maps and sets with XXH3 instead of SeaHash by default. Like
+//!   wyhash, XXH3 is considerably faster for short keys. Takes precedence over `hash_wyhash` if both
+//!   are enabled.
+//! - `profile`: Reports the time taken and bytes processed for each top-level object that is
+//!   serialized, validated, or deserialized to a hook installed with `profile::set_hook`. Enables
+//!   `std`.
+//! - `rayon`: Enables building archived hash indexes on multiple threads with
+//!   [rayon](https://docs.rs/rayon). Hash map and set fields archived with the `ParallelIndex`
+//!   wrapper, and maps and sets serialized with `par_serialize_from_iter`, hash their keys and
+//!   assign buckets in parallel.
+//! - `safe_api`: Makes validation the default way to access archives. The root re-exports of
+//!   functions that cast bytes to archived values without checking them, like `archived_root`,
+//!   are deprecated in favor of the [`unchecked`] module, and so are the framing and loading
+//!   helpers in [`util`] that don't validate unless called through an explicitly-named
+//!   `_unchecked` method. Enables `validation`.
 //! - `size_16`: Archives integral `*size` types as 16-bit integers. This is intended to be used
 //!   only for small archives and may not handle large, more general data.
 //! - `size_32`: Archives integral `*size` types as 32-bit integers. Enabled by default.
@@ -71,13 +90,19 @@
 //! - `std`: Enables standard library support. Enabled by default.
 //! - `strict`: Guarantees that types will have the same representations across platforms and
 //!   compilations. This is already the case in practice, but this feature provides a guarantee
-//!   along with C type compatibility.
+//!   along with C type compatibility. Archived struct layouts are checked at compile time with
+//!   `core::mem::offset_of!`, so this feature requires Rust 1.77 or later.
 //!
 //!   *Note*: Enabling `strict` will disable [`Archive`] implementations for tuples, as tuples
 //!   do not have a C type layout. Making a generic `Tuple<T1, T2>` and deriving [`Archive`] for it
 //!   should provide similar functionality.
 //! - `validation`: Enables validation support through `bytecheck`.
 //!
+//!   *Note*: Tuples can be archived with up to 16 elements, but `bytecheck` only implements
+//!   `CheckBytes` for tuples with up to 12 elements, so larger tuples can't be validated. Orphan
+//!   rules keep other crates from implementing [`Archive`] or `CheckBytes` for tuples themselves.
+//!   Larger groups of fields should use a struct or tuple struct that derives [`Archive`] instead.
+//!
 //! ## Crate support
 //!
 //! Some common crates need to be supported by rkyv before an official integration has been made.
@@ -87,10 +112,29 @@
 //!
 //! Crates supported by rkyv:
 //!
+//! - [`arrow`](https://docs.rs/arrow) *Archives `ScalarBuffer`s and `PrimitiveArray`s from
+//!   `arrow-buffer` and `arrow-array` with Arrow-aligned values, and shares archived values with
+//!   Arrow without copying them through the adapters in `rkyv::arrow`.*
+//! - [`bytemuck`](https://docs.rs/bytemuck) *Implements `Pod` and `Zeroable` for archived
+//!   fixed-layout types so archived slices can be cast with `bytemuck`. When `archive_le` or
+//!   `archive_be` is enabled, this also enables the `bytemuck` feature of `rend` for the
+//!   endian-aware archived primitives.*
+//! - [`ciborium`](https://docs.rs/ciborium) *Provides the `Cbor` format for `AsSerde`.*
 //! - [`indexmap`](https://docs.rs/indexmap)
+//! - [`lru`](https://docs.rs/lru) *Archives `LruCache` entries in recency order along with the
+//!   cache capacity, and deserializes them into a cache with the same capacity and order.*
+//! - [`rangemap`](https://docs.rs/rangemap) *Archives `RangeMap` and `RangeInclusiveMap` as
+//!   `ArchivedRangeMap`s.*
 //! - [`rend`](https://docs.rs/rend) *Enabled automatically when using endian-specific archive
 //!   features.*
+//! - [`serde`](https://docs.rs/serde) *Implements `serde::Serialize` for archived types so they
+//!   can be re-encoded without deserializing them, and provides a `serde::Deserializer` for
+//!   archived values in `de::serde`. Also enables the `AsSerde` wrapper, which archives types
+//!   with serde. Archived multibyte primitives don't implement `serde::Serialize` when
+//!   `archive_le` or `archive_be` is enabled.*
 //! - [`tinyvec`](https://docs.rs/tinyvec)
+//! - [`tokio`](https://docs.rs/tokio) *Provides `FrameCodec`, a `tokio-util` codec for the
+//!   length-prefixed frames in [`util`], and lets `ArchiveLoader` read from a tokio `AsyncRead`.*
 //! - [`uuid`](https://docs.rs/uuid)
 //!
 //! Support for each of these crates can be enabled with a feature of the same name. Additionally,
@@ -141,11 +185,14 @@ extern crate std;
 #[macro_use]
 pub mod macros;
 
+#[cfg(feature = "arrow")]
+pub mod arrow;
 #[cfg(feature = "bitvec")]
 pub mod bitvec;
 pub mod boxed;
+#[cfg(feature = "alloc")]
+pub mod c_header;
 pub mod collections;
-#[cfg(feature = "copy")]
 pub mod copy;
 pub mod de;
 // This is pretty unfortunate. CStr doesn't rely on the rest of std, but it's not in core.
@@ -153,10 +200,15 @@ pub mod de;
 #[cfg(feature = "std")]
 pub mod ffi;
 mod impls;
+pub mod layout;
+#[cfg(feature = "lru")]
+pub mod lru;
 pub mod net;
 pub mod niche;
 pub mod ops;
 pub mod option;
+#[cfg(feature = "profile")]
+pub mod profile;
 pub mod rc;
 pub mod rel_ptr;
 pub mod result;
@@ -171,9 +223,15 @@ pub mod with;
 
 #[cfg(feature = "rend")]
 pub use rend;
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde;
 
 use core::alloc::Layout;
 use ptr_meta::Pointee;
+
+#[doc(inline)]
+pub use copy::CopyOptimization;
 pub use rkyv_derive::{Archive, Deserialize, Serialize};
 pub use util::*;
 #[cfg(feature = "validation")]
@@ -272,7 +330,7 @@ impl Default for Infallible {
 ///
 /// // You can use the safe API with the validation feature turned on,
 /// // or you can use the unsafe API (shown here) for maximum performance
-/// let archived = unsafe { rkyv::archived_root::<Test>(&bytes[..]) };
+/// let archived = unsafe { rkyv::unchecked::archived_root::<Test>(&bytes[..]) };
 /// assert_eq!(archived, &value);
 ///
 /// // And you can always deserialize back to the original type
@@ -294,9 +352,9 @@ impl Default for Infallible {
 /// ```
 /// use core::{slice, str};
 /// use rkyv::{
-///     archived_root,
 ///     ser::{Serializer, serializers::AlignedSerializer},
 ///     out_field,
+///     unchecked::archived_root,
 ///     AlignedVec,
 ///     Archive,
 ///     Archived,
@@ -402,6 +460,13 @@ pub trait Archive {
     /// needed to make the archived type from the normal type.
     type Resolver;
 
+    /// Whether sequences of this type may be serialized and deserialized by copying their bytes
+    /// directly.
+    ///
+    /// This is disabled by default. See [`CopyOptimization`] for the requirements a type must meet
+    /// to enable it.
+    const COPY_OPTIMIZATION: CopyOptimization<Self> = CopyOptimization::disable();
+
     /// Creates the archived version of this value at the given position and writes it to the given
     /// output.
     ///
@@ -468,8 +533,8 @@ pub trait Deserialize<T, D: Fallible + ?Sized> {
 /// use rkyv::{
 ///     from_archived,
 ///     to_archived,
-///     archived_unsized_value,
 ///     ser::{serializers::AlignedSerializer, Serializer},
+///     unchecked::archived_unsized_value,
 ///     AlignedVec,
 ///     Archive,
 ///     Archived,
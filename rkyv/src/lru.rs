@@ -0,0 +1,80 @@
+//! Archived LRU caches.
+
+use crate::{
+    collections::index_map::{ArchivedIndexMap, IndexMapResolver},
+    Archive, Archived, FixedUsize,
+};
+use core::{num::NonZeroUsize, ops::Deref};
+
+/// An archived `LruCache`.
+///
+/// The entries are stored in an [`ArchivedIndexMap`] in recency order, from the most recently used
+/// entry to the least recently used one. Looking up an entry doesn't change this order.
+#[cfg_attr(feature = "validation", derive(bytecheck::CheckBytes))]
+#[cfg_attr(feature = "strict", repr(C))]
+#[derive(Debug)]
+pub struct ArchivedLruCache<K, V> {
+    entries: ArchivedIndexMap<K, V>,
+    cap: Archived<NonZeroUsize>,
+}
+
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(ArchivedLruCache<u32, u32> {
+    entries: ArchivedIndexMap<u32, u32>,
+    cap: Archived<NonZeroUsize>,
+});
+
+impl<K, V> ArchivedLruCache<K, V> {
+    /// Returns the maximum number of entries the cache can hold.
+    ///
+    /// Unbounded caches are archived with the largest capacity that an archived `usize` can hold.
+    #[inline]
+    pub fn cap(&self) -> NonZeroUsize {
+        // Safety: the archived capacity was resolved from a nonzero value
+        unsafe { NonZeroUsize::new_unchecked(from_archived!(self.cap).get() as usize) }
+    }
+
+    /// Returns the entries of the cache, ordered from most to least recently used.
+    #[inline]
+    pub fn entries(&self) -> &ArchivedIndexMap<K, V> {
+        &self.entries
+    }
+
+    /// Resolves an archived LRU cache from a given length, capacity, and parameters.
+    ///
+    /// # Safety
+    ///
+    /// - `len` must be the number of entries that were serialized
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing the entries as an index map
+    #[inline]
+    pub unsafe fn resolve_from_len(
+        len: usize,
+        cap: NonZeroUsize,
+        pos: usize,
+        resolver: LruCacheResolver,
+        out: *mut Self,
+    ) {
+        let (fp, fo) = out_field!(out.entries);
+        ArchivedIndexMap::resolve_from_len(len, pos + fp, resolver.entries_resolver, fo);
+
+        // Clamp the capacity so that unbounded caches don't truncate to an arbitrary value
+        let cap = cap.get().min(FixedUsize::MAX as usize);
+        let (fp, fo) = out_field!(out.cap);
+        NonZeroUsize::new_unchecked(cap).resolve(pos + fp, (), fo);
+    }
+}
+
+impl<K, V> Deref for ArchivedLruCache<K, V> {
+    type Target = ArchivedIndexMap<K, V>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.entries
+    }
+}
+
+/// The resolver for an [`ArchivedLruCache`].
+pub struct LruCacheResolver {
+    pub(crate) entries_resolver: IndexMapResolver,
+}
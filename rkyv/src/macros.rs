@@ -10,6 +10,24 @@ macro_rules! default {
     ($($fn:tt)*) => { $($fn)* };
 }
 
+#[cfg(feature = "profile")]
+macro_rules! profile {
+    ($operation:ident, $ty:ty, $len:expr, $body:expr) => {
+        $crate::profile::profile::<$ty, _, _>(
+            $crate::profile::Operation::$operation,
+            || $body,
+            $len,
+        )
+    };
+}
+
+#[cfg(not(feature = "profile"))]
+macro_rules! profile {
+    ($operation:ident, $ty:ty, $len:expr, $body:expr) => {
+        $body
+    };
+}
+
 /// Returns a tuple of the field offset and a mutable pointer to the field of the given struct
 /// pointer.
 ///
@@ -46,6 +64,58 @@ macro_rules! out_field {
     }};
 }
 
+/// Asserts that a struct has the layout that `repr(C)` gives its fields.
+///
+/// The struct is given with the types of all of its fields in order, and the offset of each field
+/// along with the size and alignment of the struct are checked against a
+/// [`ReprC`](crate::layout::ReprC) layout. This evaluates to `()` and panics on a mismatch, so it
+/// is usually used in a `const` to turn any layout change into a compile error. The derive emits
+/// these assertions for every archived struct with the `strict` feature.
+///
+/// Field offsets are found with `core::mem::offset_of!`, so this macro requires Rust 1.77 or later
+/// even though the rest of the crate supports older compilers.
+///
+/// # Examples
+/// ```
+/// use rkyv::assert_repr_c;
+///
+/// #[repr(C)]
+/// struct Example {
+///     a: u8,
+///     b: u32,
+/// }
+///
+/// const _: () = assert_repr_c!(Example { a: u8, b: u32 });
+/// ```
+#[macro_export]
+macro_rules! assert_repr_c {
+    ($ty:ty { $($field:tt: $field_ty:ty),* $(,)? }) => {{
+        let layout = $crate::layout::ReprC::new();
+        $(
+            ::core::assert!(
+                ::core::mem::offset_of!($ty, $field) == layout.offset::<$field_ty>(),
+                ::core::concat!(
+                    "the offset of ",
+                    ::core::stringify!($ty),
+                    "::",
+                    ::core::stringify!($field),
+                    " does not match its repr(C) layout",
+                ),
+            );
+            let layout = layout.field::<$field_ty>();
+        )*
+        ::core::assert!(
+            ::core::mem::size_of::<$ty>() == layout.size()
+                && ::core::mem::align_of::<$ty>() == layout.align(),
+            ::core::concat!(
+                "the size and alignment of ",
+                ::core::stringify!($ty),
+                " do not match its repr(C) layout",
+            ),
+        );
+    }};
+}
+
 /// Returns the unarchived value of the given archived primitive.
 ///
 /// This macro is not needed for most use cases. Its primary purpose is to simultaneously:
@@ -79,6 +79,9 @@ pub struct ArchivedSocketAddrV4 {
     pub(crate) port: Archived<u16>,
 }
 
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(ArchivedSocketAddrV4 { ip: ArchivedIpv4Addr, port: Archived<u16> });
+
 impl ArchivedSocketAddrV4 {
     /// Returns the IP address associated with this socket address.
     #[inline]
@@ -104,6 +107,14 @@ pub struct ArchivedSocketAddrV6 {
     pub(crate) scope_id: Archived<u32>,
 }
 
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(ArchivedSocketAddrV6 {
+    ip: ArchivedIpv6Addr,
+    port: Archived<u16>,
+    flowinfo: Archived<u32>,
+    scope_id: Archived<u32>,
+});
+
 impl ArchivedSocketAddrV6 {
     /// Returns the flow information associated with this address.
     ///
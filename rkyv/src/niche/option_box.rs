@@ -211,3 +211,42 @@ pub enum OptionBoxResolver<T> {
     /// The resolver for the `ArchivedBox`
     Some(BoxResolver<T>),
 }
+
+#[cfg(feature = "validation")]
+const _: () = {
+    use crate::{
+        validation::{
+            owned::{CheckOwnedPointerError, OwnedPointerError},
+            ArchiveContext, LayoutRaw,
+        },
+        RelPtr,
+    };
+    use bytecheck::{CheckBytes, Error};
+    use core::ptr;
+    use ptr_meta::Pointee;
+
+    impl<T, C> CheckBytes<C> for ArchivedOptionBox<T>
+    where
+        T: ArchivePointee + CheckBytes<C> + LayoutRaw + Pointee + ?Sized,
+        C: ArchiveContext + ?Sized,
+        T::ArchivedMetadata: CheckBytes<C>,
+        C::Error: Error,
+    {
+        type Error = CheckOwnedPointerError<T, C>;
+
+        #[inline]
+        unsafe fn check_bytes<'a>(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<&'a Self, Self::Error> {
+            let inner = ptr::addr_of!((*value).inner);
+            let rel_ptr = RelPtr::<T>::manual_check_bytes(inner.cast(), context)
+                .map_err(OwnedPointerError::PointerCheckBytesError)?;
+            // A null pointer is the niche for `None`, so there's nothing else to check
+            if !rel_ptr.is_null() {
+                ArchivedBox::check_bytes(inner, context)?;
+            }
+            Ok(&*value)
+        }
+    }
+};
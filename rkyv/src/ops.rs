@@ -16,6 +16,9 @@ pub struct ArchivedRange<T> {
     pub end: T,
 }
 
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(ArchivedRange<u32> { start: u32, end: u32 });
+
 impl<T: fmt::Debug> fmt::Debug for ArchivedRange<T> {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.start.fmt(fmt)?;
@@ -71,6 +74,9 @@ pub struct ArchivedRangeInclusive<T> {
     pub end: T,
 }
 
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(ArchivedRangeInclusive<u32> { start: u32, end: u32 });
+
 impl<T: fmt::Debug> fmt::Debug for ArchivedRangeInclusive<T> {
     #[inline]
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -123,6 +129,9 @@ pub struct ArchivedRangeFrom<T> {
     pub start: T,
 }
 
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(ArchivedRangeFrom<u32> { start: u32 });
+
 impl<T: fmt::Debug> fmt::Debug for ArchivedRangeFrom<T> {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.start.fmt(fmt)?;
@@ -164,6 +173,9 @@ pub struct ArchivedRangeTo<T> {
     pub end: T,
 }
 
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(ArchivedRangeTo<u32> { end: u32 });
+
 impl<T: fmt::Debug> fmt::Debug for ArchivedRangeTo<T> {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(fmt, "..")?;
@@ -205,6 +217,9 @@ pub struct ArchivedRangeToInclusive<T> {
     pub end: T,
 }
 
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(ArchivedRangeToInclusive<u32> { end: u32 });
+
 impl<T: fmt::Debug> fmt::Debug for ArchivedRangeToInclusive<T> {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(fmt, "..=")?;
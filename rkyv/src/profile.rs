@@ -0,0 +1,214 @@
+//! Profiling hooks for serialization, validation, and deserialization.
+//!
+//! With the `profile` feature, serializers report an [`Event`] to the installed hook for each
+//! object passed to [`serialize_value`](crate::ser::Serializer::serialize_value) or
+//! [`serialize_unsized_value`](crate::ser::Serializer::serialize_unsized_value), so every
+//! serializer is profiled, including custom ones. Validation through
+//! [`check_archived_value`](crate::check_archived_value) and
+//! [`check_archived_root`](crate::check_archived_root), and deserialization through
+//! [`from_bytes`](crate::from_bytes) and
+//! [`from_bytes_unchecked`](crate::util::unchecked::from_bytes_unchecked), report events the same
+//! way.
+//!
+//! Values nested inside a profiled object, like the contents of a `Box`, are not reported
+//! individually, so the cost of a hook is paid once per top-level object. When no hook is
+//! installed, profiling only costs a single atomic load per object. Custom deserializers can
+//! report their own events with [`profile`].
+
+use core::{
+    any::type_name,
+    cell::Cell,
+    mem, ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+    time::Duration,
+};
+use std::time::Instant;
+
+/// The kind of work that an [`Event`] measures.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// A value was serialized.
+    Serialize,
+    /// An archived value was validated.
+    Validate,
+    /// An archived value was deserialized.
+    Deserialize,
+}
+
+/// A measurement of one top-level object being serialized, validated, or deserialized.
+#[derive(Clone, Copy, Debug)]
+pub struct Event {
+    operation: Operation,
+    type_name: &'static str,
+    len: usize,
+    elapsed: Duration,
+    success: bool,
+}
+
+impl Event {
+    /// Returns the kind of work that was measured.
+    #[inline]
+    pub fn operation(&self) -> Operation {
+        self.operation
+    }
+
+    /// Returns the name of the unarchived type of the object.
+    #[inline]
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+
+    /// Returns the number of bytes processed.
+    ///
+    /// For serialization this is the number of bytes written, and for validation and
+    /// deserialization it is the length of the archive read from. Operations that fail report
+    /// zero bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether no bytes were processed.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns how long the work took.
+    #[inline]
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns whether the work completed without an error.
+    #[inline]
+    pub fn success(&self) -> bool {
+        self.success
+    }
+}
+
+/// A function that receives profiling events.
+///
+/// Hooks may be called from multiple threads at once.
+pub type Hook = fn(&Event);
+
+static HOOK: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+#[inline]
+unsafe fn from_raw(raw: *mut ()) -> Option<Hook> {
+    if raw.is_null() {
+        None
+    } else {
+        Some(mem::transmute::<*mut (), Hook>(raw))
+    }
+}
+
+/// Installs a hook to receive profiling events, returning the previously installed hook.
+#[inline]
+pub fn set_hook(hook: Hook) -> Option<Hook> {
+    unsafe { from_raw(HOOK.swap(hook as *mut (), Ordering::AcqRel)) }
+}
+
+/// Removes the installed hook and returns it.
+#[inline]
+pub fn take_hook() -> Option<Hook> {
+    unsafe { from_raw(HOOK.swap(ptr::null_mut(), Ordering::AcqRel)) }
+}
+
+/// Returns the installed hook, if any.
+#[inline]
+pub fn hook() -> Option<Hook> {
+    unsafe { from_raw(HOOK.load(Ordering::Acquire)) }
+}
+
+std::thread_local! {
+    /// The number of profiled operations running on the current thread.
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Marks a profiled operation as running on the current thread until it's dropped.
+struct DepthGuard {
+    outermost: bool,
+}
+
+impl DepthGuard {
+    #[inline]
+    fn enter() -> Self {
+        let depth = DEPTH.with(|depth| depth.replace(depth.get() + 1));
+        Self {
+            outermost: depth == 0,
+        }
+    }
+}
+
+impl Drop for DepthGuard {
+    #[inline]
+    fn drop(&mut self) {
+        DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+/// Runs `f` and reports how long it took to the installed hook.
+///
+/// `len` computes the number of bytes processed from a successful result. If no hook is
+/// installed, or if `f` is nested inside another profiled operation on the same thread, `f` is run
+/// without being timed.
+///
+/// # Examples
+///
+/// ```
+/// use rkyv::profile::{self, Event, Operation};
+///
+/// fn log(event: &Event) {
+///     println!(
+///         "{:?} {}: {} bytes in {:?}",
+///         event.operation(),
+///         event.type_name(),
+///         event.len(),
+///         event.elapsed(),
+///     );
+/// }
+///
+/// profile::set_hook(log);
+/// let bytes = rkyv::to_bytes::<_, 256>(&vec![1, 2, 3]).unwrap();
+/// let len = profile::profile::<Vec<i32>, _, ()>(
+///     Operation::Validate,
+///     || Ok(bytes.len()),
+///     |len| *len,
+/// )
+/// .unwrap();
+/// assert_eq!(len, bytes.len());
+/// profile::take_hook();
+/// ```
+#[inline]
+pub fn profile<T: ?Sized, R, E>(
+    operation: Operation,
+    f: impl FnOnce() -> Result<R, E>,
+    len: impl FnOnce(&R) -> usize,
+) -> Result<R, E> {
+    match hook() {
+        None => f(),
+        Some(hook) => {
+            let guard = DepthGuard::enter();
+            if !guard.outermost {
+                return f();
+            }
+
+            let start = Instant::now();
+            let result = f();
+            let elapsed = start.elapsed();
+            let (len, success) = match &result {
+                Ok(value) => (len(value), true),
+                Err(_) => (0, false),
+            };
+            hook(&Event {
+                operation,
+                type_name: type_name::<T>(),
+                len,
+                elapsed,
+                success,
+            });
+            result
+        }
+    }
+}
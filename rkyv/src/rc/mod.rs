@@ -4,8 +4,11 @@
 pub mod validation;
 
 use crate::{
-    ser::{Serializer, SharedSerializeRegistry},
-    ArchivePointee, ArchiveUnsized, MetadataResolver, RelPtr, SerializeUnsized,
+    ser::{
+        DedupSerializeRegistry, InternSerializeRegistry, ScratchSpace, Serializer,
+        SharedSerializeRegistry,
+    },
+    ArchivePointee, ArchiveUnsized, MetadataResolver, RelPtr, Serialize, SerializeUnsized,
 };
 use core::{borrow::Borrow, cmp, fmt, hash, marker::PhantomData, ops::Deref, pin::Pin, ptr};
 
@@ -68,6 +71,40 @@ impl<T: ArchivePointee + ?Sized, F> ArchivedRc<T, F> {
     }
 }
 
+impl<F> ArchivedRc<str, F> {
+    /// Serializes an archived `Rc` from a given string, sharing the archived string with any other
+    /// interned strings that have the same contents.
+    #[inline]
+    pub fn serialize_interned<S: Serializer + InternSerializeRegistry + ?Sized>(
+        value: &str,
+        serializer: &mut S,
+    ) -> Result<RcResolver<MetadataResolver<str>>, S::Error> {
+        Ok(RcResolver {
+            pos: serializer.serialize_interned(value)?,
+            metadata_resolver: (),
+        })
+    }
+}
+
+impl<T, F> ArchivedRc<[T], F> {
+    /// Serializes an archived `Rc` from a given slice, sharing the archived slice with any other
+    /// deduplicated slices that have the same archived bytes.
+    #[inline]
+    pub fn serialize_deduped_from_slice<U, S>(
+        slice: &[U],
+        serializer: &mut S,
+    ) -> Result<RcResolver<MetadataResolver<[U]>>, S::Error>
+    where
+        U: Serialize<S, Archived = T>,
+        S: Serializer + ScratchSpace + DedupSerializeRegistry + ?Sized,
+    {
+        Ok(RcResolver {
+            pos: serializer.serialize_deduped(slice)?,
+            metadata_resolver: (),
+        })
+    }
+}
+
 impl<T: ArchivePointee + ?Sized, F> AsRef<T> for ArchivedRc<T, F> {
     #[inline]
     fn as_ref(&self) -> &T {
@@ -2,6 +2,9 @@
 
 #[cfg(feature = "validation")]
 mod validation;
+pub mod varint;
+
+pub use self::varint::{VarintRelPtrs, VarintRelPtrsResolver};
 
 use crate::{ArchivePointee, ArchiveUnsized, Archived};
 use core::{
@@ -0,0 +1,383 @@
+//! Relative pointers with variable-length offsets.
+
+use crate::{
+    ser::{ScratchSpace, Serializer},
+    Archive, Archived, RelPtr, Serialize,
+};
+use core::{borrow::Borrow, fmt, marker::PhantomData};
+
+/// The maximum number of bytes in an encoded offset.
+pub const MAX_VARINT_LEN: usize = 10;
+
+/// Encodes an offset as a zigzag LEB128 varint and returns the number of bytes written.
+///
+/// # Examples
+///
+/// ```
+/// use rkyv::rel_ptr::varint::{decode_offset, encode_offset, MAX_VARINT_LEN};
+///
+/// let mut buf = [0; MAX_VARINT_LEN];
+/// assert_eq!(encode_offset(-1, &mut buf), 1);
+/// assert_eq!(buf[0], 0x01);
+/// assert_eq!(encode_offset(64, &mut buf), 2);
+/// assert_eq!(decode_offset(&buf), Some((64, 2)));
+/// assert_eq!(encode_offset(isize::MIN, &mut buf), (isize::BITS as usize + 6) / 7);
+/// assert_eq!(decode_offset(&buf).map(|(offset, _)| offset), Some(isize::MIN));
+/// assert_eq!(decode_offset(&[0x80, 0x80]), None);
+/// ```
+#[inline]
+pub fn encode_offset(offset: isize, out: &mut [u8; MAX_VARINT_LEN]) -> usize {
+    let offset = offset as i64;
+    let mut value = ((offset << 1) ^ (offset >> 63)) as u64;
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out[len] = byte;
+            return len + 1;
+        }
+        out[len] = byte | 0x80;
+        len += 1;
+    }
+}
+
+/// Decodes a zigzag LEB128 varint offset from the front of `bytes`.
+///
+/// Returns the offset and the number of bytes read, or `None` if the bytes do not start with a
+/// valid offset.
+#[inline]
+pub fn decode_offset(bytes: &[u8]) -> Option<(isize, usize)> {
+    let mut value = 0u64;
+    for (i, &byte) in bytes.iter().take(MAX_VARINT_LEN).enumerate() {
+        let bits = (byte & 0x7f) as u64;
+        if i == MAX_VARINT_LEN - 1 && bits > 1 {
+            return None;
+        }
+        value |= bits << (7 * i);
+        if byte & 0x80 == 0 {
+            let offset = (value >> 1) as i64 ^ -((value & 1) as i64);
+            return if offset < isize::MIN as i64 || offset > isize::MAX as i64 {
+                None
+            } else {
+                Some((offset as isize, i + 1))
+            };
+        }
+    }
+    None
+}
+
+/// A sequence of relative pointers with variable-length offsets.
+///
+/// Each offset is stored as a zigzag LEB128 varint relative to the previous pointee (the first is
+/// relative to the start of the offsets). Values serialized back-to-back usually end up close
+/// together, so most offsets only take one or two bytes instead of the four or eight bytes of a
+/// [`RelPtr`].
+///
+/// The tradeoff is access speed: offsets can only be decoded in order, so getting the pointer at an
+/// index takes linear time. Prefer a `Vec` of boxes when random access matters more than density.
+/// The `rel_ptr_offsets` benchmarks in `rkyv_bench` compare the two.
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct VarintRelPtrs<T> {
+    offsets: RelPtr<u8>,
+    offsets_len: Archived<usize>,
+    len: Archived<usize>,
+    _phantom: PhantomData<T>,
+}
+
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(VarintRelPtrs<u32> {
+    offsets: RelPtr<u8>,
+    offsets_len: Archived<usize>,
+    len: Archived<usize>,
+    _phantom: PhantomData<u32>,
+});
+
+impl<T> VarintRelPtrs<T> {
+    /// Returns the number of pointers.
+    #[inline]
+    pub fn len(&self) -> usize {
+        from_archived!(self.len) as usize
+    }
+
+    /// Returns whether there are no pointers.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the encoded offsets.
+    #[inline]
+    pub fn offsets(&self) -> &[u8] {
+        unsafe {
+            core::slice::from_raw_parts(
+                self.offsets.as_ptr(),
+                from_archived!(self.offsets_len) as usize,
+            )
+        }
+    }
+
+    /// Returns an iterator over the pointees.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            base: self.offsets.as_ptr(),
+            offsets: self.offsets(),
+            remaining: self.len(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the pointee at the given index.
+    ///
+    /// This decodes every offset before the index, so it takes linear time.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.iter().nth(index)
+    }
+
+    /// Resolves the pointers from a given length.
+    ///
+    /// # Safety
+    ///
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing `len` values with
+    ///   [`serialize_from_iter`](VarintRelPtrs::serialize_from_iter)
+    #[inline]
+    pub unsafe fn resolve_from_len(
+        len: usize,
+        pos: usize,
+        resolver: VarintRelPtrsResolver,
+        out: *mut Self,
+    ) {
+        let (fp, fo) = out_field!(out.offsets);
+        RelPtr::emplace(pos + fp, resolver.pos, fo);
+        let (fp, fo) = out_field!(out.offsets_len);
+        usize::resolve(&resolver.offsets_len, pos + fp, (), fo);
+        let (fp, fo) = out_field!(out.len);
+        usize::resolve(&len, pos + fp, (), fo);
+    }
+
+    /// Serializes each value in an iterator and the varint offsets pointing to them.
+    #[inline]
+    pub fn serialize_from_iter<U, B, I, S>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<VarintRelPtrsResolver, S::Error>
+    where
+        U: Serialize<S, Archived = T>,
+        B: Borrow<U>,
+        I: ExactSizeIterator<Item = B>,
+        S: ScratchSpace + Serializer + ?Sized,
+    {
+        use crate::ScratchVec;
+
+        unsafe {
+            let mut positions = ScratchVec::new(serializer, iter.len())?;
+            for value in iter {
+                positions.push(serializer.serialize_value(value.borrow())?);
+            }
+
+            let pos = serializer.pos();
+            let mut base = pos;
+            let mut buf = [0u8; MAX_VARINT_LEN];
+            for &target in positions.iter() {
+                let len = encode_offset(target as isize - base as isize, &mut buf);
+                serializer.write(&buf[..len])?;
+                base = target;
+            }
+            let offsets_len = serializer.pos() - pos;
+
+            positions.free(serializer)?;
+
+            Ok(VarintRelPtrsResolver { pos, offsets_len })
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for VarintRelPtrs<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<VarintRelPtrs<U>> for VarintRelPtrs<T> {
+    #[inline]
+    fn eq(&self, other: &VarintRelPtrs<U>) -> bool {
+        self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a.eq(b))
+    }
+}
+
+impl<T: Eq> Eq for VarintRelPtrs<T> {}
+
+/// An iterator over the pointees of [`VarintRelPtrs`].
+pub struct Iter<'a, T> {
+    base: *const u8,
+    offsets: &'a [u8],
+    remaining: usize,
+    _phantom: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            None
+        } else {
+            let (offset, len) = decode_offset(self.offsets)?;
+            self.offsets = &self.offsets[len..];
+            self.remaining -= 1;
+            unsafe {
+                self.base = self.base.offset(offset);
+                Some(&*self.base.cast::<T>())
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+/// The resolver for [`VarintRelPtrs`].
+pub struct VarintRelPtrsResolver {
+    pos: usize,
+    offsets_len: usize,
+}
+
+#[cfg(feature = "validation")]
+pub use self::validation::*;
+
+#[cfg(feature = "validation")]
+mod validation {
+    use super::{decode_offset, VarintRelPtrs};
+    use crate::{validation::ArchiveContext, Archived, RelPtr};
+    use bytecheck::{CheckBytes, Error};
+    use core::{convert::Infallible, fmt, ptr};
+
+    /// Errors that can occur while checking [`VarintRelPtrs`].
+    #[derive(Debug)]
+    pub enum VarintRelPtrsError<T, C> {
+        /// An offset was not a valid varint
+        InvalidOffset {
+            /// The index of the invalid offset
+            index: usize,
+        },
+        /// The offsets did not take up exactly the recorded number of bytes
+        LengthMismatch {
+            /// The recorded number of bytes
+            expected: usize,
+            /// The number of bytes used by the offsets
+            actual: usize,
+        },
+        /// A pointee failed to validate
+        ValueCheckBytesError(T),
+        /// An error occurred from the validation context
+        ContextError(C),
+    }
+
+    impl<T: fmt::Display, C: fmt::Display> fmt::Display for VarintRelPtrsError<T, C> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            match self {
+                VarintRelPtrsError::InvalidOffset { index } => {
+                    write!(f, "invalid varint offset at index {}", index)
+                }
+                VarintRelPtrsError::LengthMismatch { expected, actual } => write!(
+                    f,
+                    "offsets length mismatch: expected {} bytes, used {} bytes",
+                    expected, actual
+                ),
+                VarintRelPtrsError::ValueCheckBytesError(e) => e.fmt(f),
+                VarintRelPtrsError::ContextError(e) => e.fmt(f),
+            }
+        }
+    }
+
+    #[cfg(feature = "std")]
+    const _: () = {
+        use std::error::Error;
+
+        impl<T: Error + 'static, C: Error + 'static> Error for VarintRelPtrsError<T, C> {
+            fn source(&self) -> Option<&(dyn Error + 'static)> {
+                match self {
+                    VarintRelPtrsError::InvalidOffset { .. } => None,
+                    VarintRelPtrsError::LengthMismatch { .. } => None,
+                    VarintRelPtrsError::ValueCheckBytesError(e) => Some(e as &dyn Error),
+                    VarintRelPtrsError::ContextError(e) => Some(e as &dyn Error),
+                }
+            }
+        }
+    };
+
+    impl<T, C> CheckBytes<C> for VarintRelPtrs<T>
+    where
+        T: CheckBytes<C>,
+        C: ArchiveContext + ?Sized,
+        C::Error: Error,
+    {
+        type Error = VarintRelPtrsError<T::Error, C::Error>;
+
+        unsafe fn check_bytes<'a>(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<&'a Self, Self::Error> {
+            let rel_ptr =
+                RelPtr::<u8>::manual_check_bytes(ptr::addr_of!((*value).offsets), context).unwrap();
+            let offsets_len =
+                Archived::<usize>::check_bytes(ptr::addr_of!((*value).offsets_len), context)
+                    .map_err(|e: Infallible| match e {})?;
+            let offsets_len = from_archived!(*offsets_len) as usize;
+            let len = Archived::<usize>::check_bytes(ptr::addr_of!((*value).len), context)
+                .map_err(|e: Infallible| match e {})?;
+            let len = from_archived!(*len) as usize;
+
+            // The offsets are written after the pointees, so only bounds check them for now and
+            // claim them after all of the pointees have been checked.
+            let offsets_ptr = context
+                .check_subtree_ptr::<[u8]>(rel_ptr.base(), rel_ptr.offset(), offsets_len)
+                .map_err(VarintRelPtrsError::ContextError)?;
+            let offsets = &*offsets_ptr;
+
+            let mut base = offsets.as_ptr();
+            let mut read = 0;
+            for index in 0..len {
+                let (offset, offset_len) = decode_offset(&offsets[read..])
+                    .ok_or(VarintRelPtrsError::InvalidOffset { index })?;
+                read += offset_len;
+
+                let ptr = context
+                    .check_subtree_ptr::<T>(base, offset, ())
+                    .map_err(VarintRelPtrsError::ContextError)?;
+                let range = context
+                    .push_prefix_subtree(ptr)
+                    .map_err(VarintRelPtrsError::ContextError)?;
+                T::check_bytes(ptr, context).map_err(VarintRelPtrsError::ValueCheckBytesError)?;
+                context
+                    .pop_prefix_range(range)
+                    .map_err(VarintRelPtrsError::ContextError)?;
+
+                base = ptr.cast();
+            }
+            if read != offsets_len {
+                return Err(VarintRelPtrsError::LengthMismatch {
+                    expected: offsets_len,
+                    actual: read,
+                });
+            }
+
+            let range = context
+                .push_prefix_subtree(offsets_ptr)
+                .map_err(VarintRelPtrsError::ContextError)?;
+            context
+                .pop_prefix_range(range)
+                .map_err(VarintRelPtrsError::ContextError)?;
+
+            Ok(&*value)
+        }
+    }
+}
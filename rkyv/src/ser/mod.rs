@@ -2,8 +2,17 @@
 
 pub mod serializers;
 
-use crate::{Archive, ArchiveUnsized, Fallible, RelPtr, Serialize, SerializeUnsized};
-use core::{alloc::Layout, mem, ptr::NonNull, slice};
+use crate::{
+    collections::hash_index::HashAlgorithm, Archive, ArchiveUnsized, Fallible, FixedIsize, RelPtr,
+    ScratchVec, Serialize, SerializeUnsized,
+};
+use core::{
+    alloc::Layout,
+    borrow::Borrow,
+    mem,
+    ptr::{self, NonNull},
+    slice,
+};
 
 /// A byte sink that knows where it is.
 ///
@@ -75,9 +84,86 @@ pub trait Serializer: Fallible {
     /// Archives the given object and returns the position it was archived at.
     #[inline]
     fn serialize_value<T: Serialize<Self>>(&mut self, value: &T) -> Result<usize, Self::Error> {
-        let resolver = value.serialize(self)?;
-        self.align_for::<T::Archived>()?;
-        unsafe { self.resolve_aligned(value, resolver) }
+        let start = self.pos();
+        profile!(Serialize, T, |&(_, len): &(usize, usize)| len, {
+            let resolver = value.serialize(self)?;
+            self.align_for::<T::Archived>()?;
+            let pos = unsafe { self.resolve_aligned(value, resolver)? };
+            Ok((pos, self.pos() - start))
+        })
+        .map(|(pos, _)| pos)
+    }
+
+    /// Archives a slice of values contiguously and returns the position of the first archived value.
+    ///
+    /// All of the values are serialized before any of them are resolved, and their resolvers are
+    /// kept in scratch space in the meantime. Slices of copy-optimized types are written directly
+    /// instead.
+    #[inline]
+    fn serialize_slice<T: Serialize<Self>>(&mut self, values: &[T]) -> Result<usize, Self::Error>
+    where
+        Self: ScratchSpace,
+    {
+        if T::COPY_OPTIMIZATION.is_enabled() {
+            let pos = self.align_for::<T::Archived>()?;
+            let bytes = unsafe {
+                slice::from_raw_parts(values.as_ptr().cast::<u8>(), mem::size_of_val(values))
+            };
+            self.write(bytes)?;
+            Ok(pos)
+        } else {
+            use crate::ScratchVec;
+
+            unsafe {
+                // Only the resolvers need to be stored since the values can be borrowed from the
+                // slice again, so types with zero-sized resolvers don't use any scratch space
+                let mut resolvers = ScratchVec::new(self, values.len())?;
+                for value in values.iter() {
+                    resolvers.push(value.serialize(self)?);
+                }
+
+                let pos = self.align_for::<T::Archived>()?;
+                for (value, resolver) in values.iter().zip(resolvers.drain(..)) {
+                    self.resolve_aligned(value, resolver)?;
+                }
+
+                resolvers.free(self)?;
+
+                Ok(pos)
+            }
+        }
+    }
+
+    /// Archives the values of an iterator contiguously and returns the position of the first
+    /// archived value.
+    ///
+    /// This works like [`serialize_slice`](Serializer::serialize_slice), but is unable to perform
+    /// copy optimizations.
+    fn serialize_iter<T, B, I>(&mut self, iter: I) -> Result<usize, Self::Error>
+    where
+        T: Serialize<Self>,
+        B: Borrow<T>,
+        I: ExactSizeIterator<Item = B>,
+        Self: ScratchSpace,
+    {
+        use crate::ScratchVec;
+
+        unsafe {
+            let mut resolvers = ScratchVec::new(self, iter.len())?;
+            for value in iter {
+                let resolver = value.borrow().serialize(self)?;
+                resolvers.push((value, resolver));
+            }
+
+            let pos = self.align_for::<T::Archived>()?;
+            for (value, resolver) in resolvers.drain(..) {
+                self.resolve_aligned(value.borrow(), resolver)?;
+            }
+
+            resolvers.free(self)?;
+
+            Ok(pos)
+        }
     }
 
     /// Resolves the given reference with its resolver and writes the archived reference.
@@ -114,10 +200,36 @@ pub trait Serializer: Fallible {
         &mut self,
         value: &T,
     ) -> Result<usize, Self::Error> {
-        let to = value.serialize_unsized(self)?;
-        let metadata_resolver = value.serialize_metadata(self)?;
-        self.align_for::<RelPtr<T::Archived>>()?;
-        unsafe { self.resolve_unsized_aligned(value, to, metadata_resolver) }
+        let start = self.pos();
+        profile!(Serialize, T, |&(_, len): &(usize, usize)| len, {
+            let to = value.serialize_unsized(self)?;
+            let metadata_resolver = value.serialize_metadata(self)?;
+            self.align_for::<RelPtr<T::Archived>>()?;
+            let pos = unsafe { self.resolve_unsized_aligned(value, to, metadata_resolver)? };
+            Ok((pos, self.pos() - start))
+        })
+        .map(|(pos, _)| pos)
+    }
+
+    /// Returns the seed that archived hash indexes built by this serializer are keyed with.
+    ///
+    /// The default seed of `0` makes hash indexes reproducible, but lets anyone who knows the keys
+    /// that will be archived construct keys that collide. Serializers that archive maps with
+    /// untrusted keys should return a seed that can't be guessed, for example by wrapping them in
+    /// a [`SeededSerializer`](serializers::SeededSerializer).
+    #[inline]
+    fn hash_seed(&self) -> u64 {
+        0
+    }
+
+    /// Returns the algorithm that archived hash indexes built by this serializer are hashed with.
+    ///
+    /// This is [`HashAlgorithm::DEFAULT`] unless the serializer chooses another, for example by
+    /// wrapping it in a [`SeededSerializer`](serializers::SeededSerializer). Archived hash indexes
+    /// record their algorithm, so they can be read no matter which one was chosen.
+    #[inline]
+    fn hash_algorithm(&self) -> HashAlgorithm {
+        HashAlgorithm::DEFAULT
     }
 }
 
@@ -186,3 +298,122 @@ pub trait SharedSerializeRegistry: Fallible {
         }
     }
 }
+
+/// A registry that tracks serialized strings by their contents.
+///
+/// This trait is required to serialize interned strings.
+pub trait InternSerializeRegistry: Fallible {
+    /// Gets the position of a previously-added string with the same contents.
+    ///
+    /// Returns `None` if no such string has been added.
+    fn get_interned(&self, value: &str) -> Option<usize>;
+
+    /// Adds the position of a string to the registry.
+    fn add_interned(&mut self, value: &str, pos: usize) -> Result<(), Self::Error>;
+
+    /// Archives the given string and returns its position. If a string with the same contents has
+    /// already been added then it returns the position of the previously added string.
+    #[inline]
+    fn serialize_interned(&mut self, value: &str) -> Result<usize, Self::Error>
+    where
+        Self: Serializer,
+    {
+        if let Some(pos) = self.get_interned(value) {
+            Ok(pos)
+        } else {
+            let pos = value.serialize_unsized(self)?;
+            self.add_interned(value, pos)?;
+            Ok(pos)
+        }
+    }
+}
+
+/// A registry that tracks serialized data by its archived bytes.
+///
+/// This trait is required to deduplicate data that isn't shared in memory.
+pub trait DedupSerializeRegistry: Fallible {
+    /// Gets the position of previously-added data with the same bytes that is aligned to `align`.
+    ///
+    /// Returns `None` if no such data has been added.
+    fn get_deduped(&self, bytes: &[u8], align: usize) -> Option<usize>;
+
+    /// Adds the position of some data to the registry.
+    fn add_deduped(&mut self, bytes: &[u8], pos: usize) -> Result<(), Self::Error>;
+
+    /// Archives a slice of values contiguously and returns the position of the first archived
+    /// value. If a slice with the same archived bytes has already been added then it returns the
+    /// position of the previously added slice.
+    ///
+    /// The values are archived into scratch space at a fixed position past the end of the archive
+    /// and compared by those bytes, which only depend on what the values point to and not on where
+    /// they're written. Dependencies are serialized before the values can be compared, so values
+    /// with dependencies are only deduplicated when their dependencies are shared or deduplicated
+    /// too. Values are written without being deduplicated if their resolvers need to be dropped or
+    /// the archive is too large to leave room for the fixed position. Empty slices are never
+    /// deduplicated.
+    fn serialize_deduped<T: Serialize<Self>>(&mut self, values: &[T]) -> Result<usize, Self::Error>
+    where
+        Self: Serializer + ScratchSpace,
+    {
+        let layout = Layout::array::<T::Archived>(values.len()).unwrap();
+        if layout.size() == 0 {
+            // Shared pointers to the same position must have the same type to pass validation, so
+            // each empty slice gets a position of its own
+            let pos = self.align(layout.align())?;
+            self.pad(1)?;
+            return Ok(pos);
+        }
+
+        unsafe {
+            let mut resolvers = ScratchVec::new(self, values.len())?;
+            for value in values.iter() {
+                resolvers.push(value.serialize(self)?);
+            }
+
+            // Relative pointers from halfway through the offset range can reach every position
+            // before it, and a power of two is aligned for any values that fit there
+            let max_offset = usize::min(FixedIsize::MAX as usize, isize::MAX as usize);
+            let canonical_pos = max_offset / 2 + 1;
+            let mask = layout.align() - 1;
+            let start = (self.pos() + mask) & !mask;
+
+            // Archiving the values at both positions uses each resolver twice, which is only sound
+            // if resolvers don't need to be dropped
+            let canonical = if !mem::needs_drop::<T::Resolver>()
+                && start < canonical_pos
+                && layout.size() <= max_offset - canonical_pos
+                && layout.align() <= canonical_pos
+            {
+                let scratch = self.push_scratch(layout)?.cast::<u8>();
+                scratch.as_ptr().write_bytes(0, layout.size());
+                let out = scratch.as_ptr().cast::<T::Archived>();
+                for (i, (value, resolver)) in values.iter().zip(resolvers.iter()).enumerate() {
+                    let pos = canonical_pos + i * mem::size_of::<T::Archived>();
+                    value.resolve(pos, ptr::read(resolver), out.add(i));
+                }
+                let bytes = slice::from_raw_parts(scratch.as_ptr(), layout.size());
+
+                if let Some(pos) = self.get_deduped(bytes, layout.align()) {
+                    self.pop_scratch(scratch, layout)?;
+                    resolvers.free(self)?;
+                    return Ok(pos);
+                }
+                Some(scratch)
+            } else {
+                None
+            };
+
+            let pos = self.align(layout.align())?;
+            for (value, resolver) in values.iter().zip(resolvers.drain(..)) {
+                self.resolve_aligned(value, resolver)?;
+            }
+
+            if let Some(scratch) = canonical {
+                self.add_deduped(slice::from_raw_parts(scratch.as_ptr(), layout.size()), pos)?;
+                self.pop_scratch(scratch, layout)?;
+            }
+            resolvers.free(self)?;
+            Ok(pos)
+        }
+    }
+}
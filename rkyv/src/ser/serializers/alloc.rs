@@ -1,5 +1,8 @@
 use crate::{
-    ser::{serializers::BufferScratch, ScratchSpace, Serializer, SharedSerializeRegistry},
+    ser::{
+        serializers::BufferScratch, DedupSerializeRegistry, InternSerializeRegistry, ScratchSpace,
+        Serializer, SharedSerializeRegistry,
+    },
     AlignedBytes, AlignedVec, Archive, ArchiveUnsized, Fallible, RelPtr,
 };
 #[cfg(not(feature = "std"))]
@@ -353,10 +356,13 @@ const _: () = {
     impl Error for SharedSerializeMapError {}
 };
 
-/// An adapter that adds shared serialization support to a serializer.
+/// An adapter that adds shared serialization, string interning, and deduplication support to a
+/// serializer.
 #[derive(Debug)]
 pub struct SharedSerializeMap {
     shared_resolvers: hash_map::HashMap<*const u8, usize>,
+    interned: hash_map::HashMap<Box<str>, usize>,
+    deduped: hash_map::HashMap<Box<[u8]>, usize>,
 }
 
 // SAFETY: SharedSerializeMap is safe to send to another thread
@@ -373,6 +379,8 @@ impl SharedSerializeMap {
     pub fn new() -> Self {
         Self {
             shared_resolvers: hash_map::HashMap::new(),
+            interned: hash_map::HashMap::new(),
+            deduped: hash_map::HashMap::new(),
         }
     }
 }
@@ -405,3 +413,32 @@ impl SharedSerializeRegistry for SharedSerializeMap {
         }
     }
 }
+
+impl InternSerializeRegistry for SharedSerializeMap {
+    #[inline]
+    fn get_interned(&self, value: &str) -> Option<usize> {
+        self.interned.get(value).copied()
+    }
+
+    #[inline]
+    fn add_interned(&mut self, value: &str, pos: usize) -> Result<(), Self::Error> {
+        self.interned.insert(value.into(), pos);
+        Ok(())
+    }
+}
+
+impl DedupSerializeRegistry for SharedSerializeMap {
+    #[inline]
+    fn get_deduped(&self, bytes: &[u8], align: usize) -> Option<usize> {
+        self.deduped
+            .get(bytes)
+            .copied()
+            .filter(|pos| pos & (align - 1) == 0)
+    }
+
+    #[inline]
+    fn add_deduped(&mut self, bytes: &[u8], pos: usize) -> Result<(), Self::Error> {
+        self.deduped.insert(bytes.into(), pos);
+        Ok(())
+    }
+}
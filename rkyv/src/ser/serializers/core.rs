@@ -1,5 +1,9 @@
 use crate::{
-    ser::{ScratchSpace, Serializer},
+    collections::hash_index::HashAlgorithm,
+    ser::{
+        DedupSerializeRegistry, InternSerializeRegistry, ScratchSpace, Serializer,
+        SharedSerializeRegistry,
+    },
     Fallible,
 };
 use core::{
@@ -54,8 +58,8 @@ const _: () = {
 /// # Examples
 /// ```
 /// use rkyv::{
-///     archived_value,
 ///     ser::{Serializer, serializers::BufferSerializer},
+///     unchecked::archived_value,
 ///     AlignedBytes,
 ///     AlignedVec,
 ///     Archive,
@@ -413,3 +417,351 @@ impl<T> From<T> for ScratchTracker<T> {
         Self::new(inner)
     }
 }
+
+/// A passthrough serializer that tracks buffer usage.
+///
+/// Together with [`ScratchTracker`], this can be used to size fixed buffers for serializers like
+/// [`BufferSerializer`] from real workloads.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     ser::{
+///         serializers::{AlignedSerializer, AllocScratch, CompositeSerializer, SerializerTracker},
+///         Serializer,
+///     },
+///     AlignedVec, Infallible,
+/// };
+///
+/// let mut serializer = CompositeSerializer::new(
+///     SerializerTracker::new(AlignedSerializer::new(AlignedVec::new())),
+///     AllocScratch::default(),
+///     Infallible,
+/// );
+/// serializer.serialize_value(&(1u8, vec![1u64, 2, 3])).unwrap();
+///
+/// let tracker = serializer.into_components().0;
+/// assert_eq!(tracker.bytes_written(), tracker.inner().pos());
+/// assert_eq!(tracker.max_alignment(), 8);
+/// ```
+#[derive(Debug)]
+pub struct SerializerTracker<T> {
+    inner: T,
+    bytes_written: usize,
+    padding_bytes: usize,
+    max_padding: usize,
+    max_alignment: usize,
+}
+
+impl<T> SerializerTracker<T> {
+    /// Creates a new serializer tracker from the given inner serializer.
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            bytes_written: 0,
+            padding_bytes: 0,
+            max_padding: 0,
+            max_alignment: 1,
+        }
+    }
+
+    /// Returns a reference to the inner serializer.
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Consumes the tracker and returns the inner serializer.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Returns the total number of bytes written during serialization, including padding.
+    ///
+    /// For serializers that start out empty, this is the size of the final buffer.
+    pub fn bytes_written(&self) -> usize {
+        self.bytes_written
+    }
+
+    /// Returns the total number of bytes written as alignment padding during serialization.
+    pub fn padding_bytes(&self) -> usize {
+        self.padding_bytes
+    }
+
+    /// Returns the largest number of padding bytes written at once during serialization.
+    pub fn max_padding(&self) -> usize {
+        self.max_padding
+    }
+
+    /// Returns the maximum alignment requested during serialization.
+    pub fn max_alignment(&self) -> usize {
+        self.max_alignment
+    }
+}
+
+impl<T: Fallible> Fallible for SerializerTracker<T> {
+    type Error = T::Error;
+}
+
+impl<T: Serializer> Serializer for SerializerTracker<T> {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.inner.pos()
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.inner.write(bytes)?;
+
+        self.bytes_written += bytes.len();
+
+        Ok(())
+    }
+
+    #[inline]
+    fn pad(&mut self, padding: usize) -> Result<(), Self::Error> {
+        self.inner.pad(padding)?;
+
+        self.bytes_written += padding;
+        self.padding_bytes += padding;
+        self.max_padding = usize::max(self.max_padding, padding);
+
+        Ok(())
+    }
+
+    #[inline]
+    fn align(&mut self, align: usize) -> Result<usize, Self::Error> {
+        let mask = align - 1;
+        debug_assert_eq!(align & mask, 0);
+
+        self.max_alignment = usize::max(self.max_alignment, align);
+        self.pad((align - (self.pos() & mask)) & mask)?;
+        Ok(self.pos())
+    }
+
+    #[inline]
+    fn hash_seed(&self) -> u64 {
+        self.inner.hash_seed()
+    }
+
+    #[inline]
+    fn hash_algorithm(&self) -> HashAlgorithm {
+        self.inner.hash_algorithm()
+    }
+}
+
+impl<T: ScratchSpace> ScratchSpace for SerializerTracker<T> {
+    #[inline]
+    unsafe fn push_scratch(&mut self, layout: Layout) -> Result<NonNull<[u8]>, Self::Error> {
+        self.inner.push_scratch(layout)
+    }
+
+    #[inline]
+    unsafe fn pop_scratch(&mut self, ptr: NonNull<u8>, layout: Layout) -> Result<(), Self::Error> {
+        self.inner.pop_scratch(ptr, layout)
+    }
+}
+
+impl<T: SharedSerializeRegistry> SharedSerializeRegistry for SerializerTracker<T> {
+    #[inline]
+    fn get_shared_ptr(&self, value: *const u8) -> Option<usize> {
+        self.inner.get_shared_ptr(value)
+    }
+
+    #[inline]
+    fn add_shared_ptr(&mut self, value: *const u8, pos: usize) -> Result<(), Self::Error> {
+        self.inner.add_shared_ptr(value, pos)
+    }
+}
+
+impl<T: InternSerializeRegistry> InternSerializeRegistry for SerializerTracker<T> {
+    #[inline]
+    fn get_interned(&self, value: &str) -> Option<usize> {
+        self.inner.get_interned(value)
+    }
+
+    #[inline]
+    fn add_interned(&mut self, value: &str, pos: usize) -> Result<(), Self::Error> {
+        self.inner.add_interned(value, pos)
+    }
+}
+
+impl<T: DedupSerializeRegistry> DedupSerializeRegistry for SerializerTracker<T> {
+    #[inline]
+    fn get_deduped(&self, bytes: &[u8], align: usize) -> Option<usize> {
+        self.inner.get_deduped(bytes, align)
+    }
+
+    #[inline]
+    fn add_deduped(&mut self, bytes: &[u8], pos: usize) -> Result<(), Self::Error> {
+        self.inner.add_deduped(bytes, pos)
+    }
+}
+
+impl<T> From<T> for SerializerTracker<T> {
+    fn from(inner: T) -> Self {
+        Self::new(inner)
+    }
+}
+
+/// A passthrough serializer that keys the archived hash indexes it builds with a chosen seed.
+///
+/// Archived hash maps built from keys that an attacker can influence are vulnerable to collision
+/// flooding if the attacker can predict the hash seed. Wrapping the serializer with an unguessable
+/// seed (e.g. from [`SeededSerializer::random`]) prevents this. The seed is stored in the archive,
+/// so archives can be read without knowing it.
+///
+/// The hash algorithm can also be chosen with [`with_algorithm`](SeededSerializer::with_algorithm).
+/// Otherwise, the algorithm of the inner serializer is used.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     archived_root,
+///     ser::{
+///         serializers::{AllocSerializer, SeededSerializer},
+///         Serializer,
+///     },
+/// };
+/// use std::collections::HashMap;
+///
+/// let mut value = HashMap::new();
+/// value.insert("hello".to_string(), 1);
+/// value.insert("world".to_string(), 2);
+///
+/// let mut serializer = SeededSerializer::new(AllocSerializer::<256>::default(), 0x1234);
+/// serializer.serialize_value(&value).unwrap();
+/// let bytes = serializer.into_inner().into_serializer().into_inner();
+///
+/// let archived = unsafe { archived_root::<HashMap<String, i32>>(&bytes) };
+/// assert_eq!(archived.get("hello"), Some(&1));
+/// assert_eq!(archived.get("world"), Some(&2));
+/// ```
+#[derive(Debug)]
+pub struct SeededSerializer<T> {
+    inner: T,
+    seed: u64,
+    algorithm: Option<HashAlgorithm>,
+}
+
+impl<T> SeededSerializer<T> {
+    /// Creates a new seeded serializer from the given inner serializer and seed.
+    #[inline]
+    pub fn new(inner: T, seed: u64) -> Self {
+        Self {
+            inner,
+            seed,
+            algorithm: None,
+        }
+    }
+
+    /// Sets the hash algorithm used for hash indexes.
+    #[inline]
+    pub fn with_algorithm(mut self, algorithm: HashAlgorithm) -> Self {
+        self.algorithm = Some(algorithm);
+        self
+    }
+
+    /// Returns the seed used for hash indexes.
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns a reference to the inner serializer.
+    #[inline]
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Consumes the seeded serializer and returns the inner serializer.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: Fallible> Fallible for SeededSerializer<T> {
+    type Error = T::Error;
+}
+
+impl<T: Serializer> Serializer for SeededSerializer<T> {
+    #[inline]
+    fn pos(&self) -> usize {
+        self.inner.pos()
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.inner.write(bytes)
+    }
+
+    #[inline]
+    fn pad(&mut self, padding: usize) -> Result<(), Self::Error> {
+        self.inner.pad(padding)
+    }
+
+    #[inline]
+    fn align(&mut self, align: usize) -> Result<usize, Self::Error> {
+        self.inner.align(align)
+    }
+
+    #[inline]
+    fn hash_seed(&self) -> u64 {
+        self.seed
+    }
+
+    #[inline]
+    fn hash_algorithm(&self) -> HashAlgorithm {
+        self.algorithm.unwrap_or_else(|| self.inner.hash_algorithm())
+    }
+}
+
+impl<T: ScratchSpace> ScratchSpace for SeededSerializer<T> {
+    #[inline]
+    unsafe fn push_scratch(&mut self, layout: Layout) -> Result<NonNull<[u8]>, Self::Error> {
+        self.inner.push_scratch(layout)
+    }
+
+    #[inline]
+    unsafe fn pop_scratch(&mut self, ptr: NonNull<u8>, layout: Layout) -> Result<(), Self::Error> {
+        self.inner.pop_scratch(ptr, layout)
+    }
+}
+
+impl<T: SharedSerializeRegistry> SharedSerializeRegistry for SeededSerializer<T> {
+    #[inline]
+    fn get_shared_ptr(&self, value: *const u8) -> Option<usize> {
+        self.inner.get_shared_ptr(value)
+    }
+
+    #[inline]
+    fn add_shared_ptr(&mut self, value: *const u8, pos: usize) -> Result<(), Self::Error> {
+        self.inner.add_shared_ptr(value, pos)
+    }
+}
+
+impl<T: InternSerializeRegistry> InternSerializeRegistry for SeededSerializer<T> {
+    #[inline]
+    fn get_interned(&self, value: &str) -> Option<usize> {
+        self.inner.get_interned(value)
+    }
+
+    #[inline]
+    fn add_interned(&mut self, value: &str, pos: usize) -> Result<(), Self::Error> {
+        self.inner.add_interned(value, pos)
+    }
+}
+
+impl<T: DedupSerializeRegistry> DedupSerializeRegistry for SeededSerializer<T> {
+    #[inline]
+    fn get_deduped(&self, bytes: &[u8], align: usize) -> Option<usize> {
+        self.inner.get_deduped(bytes, align)
+    }
+
+    #[inline]
+    fn add_deduped(&mut self, bytes: &[u8], pos: usize) -> Result<(), Self::Error> {
+        self.inner.add_deduped(bytes, pos)
+    }
+}
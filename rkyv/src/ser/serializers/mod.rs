@@ -9,7 +9,11 @@ mod std;
 #[cfg(feature = "alloc")]
 use crate::AlignedVec;
 use crate::{
-    ser::{ScratchSpace, Serializer, SharedSerializeRegistry},
+    collections::hash_index::HashAlgorithm,
+    ser::{
+        DedupSerializeRegistry, InternSerializeRegistry, ScratchSpace, Serializer,
+        SharedSerializeRegistry,
+    },
     AlignedBytes, Archive, ArchiveUnsized, Fallible, Infallible,
 };
 use ::core::{alloc::Layout, fmt, ptr::NonNull};
@@ -174,6 +178,16 @@ impl<S: Serializer, C: Fallible, H: Fallible> Serializer for CompositeSerializer
             .resolve_unsized_aligned(value, to, metadata_resolver)
             .map_err(CompositeSerializerError::SerializerError)
     }
+
+    #[inline]
+    fn hash_seed(&self) -> u64 {
+        self.serializer.hash_seed()
+    }
+
+    #[inline]
+    fn hash_algorithm(&self) -> HashAlgorithm {
+        self.serializer.hash_algorithm()
+    }
 }
 
 impl<S: Fallible, C: ScratchSpace, H: Fallible> ScratchSpace for CompositeSerializer<S, C, H> {
@@ -208,6 +222,38 @@ impl<S: Fallible, C: Fallible, H: SharedSerializeRegistry> SharedSerializeRegist
     }
 }
 
+impl<S: Fallible, C: Fallible, H: InternSerializeRegistry> InternSerializeRegistry
+    for CompositeSerializer<S, C, H>
+{
+    #[inline]
+    fn get_interned(&self, value: &str) -> Option<usize> {
+        self.shared.get_interned(value)
+    }
+
+    #[inline]
+    fn add_interned(&mut self, value: &str, pos: usize) -> Result<(), Self::Error> {
+        self.shared
+            .add_interned(value, pos)
+            .map_err(CompositeSerializerError::SharedError)
+    }
+}
+
+impl<S: Fallible, C: Fallible, H: DedupSerializeRegistry> DedupSerializeRegistry
+    for CompositeSerializer<S, C, H>
+{
+    #[inline]
+    fn get_deduped(&self, bytes: &[u8], align: usize) -> Option<usize> {
+        self.shared.get_deduped(bytes, align)
+    }
+
+    #[inline]
+    fn add_deduped(&mut self, bytes: &[u8], pos: usize) -> Result<(), Self::Error> {
+        self.shared
+            .add_deduped(bytes, pos)
+            .map_err(CompositeSerializerError::SharedError)
+    }
+}
+
 /// A serializer suitable for environments where allocations cannot be made.
 ///
 /// `CoreSerializer` takes two arguments: the amount of serialization memory to allocate and the
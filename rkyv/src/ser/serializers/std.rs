@@ -1,5 +1,20 @@
-use crate::{ser::Serializer, Fallible};
-use std::io;
+use crate::{
+    ser::{
+        serializers::{
+            AlignedSerializer, AllocScratch, AllocSerializer, BufferScratch, CompositeSerializer,
+            FallbackScratch, SeededSerializer, SharedSerializeMap,
+        },
+        Serializer,
+    },
+    AlignedVec, Fallible, Serialize,
+};
+use core::{
+    borrow::{Borrow, BorrowMut},
+    hash::{BuildHasher, Hasher},
+    mem::ManuallyDrop,
+    ops::{Deref, DerefMut},
+};
+use std::{collections::hash_map::RandomState, io, sync::Mutex};
 
 /// Wraps a type that implements [`io::Write`](std::io::Write) and equips it with [`Serializer`].
 ///
@@ -59,3 +74,335 @@ impl<W: io::Write> Serializer for WriteSerializer<W> {
         Ok(())
     }
 }
+
+/// A pool of [`AlignedVec`]s that can be reused across serializations.
+///
+/// Buffers checked out of the pool are returned to it when they are dropped, so services that
+/// serialize many values can reuse the same allocations instead of growing a new buffer for each
+/// one. The pool can be shared between threads.
+///
+/// # Examples
+/// ```
+/// use rkyv::ser::{serializers::BufferPool, Serializer};
+///
+/// let pool = BufferPool::new();
+///
+/// for i in 0..3u32 {
+///     let mut serializer = pool.serializer(1024);
+///     serializer.serialize_value(&i).unwrap();
+///     let buf = serializer.into_serializer().into_inner();
+///     assert_eq!(buf.len(), 4);
+///     // Dropping `buf` returns it to the pool
+/// }
+///
+/// // One buffer for the output and one for scratch space
+/// assert_eq!(pool.len(), 2);
+/// ```
+#[derive(Debug)]
+pub struct BufferPool {
+    buffers: Mutex<Vec<AlignedVec>>,
+    max_buffers: usize,
+}
+
+impl BufferPool {
+    /// Creates a new buffer pool that keeps every buffer returned to it.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_limit(usize::MAX)
+    }
+
+    /// Creates a new buffer pool that keeps at most `max_buffers` idle buffers.
+    ///
+    /// Buffers returned to a full pool are deallocated.
+    #[inline]
+    pub fn with_limit(max_buffers: usize) -> Self {
+        Self {
+            buffers: Mutex::new(Vec::new()),
+            max_buffers,
+        }
+    }
+
+    /// Returns the number of idle buffers in the pool.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns whether the pool has no idle buffers.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks out an empty buffer from the pool, allocating a new one if none are idle.
+    #[inline]
+    pub fn checkout(&self) -> PooledBuffer<'_> {
+        let buffer = self.lock().pop().unwrap_or_default();
+        PooledBuffer {
+            pool: self,
+            buffer: ManuallyDrop::new(buffer),
+        }
+    }
+
+    /// Checks out a buffer of `len` zeroed bytes for use as scratch space.
+    #[inline]
+    pub fn checkout_scratch(&self, len: usize) -> PooledBuffer<'_> {
+        let mut buffer = self.checkout();
+        buffer.resize(len, 0);
+        buffer
+    }
+
+    /// Creates a serializer that writes to a pooled buffer and uses another pooled buffer of
+    /// `scratch_len` bytes for scratch space.
+    ///
+    /// Calling `into_inner` on the underlying [`AlignedSerializer`] returns the pooled buffer
+    /// holding the serialized bytes.
+    #[inline]
+    pub fn serializer(&self, scratch_len: usize) -> PooledSerializer<'_> {
+        CompositeSerializer::new(
+            AlignedSerializer::new(self.checkout()),
+            FallbackScratch::new(
+                BufferScratch::new(self.checkout_scratch(scratch_len)),
+                AllocScratch::new(),
+            ),
+            SharedSerializeMap::new(),
+        )
+    }
+
+    #[inline]
+    fn checkin(&self, mut buffer: AlignedVec) {
+        let mut buffers = self.lock();
+        if buffers.len() < self.max_buffers {
+            buffer.clear();
+            buffers.push(buffer);
+        }
+    }
+
+    #[inline]
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<AlignedVec>> {
+        // The buffers are always in a valid state, so a poisoned lock can still be used
+        self.buffers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for BufferPool {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An [`AlignedVec`] checked out of a [`BufferPool`].
+///
+/// The buffer is returned to its pool when dropped. Use [`into_inner`](PooledBuffer::into_inner)
+/// to keep the buffer instead.
+#[derive(Debug)]
+pub struct PooledBuffer<'a> {
+    pool: &'a BufferPool,
+    buffer: ManuallyDrop<AlignedVec>,
+}
+
+impl<'a> PooledBuffer<'a> {
+    /// Detaches the buffer from its pool and returns it.
+    #[inline]
+    pub fn into_inner(self) -> AlignedVec {
+        let mut this = ManuallyDrop::new(self);
+        unsafe { ManuallyDrop::take(&mut this.buffer) }
+    }
+}
+
+impl Drop for PooledBuffer<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        let buffer = unsafe { ManuallyDrop::take(&mut self.buffer) };
+        self.pool.checkin(buffer);
+    }
+}
+
+impl Deref for PooledBuffer<'_> {
+    type Target = AlignedVec;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.buffer
+    }
+}
+
+impl DerefMut for PooledBuffer<'_> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.buffer
+    }
+}
+
+impl Borrow<AlignedVec> for PooledBuffer<'_> {
+    #[inline]
+    fn borrow(&self) -> &AlignedVec {
+        &self.buffer
+    }
+}
+
+impl BorrowMut<AlignedVec> for PooledBuffer<'_> {
+    #[inline]
+    fn borrow_mut(&mut self) -> &mut AlignedVec {
+        &mut self.buffer
+    }
+}
+
+impl AsRef<[u8]> for PooledBuffer<'_> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.buffer.as_slice()
+    }
+}
+
+impl AsMut<[u8]> for PooledBuffer<'_> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [u8] {
+        self.buffer.as_mut_slice()
+    }
+}
+
+/// A general-purpose serializer that reuses buffers from a [`BufferPool`].
+///
+/// See [`BufferPool::serializer`] for more details.
+pub type PooledSerializer<'a> = CompositeSerializer<
+    AlignedSerializer<PooledBuffer<'a>>,
+    FallbackScratch<BufferScratch<PooledBuffer<'a>>, AllocScratch>,
+    SharedSerializeMap,
+>;
+
+/// A serializer that multiple threads can serialize independent values into at the same time.
+///
+/// Each value is serialized into its own region with an [`AllocSerializer`] with `N` bytes of
+/// scratch space. When serialization is finished, the regions are stitched together into a single
+/// buffer with one root per value. Relative pointers only point within their own region, so the
+/// regions don't need to be fixed up when they are copied.
+///
+/// Shared pointers are only shared within a single region. Values that are serialized separately
+/// but share pointers will each get their own copy.
+///
+/// Regions are placed at multiples of [`AlignedVec::ALIGNMENT`] (16 bytes), which is also the
+/// alignment of the final buffer. Values with archived types that require a larger alignment may
+/// end up misaligned in the stitched buffer and shouldn't be serialized with this serializer.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{ser::serializers::ConcurrentSerializer, unchecked::archived_value};
+/// use std::sync::Arc;
+///
+/// let serializer = Arc::new(ConcurrentSerializer::<256>::new());
+/// let threads = (0..4)
+///     .map(|i| {
+///         let serializer = serializer.clone();
+///         std::thread::spawn(move || serializer.serialize_value(&format!("thread {}", i)).unwrap())
+///     })
+///     .collect::<Vec<_>>();
+/// let ids = threads.into_iter().map(|t| t.join().unwrap()).collect::<Vec<_>>();
+///
+/// let serializer = Arc::try_unwrap(serializer).unwrap();
+/// let (buf, roots) = serializer.finish();
+/// for (i, id) in ids.into_iter().enumerate() {
+///     let archived = unsafe { archived_value::<String>(buf.as_ref(), roots[id]) };
+///     assert_eq!(archived, &format!("thread {}", i));
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ConcurrentSerializer<const N: usize> {
+    regions: Mutex<Vec<(AlignedVec, usize)>>,
+}
+
+impl<const N: usize> ConcurrentSerializer<N> {
+    /// Creates a new concurrent serializer.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            regions: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Returns the number of values that have been serialized.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns whether no values have been serialized.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Serializes a value into its own region.
+    ///
+    /// Returns the index of the value's root in the roots returned from
+    /// [`finish`](ConcurrentSerializer::finish).
+    pub fn serialize_value<T: Serialize<AllocSerializer<N>>>(
+        &self,
+        value: &T,
+    ) -> Result<usize, <AllocSerializer<N> as Fallible>::Error> {
+        let mut serializer = AllocSerializer::<N>::default();
+        let root = serializer.serialize_value(value)?;
+        let bytes = serializer.into_serializer().into_inner();
+
+        let mut regions = self.lock();
+        regions.push((bytes, root));
+        Ok(regions.len() - 1)
+    }
+
+    /// Stitches the serialized regions together.
+    ///
+    /// Returns the buffer and the positions of the roots of each serialized value, in the order
+    /// given by the indices returned from [`serialize_value`](ConcurrentSerializer::serialize_value).
+    pub fn finish(self) -> (AlignedVec, Vec<usize>) {
+        let regions = self
+            .regions
+            .into_inner()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let mask = AlignedVec::ALIGNMENT - 1;
+        let len = regions
+            .iter()
+            .fold(0, |len, (bytes, _)| ((len + mask) & !mask) + bytes.len());
+
+        let mut buf = AlignedVec::with_capacity(len);
+        let mut roots = Vec::with_capacity(regions.len());
+        for (bytes, root) in regions.iter() {
+            // Regions are aligned so that everything inside of them stays aligned
+            buf.resize((buf.len() + mask) & !mask, 0);
+            roots.push(buf.len() + root);
+            buf.extend_from_slice(bytes.as_slice());
+        }
+
+        (buf, roots)
+    }
+
+    #[inline]
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<(AlignedVec, usize)>> {
+        // Regions are only pushed after they're finished, so a poisoned lock can still be used
+        self.regions
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<const N: usize> Default for ConcurrentSerializer<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SeededSerializer<T> {
+    /// Creates a new seeded serializer from the given inner serializer with a random seed.
+    ///
+    /// The seed is drawn from the same source of randomness as the standard library's
+    /// [`HashMap`](std::collections::HashMap).
+    #[inline]
+    pub fn random(inner: T) -> Self {
+        Self::new(inner, RandomState::new().build_hasher().finish())
+    }
+}
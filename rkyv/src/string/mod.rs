@@ -180,6 +180,41 @@ impl PartialEq<ArchivedString> for &str {
     }
 }
 
+impl PartialEq<ArchivedString> for str {
+    #[inline]
+    fn eq(&self, other: &ArchivedString) -> bool {
+        PartialEq::eq(other.as_str(), self)
+    }
+}
+
+impl PartialOrd<&str> for ArchivedString {
+    #[inline]
+    fn partial_cmp(&self, other: &&str) -> Option<cmp::Ordering> {
+        PartialOrd::partial_cmp(self.as_str(), *other)
+    }
+}
+
+impl PartialOrd<str> for ArchivedString {
+    #[inline]
+    fn partial_cmp(&self, other: &str) -> Option<cmp::Ordering> {
+        PartialOrd::partial_cmp(self.as_str(), other)
+    }
+}
+
+impl PartialOrd<ArchivedString> for &str {
+    #[inline]
+    fn partial_cmp(&self, other: &ArchivedString) -> Option<cmp::Ordering> {
+        PartialOrd::partial_cmp(*self, other.as_str())
+    }
+}
+
+impl PartialOrd<ArchivedString> for str {
+    #[inline]
+    fn partial_cmp(&self, other: &ArchivedString) -> Option<cmp::Ordering> {
+        PartialOrd::partial_cmp(self, other.as_str())
+    }
+}
+
 /// The resolver for `String`.
 pub struct StringResolver {
     pos: usize,
@@ -187,10 +222,7 @@ pub struct StringResolver {
 
 #[cfg(feature = "validation")]
 const _: () = {
-    use crate::validation::{
-        owned::OwnedPointerError,
-        ArchiveContext,
-    };
+    use crate::validation::{owned::OwnedPointerError, ArchiveContext};
     use bytecheck::{CheckBytes, Error};
 
     impl<C: ArchiveContext + ?Sized> CheckBytes<C> for ArchivedString
@@ -10,6 +10,9 @@ pub struct ArchivedDuration {
     nanos: Archived<u32>,
 }
 
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(ArchivedDuration { secs: Archived<u64>, nanos: Archived<u32> });
+
 const NANOS_PER_SEC: u32 = 1_000_000_000;
 const NANOS_PER_MILLI: u32 = 1_000_000;
 const NANOS_PER_MICRO: u32 = 1_000;
@@ -111,9 +114,9 @@ impl ArchivedDuration {
 
 #[cfg(feature = "validation")]
 const _: () = {
-    use core::fmt;
     use crate::Fallible;
     use bytecheck::CheckBytes;
+    use core::fmt;
 
     /// An error resulting from an invalid duration.
     ///
@@ -134,14 +137,15 @@ const _: () = {
         type Error = DurationError;
 
         #[inline]
-        unsafe fn check_bytes<'a>(
-            value: *const Self,
-            _: &mut C,
-        ) -> Result<&'a Self, Self::Error> {
+        unsafe fn check_bytes<'a>(value: *const Self, _: &mut C) -> Result<&'a Self, Self::Error> {
             // The fields of `ArchivedDuration` are always valid
             let duration = &*value;
 
-            if duration.secs.checked_add((duration.nanos / 1_000_000_000) as u64).is_none() {
+            if duration
+                .secs
+                .checked_add((duration.nanos / 1_000_000_000) as u64)
+                .is_none()
+            {
                 Err(DurationError)
             } else {
                 Ok(duration)
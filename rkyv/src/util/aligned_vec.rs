@@ -23,7 +23,7 @@ use std::{alloc, io};
 /// type.
 ///
 /// ```
-/// # use rkyv::{archived_value, AlignedBytes, AlignedVec, Archive, Serialize};
+/// # use rkyv::{unchecked::archived_value, AlignedBytes, AlignedVec, Archive, Serialize};
 /// # use rkyv::ser::Serializer;
 /// # use rkyv::ser::serializers::CoreSerializer;
 /// #
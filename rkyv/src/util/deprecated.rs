@@ -0,0 +1,135 @@
+//! Deprecated root re-exports of the [`unchecked`](super::unchecked) functions.
+//!
+//! With the `safe_api` feature, these still forward to the unchecked functions so existing code
+//! keeps compiling, but warn so that callers either validate with
+//! [`check_archived_root`](crate::check_archived_root) or opt out explicitly through
+//! [`rkyv::unchecked`](super::unchecked).
+
+use super::unchecked;
+#[cfg(feature = "alloc")]
+use crate::{de::deserializers::SharedDeserializeMap, Deserialize, Fallible};
+use crate::{Archive, ArchiveUnsized};
+use core::pin::Pin;
+
+/// Casts an archived value from the given byte slice at the given position.
+///
+/// # Safety
+///
+/// See [`unchecked::archived_value`].
+#[deprecated(note = "use `check_archived_value` or `rkyv::unchecked::archived_value` instead")]
+#[inline]
+pub unsafe fn archived_value<T: Archive + ?Sized>(bytes: &[u8], pos: usize) -> &T::Archived {
+    unchecked::archived_value::<T>(bytes, pos)
+}
+
+/// Casts a mutable archived value from the given byte slice at the given position.
+///
+/// # Safety
+///
+/// See [`unchecked::archived_value_mut`].
+#[deprecated(note = "use `rkyv::unchecked::archived_value_mut` instead")]
+#[inline]
+pub unsafe fn archived_value_mut<T: Archive + ?Sized>(
+    bytes: Pin<&mut [u8]>,
+    pos: usize,
+) -> Pin<&mut T::Archived> {
+    unchecked::archived_value_mut::<T>(bytes, pos)
+}
+
+/// Casts a [`RelPtr`](crate::RelPtr) to the given unsized type from the given byte slice at the
+/// given position and returns the value it points to.
+///
+/// # Safety
+///
+/// See [`unchecked::archived_unsized_value`].
+#[deprecated(note = "use `rkyv::unchecked::archived_unsized_value` instead")]
+#[inline]
+pub unsafe fn archived_unsized_value<T: ArchiveUnsized + ?Sized>(
+    bytes: &[u8],
+    pos: usize,
+) -> &T::Archived {
+    unchecked::archived_unsized_value::<T>(bytes, pos)
+}
+
+/// Casts a mutable [`RelPtr`](crate::RelPtr) to the given unsized type from the given byte slice
+/// at the given position and returns the value it points to.
+///
+/// # Safety
+///
+/// See [`unchecked::archived_unsized_value_mut`].
+#[deprecated(note = "use `rkyv::unchecked::archived_unsized_value_mut` instead")]
+#[inline]
+pub unsafe fn archived_unsized_value_mut<T: ArchiveUnsized + ?Sized>(
+    bytes: Pin<&mut [u8]>,
+    pos: usize,
+) -> Pin<&mut T::Archived> {
+    unchecked::archived_unsized_value_mut::<T>(bytes, pos)
+}
+
+/// Casts an archived value from the given byte slice by calculating the root position.
+///
+/// # Safety
+///
+/// See [`unchecked::archived_root`].
+#[deprecated(note = "use `check_archived_root` or `rkyv::unchecked::archived_root` instead")]
+#[inline]
+pub unsafe fn archived_root<T: Archive + ?Sized>(bytes: &[u8]) -> &T::Archived {
+    unchecked::archived_root::<T>(bytes)
+}
+
+/// Casts a mutable archived value from the given byte slice by calculating the root position.
+///
+/// # Safety
+///
+/// See [`unchecked::archived_root_mut`].
+#[deprecated(note = "use `rkyv::unchecked::archived_root_mut` instead")]
+#[inline]
+pub unsafe fn archived_root_mut<T: Archive + ?Sized>(
+    bytes: Pin<&mut [u8]>,
+) -> Pin<&mut T::Archived> {
+    unchecked::archived_root_mut::<T>(bytes)
+}
+
+/// Casts a [`RelPtr`](crate::RelPtr) to the given unsized type from the given byte slice by
+/// calculating the root position.
+///
+/// # Safety
+///
+/// See [`unchecked::archived_unsized_root`].
+#[deprecated(note = "use `rkyv::unchecked::archived_unsized_root` instead")]
+#[inline]
+pub unsafe fn archived_unsized_root<T: ArchiveUnsized + ?Sized>(bytes: &[u8]) -> &T::Archived {
+    unchecked::archived_unsized_root::<T>(bytes)
+}
+
+/// Casts a mutable [`RelPtr`](crate::RelPtr) to the given unsized type from the given byte slice
+/// by calculating the root position.
+///
+/// # Safety
+///
+/// See [`unchecked::archived_unsized_root_mut`].
+#[deprecated(note = "use `rkyv::unchecked::archived_unsized_root_mut` instead")]
+#[inline]
+pub unsafe fn archived_unsized_root_mut<T: ArchiveUnsized + ?Sized>(
+    bytes: Pin<&mut [u8]>,
+) -> Pin<&mut T::Archived> {
+    unchecked::archived_unsized_root_mut::<T>(bytes)
+}
+
+/// Deserializes a value from the given bytes.
+///
+/// # Safety
+///
+/// See [`unchecked::from_bytes_unchecked`].
+#[cfg(feature = "alloc")]
+#[deprecated(note = "use `from_bytes` or `rkyv::unchecked::from_bytes_unchecked` instead")]
+#[inline]
+pub unsafe fn from_bytes_unchecked<T>(
+    bytes: &[u8],
+) -> Result<T, <SharedDeserializeMap as Fallible>::Error>
+where
+    T: Archive,
+    T::Archived: Deserialize<T, SharedDeserializeMap>,
+{
+    unchecked::from_bytes_unchecked::<T>(bytes)
+}
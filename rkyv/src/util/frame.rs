@@ -0,0 +1,387 @@
+use crate::util::AlignedVec;
+#[cfg(feature = "validation")]
+use crate::{
+    validation::validators::{check_archived_root, DefaultValidator},
+    Archive,
+};
+#[cfg(all(feature = "validation", not(feature = "std")))]
+use ::alloc::string::{String, ToString};
+#[cfg(not(feature = "std"))]
+use ::alloc::vec::Vec;
+#[cfg(feature = "validation")]
+use bytecheck::CheckBytes;
+#[cfg(feature = "tokio")]
+use bytes::{Buf, BufMut, BytesMut};
+use core::{convert::TryInto, fmt};
+#[cfg(feature = "tokio")]
+use tokio_util::codec::{Decoder, Encoder};
+
+/// The size of the length prefix of each frame in bytes.
+pub const FRAME_HEADER_SIZE: usize = 4;
+
+/// Errors that can occur while encoding or decoding frames.
+#[derive(Debug)]
+pub enum FrameError {
+    /// The frame was longer than the maximum frame length.
+    FrameTooLong {
+        /// The length of the frame.
+        len: usize,
+        /// The maximum frame length.
+        max: usize,
+    },
+    /// The frame failed validation.
+    #[cfg(feature = "validation")]
+    InvalidArchive(String),
+    /// An IO error occurred while reading or writing frames.
+    #[cfg(feature = "tokio")]
+    Io(std::io::Error),
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::FrameTooLong { len, max } => write!(
+                f,
+                "frame length {} exceeds the maximum frame length of {}",
+                len, max
+            ),
+            #[cfg(feature = "validation")]
+            FrameError::InvalidArchive(error) => write!(f, "invalid archive in frame: {}", error),
+            #[cfg(feature = "tokio")]
+            FrameError::Io(error) => write!(f, "frame IO error: {}", error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FrameError {}
+
+#[cfg(feature = "tokio")]
+impl From<std::io::Error> for FrameError {
+    #[inline]
+    fn from(error: std::io::Error) -> Self {
+        FrameError::Io(error)
+    }
+}
+
+/// Appends an archive to `dst` as a frame prefixed with its length.
+///
+/// The length is written as a little-endian `u32`. Frames can be read back with a
+/// [`FrameDecoder`].
+#[inline]
+pub fn encode_frame(archive: &[u8], dst: &mut Vec<u8>) -> Result<(), FrameError> {
+    let len: u32 = archive
+        .len()
+        .try_into()
+        .map_err(|_| FrameError::FrameTooLong {
+            len: archive.len(),
+            max: u32::MAX as usize,
+        })?;
+
+    dst.reserve(FRAME_HEADER_SIZE + archive.len());
+    dst.extend_from_slice(&len.to_le_bytes());
+    dst.extend_from_slice(archive);
+    Ok(())
+}
+
+/// A decoder that splits a stream of bytes into length-prefixed archives.
+///
+/// Bytes are pushed into the decoder as they arrive, and complete frames are returned as
+/// [`AlignedVec`]s so they can be accessed or validated directly. Frames are written with
+/// [`encode_frame`].
+///
+/// With the `tokio` feature, `FrameCodec` reads and writes the same frames on `Framed` transports.
+///
+/// # Examples
+///
+/// ```
+/// use rkyv::{unchecked::archived_root, util::{encode_frame, FrameDecoder}};
+///
+/// let mut stream = Vec::new();
+/// encode_frame(&rkyv::to_bytes::<_, 256>(&"hello".to_string()).unwrap(), &mut stream).unwrap();
+/// encode_frame(&rkyv::to_bytes::<_, 256>(&"world".to_string()).unwrap(), &mut stream).unwrap();
+///
+/// let mut decoder = FrameDecoder::new();
+/// let mut received = Vec::new();
+/// // Feed the stream in small pieces, like a socket would
+/// for chunk in stream.chunks(3) {
+///     decoder.extend_from_slice(chunk);
+///     while let Some(frame) = decoder.decode_unchecked().unwrap() {
+///         let archived = unsafe { archived_root::<String>(&frame) };
+///         received.push(archived.to_string());
+///     }
+/// }
+///
+/// assert_eq!(received, ["hello", "world"]);
+/// ```
+#[derive(Debug)]
+pub struct FrameDecoder {
+    buffer: Vec<u8>,
+    start: usize,
+    max_frame_len: usize,
+}
+
+impl Default for FrameDecoder {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameDecoder {
+    /// The default maximum frame length, 8 MiB.
+    pub const DEFAULT_MAX_FRAME_LEN: usize = 8 * 1024 * 1024;
+
+    /// Creates a new decoder with the default maximum frame length.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_max_frame_len(Self::DEFAULT_MAX_FRAME_LEN)
+    }
+
+    /// Creates a new decoder that rejects frames longer than `max_frame_len` bytes.
+    #[inline]
+    pub fn with_max_frame_len(max_frame_len: usize) -> Self {
+        Self {
+            buffer: Vec::new(),
+            start: 0,
+            max_frame_len,
+        }
+    }
+
+    /// Returns the maximum frame length of the decoder.
+    #[inline]
+    pub fn max_frame_len(&self) -> usize {
+        self.max_frame_len
+    }
+
+    /// Returns the number of bytes received that have not been decoded yet.
+    #[inline]
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.len() - self.start
+    }
+
+    /// Adds received bytes to the decoder.
+    #[inline]
+    pub fn extend_from_slice(&mut self, bytes: &[u8]) {
+        // Drop decoded frames before growing the buffer
+        if self.start > 0 && self.buffer.len() + bytes.len() > self.buffer.capacity() {
+            self.buffer.drain(..self.start);
+            self.start = 0;
+        }
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Decodes the next complete frame.
+    ///
+    /// Returns `Ok(None)` if more bytes are needed to complete the frame. Once a frame is too long,
+    /// the stream can't be resynchronized and the decoder should be discarded.
+    ///
+    /// This is the same as [`decode_unchecked`](FrameDecoder::decode_unchecked), and is
+    /// deprecated with the `safe_api` feature.
+    #[cfg_attr(
+        feature = "safe_api",
+        deprecated(note = "use `decode_checked` or `decode_unchecked` instead")
+    )]
+    #[inline]
+    pub fn decode(&mut self) -> Result<Option<AlignedVec>, FrameError> {
+        self.decode_unchecked()
+    }
+
+    /// Decodes the next complete frame without validating it.
+    ///
+    /// Returns `Ok(None)` if more bytes are needed to complete the frame. Once a frame is too long,
+    /// the stream can't be resynchronized and the decoder should be discarded. Use `decode_checked`
+    /// for frames that may be untrusted.
+    pub fn decode_unchecked(&mut self) -> Result<Option<AlignedVec>, FrameError> {
+        let available = &self.buffer[self.start..];
+        if available.len() < FRAME_HEADER_SIZE {
+            return Ok(None);
+        }
+
+        let len = u32::from_le_bytes(available[..FRAME_HEADER_SIZE].try_into().unwrap()) as usize;
+        if len > self.max_frame_len {
+            return Err(FrameError::FrameTooLong {
+                len,
+                max: self.max_frame_len,
+            });
+        }
+        if available.len() < FRAME_HEADER_SIZE + len {
+            return Ok(None);
+        }
+
+        let mut frame = AlignedVec::with_capacity(len);
+        frame.extend_from_slice(&available[FRAME_HEADER_SIZE..FRAME_HEADER_SIZE + len]);
+        self.start += FRAME_HEADER_SIZE + len;
+        if self.start == self.buffer.len() {
+            self.buffer.clear();
+            self.start = 0;
+        }
+
+        Ok(Some(frame))
+    }
+
+    /// Decodes the next complete frame and validates it as an archive with a root of type `T`.
+    ///
+    /// Returns `Ok(None)` if more bytes are needed to complete the frame. Frames that fail
+    /// validation are consumed, so decoding can continue with the next frame.
+    #[cfg(feature = "validation")]
+    pub fn decode_checked<T: Archive>(&mut self) -> Result<Option<AlignedVec>, FrameError>
+    where
+        T::Archived: for<'a> CheckBytes<DefaultValidator<'a>>,
+    {
+        let frame = match self.decode_unchecked()? {
+            Some(frame) => frame,
+            None => return Ok(None),
+        };
+        check_archived_root::<T>(&frame)
+            .map_err(|error| FrameError::InvalidArchive(error.to_string()))?;
+        Ok(Some(frame))
+    }
+}
+
+#[cfg(feature = "tokio")]
+type CheckFrame = fn(&[u8]) -> Result<(), FrameError>;
+
+#[cfg(all(feature = "tokio", feature = "validation"))]
+fn check_frame<T: Archive>(frame: &[u8]) -> Result<(), FrameError>
+where
+    T::Archived: for<'a> CheckBytes<DefaultValidator<'a>>,
+{
+    check_archived_root::<T>(frame)
+        .map(|_| ())
+        .map_err(|error| FrameError::InvalidArchive(error.to_string()))
+}
+
+/// A `tokio-util` codec that frames archives with a length prefix.
+///
+/// The frames are the same as the ones written by [`encode_frame`] and read by [`FrameDecoder`], so
+/// it can be used with `Framed` transports. Decoded frames are copied out of the receive buffer
+/// into [`AlignedVec`]s so they're aligned no matter where they were received. With the
+/// `validation` feature, `checked` makes the codec validate every frame it decodes.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::BytesMut;
+/// use rkyv::{archived_root, util::FrameCodec};
+/// use tokio_util::codec::{Decoder, Encoder};
+///
+/// let mut codec = FrameCodec::new();
+/// let mut buf = BytesMut::new();
+/// let archive = rkyv::to_bytes::<_, 256>(&"hello".to_string()).unwrap();
+/// codec.encode(archive.as_slice(), &mut buf).unwrap();
+///
+/// let frame = codec.decode(&mut buf).unwrap().unwrap();
+/// let archived = unsafe { archived_root::<String>(&frame) };
+/// assert_eq!(archived, "hello");
+/// ```
+#[cfg(feature = "tokio")]
+#[derive(Clone, Debug)]
+pub struct FrameCodec {
+    max_frame_len: usize,
+    check: Option<CheckFrame>,
+}
+
+#[cfg(feature = "tokio")]
+impl Default for FrameCodec {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl FrameCodec {
+    /// Creates a new codec with the default maximum frame length.
+    #[inline]
+    pub fn new() -> Self {
+        Self::with_max_frame_len(FrameDecoder::DEFAULT_MAX_FRAME_LEN)
+    }
+
+    /// Creates a new codec that rejects frames longer than `max_frame_len` bytes.
+    #[inline]
+    pub fn with_max_frame_len(max_frame_len: usize) -> Self {
+        Self {
+            max_frame_len,
+            check: None,
+        }
+    }
+
+    /// Makes the codec validate each decoded frame as an archive with a root of type `T`.
+    ///
+    /// A frame that fails validation is returned as a [`FrameError::InvalidArchive`] error.
+    /// `Framed` streams end after their codec returns an error, so a single invalid frame closes
+    /// the stream. To skip invalid frames and keep reading, use an unchecked codec and validate
+    /// each frame with [`check_archived_root`](crate::check_archived_root) instead.
+    #[cfg(feature = "validation")]
+    #[inline]
+    pub fn checked<T: Archive>(mut self) -> Self
+    where
+        T::Archived: for<'a> CheckBytes<DefaultValidator<'a>>,
+    {
+        self.check = Some(check_frame::<T>);
+        self
+    }
+
+    /// Returns the maximum frame length of the codec.
+    #[inline]
+    pub fn max_frame_len(&self) -> usize {
+        self.max_frame_len
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Decoder for FrameCodec {
+    type Item = AlignedVec;
+    type Error = FrameError;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<AlignedVec>, FrameError> {
+        if src.len() < FRAME_HEADER_SIZE {
+            return Ok(None);
+        }
+
+        let len = u32::from_le_bytes(src[..FRAME_HEADER_SIZE].try_into().unwrap()) as usize;
+        if len > self.max_frame_len {
+            return Err(FrameError::FrameTooLong {
+                len,
+                max: self.max_frame_len,
+            });
+        }
+        if src.len() < FRAME_HEADER_SIZE + len {
+            // Make room for the rest of the frame so it can be received in one read
+            src.reserve(FRAME_HEADER_SIZE + len - src.len());
+            return Ok(None);
+        }
+
+        src.advance(FRAME_HEADER_SIZE);
+        let bytes = src.split_to(len);
+        let mut frame = AlignedVec::with_capacity(len);
+        frame.extend_from_slice(&bytes);
+        if let Some(check) = self.check {
+            check(&frame)?;
+        }
+
+        Ok(Some(frame))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<B: AsRef<[u8]>> Encoder<B> for FrameCodec {
+    type Error = FrameError;
+
+    fn encode(&mut self, archive: B, dst: &mut BytesMut) -> Result<(), FrameError> {
+        let archive = archive.as_ref();
+        let max = usize::min(self.max_frame_len, u32::MAX as usize);
+        if archive.len() > max {
+            return Err(FrameError::FrameTooLong {
+                len: archive.len(),
+                max,
+            });
+        }
+
+        dst.reserve(FRAME_HEADER_SIZE + archive.len());
+        dst.put_u32_le(archive.len() as u32);
+        dst.extend_from_slice(archive);
+        Ok(())
+    }
+}
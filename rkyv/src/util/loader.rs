@@ -0,0 +1,331 @@
+use crate::util::AlignedVec;
+#[cfg(feature = "validation")]
+use crate::{
+    validation::validators::{check_archived_root, check_archived_value, DefaultValidator},
+    Archive,
+};
+#[cfg(all(feature = "validation", not(feature = "std")))]
+use ::alloc::string::{String, ToString};
+#[cfg(feature = "validation")]
+use bytecheck::CheckBytes;
+use core::fmt;
+#[cfg(feature = "tokio")]
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// Errors that can occur while loading an archive.
+#[derive(Debug)]
+pub enum LoadError {
+    /// The archive was finished before all of its bytes were received.
+    Incomplete {
+        /// The number of bytes received.
+        received: usize,
+        /// The length of the archive.
+        len: usize,
+    },
+    /// The archive failed validation.
+    #[cfg(feature = "validation")]
+    InvalidArchive(String),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Incomplete { received, len } => write!(
+                f,
+                "archive is incomplete: received {} of {} bytes",
+                received, len
+            ),
+            #[cfg(feature = "validation")]
+            LoadError::InvalidArchive(error) => write!(f, "invalid archive: {}", error),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LoadError {}
+
+/// A loader that receives an archive of a known length in pieces.
+///
+/// The loader owns an [`AlignedVec`] sized for the whole archive, and exposes the part that hasn't
+/// been received yet through [`unfilled_mut`](ArchiveLoader::unfilled_mut) so readers can write
+/// into it directly. With the `tokio` feature, `read_from_async` fills it from a tokio
+/// `AsyncRead`.
+///
+/// The root is written last, so the archive as a whole can only be validated once it's complete.
+/// Serializers write the dependencies of a value before the value itself though, so values at
+/// known positions (for example, the positions returned by `serialize_value` for each record in a
+/// stream) can be validated with `check_received` as soon as their bytes arrive. Large archives
+/// can be validated without blocking an async runtime with
+/// `validation::chunked::ChunkedValidator::check_async`.
+///
+/// # Examples
+///
+/// ```
+/// use rkyv::{unchecked::archived_root, util::ArchiveLoader};
+///
+/// let bytes = rkyv::to_bytes::<_, 256>(&vec![1, 2, 3, 4]).unwrap();
+///
+/// let mut loader = ArchiveLoader::new(bytes.len());
+/// // Receive the archive in small pieces, like a socket would
+/// for chunk in bytes.chunks(3) {
+///     let unfilled = loader.unfilled_mut();
+///     unfilled[..chunk.len()].copy_from_slice(chunk);
+///     loader.advance(chunk.len());
+/// }
+/// assert!(loader.is_complete());
+///
+/// let buf = loader.finish_unchecked().unwrap();
+/// let archived = unsafe { archived_root::<Vec<i32>>(&buf) };
+/// assert_eq!(archived.as_slice(), &[1, 2, 3, 4]);
+/// ```
+#[derive(Debug)]
+pub struct ArchiveLoader {
+    buffer: AlignedVec,
+    received: usize,
+}
+
+impl ArchiveLoader {
+    /// Creates a new loader for an archive that is `len` bytes long.
+    #[inline]
+    pub fn new(len: usize) -> Self {
+        let mut buffer = AlignedVec::with_capacity(len);
+        buffer.resize(len, 0);
+        Self {
+            buffer,
+            received: 0,
+        }
+    }
+
+    /// Returns the length of the archive.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns whether the archive is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Returns the number of bytes received so far.
+    #[inline]
+    pub fn received(&self) -> usize {
+        self.received
+    }
+
+    /// Returns whether the whole archive has been received.
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.received == self.buffer.len()
+    }
+
+    /// Returns the bytes received so far.
+    #[inline]
+    pub fn received_bytes(&self) -> &[u8] {
+        &self.buffer[..self.received]
+    }
+
+    /// Returns the part of the buffer that hasn't been received yet.
+    ///
+    /// After writing received bytes to the start of this slice, call
+    /// [`advance`](ArchiveLoader::advance) with the number of bytes written.
+    #[inline]
+    pub fn unfilled_mut(&mut self) -> &mut [u8] {
+        &mut self.buffer[self.received..]
+    }
+
+    /// Marks the next `count` bytes of the buffer as received.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is greater than the length of the unfilled part of the buffer.
+    #[inline]
+    pub fn advance(&mut self, count: usize) {
+        assert!(
+            count <= self.buffer.len() - self.received,
+            "advanced past the end of the archive"
+        );
+        self.received += count;
+    }
+
+    /// Copies as many bytes as still fit in the archive from `bytes`, returning the number copied.
+    #[inline]
+    pub fn extend_from_slice(&mut self, bytes: &[u8]) -> usize {
+        let unfilled = self.unfilled_mut();
+        let count = unfilled.len().min(bytes.len());
+        unfilled[..count].copy_from_slice(&bytes[..count]);
+        self.received += count;
+        count
+    }
+
+    /// Reads the next part of the archive from a reader, returning the number of bytes read.
+    ///
+    /// Returns an error of kind [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) if the reader
+    /// ends before the archive is complete.
+    #[cfg(feature = "std")]
+    pub fn read_from<R: io::Read + ?Sized>(&mut self, reader: &mut R) -> io::Result<usize> {
+        if self.is_complete() {
+            return Ok(0);
+        }
+        let count = reader.read(self.unfilled_mut())?;
+        if count == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        self.advance(count);
+        Ok(count)
+    }
+
+    /// Attempts to read the next part of the archive from an async reader, returning the number of
+    /// bytes read.
+    ///
+    /// Returns an error of kind [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) if the reader
+    /// ends before the archive is complete.
+    #[cfg(feature = "tokio")]
+    pub fn poll_read_from<R: AsyncRead + ?Sized>(
+        &mut self,
+        cx: &mut Context<'_>,
+        reader: Pin<&mut R>,
+    ) -> Poll<io::Result<usize>> {
+        if self.is_complete() {
+            return Poll::Ready(Ok(0));
+        }
+        let mut buf = ReadBuf::new(self.unfilled_mut());
+        match reader.poll_read(cx, &mut buf) {
+            Poll::Ready(Ok(())) => {
+                let count = buf.filled().len();
+                if count == 0 {
+                    Poll::Ready(Err(io::ErrorKind::UnexpectedEof.into()))
+                } else {
+                    self.advance(count);
+                    Poll::Ready(Ok(count))
+                }
+            }
+            Poll::Ready(Err(error)) => Poll::Ready(Err(error)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    /// Reads the next part of the archive from an async reader, returning the number of bytes
+    /// read.
+    ///
+    /// This is the async version of [`read_from`](ArchiveLoader::read_from).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rkyv::{util::ArchiveLoader, AlignedVec};
+    /// use tokio::io::AsyncRead;
+    ///
+    /// async fn load<R: AsyncRead + Unpin>(
+    ///     reader: &mut R,
+    ///     len: usize,
+    /// ) -> std::io::Result<AlignedVec> {
+    ///     let mut loader = ArchiveLoader::new(len);
+    ///     while !loader.is_complete() {
+    ///         loader.read_from_async(reader).await?;
+    ///     }
+    ///     Ok(loader.finish_unchecked().unwrap())
+    /// }
+    /// ```
+    #[cfg(feature = "tokio")]
+    #[inline]
+    pub fn read_from_async<'a, R: AsyncRead + Unpin + ?Sized>(
+        &'a mut self,
+        reader: &'a mut R,
+    ) -> ReadFrom<'a, R> {
+        ReadFrom {
+            loader: self,
+            reader,
+        }
+    }
+
+    /// Validates a value of type `T` at `pos` against the bytes received so far.
+    ///
+    /// Returns `Ok(None)` if the value itself hasn't been fully received yet. The value may only
+    /// point to bytes that have already been received, which is the case for values written by a
+    /// serializer since it writes the dependencies of a value first.
+    #[cfg(feature = "validation")]
+    pub fn check_received<T: Archive>(&self, pos: usize) -> Result<Option<&T::Archived>, LoadError>
+    where
+        T::Archived: for<'a> CheckBytes<DefaultValidator<'a>>,
+    {
+        match pos.checked_add(core::mem::size_of::<T::Archived>()) {
+            Some(end) if end <= self.received => {
+                check_archived_value::<T>(self.received_bytes(), pos)
+                    .map(Some)
+                    .map_err(|error| LoadError::InvalidArchive(error.to_string()))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Finishes loading and returns the archive.
+    ///
+    /// This is the same as [`finish_unchecked`](ArchiveLoader::finish_unchecked), and is
+    /// deprecated with the `safe_api` feature.
+    #[cfg_attr(
+        feature = "safe_api",
+        deprecated(note = "use `finish_checked` or `finish_unchecked` instead")
+    )]
+    #[inline]
+    pub fn finish(self) -> Result<AlignedVec, LoadError> {
+        self.finish_unchecked()
+    }
+
+    /// Finishes loading and returns the archive without validating it.
+    ///
+    /// Use `finish_checked` for archives that may be untrusted.
+    #[inline]
+    pub fn finish_unchecked(self) -> Result<AlignedVec, LoadError> {
+        if self.is_complete() {
+            Ok(self.buffer)
+        } else {
+            Err(LoadError::Incomplete {
+                received: self.received,
+                len: self.buffer.len(),
+            })
+        }
+    }
+
+    /// Finishes loading and validates the archive as having a root of type `T`.
+    #[cfg(feature = "validation")]
+    pub fn finish_checked<T: Archive>(self) -> Result<AlignedVec, LoadError>
+    where
+        T::Archived: for<'a> CheckBytes<DefaultValidator<'a>>,
+    {
+        let buffer = self.finish_unchecked()?;
+        check_archived_root::<T>(&buffer)
+            .map_err(|error| LoadError::InvalidArchive(error.to_string()))?;
+        Ok(buffer)
+    }
+}
+
+/// A future that reads the next part of an archive from an async reader.
+///
+/// This is returned by [`ArchiveLoader::read_from_async`].
+#[cfg(feature = "tokio")]
+#[derive(Debug)]
+pub struct ReadFrom<'a, R: ?Sized> {
+    loader: &'a mut ArchiveLoader,
+    reader: &'a mut R,
+}
+
+#[cfg(feature = "tokio")]
+impl<R: AsyncRead + Unpin + ?Sized> Future for ReadFrom<'_, R> {
+    type Output = io::Result<usize>;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        this.loader.poll_read_from(cx, Pin::new(&mut *this.reader))
+    }
+}
@@ -0,0 +1,241 @@
+//! Incremental validation of archives with a root vector.
+
+use crate::{
+    validation::{
+        owned::OwnedPointerError, validators::DefaultValidator, ArchiveContext, CheckArchiveError,
+        CheckTypeError,
+    },
+    vec::ArchivedVec,
+    Archive, RelPtr,
+};
+use bytecheck::{CheckBytes, Error, SliceCheckError};
+use core::{
+    fmt,
+    future::Future,
+    mem::size_of,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// The error type returned by a [`ChunkedValidator`] for a root of type `Vec<T>`.
+pub type CheckChunkedError<T, C> = CheckTypeError<ArchivedVec<<T as Archive>::Archived>, C>;
+
+/// A validator that checks an archive with a root of type `Vec<T>` a few elements at a time.
+///
+/// Validating a large archive in one call can take a long time, which blocks the thread doing it.
+/// A `ChunkedValidator` performs the same checks as
+/// [`check_archived_root`](crate::validation::validators::check_archived_root) for `Vec<T>`, but
+/// splits the work into calls to [`check_next`](ChunkedValidator::check_next) so the caller can
+/// yield between them (for example, back to an async runtime). The archived vector is only
+/// returned once every element has been validated.
+///
+/// # Examples
+///
+/// ```
+/// use rkyv::validation::chunked::ChunkedValidator;
+///
+/// let value = (0..1000).map(|i| i.to_string()).collect::<Vec<_>>();
+/// let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+///
+/// let mut validator = ChunkedValidator::<String, _>::new(&bytes).unwrap();
+/// while !validator.check_next(100).unwrap() {
+///     // Yield to the runtime here
+/// }
+/// let archived = validator.finish().unwrap();
+/// assert_eq!(archived.len(), 1000);
+/// assert_eq!(archived[999], "999");
+/// ```
+///
+/// On an async runtime, [`check_async`](ChunkedValidator::check_async) does the same loop and
+/// yields to the executor between chunks.
+pub struct ChunkedValidator<'a, T: Archive, C: ArchiveContext> {
+    root: &'a [u8],
+    value: *const ArchivedVec<T::Archived>,
+    elements: *const T::Archived,
+    len: usize,
+    checked: usize,
+    root_range: Option<C::PrefixRange>,
+    elements_range: Option<C::PrefixRange>,
+    context: C,
+}
+
+impl<'a, T: Archive> ChunkedValidator<'a, T, DefaultValidator<'a>>
+where
+    T::Archived: CheckBytes<DefaultValidator<'a>>,
+{
+    /// Starts validating the given archive with the default validator.
+    #[inline]
+    pub fn new(buf: &'a [u8]) -> Result<Self, CheckChunkedError<T, DefaultValidator<'a>>> {
+        Self::with_context(buf, DefaultValidator::new(buf))
+    }
+}
+
+impl<'a, T, C> ChunkedValidator<'a, T, C>
+where
+    T: Archive,
+    T::Archived: CheckBytes<C>,
+    C: ArchiveContext,
+    C::Error: Error,
+{
+    /// Starts validating the given archive with a context.
+    ///
+    /// This checks the root vector itself but none of its elements.
+    pub fn with_context(buf: &'a [u8], mut context: C) -> Result<Self, CheckChunkedError<T, C>> {
+        let pos = buf.len() as isize - size_of::<ArchivedVec<T::Archived>>() as isize;
+        unsafe {
+            let value: *const ArchivedVec<T::Archived> = context
+                .check_subtree_ptr(buf.as_ptr(), pos, ())
+                .map_err(CheckArchiveError::ContextError)?;
+            let root_range = context
+                .push_prefix_subtree(value)
+                .map_err(CheckArchiveError::ContextError)?;
+
+            let rel_ptr = RelPtr::<[T::Archived]>::manual_check_bytes(value.cast(), &mut context)
+                .map_err(|e| {
+                CheckArchiveError::CheckBytesError(OwnedPointerError::PointerCheckBytesError(e))
+            })?;
+            let ptr = context.check_subtree_rel_ptr(rel_ptr).map_err(|e| {
+                CheckArchiveError::CheckBytesError(OwnedPointerError::ContextError(e))
+            })?;
+            let elements_range = context.push_prefix_subtree(ptr).map_err(|e| {
+                CheckArchiveError::CheckBytesError(OwnedPointerError::ContextError(e))
+            })?;
+
+            Ok(Self {
+                root: buf,
+                value,
+                elements: ptr.cast(),
+                len: ptr_meta::metadata(ptr),
+                checked: 0,
+                root_range: Some(root_range),
+                elements_range: Some(elements_range),
+                context,
+            })
+        }
+    }
+
+    /// Validates up to `count` more elements of the root vector.
+    ///
+    /// Returns whether all of the elements have been validated.
+    pub fn check_next(&mut self, count: usize) -> Result<bool, CheckChunkedError<T, C>> {
+        let end = self.len.min(self.checked.saturating_add(count));
+        while self.checked < end {
+            let index = self.checked;
+            unsafe {
+                T::Archived::check_bytes(self.elements.add(index), &mut self.context).map_err(
+                    |error| {
+                        CheckArchiveError::CheckBytesError(OwnedPointerError::ValueCheckBytesError(
+                            SliceCheckError::CheckBytes { index, error },
+                        ))
+                    },
+                )?;
+            }
+            self.checked += 1;
+        }
+        Ok(self.checked == self.len)
+    }
+
+    /// Validates any remaining elements and finishes validating the archive.
+    pub fn finish(mut self) -> Result<&'a ArchivedVec<T::Archived>, CheckChunkedError<T, C>> {
+        self.check_next(usize::MAX)?;
+
+        if let Some(range) = self.elements_range.take() {
+            self.context.pop_prefix_range(range).map_err(|e| {
+                CheckArchiveError::CheckBytesError(OwnedPointerError::ContextError(e))
+            })?;
+        }
+        if let Some(range) = self.root_range.take() {
+            self.context
+                .pop_prefix_range(range)
+                .map_err(CheckArchiveError::ContextError)?;
+        }
+        self.context
+            .finish()
+            .map_err(CheckArchiveError::ContextError)?;
+
+        Ok(unsafe { &*self.value })
+    }
+
+    /// Validates the archive `chunk_len` elements at a time, yielding to the executor between
+    /// chunks.
+    ///
+    /// The returned future works with any executor. It is `Send` whenever the context is, so it can
+    /// be spawned on multithreaded runtimes.
+    pub async fn check_async(
+        mut self,
+        chunk_len: usize,
+    ) -> Result<&'a ArchivedVec<T::Archived>, CheckChunkedError<T, C>>
+    where
+        T::Archived: 'a,
+    {
+        while !self.check_next(chunk_len)? {
+            YieldNow(false).await;
+        }
+        self.finish()
+    }
+}
+
+// SAFETY: ChunkedValidator is safe to send to another thread if its context is and the archived
+// elements it points to can be shared between threads
+// This trait is not automatically implemented because the struct contains pointers
+unsafe impl<'a, T, C> Send for ChunkedValidator<'a, T, C>
+where
+    T: Archive,
+    T::Archived: Sync,
+    C: ArchiveContext + Send,
+    C::PrefixRange: Send,
+{
+}
+
+/// A future that returns control to the executor once before completing.
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    #[inline]
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+impl<'a, T: Archive, C: ArchiveContext> ChunkedValidator<'a, T, C> {
+    /// Returns the archive being validated.
+    #[inline]
+    pub fn archive(&self) -> &'a [u8] {
+        self.root
+    }
+
+    /// Returns the number of elements in the root vector.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the root vector is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of elements that have been validated so far.
+    #[inline]
+    pub fn checked(&self) -> usize {
+        self.checked
+    }
+}
+
+impl<'a, T: Archive, C: ArchiveContext> fmt::Debug for ChunkedValidator<'a, T, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChunkedValidator")
+            .field("len", &self.len)
+            .field("checked", &self.checked)
+            .finish()
+    }
+}
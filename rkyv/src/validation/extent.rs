@@ -0,0 +1,281 @@
+//! Byte ranges of nested archived values.
+
+use crate::{
+    validation::{
+        validators::DefaultValidator, ArchiveContext, CheckArchiveError, CheckTypeError,
+        SharedContext,
+    },
+    AlignedVec, Fallible,
+};
+#[cfg(not(feature = "std"))]
+use ::alloc::vec::Vec;
+use bytecheck::CheckBytes;
+use core::{alloc::Layout, any::TypeId, fmt, ops::Range};
+
+/// A validation context that records the bytes of the archive that are claimed while checking.
+///
+/// Every object that a value owns, including out-of-line data behind relative pointers and shared
+/// pointers, is claimed as a subtree range during validation. `ExtentValidator` wraps another
+/// context and records each of those ranges.
+pub struct ExtentValidator<'a, C> {
+    buf: &'a [u8],
+    inner: C,
+    ranges: Vec<Range<usize>>,
+}
+
+impl<'a, C> ExtentValidator<'a, C> {
+    /// Wraps the given context, recording claimed ranges relative to the start of `buf`.
+    #[inline]
+    pub fn new(buf: &'a [u8], inner: C) -> Self {
+        Self {
+            buf,
+            inner,
+            ranges: Vec::new(),
+        }
+    }
+
+    /// Consumes the validator, returning the wrapped context.
+    #[inline]
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+
+    /// Returns the recorded ranges, in the order they were claimed.
+    #[inline]
+    pub fn ranges(&self) -> &[Range<usize>] {
+        &self.ranges
+    }
+}
+
+impl<C: fmt::Debug> fmt::Debug for ExtentValidator<'_, C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExtentValidator")
+            .field("inner", &self.inner)
+            .field("ranges", &self.ranges)
+            .finish()
+    }
+}
+
+impl<C: Fallible> Fallible for ExtentValidator<'_, C> {
+    type Error = C::Error;
+}
+
+impl<C: ArchiveContext> ArchiveContext for ExtentValidator<'_, C> {
+    type PrefixRange = C::PrefixRange;
+    type SuffixRange = C::SuffixRange;
+
+    #[inline]
+    unsafe fn bounds_check_ptr(
+        &mut self,
+        base: *const u8,
+        offset: isize,
+    ) -> Result<*const u8, Self::Error> {
+        self.inner.bounds_check_ptr(base, offset)
+    }
+
+    #[inline]
+    unsafe fn bounds_check_layout(
+        &mut self,
+        data_address: *const u8,
+        layout: &Layout,
+    ) -> Result<(), Self::Error> {
+        self.inner.bounds_check_layout(data_address, layout)
+    }
+
+    #[inline]
+    unsafe fn bounds_check_subtree_ptr_layout(
+        &mut self,
+        data_address: *const u8,
+        layout: &Layout,
+    ) -> Result<(), Self::Error> {
+        self.inner
+            .bounds_check_subtree_ptr_layout(data_address, layout)?;
+        if layout.size() != 0 {
+            let start = data_address as usize - self.buf.as_ptr() as usize;
+            self.ranges.push(start..start + layout.size());
+        }
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn push_prefix_subtree_range(
+        &mut self,
+        root: *const u8,
+        end: *const u8,
+    ) -> Result<Self::PrefixRange, Self::Error> {
+        self.inner.push_prefix_subtree_range(root, end)
+    }
+
+    #[inline]
+    fn pop_prefix_range(&mut self, range: Self::PrefixRange) -> Result<(), Self::Error> {
+        self.inner.pop_prefix_range(range)
+    }
+
+    #[inline]
+    unsafe fn push_suffix_subtree_range(
+        &mut self,
+        start: *const u8,
+        root: *const u8,
+    ) -> Result<Self::SuffixRange, Self::Error> {
+        self.inner.push_suffix_subtree_range(start, root)
+    }
+
+    #[inline]
+    fn pop_suffix_range(&mut self, range: Self::SuffixRange) -> Result<(), Self::Error> {
+        self.inner.pop_suffix_range(range)
+    }
+
+    #[inline]
+    fn finish(&mut self) -> Result<(), Self::Error> {
+        self.inner.finish()
+    }
+}
+
+impl<C: SharedContext> SharedContext for ExtentValidator<'_, C> {
+    #[inline]
+    fn register_shared_ptr(
+        &mut self,
+        ptr: *const u8,
+        type_id: TypeId,
+    ) -> Result<bool, Self::Error> {
+        self.inner.register_shared_ptr(ptr, type_id)
+    }
+}
+
+/// The byte ranges that an archived value occupies within its archive.
+#[derive(Debug)]
+pub struct Extents<'a> {
+    buf: &'a [u8],
+    root: Range<usize>,
+    ranges: Vec<Range<usize>>,
+}
+
+impl<'a> Extents<'a> {
+    /// Returns the range of the value itself, not including any out-of-line data it owns.
+    #[inline]
+    pub fn root(&self) -> Range<usize> {
+        self.root.clone()
+    }
+
+    /// Returns the sorted, non-overlapping ranges of the value and all of the data it owns.
+    ///
+    /// Adjacent ranges are merged, so any padding between owned objects separates ranges.
+    #[inline]
+    pub fn ranges(&self) -> &[Range<usize>] {
+        &self.ranges
+    }
+
+    /// Returns the smallest range that contains the value and all of the data it owns.
+    #[inline]
+    pub fn span(&self) -> Range<usize> {
+        self.ranges[0].start..self.ranges[self.ranges.len() - 1].end
+    }
+
+    /// Returns the total number of bytes owned by the value.
+    #[inline]
+    pub fn total_len(&self) -> usize {
+        self.ranges.iter().map(|range| range.len()).sum()
+    }
+
+    /// Returns whether the value and all of the data it owns are stored in one contiguous range.
+    #[inline]
+    pub fn is_contiguous(&self) -> bool {
+        self.ranges.len() == 1
+    }
+
+    /// Copies the value and all of the data it owns out into an independent archive.
+    ///
+    /// Values are always serialized after the data they own, so the value is the root of the new
+    /// archive. The copy starts on an [`AlignedVec::ALIGNMENT`] boundary of the original archive so
+    /// that every object stays aligned, and may include some bytes before the value's data that
+    /// aren't owned by it.
+    pub fn to_archive(&self) -> AlignedVec {
+        let span = self.span();
+        let start = span.start & !(AlignedVec::ALIGNMENT - 1);
+        let mut result = AlignedVec::with_capacity(self.root.end - start);
+        result.extend_from_slice(&self.buf[start..self.root.end]);
+        result
+    }
+}
+
+/// Finds the byte ranges that `value` and all of the data it owns occupy within `buf`.
+///
+/// `value` must be located inside of `buf`. It and all of the data it owns are validated with the
+/// default validator while their ranges are recorded.
+///
+/// # Examples
+///
+/// ```
+/// use rkyv::{
+///     unchecked::archived_root,
+///     validation::extent::archived_extents,
+///     Archive, Serialize,
+/// };
+/// use bytecheck::CheckBytes;
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive_attr(derive(CheckBytes))]
+/// struct Example {
+///     id: u32,
+///     names: Vec<String>,
+///     tags: Vec<String>,
+/// }
+///
+/// let value = Example {
+///     id: 42,
+///     names: vec!["alice".to_string(), "a name longer than eight bytes".to_string()],
+///     tags: vec!["tagged".to_string()],
+/// };
+/// let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+/// let archived = unsafe { archived_root::<Example>(&bytes) };
+///
+/// let extents = archived_extents(&bytes, &archived.names).unwrap();
+/// assert!(extents.span().end <= extents.root().end);
+///
+/// let names = extents.to_archive();
+/// let names = unsafe { archived_root::<Vec<String>>(&names) };
+/// assert_eq!(names[1], "a name longer than eight bytes");
+/// ```
+pub fn archived_extents<'a, T>(
+    buf: &'a [u8],
+    value: &T,
+) -> Result<Extents<'a>, CheckTypeError<T, ExtentValidator<'a, DefaultValidator<'a>>>>
+where
+    T: CheckBytes<ExtentValidator<'a, DefaultValidator<'a>>>,
+{
+    let mut context = ExtentValidator::new(buf, DefaultValidator::new(buf));
+    let pos = value as *const T as isize - buf.as_ptr() as isize;
+    unsafe {
+        let ptr = context
+            .check_subtree_ptr::<T>(buf.as_ptr(), pos, ())
+            .map_err(CheckArchiveError::ContextError)?;
+        let range = context
+            .push_prefix_subtree(ptr)
+            .map_err(CheckArchiveError::ContextError)?;
+        T::check_bytes(ptr, &mut context).map_err(CheckArchiveError::CheckBytesError)?;
+        context
+            .pop_prefix_range(range)
+            .map_err(CheckArchiveError::ContextError)?;
+        context.finish().map_err(CheckArchiveError::ContextError)?;
+    }
+
+    let root = pos as usize..pos as usize + core::mem::size_of::<T>();
+    let mut ranges = context.ranges;
+    if ranges.is_empty() {
+        ranges.push(root.clone());
+    }
+    ranges.sort_unstable_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+
+    Ok(Extents {
+        buf,
+        root,
+        ranges: merged,
+    })
+}
@@ -1,5 +1,7 @@
 //! Validation implementations and helper types.
 
+pub mod chunked;
+pub mod extent;
 pub mod owned;
 pub mod validators;
 
@@ -312,7 +314,7 @@ where
     T::Archived: CheckBytes<C> + Pointee<Metadata = ()>,
     C: ArchiveContext + ?Sized,
 {
-    unsafe {
+    profile!(Validate, T, |_| buf.len(), unsafe {
         let ptr = context
             .check_subtree_ptr(buf.as_ptr(), pos, ())
             .map_err(CheckArchiveError::ContextError)?;
@@ -328,7 +330,7 @@ where
 
         context.finish().map_err(CheckArchiveError::ContextError)?;
         Ok(result)
-    }
+    })
 }
 
 /// Checks the given archive with an additional context.
@@ -167,8 +167,8 @@ impl<'a> SharedContext for DefaultValidator<'a> {
 
 /// Checks the given archive at the given position for an archived version of the given type.
 ///
-/// This is a safe alternative to [`archived_value`](crate::archived_value) for types that implement
-/// `CheckBytes`.
+/// This is a safe alternative to [`archived_value`](crate::unchecked::archived_value) for types
+/// that implement `CheckBytes`.
 ///
 /// # Examples
 /// ```
@@ -213,8 +213,8 @@ where
 
 /// Checks the given archive at the given position for an archived version of the given type.
 ///
-/// This is a safe alternative to [`archived_value`](crate::archived_value) for types that implement
-/// `CheckBytes`.
+/// This is a safe alternative to [`archived_value`](crate::unchecked::archived_value) for types
+/// that implement `CheckBytes`.
 ///
 /// See [`check_archived_value`] for more details.
 #[inline]
@@ -67,8 +67,13 @@ where
     T: Archive,
     T::Archived: 'a + CheckBytes<DefaultValidator<'a>> + Deserialize<T, SharedDeserializeMap>,
 {
-    check_archived_root::<'a, T>(bytes)
-        .map_err(CheckDeserializeError::CheckBytesError)?
-        .deserialize(&mut SharedDeserializeMap::default())
-        .map_err(CheckDeserializeError::DeserializeError)
+    let archived =
+        check_archived_root::<'a, T>(bytes).map_err(CheckDeserializeError::CheckBytesError)?;
+    profile!(
+        Deserialize,
+        T,
+        |_| bytes.len(),
+        archived.deserialize(&mut SharedDeserializeMap::default())
+    )
+    .map_err(CheckDeserializeError::DeserializeError)
 }
@@ -0,0 +1,328 @@
+#[cfg(feature = "alloc")]
+use crate::vec::VecResolver;
+use crate::{Archived, RelPtr};
+#[cfg(all(feature = "alloc", not(feature = "std")))]
+use alloc::vec::Vec;
+use core::{
+    borrow::Borrow,
+    cmp, fmt, hash,
+    mem::{size_of, ManuallyDrop, MaybeUninit},
+    ops::{Deref, Index},
+    pin::Pin,
+    slice::SliceIndex,
+};
+
+#[repr(C)]
+struct InlineBytes<T, const N: usize> {
+    _align: [T; 0],
+    bytes: [MaybeUninit<u8>; N],
+}
+
+#[repr(C)]
+union InlineVecRepr<T, const N: usize> {
+    out_of_line: ManuallyDrop<RelPtr<T>>,
+    inline: ManuallyDrop<InlineBytes<T, N>>,
+}
+
+/// An archived [`Vec`] that stores up to `N` bytes of elements inline.
+///
+/// Vecs with few enough elements to fit in `N` bytes are stored directly inside the archived vec in
+/// place of the [`RelPtr`], which avoids a pointer chase for short vecs. Longer vecs are stored out
+/// of line like an [`ArchivedVec`](crate::vec::ArchivedVec). The archived vec is always at least
+/// as large as an `ArchivedVec`.
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedInlineVec<T, const N: usize> {
+    repr: InlineVecRepr<T, N>,
+    len: Archived<usize>,
+}
+
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(ArchivedInlineVec<u32, 16> {
+    repr: InlineVecRepr<u32, 16>,
+    len: Archived<usize>,
+});
+
+impl<T, const N: usize> ArchivedInlineVec<T, N> {
+    /// The maximum number of elements that can be stored inline.
+    pub const INLINE_CAPACITY: usize = if size_of::<T>() == 0 {
+        usize::MAX
+    } else {
+        N / size_of::<T>()
+    };
+
+    /// Returns whether the elements of the archived vec are stored inline.
+    #[inline]
+    pub fn is_inline(&self) -> bool {
+        self.len() <= Self::INLINE_CAPACITY
+    }
+
+    /// Returns a pointer to the first element of the archived vec.
+    #[inline]
+    pub fn as_ptr(&self) -> *const T {
+        unsafe {
+            if self.is_inline() {
+                self.repr.inline.bytes.as_ptr().cast()
+            } else {
+                self.repr.out_of_line.as_ptr()
+            }
+        }
+    }
+
+    #[inline]
+    fn as_mut_ptr(&mut self) -> *mut T {
+        unsafe {
+            if self.is_inline() {
+                (*self.repr.inline).bytes.as_mut_ptr().cast()
+            } else {
+                (*self.repr.out_of_line).as_mut_ptr()
+            }
+        }
+    }
+
+    /// Returns the number of elements in the archived vec.
+    #[inline]
+    pub fn len(&self) -> usize {
+        from_archived!(self.len) as usize
+    }
+
+    /// Returns whether the archived vec is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets the elements of the archived vec as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { core::slice::from_raw_parts(self.as_ptr(), self.len()) }
+    }
+
+    /// Gets the elements of the archived vec as a pinned mutable slice.
+    #[inline]
+    pub fn pin_mut_slice(self: Pin<&mut Self>) -> Pin<&mut [T]> {
+        unsafe {
+            self.map_unchecked_mut(|s| core::slice::from_raw_parts_mut(s.as_mut_ptr(), s.len()))
+        }
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for ArchivedInlineVec<T, N> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, const N: usize> Borrow<[T]> for ArchivedInlineVec<T, N> {
+    #[inline]
+    fn borrow(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ArchivedInlineVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
+
+impl<T, const N: usize> Deref for ArchivedInlineVec<T, N> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for ArchivedInlineVec<T, N> {}
+
+impl<T: hash::Hash, const N: usize> hash::Hash for ArchivedInlineVec<T, N> {
+    #[inline]
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state)
+    }
+}
+
+impl<T, I: SliceIndex<[T]>, const N: usize> Index<I> for ArchivedInlineVec<T, N> {
+    type Output = <[T] as Index<I>>::Output;
+
+    #[inline]
+    fn index(&self, index: I) -> &Self::Output {
+        self.as_slice().index(index)
+    }
+}
+
+impl<T: Ord, const N: usize> Ord for ArchivedInlineVec<T, N> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        self.as_slice().cmp(other.as_slice())
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize, const M: usize> PartialEq<ArchivedInlineVec<U, M>>
+    for ArchivedInlineVec<T, N>
+{
+    #[inline]
+    fn eq(&self, other: &ArchivedInlineVec<U, M>) -> bool {
+        self.as_slice().eq(other.as_slice())
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U]> for ArchivedInlineVec<T, N> {
+    #[inline]
+    fn eq(&self, other: &[U]) -> bool {
+        self.as_slice().eq(other)
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<ArchivedInlineVec<U, N>> for [T] {
+    #[inline]
+    fn eq(&self, other: &ArchivedInlineVec<U, N>) -> bool {
+        self.eq(other.as_slice())
+    }
+}
+
+impl<T: PartialOrd, const N: usize> PartialOrd for ArchivedInlineVec<T, N> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        self.as_slice().partial_cmp(other.as_slice())
+    }
+}
+
+#[cfg(feature = "alloc")]
+enum InlineVecResolverRepr<R> {
+    Inline(Vec<R>),
+    OutOfLine(VecResolver),
+}
+
+/// The resolver for [`ArchivedInlineVec`].
+#[cfg(feature = "alloc")]
+pub struct InlineVecResolver<R> {
+    repr: InlineVecResolverRepr<R>,
+}
+
+#[cfg(feature = "alloc")]
+const _: () = {
+    use crate::{ser::Serializer, vec::ArchivedVec, Archive, Serialize, SerializeUnsized};
+    use core::ptr;
+
+    impl<T, const N: usize> ArchivedInlineVec<T, N> {
+        /// Resolves an archived inline `Vec` from a given slice.
+        ///
+        /// # Safety
+        ///
+        /// - `pos` must be the position of `out` within the archive
+        /// - `resolver` must be the result of serializing `slice` with
+        ///   [`serialize_from_slice`](ArchivedInlineVec::serialize_from_slice)
+        #[inline]
+        pub unsafe fn resolve_from_slice<U: Archive<Archived = T>>(
+            slice: &[U],
+            pos: usize,
+            resolver: InlineVecResolver<U::Resolver>,
+            out: *mut Self,
+        ) {
+            match resolver.repr {
+                InlineVecResolverRepr::Inline(resolvers) => {
+                    let (fp, fo) = out_field!(out.repr);
+                    // Zero the whole representation so the unused inline bytes are initialized
+                    ptr::write_bytes(fo.cast::<u8>(), 0, size_of::<InlineVecRepr<T, N>>());
+                    let elements = fo.cast::<T>();
+                    for (i, (value, resolver)) in slice.iter().zip(resolvers).enumerate() {
+                        value.resolve(pos + fp + i * size_of::<T>(), resolver, elements.add(i));
+                    }
+                }
+                InlineVecResolverRepr::OutOfLine(resolver) => {
+                    let (fp, fo) = out_field!(out.repr);
+                    ptr::write_bytes(fo.cast::<u8>(), 0, size_of::<InlineVecRepr<T, N>>());
+                    RelPtr::<T>::emplace(pos + fp, resolver.pos, fo.cast());
+                }
+            }
+            let (fp, fo) = out_field!(out.len);
+            usize::resolve(&slice.len(), pos + fp, (), fo);
+        }
+
+        /// Serializes an archived inline `Vec` from a given slice.
+        ///
+        /// Slices short enough to be stored inline only serialize the dependencies of their elements.
+        /// Their resolvers are held by the returned resolver until the archived vec is resolved, so
+        /// they're allocated on the heap instead of in scratch space.
+        #[inline]
+        pub fn serialize_from_slice<U, S>(
+            slice: &[U],
+            serializer: &mut S,
+        ) -> Result<InlineVecResolver<U::Resolver>, S::Error>
+        where
+            U: Serialize<S, Archived = T>,
+            S: Serializer + ?Sized,
+            // Longer slices are serialized out of line the same way as `ArchivedVec`
+            [U]: SerializeUnsized<S>,
+        {
+            let repr = if slice.len() <= Self::INLINE_CAPACITY {
+                InlineVecResolverRepr::Inline(
+                    slice
+                        .iter()
+                        .map(|value| value.serialize(serializer))
+                        .collect::<Result<Vec<_>, _>>()?,
+                )
+            } else {
+                InlineVecResolverRepr::OutOfLine(ArchivedVec::<T>::serialize_from_slice(
+                    slice, serializer,
+                )?)
+            };
+            Ok(InlineVecResolver { repr })
+        }
+    }
+};
+
+#[cfg(feature = "validation")]
+const _: () = {
+    use crate::validation::{
+        owned::{CheckOwnedPointerError, OwnedPointerError},
+        ArchiveContext,
+    };
+    use bytecheck::{CheckBytes, Error};
+    use core::ptr;
+
+    impl<T, C, const N: usize> CheckBytes<C> for ArchivedInlineVec<T, N>
+    where
+        T: CheckBytes<C>,
+        C: ArchiveContext + ?Sized,
+        C::Error: Error,
+    {
+        type Error = CheckOwnedPointerError<[T], C>;
+
+        #[inline]
+        unsafe fn check_bytes<'a>(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<&'a Self, Self::Error> {
+            let len = Archived::<usize>::check_bytes(ptr::addr_of!((*value).len), context)
+                .map_err(OwnedPointerError::PointerCheckBytesError)?;
+            let len = from_archived!(*len) as usize;
+
+            let repr = ptr::addr_of!((*value).repr);
+            if len <= Self::INLINE_CAPACITY {
+                let ptr = ptr_meta::from_raw_parts(repr.cast(), len);
+                <[T]>::check_bytes(ptr, context)
+                    .map_err(OwnedPointerError::ValueCheckBytesError)?;
+            } else {
+                let rel_ptr = RelPtr::<T>::manual_check_bytes(repr.cast(), context).unwrap();
+                let ptr = context
+                    .check_subtree_ptr::<[T]>(rel_ptr.base(), rel_ptr.offset(), len)
+                    .map_err(OwnedPointerError::ContextError)?;
+
+                let range = context
+                    .push_prefix_subtree(ptr)
+                    .map_err(OwnedPointerError::ContextError)?;
+                <[T]>::check_bytes(ptr, context)
+                    .map_err(OwnedPointerError::ValueCheckBytesError)?;
+                context
+                    .pop_prefix_range(range)
+                    .map_err(OwnedPointerError::ContextError)?;
+            }
+
+            Ok(&*value)
+        }
+    }
+};
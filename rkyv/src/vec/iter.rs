@@ -0,0 +1,82 @@
+use crate::{
+    ser::{ScratchSpace, Serializer},
+    vec::{ArchivedVec, VecResolver},
+    Archive, Serialize,
+};
+
+/// A collection whose elements are produced by an iterator, archived as an [`ArchivedVec`].
+///
+/// The elements are serialized with
+/// [`serialize_from_clone_iter`](ArchivedVec::serialize_from_clone_iter), so they never need to be
+/// in memory at the same time. Combined with a serializer that writes straight to its output (like
+/// [`WriteSerializer`](crate::ser::serializers::WriteSerializer)), this can archive collections
+/// that are much larger than the available memory.
+///
+/// # Examples
+///
+/// ```
+/// use rkyv::{
+///     ser::{
+///         serializers::{AllocScratch, CompositeSerializer, WriteSerializer},
+///         Serializer,
+///     },
+///     unchecked::archived_root,
+///     vec::IterVec,
+///     AlignedVec, Archive, Infallible, Serialize,
+/// };
+///
+/// #[derive(Archive, Serialize)]
+/// struct Dataset {
+///     name: String,
+///     ids: IterVec<core::ops::Range<u32>>,
+/// }
+///
+/// let dataset = Dataset {
+///     name: "users".to_string(),
+///     ids: IterVec(0..1000),
+/// };
+///
+/// // The writer could be a file or a socket instead
+/// let mut serializer = CompositeSerializer::new(
+///     WriteSerializer::new(Vec::new()),
+///     AllocScratch::default(),
+///     Infallible,
+/// );
+/// serializer.serialize_value(&dataset).unwrap();
+/// let written = serializer.into_serializer().into_inner();
+///
+/// let mut bytes = AlignedVec::new();
+/// bytes.extend_from_slice(&written);
+/// let archived = unsafe { archived_root::<Dataset>(&bytes) };
+/// assert_eq!(archived.name, "users");
+/// assert_eq!(archived.ids.len(), 1000);
+/// assert_eq!(archived.ids[12], 12);
+/// ```
+#[derive(Clone, Debug)]
+pub struct IterVec<I>(pub I);
+
+impl<I> Archive for IterVec<I>
+where
+    I: ExactSizeIterator,
+    I::Item: Archive,
+{
+    type Archived = ArchivedVec<<I::Item as Archive>::Archived>;
+    type Resolver = VecResolver;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        ArchivedVec::resolve_from_len(self.0.len(), pos, resolver, out);
+    }
+}
+
+impl<I, S> Serialize<S> for IterVec<I>
+where
+    I: ExactSizeIterator + Clone,
+    I::Item: Serialize<S>,
+    S: ScratchSpace + Serializer + ?Sized,
+{
+    #[inline]
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedVec::serialize_from_clone_iter(self.0.clone(), serializer)
+    }
+}
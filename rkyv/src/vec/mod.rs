@@ -1,6 +1,12 @@
 //! An archived version of `Vec`.
 
+mod inline;
+mod iter;
+#[cfg(feature = "alloc")]
+mod overlay;
 mod raw;
+mod rle;
+mod sparse;
 
 use crate::{
     ser::{ScratchSpace, Serializer},
@@ -14,7 +20,9 @@ use core::{
     slice::SliceIndex,
 };
 
-pub use self::raw::*;
+#[cfg(feature = "alloc")]
+pub use self::overlay::{ArchivedVecOverlay, Iter as OverlayIter, OverlaySerializer};
+pub use self::{inline::*, iter::*, raw::*, rle::*, sparse::*};
 
 /// An archived [`Vec`].
 ///
@@ -26,6 +34,9 @@ pub struct ArchivedVec<T> {
     len: Archived<usize>,
 }
 
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(ArchivedVec<u32> { ptr: RelPtr<u32>, len: Archived<usize> });
+
 impl<T> ArchivedVec<T> {
     /// Returns a pointer to the first element of the archived vec.
     #[inline]
@@ -144,6 +155,54 @@ impl<T> ArchivedVec<T> {
         Ok(VecResolver { pos })
     }
 
+    /// Serializes an archived `Vec` from a given slice with the elements aligned to `align` bytes
+    /// and padded to a multiple of `align` bytes.
+    ///
+    /// The elements are aligned relative to the start of the archive, so they are only aligned in
+    /// memory if the archive is loaded at an address aligned to at least `align` bytes. `align`
+    /// must be a power of two, and is raised to the alignment of `T` if it is smaller.
+    #[inline]
+    pub fn serialize_aligned_from_slice<U, S>(
+        slice: &[U],
+        align: usize,
+        serializer: &mut S,
+    ) -> Result<VecResolver, S::Error>
+    where
+        U: Serialize<S, Archived = T, Resolver = ()>,
+        S: Serializer + ?Sized,
+    {
+        fn pad_to<S: Serializer + ?Sized>(
+            serializer: &mut S,
+            align: usize,
+        ) -> Result<(), S::Error> {
+            let mut padding = (align - (serializer.pos() & (align - 1))) & (align - 1);
+            while padding > 0 {
+                let len = cmp::min(padding, 16);
+                serializer.pad(len)?;
+                padding -= len;
+            }
+            Ok(())
+        }
+
+        let align = cmp::max(align, core::mem::align_of::<T>());
+        debug_assert!(align.is_power_of_two());
+
+        for value in slice.iter() {
+            value.serialize(serializer)?;
+        }
+
+        pad_to(serializer, align)?;
+        let pos = serializer.pos();
+        for value in slice.iter() {
+            unsafe {
+                serializer.resolve_aligned(value, ())?;
+            }
+        }
+        pad_to(serializer, align)?;
+
+        Ok(VecResolver { pos })
+    }
+
     /// Serializes an archived `Vec` from a given iterator.
     ///
     /// This method is unable to perform copy optimizations; prefer
@@ -158,20 +217,59 @@ impl<T> ArchivedVec<T> {
         B: Borrow<U>,
         I: ExactSizeIterator<Item = B>,
         S: ScratchSpace + Serializer + ?Sized,
+    {
+        Ok(VecResolver {
+            pos: serializer.serialize_iter::<U, _, _>(iter)?,
+        })
+    }
+
+    /// Serializes an archived `Vec` from a given iterator without holding its elements in memory.
+    ///
+    /// A clone of `iter` is iterated to serialize the dependencies of each element, then `iter`
+    /// itself is iterated to write the archived elements. Only the resolvers of the elements are
+    /// kept in scratch space between the two passes, so elements can be produced on the fly and
+    /// dropped as soon as they've been written. Both passes must produce the same elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either pass produces a different number of elements than `iter.len()`.
+    pub fn serialize_from_clone_iter<U, I, S>(
+        iter: I,
+        serializer: &mut S,
+    ) -> Result<VecResolver, S::Error>
+    where
+        U: Serialize<S, Archived = T>,
+        I: ExactSizeIterator<Item = U> + Clone,
+        S: ScratchSpace + Serializer + ?Sized,
     {
         use crate::ScratchVec;
 
+        let len = iter.len();
         unsafe {
-            let mut resolvers = ScratchVec::new(serializer, iter.len())?;
-
-            for value in iter {
-                let resolver = value.borrow().serialize(serializer)?;
-                resolvers.push((value, resolver));
+            let mut resolvers = ScratchVec::new(serializer, len)?;
+            for value in iter.clone() {
+                assert!(
+                    resolvers.len() < len,
+                    "iterator produced more elements than its length"
+                );
+                resolvers.push(value.serialize(serializer)?);
             }
+            assert_eq!(
+                resolvers.len(),
+                len,
+                "iterator produced fewer elements than its length"
+            );
+
             let pos = serializer.align_for::<T>()?;
-            for (value, resolver) in resolvers.drain(..) {
-                serializer.resolve_aligned(value.borrow(), resolver)?;
+            let mut resolved = 0;
+            for (value, resolver) in iter.zip(resolvers.drain(..)) {
+                serializer.resolve_aligned(&value, resolver)?;
+                resolved += 1;
             }
+            assert_eq!(
+                resolved, len,
+                "iterator produced fewer elements on its second pass"
+            );
 
             resolvers.free(serializer)?;
 
@@ -0,0 +1,249 @@
+use crate::{
+    ser::{
+        serializers::{AlignedSerializer, AllocScratch, CompositeSerializer, SharedSerializeMap},
+        ScratchSpace, Serializer,
+    },
+    unchecked,
+    vec::{ArchivedVec, VecResolver},
+    AlignedVec, Archive, Deserialize, Fallible, Infallible, Serialize,
+};
+#[cfg(not(feature = "std"))]
+use ::alloc::{collections::BTreeMap, vec::Vec};
+use core::{fmt, iter::FusedIterator, mem};
+#[cfg(feature = "std")]
+use std::collections::BTreeMap;
+
+/// The serializer used to write changes to the side buffer of an [`ArchivedVecOverlay`].
+pub type OverlaySerializer =
+    CompositeSerializer<AlignedSerializer<AlignedVec>, AllocScratch, SharedSerializeMap>;
+
+/// A copy-on-write overlay on top of an [`ArchivedVec`].
+///
+/// Replaced and appended elements are serialized into a side buffer instead of touching the
+/// archive, and reads transparently return either the original archived element or its
+/// replacement. Only the changed elements are ever serialized, so making a few changes to a very
+/// large archived vec doesn't require deserializing it first.
+///
+/// The overlay can be serialized to write out a fresh, compact archived vec with all of the
+/// changes applied. The elements are deserialized and reserialized one at a time, so the whole
+/// vec never has to be in memory.
+///
+/// # Examples
+///
+/// ```
+/// use rkyv::{unchecked::archived_root, vec::ArchivedVecOverlay};
+///
+/// let values = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+/// let bytes = rkyv::to_bytes::<_, 256>(&values).unwrap();
+/// let archived = unsafe { archived_root::<Vec<String>>(&bytes) };
+///
+/// let mut overlay = ArchivedVecOverlay::<String>::new(archived);
+/// overlay.set(1, &"changed".to_string()).unwrap();
+/// overlay.push(&"d".to_string()).unwrap();
+/// assert_eq!(overlay.len(), 4);
+/// assert_eq!(overlay.get(1).unwrap(), "changed");
+/// assert_eq!(overlay.get(3).unwrap(), "d");
+///
+/// let bytes = rkyv::to_bytes::<_, 256>(&overlay).unwrap();
+/// let archived = unsafe { archived_root::<Vec<String>>(&bytes) };
+/// assert_eq!(archived.as_slice(), ["a", "changed", "c", "d"]);
+/// ```
+pub struct ArchivedVecOverlay<'a, T: Archive> {
+    base: &'a [T::Archived],
+    side: AlignedVec,
+    replaced: BTreeMap<usize, usize>,
+    appended: Vec<usize>,
+}
+
+impl<'a, T: Archive> ArchivedVecOverlay<'a, T> {
+    /// Creates a new overlay with no changes on top of the given archived vec.
+    #[inline]
+    pub fn new(base: &'a ArchivedVec<T::Archived>) -> Self {
+        Self {
+            base: base.as_slice(),
+            side: AlignedVec::new(),
+            replaced: BTreeMap::new(),
+            appended: Vec::new(),
+        }
+    }
+
+    /// Returns the number of elements in the overlay, including appended elements.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.base.len() + self.appended.len()
+    }
+
+    /// Returns whether the overlay contains no elements.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns whether any elements have been replaced or appended.
+    #[inline]
+    pub fn is_modified(&self) -> bool {
+        !self.replaced.is_empty() || !self.appended.is_empty()
+    }
+
+    /// Returns the size of the side buffer holding the changes.
+    ///
+    /// Elements that are replaced more than once leave their earlier replacements in the side
+    /// buffer until the overlay is reserialized.
+    #[inline]
+    pub fn side_len(&self) -> usize {
+        self.side.len()
+    }
+
+    #[inline]
+    fn side_value(&self, pos: usize) -> &T::Archived {
+        unsafe { unchecked::archived_value::<T>(self.side.as_slice(), pos) }
+    }
+
+    /// Gets the element at the given index, with any changes applied.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T::Archived> {
+        if let Some(&pos) = self.replaced.get(&index) {
+            Some(self.side_value(pos))
+        } else if index < self.base.len() {
+            Some(&self.base[index])
+        } else {
+            self.appended
+                .get(index - self.base.len())
+                .map(|&pos| self.side_value(pos))
+        }
+    }
+
+    /// Gets an iterator over the elements of the overlay, with any changes applied.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, 'a, T> {
+        Iter {
+            overlay: self,
+            index: 0,
+        }
+    }
+
+    fn serialize_side(&mut self, value: &T) -> Result<usize, <OverlaySerializer as Fallible>::Error>
+    where
+        T: Serialize<OverlaySerializer>,
+    {
+        let mut serializer = CompositeSerializer::new(
+            AlignedSerializer::new(mem::take(&mut self.side)),
+            AllocScratch::default(),
+            SharedSerializeMap::default(),
+        );
+        let result = serializer.serialize_value(value);
+        self.side = serializer.into_serializer().into_inner();
+        result
+    }
+
+    /// Replaces the element at the given index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set(
+        &mut self,
+        index: usize,
+        value: &T,
+    ) -> Result<(), <OverlaySerializer as Fallible>::Error>
+    where
+        T: Serialize<OverlaySerializer>,
+    {
+        let len = self.len();
+        assert!(
+            index < len,
+            "index out of bounds: the len is {} but the index is {}",
+            len,
+            index
+        );
+
+        let pos = self.serialize_side(value)?;
+        if index < self.base.len() {
+            self.replaced.insert(index, pos);
+        } else {
+            self.appended[index - self.base.len()] = pos;
+        }
+        Ok(())
+    }
+
+    /// Appends an element to the end of the overlay.
+    pub fn push(&mut self, value: &T) -> Result<(), <OverlaySerializer as Fallible>::Error>
+    where
+        T: Serialize<OverlaySerializer>,
+    {
+        let pos = self.serialize_side(value)?;
+        self.appended.push(pos);
+        Ok(())
+    }
+}
+
+impl<T: Archive> fmt::Debug for ArchivedVecOverlay<'_, T>
+where
+    T::Archived: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Archive> Archive for ArchivedVecOverlay<'_, T> {
+    type Archived = ArchivedVec<T::Archived>;
+    type Resolver = VecResolver;
+
+    #[inline]
+    unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        ArchivedVec::resolve_from_len(self.len(), pos, resolver, out);
+    }
+}
+
+impl<T, S> Serialize<S> for ArchivedVecOverlay<'_, T>
+where
+    T: Serialize<S>,
+    T::Archived: Deserialize<T, Infallible>,
+    S: ScratchSpace + Serializer + ?Sized,
+{
+    #[inline]
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedVec::serialize_from_clone_iter(
+            self.iter()
+                .map(|value| value.deserialize(&mut Infallible).unwrap()),
+            serializer,
+        )
+    }
+}
+
+/// An iterator over the elements of an [`ArchivedVecOverlay`].
+pub struct Iter<'o, 'a, T: Archive> {
+    overlay: &'o ArchivedVecOverlay<'a, T>,
+    index: usize,
+}
+
+impl<T: Archive> Clone for Iter<'_, '_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            overlay: self.overlay,
+            index: self.index,
+        }
+    }
+}
+
+impl<'o, T: Archive> Iterator for Iter<'o, '_, T> {
+    type Item = &'o T::Archived;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = self.overlay.get(self.index)?;
+        self.index += 1;
+        Some(value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.overlay.len() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T: Archive> ExactSizeIterator for Iter<'_, '_, T> {}
+impl<T: Archive> FusedIterator for Iter<'_, '_, T> {}
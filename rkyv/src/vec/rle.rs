@@ -0,0 +1,330 @@
+use crate::{
+    ser::{ScratchSpace, Serializer},
+    vec::{ArchivedVec, VecResolver},
+    Archived, Serialize,
+};
+use core::{fmt, iter::FusedIterator};
+
+/// An archived `Vec` that stores runs of equal elements once.
+///
+/// Each run of consecutive equal elements is stored as a single value along with the index where
+/// the run ends. Elements can still be accessed by index with a binary search over the runs.
+///
+/// This is the archived type of the [`RleVec`](crate::with::RleVec) wrapper.
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedRleVec<T> {
+    values: ArchivedVec<T>,
+    ends: ArchivedVec<Archived<usize>>,
+}
+
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(ArchivedRleVec<u32> {
+    values: ArchivedVec<u32>,
+    ends: ArchivedVec<Archived<usize>>,
+});
+
+impl<T> ArchivedRleVec<T> {
+    /// Returns the number of elements in the archived vec.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.ends
+            .last()
+            .map_or(0, |end| from_archived!(*end) as usize)
+    }
+
+    /// Returns whether the archived vec is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the number of runs in the archived vec.
+    #[inline]
+    pub fn run_count(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns the element at the given index, or `None` if it is out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        let run = self
+            .ends
+            .partition_point(|end| from_archived!(*end) as usize <= index);
+        self.values.get(run)
+    }
+
+    /// Returns an iterator over the elements of the archived vec.
+    #[inline]
+    pub fn iter(&self) -> RleVecIter<'_, T> {
+        RleVecIter {
+            runs: self.runs(),
+            current: None,
+        }
+    }
+
+    /// Returns an iterator over the runs of the archived vec and their lengths.
+    #[inline]
+    pub fn runs(&self) -> RleVecRuns<'_, T> {
+        RleVecRuns {
+            values: self.values.as_slice(),
+            ends: self.ends.as_slice(),
+            start: 0,
+        }
+    }
+
+    /// Resolves an archived run-length encoded vec from a given slice.
+    ///
+    /// # Safety
+    ///
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing `slice`
+    #[inline]
+    pub unsafe fn resolve_from_slice<U: PartialEq>(
+        slice: &[U],
+        pos: usize,
+        resolver: RleVecResolver,
+        out: *mut Self,
+    ) {
+        let run_count = count_runs(slice);
+        let (fp, fo) = out_field!(out.values);
+        ArchivedVec::resolve_from_len(run_count, pos + fp, resolver.values, fo);
+        let (fp, fo) = out_field!(out.ends);
+        ArchivedVec::resolve_from_len(run_count, pos + fp, resolver.ends, fo);
+    }
+
+    /// Serializes an archived run-length encoded vec from a given slice.
+    pub fn serialize_from_slice<U, S>(
+        slice: &[U],
+        serializer: &mut S,
+    ) -> Result<RleVecResolver, S::Error>
+    where
+        U: Serialize<S, Archived = T> + PartialEq,
+        S: ScratchSpace + Serializer + ?Sized,
+    {
+        use crate::ScratchVec;
+
+        unsafe {
+            let run_count = count_runs(slice);
+            let mut values = ScratchVec::<&U>::new(serializer, run_count)?;
+            let mut ends = ScratchVec::<usize>::new(serializer, run_count)?;
+            for (i, value) in slice.iter().enumerate() {
+                if values.last() != Some(&value) {
+                    if !values.is_empty() {
+                        ends.push(i);
+                    }
+                    values.push(value);
+                }
+            }
+            if !values.is_empty() {
+                ends.push(slice.len());
+            }
+
+            let values_resolver =
+                ArchivedVec::serialize_from_iter::<U, _, _, _>(values.iter().copied(), serializer)?;
+            let ends_resolver = ArchivedVec::serialize_from_slice(ends.as_slice(), serializer)?;
+
+            ends.free(serializer)?;
+            values.free(serializer)?;
+
+            Ok(RleVecResolver {
+                values: values_resolver,
+                ends: ends_resolver,
+            })
+        }
+    }
+}
+
+fn count_runs<T: PartialEq>(slice: &[T]) -> usize {
+    match slice.first() {
+        None => 0,
+        Some(_) => 1 + slice.windows(2).filter(|w| w[0] != w[1]).count(),
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArchivedRleVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ArchivedRleVec<T> {
+    type Item = &'a T;
+    type IntoIter = RleVecIter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<[U]> for ArchivedRleVec<T> {
+    #[inline]
+    fn eq(&self, other: &[U]) -> bool {
+        self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a.eq(b))
+    }
+}
+
+#[cfg(feature = "validation")]
+const _: () = {
+    use crate::validation::ArchiveContext;
+    use bytecheck::{CheckBytes, Error};
+    use core::ptr;
+
+    impl<T, C> CheckBytes<C> for ArchivedRleVec<T>
+    where
+        T: CheckBytes<C>,
+        C: ArchiveContext + ?Sized,
+        C::Error: Error,
+    {
+        type Error = RleVecError<
+            <ArchivedVec<T> as CheckBytes<C>>::Error,
+            <ArchivedVec<Archived<usize>> as CheckBytes<C>>::Error,
+        >;
+
+        unsafe fn check_bytes<'a>(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<&'a Self, Self::Error> {
+            let values = ArchivedVec::<T>::check_bytes(ptr::addr_of!((*value).values), context)
+                .map_err(RleVecError::ValuesCheckError)?;
+            let ends =
+                ArchivedVec::<Archived<usize>>::check_bytes(ptr::addr_of!((*value).ends), context)
+                    .map_err(RleVecError::EndsCheckError)?;
+
+            if values.len() != ends.len() {
+                return Err(RleVecError::LengthMismatch {
+                    values: values.len(),
+                    ends: ends.len(),
+                });
+            }
+            let mut start = 0;
+            for (run, end) in ends.iter().enumerate() {
+                let end = from_archived!(*end) as usize;
+                if end <= start {
+                    return Err(RleVecError::InvalidRunEnd { run, start, end });
+                }
+                start = end;
+            }
+
+            Ok(&*value)
+        }
+    }
+};
+
+/// Errors that can occur while checking an [`ArchivedRleVec`].
+#[cfg(feature = "validation")]
+#[derive(Debug)]
+pub enum RleVecError<V, E> {
+    /// An error occurred while checking the values of the runs
+    ValuesCheckError(V),
+    /// An error occurred while checking the ends of the runs
+    EndsCheckError(E),
+    /// The number of values didn't match the number of run ends
+    LengthMismatch {
+        /// The number of values
+        values: usize,
+        /// The number of run ends
+        ends: usize,
+    },
+    /// A run didn't end after it started
+    InvalidRunEnd {
+        /// The index of the run
+        run: usize,
+        /// The index where the run starts
+        start: usize,
+        /// The index where the run ends
+        end: usize,
+    },
+}
+
+#[cfg(feature = "validation")]
+impl<V: fmt::Display, E: fmt::Display> fmt::Display for RleVecError<V, E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RleVecError::ValuesCheckError(e) => write!(f, "values check error: {}", e),
+            RleVecError::EndsCheckError(e) => write!(f, "ends check error: {}", e),
+            RleVecError::LengthMismatch { values, ends } => write!(
+                f,
+                "length mismatch: {} values but {} run ends",
+                values, ends
+            ),
+            RleVecError::InvalidRunEnd { run, start, end } => write!(
+                f,
+                "invalid run end: run {} starts at {} but ends at {}",
+                run, start, end
+            ),
+        }
+    }
+}
+
+#[cfg(all(feature = "validation", feature = "std"))]
+const _: () = {
+    use std::error::Error;
+
+    impl<V: Error + 'static, E: Error + 'static> Error for RleVecError<V, E> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                RleVecError::ValuesCheckError(e) => Some(e as &dyn Error),
+                RleVecError::EndsCheckError(e) => Some(e as &dyn Error),
+                RleVecError::LengthMismatch { .. } | RleVecError::InvalidRunEnd { .. } => None,
+            }
+        }
+    }
+};
+
+/// The resolver for [`ArchivedRleVec`].
+pub struct RleVecResolver {
+    values: VecResolver,
+    ends: VecResolver,
+}
+
+/// An iterator over the runs of an [`ArchivedRleVec`] and their lengths.
+pub struct RleVecRuns<'a, T> {
+    values: &'a [T],
+    ends: &'a [Archived<usize>],
+    start: usize,
+}
+
+impl<'a, T> Iterator for RleVecRuns<'a, T> {
+    type Item = (&'a T, usize);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (value, values) = self.values.split_first()?;
+        let (end, ends) = self.ends.split_first()?;
+        self.values = values;
+        self.ends = ends;
+
+        let end = from_archived!(*end) as usize;
+        let len = end.saturating_sub(self.start);
+        self.start = end;
+        Some((value, len))
+    }
+}
+
+impl<'a, T> FusedIterator for RleVecRuns<'a, T> {}
+
+/// An iterator over the elements of an [`ArchivedRleVec`].
+pub struct RleVecIter<'a, T> {
+    runs: RleVecRuns<'a, T>,
+    current: Option<(&'a T, usize)>,
+}
+
+impl<'a, T> Iterator for RleVecIter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match &mut self.current {
+                Some((value, remaining)) if *remaining > 0 => {
+                    *remaining -= 1;
+                    return Some(*value);
+                }
+                _ => self.current = Some(self.runs.next()?),
+            }
+        }
+    }
+}
+
+impl<'a, T> FusedIterator for RleVecIter<'a, T> {}
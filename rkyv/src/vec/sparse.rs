@@ -0,0 +1,379 @@
+use crate::{
+    ser::{ScratchSpace, Serializer},
+    vec::{ArchivedVec, VecResolver},
+    Archive, Archived, Serialize,
+};
+use core::{fmt, iter::FusedIterator};
+
+/// An archived `Vec` that only stores the elements that differ from a default value.
+///
+/// The elements that aren't equal to the default value are stored along with their indices, and
+/// looked up with a binary search. Every other element is a reference to the archived default
+/// value.
+///
+/// This is the archived type of the [`SparseVec`](crate::with::SparseVec) wrapper.
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedSparseVec<T> {
+    len: Archived<usize>,
+    default: T,
+    indices: ArchivedVec<Archived<usize>>,
+    values: ArchivedVec<T>,
+}
+
+#[cfg(feature = "strict")]
+const _: () = assert_repr_c!(ArchivedSparseVec<u32> {
+    len: Archived<usize>,
+    default: u32,
+    indices: ArchivedVec<Archived<usize>>,
+    values: ArchivedVec<u32>,
+});
+
+impl<T> ArchivedSparseVec<T> {
+    /// Returns the number of elements in the archived vec.
+    #[inline]
+    pub fn len(&self) -> usize {
+        from_archived!(self.len) as usize
+    }
+
+    /// Returns whether the archived vec is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the default value of the archived vec.
+    #[inline]
+    pub fn default_value(&self) -> &T {
+        &self.default
+    }
+
+    /// Returns the number of elements that are stored because they differ from the default value.
+    #[inline]
+    pub fn stored_len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns the element at the given index, or `None` if it is out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len() {
+            return None;
+        }
+        match self
+            .indices
+            .binary_search_by(|i| (from_archived!(*i) as usize).cmp(&index))
+        {
+            Ok(i) => self.values.get(i),
+            Err(_) => Some(&self.default),
+        }
+    }
+
+    /// Returns an iterator over the elements of the archived vec.
+    #[inline]
+    pub fn iter(&self) -> SparseVecIter<'_, T> {
+        SparseVecIter {
+            vec: self,
+            index: 0,
+            entries: self.entries(),
+        }
+    }
+
+    /// Returns an iterator over the stored elements of the archived vec and their indices.
+    #[inline]
+    pub fn entries(&self) -> SparseVecEntries<'_, T> {
+        SparseVecEntries {
+            indices: self.indices.as_slice(),
+            values: self.values.as_slice(),
+        }
+    }
+
+    /// Resolves an archived sparse vec from a given slice.
+    ///
+    /// # Safety
+    ///
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing `slice` with `default`
+    #[inline]
+    pub unsafe fn resolve_from_slice<U: Archive<Archived = T> + PartialEq>(
+        slice: &[U],
+        default: &U,
+        pos: usize,
+        resolver: SparseVecResolver<U::Resolver>,
+        out: *mut Self,
+    ) {
+        let stored_len = slice.iter().filter(|value| *value != default).count();
+        let (fp, fo) = out_field!(out.len);
+        usize::resolve(&slice.len(), pos + fp, (), fo);
+        let (fp, fo) = out_field!(out.default);
+        default.resolve(pos + fp, resolver.default, fo);
+        let (fp, fo) = out_field!(out.indices);
+        ArchivedVec::resolve_from_len(stored_len, pos + fp, resolver.indices, fo);
+        let (fp, fo) = out_field!(out.values);
+        ArchivedVec::resolve_from_len(stored_len, pos + fp, resolver.values, fo);
+    }
+
+    /// Serializes an archived sparse vec from a given slice, storing only the elements that aren't
+    /// equal to `default`.
+    pub fn serialize_from_slice<U, S>(
+        slice: &[U],
+        default: &U,
+        serializer: &mut S,
+    ) -> Result<SparseVecResolver<U::Resolver>, S::Error>
+    where
+        U: Serialize<S, Archived = T> + PartialEq,
+        S: ScratchSpace + Serializer + ?Sized,
+    {
+        use crate::ScratchVec;
+
+        unsafe {
+            let stored_len = slice.iter().filter(|value| *value != default).count();
+            let mut indices = ScratchVec::<usize>::new(serializer, stored_len)?;
+            for (i, value) in slice.iter().enumerate() {
+                if value != default {
+                    indices.push(i);
+                }
+            }
+
+            let default_resolver = default.serialize(serializer)?;
+            let indices_resolver =
+                ArchivedVec::serialize_from_slice(indices.as_slice(), serializer)?;
+            let values_resolver = ArchivedVec::serialize_from_iter::<U, _, _, _>(
+                indices.iter().map(|&i| &slice[i]),
+                serializer,
+            )?;
+
+            indices.free(serializer)?;
+
+            Ok(SparseVecResolver {
+                default: default_resolver,
+                indices: indices_resolver,
+                values: values_resolver,
+            })
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for ArchivedSparseVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a ArchivedSparseVec<T> {
+    type Item = &'a T;
+    type IntoIter = SparseVecIter<'a, T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<[U]> for ArchivedSparseVec<T> {
+    #[inline]
+    fn eq(&self, other: &[U]) -> bool {
+        self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a.eq(b))
+    }
+}
+
+#[cfg(feature = "validation")]
+const _: () = {
+    use crate::validation::ArchiveContext;
+    use bytecheck::{CheckBytes, Error};
+    use core::ptr;
+
+    impl<T, C> CheckBytes<C> for ArchivedSparseVec<T>
+    where
+        T: CheckBytes<C>,
+        C: ArchiveContext + ?Sized,
+        C::Error: Error,
+    {
+        type Error = SparseVecError<
+            T::Error,
+            <ArchivedVec<Archived<usize>> as CheckBytes<C>>::Error,
+            <ArchivedVec<T> as CheckBytes<C>>::Error,
+        >;
+
+        unsafe fn check_bytes<'a>(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<&'a Self, Self::Error> {
+            // Every bit pattern is a valid length
+            let len = from_archived!(*ptr::addr_of!((*value).len)) as usize;
+            T::check_bytes(ptr::addr_of!((*value).default), context)
+                .map_err(SparseVecError::DefaultCheckError)?;
+            let indices = ArchivedVec::<Archived<usize>>::check_bytes(
+                ptr::addr_of!((*value).indices),
+                context,
+            )
+            .map_err(SparseVecError::IndicesCheckError)?;
+            let values = ArchivedVec::<T>::check_bytes(ptr::addr_of!((*value).values), context)
+                .map_err(SparseVecError::ValuesCheckError)?;
+
+            if indices.len() != values.len() {
+                return Err(SparseVecError::LengthMismatch {
+                    indices: indices.len(),
+                    values: values.len(),
+                });
+            }
+            let mut next = 0;
+            for index in indices.iter() {
+                let index = from_archived!(*index) as usize;
+                if index < next || index >= len {
+                    return Err(SparseVecError::InvalidIndex { index, next, len });
+                }
+                next = index + 1;
+            }
+
+            Ok(&*value)
+        }
+    }
+};
+
+/// Errors that can occur while checking an [`ArchivedSparseVec`].
+#[cfg(feature = "validation")]
+#[derive(Debug)]
+pub enum SparseVecError<T, I, V> {
+    /// An error occurred while checking the default value
+    DefaultCheckError(T),
+    /// An error occurred while checking the indices of the stored values
+    IndicesCheckError(I),
+    /// An error occurred while checking the stored values
+    ValuesCheckError(V),
+    /// The number of indices didn't match the number of stored values
+    LengthMismatch {
+        /// The number of indices
+        indices: usize,
+        /// The number of stored values
+        values: usize,
+    },
+    /// An index was out of order or out of bounds
+    InvalidIndex {
+        /// The invalid index
+        index: usize,
+        /// The smallest index that could follow the previous one
+        next: usize,
+        /// The length of the vec
+        len: usize,
+    },
+}
+
+#[cfg(feature = "validation")]
+impl<T, I, V> fmt::Display for SparseVecError<T, I, V>
+where
+    T: fmt::Display,
+    I: fmt::Display,
+    V: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SparseVecError::DefaultCheckError(e) => write!(f, "default check error: {}", e),
+            SparseVecError::IndicesCheckError(e) => write!(f, "indices check error: {}", e),
+            SparseVecError::ValuesCheckError(e) => write!(f, "values check error: {}", e),
+            SparseVecError::LengthMismatch { indices, values } => write!(
+                f,
+                "length mismatch: {} indices but {} stored values",
+                indices, values
+            ),
+            SparseVecError::InvalidIndex { index, next, len } => write!(
+                f,
+                "invalid index: expected an index in {}..{} but found {}",
+                next, len, index
+            ),
+        }
+    }
+}
+
+#[cfg(all(feature = "validation", feature = "std"))]
+const _: () = {
+    use std::error::Error;
+
+    impl<T, I, V> Error for SparseVecError<T, I, V>
+    where
+        T: Error + 'static,
+        I: Error + 'static,
+        V: Error + 'static,
+    {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            match self {
+                SparseVecError::DefaultCheckError(e) => Some(e as &dyn Error),
+                SparseVecError::IndicesCheckError(e) => Some(e as &dyn Error),
+                SparseVecError::ValuesCheckError(e) => Some(e as &dyn Error),
+                SparseVecError::LengthMismatch { .. } | SparseVecError::InvalidIndex { .. } => None,
+            }
+        }
+    }
+};
+
+/// The resolver for [`ArchivedSparseVec`].
+pub struct SparseVecResolver<R> {
+    default: R,
+    indices: VecResolver,
+    values: VecResolver,
+}
+
+/// An iterator over the stored elements of an [`ArchivedSparseVec`] and their indices.
+pub struct SparseVecEntries<'a, T> {
+    indices: &'a [Archived<usize>],
+    values: &'a [T],
+}
+
+impl<'a, T> Iterator for SparseVecEntries<'a, T> {
+    type Item = (usize, &'a T);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, indices) = self.indices.split_first()?;
+        let (value, values) = self.values.split_first()?;
+        self.indices = indices;
+        self.values = values;
+        Some((from_archived!(*index) as usize, value))
+    }
+}
+
+impl<'a, T> FusedIterator for SparseVecEntries<'a, T> {}
+
+/// An iterator over the elements of an [`ArchivedSparseVec`].
+pub struct SparseVecIter<'a, T> {
+    vec: &'a ArchivedSparseVec<T>,
+    index: usize,
+    entries: SparseVecEntries<'a, T>,
+}
+
+impl<'a, T> Iterator for SparseVecIter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.vec.len() {
+            return None;
+        }
+
+        // Skip entries before the current index in case the indices are out of order
+        while let Some(&next) = self.entries.indices.first() {
+            if from_archived!(next) as usize >= self.index {
+                break;
+            }
+            self.entries.next();
+        }
+
+        let result = match self.entries.indices.first() {
+            Some(&next) if from_archived!(next) as usize == self.index => {
+                self.entries.next().map(|(_, value)| value)
+            }
+            _ => None,
+        };
+        self.index += 1;
+        Some(result.unwrap_or(&self.vec.default))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.vec.len() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for SparseVecIter<'a, T> {}
+
+impl<'a, T> FusedIterator for SparseVecIter<'a, T> {}
@@ -1,30 +1,54 @@
 use crate::{
     boxed::{ArchivedBox, BoxResolver},
-    collections::util::Entry,
+    collections::{
+        btree_map::{ArchivedBTreeMap, BTreeMapResolver},
+        btree_set::{ArchivedBTreeSet, BTreeSetResolver},
+        multi_map::{ArchivedMultiMap, MultiMapResolver},
+        range_map::{self, ArchivedRangeInclusiveMap, ArchivedRangeMap, RangeMapResolver},
+        util::Entry,
+    },
+    de::SharedDeserializeRegistry,
     niche::option_box::{ArchivedOptionBox, OptionBoxResolver},
-    ser::{ScratchSpace, Serializer},
+    rc::{ArchivedRc, RcResolver},
+    rel_ptr::{VarintRelPtrs, VarintRelPtrsResolver},
+    ser::{DedupSerializeRegistry, InternSerializeRegistry, ScratchSpace, Serializer},
     string::{ArchivedString, StringResolver},
-    vec::{ArchivedVec, RawArchivedVec, VecResolver},
+    vec::{
+        ArchivedInlineVec, ArchivedRleVec, ArchivedSparseVec, ArchivedVec, InlineVecResolver,
+        RawArchivedVec, RleVecResolver, SparseVecResolver, VecResolver,
+    },
     with::{
-        ArchiveWith, AsOwned, AsVec, CopyOptimize, DeserializeWith, Map, Niche, Raw, RefAsBox,
-        SerializeWith, With,
+        ArchiveWith, ArrowBuffer, AsOwned, AsVec, CopyOptimize, Dedup, DeserializeWith, InScratch,
+        Inline, InlineVec, Intern, Map, MultiMap, Niche, RangeMap, RangeMapError, Raw, RefAsBox,
+        RleVec, SerializeWith, SparseVec, VarintOffsets, With,
     },
-    Archive, ArchiveUnsized, ArchivedMetadata, Deserialize, DeserializeUnsized, Fallible,
+    Archive, ArchiveUnsized, Archived, ArchivedMetadata, Deserialize, DeserializeUnsized, Fallible,
     MetadataResolver, Serialize, SerializeUnsized,
 };
-use ::core::marker::PhantomData;
+use ::core::{
+    hash::Hash,
+    marker::PhantomData,
+    ops::{Range, RangeInclusive},
+};
+#[cfg(all(not(feature = "std"), has_atomics))]
+use alloc::sync::Arc;
 #[cfg(not(feature = "std"))]
 use alloc::{
     borrow::Cow,
     boxed::Box,
     collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+    string::String,
     vec::Vec,
 };
+#[cfg(all(feature = "std", has_atomics))]
+use std::sync::Arc;
 #[cfg(feature = "std")]
 use std::{
     borrow::Cow,
     boxed::Box,
     collections::{BTreeMap, BTreeSet},
+    rc::Rc,
 };
 
 // Map for Vecs
@@ -104,6 +128,37 @@ where
     }
 }
 
+// Inline
+
+impl<F, D> DeserializeWith<F::Archived, Box<F>, D> for Inline
+where
+    F: Archive,
+    F::Archived: Deserialize<F, D>,
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize_with(field: &F::Archived, deserializer: &mut D) -> Result<Box<F>, D::Error> {
+        Ok(Box::new(field.deserialize(deserializer)?))
+    }
+}
+
+// RefAsBox
+
+impl<F, D> DeserializeWith<ArchivedBox<F::Archived>, Box<F>, D> for RefAsBox
+where
+    F: ArchiveUnsized + ?Sized,
+    F::Archived: DeserializeUnsized<F, D>,
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize_with(
+        field: &ArchivedBox<F::Archived>,
+        deserializer: &mut D,
+    ) -> Result<Box<F>, D::Error> {
+        field.deserialize(deserializer)
+    }
+}
+
 // AsOwned
 
 impl<'a, F: Archive + Clone> ArchiveWith<Cow<'a, F>> for AsOwned {
@@ -602,3 +657,714 @@ where
         Ok(result)
     }
 }
+
+// InlineVec
+
+impl<T: Archive, const N: usize> ArchiveWith<Vec<T>> for InlineVec<N> {
+    type Archived = ArchivedInlineVec<T::Archived, N>;
+    type Resolver = InlineVecResolver<T::Resolver>;
+
+    unsafe fn resolve_with(
+        field: &Vec<T>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedInlineVec::resolve_from_slice(field.as_slice(), pos, resolver, out);
+    }
+}
+
+impl<T, S, const N: usize> SerializeWith<Vec<T>, S> for InlineVec<N>
+where
+    T: Serialize<S>,
+    [T]: SerializeUnsized<S>,
+    S: Serializer + ?Sized,
+{
+    fn serialize_with(field: &Vec<T>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedInlineVec::<T::Archived, N>::serialize_from_slice(field.as_slice(), serializer)
+    }
+}
+
+impl<T, D, const N: usize> DeserializeWith<ArchivedInlineVec<T::Archived, N>, Vec<T>, D>
+    for InlineVec<N>
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedInlineVec<T::Archived, N>,
+        deserializer: &mut D,
+    ) -> Result<Vec<T>, D::Error> {
+        field
+            .iter()
+            .map(|value| value.deserialize(deserializer))
+            .collect()
+    }
+}
+
+// VarintOffsets
+
+impl<T: Archive> ArchiveWith<Vec<Box<T>>> for VarintOffsets {
+    type Archived = VarintRelPtrs<T::Archived>;
+    type Resolver = VarintRelPtrsResolver;
+
+    unsafe fn resolve_with(
+        field: &Vec<Box<T>>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        VarintRelPtrs::resolve_from_len(field.len(), pos, resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<Vec<Box<T>>, S> for VarintOffsets
+where
+    T: Serialize<S>,
+    S: ScratchSpace + Serializer + ?Sized,
+{
+    fn serialize_with(field: &Vec<Box<T>>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        VarintRelPtrs::<T::Archived>::serialize_from_iter::<T, _, _, _>(
+            field.iter().map(|value| &**value),
+            serializer,
+        )
+    }
+}
+
+impl<T, D> DeserializeWith<VarintRelPtrs<T::Archived>, Vec<Box<T>>, D> for VarintOffsets
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &VarintRelPtrs<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<Vec<Box<T>>, D::Error> {
+        field
+            .iter()
+            .map(|value| value.deserialize(deserializer).map(Box::new))
+            .collect()
+    }
+}
+
+// ArrowBuffer
+
+impl<T: Archive> ArchiveWith<Vec<T>> for ArrowBuffer {
+    type Archived = ArchivedVec<T::Archived>;
+    type Resolver = VecResolver;
+
+    unsafe fn resolve_with(
+        field: &Vec<T>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedVec::resolve_from_len(field.len(), pos, resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<Vec<T>, S> for ArrowBuffer
+where
+    T: Serialize<S, Resolver = ()>,
+    S: Serializer + ?Sized,
+{
+    fn serialize_with(field: &Vec<T>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedVec::serialize_aligned_from_slice(
+            field.as_slice(),
+            ArrowBuffer::ALIGNMENT,
+            serializer,
+        )
+    }
+}
+
+impl<T, D> DeserializeWith<ArchivedVec<T::Archived>, Vec<T>, D> for ArrowBuffer
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedVec<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<Vec<T>, D::Error> {
+        field
+            .iter()
+            .map(|value| value.deserialize(deserializer))
+            .collect()
+    }
+}
+
+// Intern
+
+#[cfg(has_atomics)]
+impl ArchiveWith<Arc<str>> for Intern {
+    type Archived = Archived<Arc<str>>;
+    type Resolver = RcResolver<MetadataResolver<str>>;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &Arc<str>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedRc::resolve_from_ref(field.as_ref(), pos, resolver, out);
+    }
+}
+
+#[cfg(has_atomics)]
+impl<S: Serializer + InternSerializeRegistry + ?Sized> SerializeWith<Arc<str>, S> for Intern {
+    #[inline]
+    fn serialize_with(field: &Arc<str>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        <Archived<Arc<str>>>::serialize_interned(field.as_ref(), serializer)
+    }
+}
+
+#[cfg(has_atomics)]
+impl<D> DeserializeWith<Archived<Arc<str>>, Arc<str>, D> for Intern
+where
+    D: SharedDeserializeRegistry + ?Sized,
+{
+    #[inline]
+    fn deserialize_with(
+        field: &Archived<Arc<str>>,
+        deserializer: &mut D,
+    ) -> Result<Arc<str>, D::Error> {
+        field.deserialize(deserializer)
+    }
+}
+
+#[cfg(has_atomics)]
+impl ArchiveWith<String> for Intern {
+    type Archived = Archived<Arc<str>>;
+    type Resolver = RcResolver<MetadataResolver<str>>;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &String,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedRc::resolve_from_ref(field.as_str(), pos, resolver, out);
+    }
+}
+
+#[cfg(has_atomics)]
+impl<S: Serializer + InternSerializeRegistry + ?Sized> SerializeWith<String, S> for Intern {
+    #[inline]
+    fn serialize_with(field: &String, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        <Archived<Arc<str>>>::serialize_interned(field.as_str(), serializer)
+    }
+}
+
+#[cfg(has_atomics)]
+impl<D: Fallible + ?Sized> DeserializeWith<Archived<Arc<str>>, String, D> for Intern {
+    #[inline]
+    fn deserialize_with(field: &Archived<Arc<str>>, _: &mut D) -> Result<String, D::Error> {
+        Ok(field.get().into())
+    }
+}
+
+// Dedup
+
+impl<T: Archive> ArchiveWith<Vec<T>> for Dedup {
+    type Archived = Archived<Rc<[T]>>;
+    type Resolver = RcResolver<MetadataResolver<[T]>>;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &Vec<T>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedRc::resolve_from_ref(field.as_slice(), pos, resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<Vec<T>, S> for Dedup
+where
+    T: Serialize<S>,
+    S: Serializer + ScratchSpace + DedupSerializeRegistry + ?Sized,
+{
+    #[inline]
+    fn serialize_with(field: &Vec<T>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        <Archived<Rc<[T]>>>::serialize_deduped_from_slice(field.as_slice(), serializer)
+    }
+}
+
+impl<T, D> DeserializeWith<Archived<Rc<[T]>>, Vec<T>, D> for Dedup
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize_with(
+        field: &Archived<Rc<[T]>>,
+        deserializer: &mut D,
+    ) -> Result<Vec<T>, D::Error> {
+        field
+            .iter()
+            .map(|value| value.deserialize(deserializer))
+            .collect()
+    }
+}
+
+impl<T: Archive> ArchiveWith<Box<[T]>> for Dedup {
+    type Archived = Archived<Rc<[T]>>;
+    type Resolver = RcResolver<MetadataResolver<[T]>>;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &Box<[T]>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedRc::resolve_from_ref(field.as_ref(), pos, resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<Box<[T]>, S> for Dedup
+where
+    T: Serialize<S>,
+    S: Serializer + ScratchSpace + DedupSerializeRegistry + ?Sized,
+{
+    #[inline]
+    fn serialize_with(field: &Box<[T]>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        <Archived<Rc<[T]>>>::serialize_deduped_from_slice(field.as_ref(), serializer)
+    }
+}
+
+impl<T, D> DeserializeWith<Archived<Rc<[T]>>, Box<[T]>, D> for Dedup
+where
+    T: Archive,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize_with(
+        field: &Archived<Rc<[T]>>,
+        deserializer: &mut D,
+    ) -> Result<Box<[T]>, D::Error> {
+        field
+            .iter()
+            .map(|value| value.deserialize(deserializer))
+            .collect()
+    }
+}
+
+// RleVec
+
+impl<T: Archive + PartialEq> ArchiveWith<Vec<T>> for RleVec {
+    type Archived = ArchivedRleVec<T::Archived>;
+    type Resolver = RleVecResolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &Vec<T>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedRleVec::resolve_from_slice(field.as_slice(), pos, resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<Vec<T>, S> for RleVec
+where
+    T: Serialize<S> + PartialEq,
+    S: ScratchSpace + Serializer + ?Sized,
+{
+    #[inline]
+    fn serialize_with(field: &Vec<T>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedRleVec::serialize_from_slice(field.as_slice(), serializer)
+    }
+}
+
+impl<T, D> DeserializeWith<ArchivedRleVec<T::Archived>, Vec<T>, D> for RleVec
+where
+    T: Archive + Clone,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedRleVec<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<Vec<T>, D::Error> {
+        let mut result = Vec::with_capacity(field.len());
+        for (value, len) in field.runs() {
+            let value = value.deserialize(deserializer)?;
+            result.resize(result.len() + len, value);
+        }
+        Ok(result)
+    }
+}
+
+// SparseVec
+
+impl<T: Archive + Default + PartialEq> ArchiveWith<Vec<T>> for SparseVec {
+    type Archived = ArchivedSparseVec<T::Archived>;
+    type Resolver = SparseVecResolver<T::Resolver>;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &Vec<T>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedSparseVec::resolve_from_slice(field.as_slice(), &T::default(), pos, resolver, out);
+    }
+}
+
+impl<T, S> SerializeWith<Vec<T>, S> for SparseVec
+where
+    T: Serialize<S> + Default + PartialEq,
+    S: ScratchSpace + Serializer + ?Sized,
+{
+    #[inline]
+    fn serialize_with(field: &Vec<T>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedSparseVec::serialize_from_slice(field.as_slice(), &T::default(), serializer)
+    }
+}
+
+impl<T, D> DeserializeWith<ArchivedSparseVec<T::Archived>, Vec<T>, D> for SparseVec
+where
+    T: Archive + Clone,
+    T::Archived: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedSparseVec<T::Archived>,
+        deserializer: &mut D,
+    ) -> Result<Vec<T>, D::Error> {
+        let default = field.default_value().deserialize(deserializer)?;
+        let mut result = Vec::new();
+        result.resize(field.len(), default);
+        for (index, value) in field.entries() {
+            if let Some(slot) = result.get_mut(index) {
+                *slot = value.deserialize(deserializer)?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+// MultiMap
+
+impl<K: Archive, V: Archive> ArchiveWith<BTreeMap<K, Vec<V>>> for MultiMap {
+    type Archived = ArchivedMultiMap<K::Archived, V::Archived>;
+    type Resolver = MultiMapResolver;
+
+    unsafe fn resolve_with(
+        field: &BTreeMap<K, Vec<V>>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        let values_len = field.values().map(|values| values.len()).sum();
+        ArchivedMultiMap::resolve_from_len(field.len(), values_len, pos, resolver, out);
+    }
+}
+
+impl<K, V, S> SerializeWith<BTreeMap<K, Vec<V>>, S> for MultiMap
+where
+    K: Serialize<S> + Hash + Eq,
+    V: Serialize<S>,
+    S: ScratchSpace + Serializer + ?Sized,
+{
+    fn serialize_with(
+        field: &BTreeMap<K, Vec<V>>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        unsafe {
+            ArchivedMultiMap::serialize_from_iter(
+                field.iter().map(|(key, values)| (key, values.as_slice())),
+                serializer,
+            )
+        }
+    }
+}
+
+impl<K, V, D> DeserializeWith<ArchivedMultiMap<K::Archived, V::Archived>, BTreeMap<K, Vec<V>>, D>
+    for MultiMap
+where
+    K: Archive + Ord,
+    V: Archive,
+    K::Archived: Deserialize<K, D>,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedMultiMap<K::Archived, V::Archived>,
+        deserializer: &mut D,
+    ) -> Result<BTreeMap<K, Vec<V>>, D::Error> {
+        let mut result = BTreeMap::new();
+        for (key, values) in field.iter() {
+            let mut deserialized = Vec::with_capacity(values.len());
+            for value in values {
+                deserialized.push(value.deserialize(deserializer)?);
+            }
+            result.insert(key.deserialize(deserializer)?, deserialized);
+        }
+        Ok(result)
+    }
+}
+
+// InScratch
+
+impl<K: Archive + Ord, V: Archive> ArchiveWith<BTreeMap<K, V>> for InScratch
+where
+    K::Archived: Ord,
+{
+    type Archived = ArchivedBTreeMap<K::Archived, V::Archived>;
+    type Resolver = BTreeMapResolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &BTreeMap<K, V>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        field.resolve(pos, resolver, out);
+    }
+}
+
+impl<K, V, S> SerializeWith<BTreeMap<K, V>, S> for InScratch
+where
+    K: Serialize<S> + Ord,
+    K::Archived: Ord,
+    V: Serialize<S>,
+    S: ScratchSpace + Serializer + ?Sized,
+{
+    #[inline]
+    fn serialize_with(
+        field: &BTreeMap<K, V>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        unsafe {
+            ArchivedBTreeMap::serialize_from_reverse_iter_in_scratch(field.iter().rev(), serializer)
+        }
+    }
+}
+
+impl<K, V, D> DeserializeWith<ArchivedBTreeMap<K::Archived, V::Archived>, BTreeMap<K, V>, D>
+    for InScratch
+where
+    K: Archive + Ord,
+    K::Archived: Deserialize<K, D> + Ord,
+    V: Archive,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize_with(
+        field: &ArchivedBTreeMap<K::Archived, V::Archived>,
+        deserializer: &mut D,
+    ) -> Result<BTreeMap<K, V>, D::Error> {
+        field.deserialize(deserializer)
+    }
+}
+
+impl<K: Archive + Ord> ArchiveWith<BTreeSet<K>> for InScratch
+where
+    K::Archived: Ord,
+{
+    type Archived = ArchivedBTreeSet<K::Archived>;
+    type Resolver = BTreeSetResolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &BTreeSet<K>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        field.resolve(pos, resolver, out);
+    }
+}
+
+impl<K, S> SerializeWith<BTreeSet<K>, S> for InScratch
+where
+    K: Serialize<S> + Ord,
+    K::Archived: Ord,
+    S: ScratchSpace + Serializer + ?Sized,
+{
+    #[inline]
+    fn serialize_with(field: &BTreeSet<K>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        unsafe {
+            ArchivedBTreeSet::serialize_from_reverse_iter_in_scratch(field.iter().rev(), serializer)
+        }
+    }
+}
+
+impl<K, D> DeserializeWith<ArchivedBTreeSet<K::Archived>, BTreeSet<K>, D> for InScratch
+where
+    K: Archive + Ord,
+    K::Archived: Deserialize<K, D> + Ord,
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize_with(
+        field: &ArchivedBTreeSet<K::Archived>,
+        deserializer: &mut D,
+    ) -> Result<BTreeSet<K>, D::Error> {
+        field.deserialize(deserializer)
+    }
+}
+
+// RangeMap
+
+/// Serializes ranges and values as a range map after sorting them and checking that they don't
+/// overlap.
+fn serialize_range_map<K, V, R, S>(
+    field: &[(R, V)],
+    serializer: &mut S,
+) -> Result<RangeMapResolver, S::Error>
+where
+    K: Archive + Ord,
+    V: Serialize<S>,
+    R: range_map::MapRange<K> + Serialize<S>,
+    S: ScratchSpace + Serializer + ?Sized,
+    S::Error: From<RangeMapError>,
+{
+    use crate::ScratchVec;
+
+    unsafe {
+        let mut sorted = ScratchVec::new(serializer, field.len())?;
+        for (range, value) in field.iter() {
+            sorted.push((range, value));
+        }
+        // Empty ranges sort before other ranges with the same start so they can't hide them
+        sorted.sort_unstable_by(|(a, _), (b, _)| (a.start(), a.end()).cmp(&(b.start(), b.end())));
+        if sorted.windows(2).any(|w| {
+            !range_map::is_empty(w[0].0) && !range_map::ends_before(w[0].0, w[1].0.start())
+        }) {
+            return Err(RangeMapError::OverlappingRanges.into());
+        }
+
+        let resolver =
+            ArchivedRangeMap::<K::Archived, V::Archived, R::Archived>::serialize_from_iter(
+                sorted.iter().copied(),
+                serializer,
+            )?;
+        sorted.free(serializer)?;
+        Ok(resolver)
+    }
+}
+
+impl<K: Archive, V: Archive> ArchiveWith<Vec<(Range<K>, V)>> for RangeMap {
+    type Archived = ArchivedRangeMap<K::Archived, V::Archived>;
+    type Resolver = RangeMapResolver;
+
+    unsafe fn resolve_with(
+        field: &Vec<(Range<K>, V)>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedRangeMap::resolve_from_len(field.len(), pos, resolver, out);
+    }
+}
+
+impl<K, V, S> SerializeWith<Vec<(Range<K>, V)>, S> for RangeMap
+where
+    K: Serialize<S> + Ord,
+    V: Serialize<S>,
+    S: ScratchSpace + Serializer + ?Sized,
+    S::Error: From<RangeMapError>,
+{
+    fn serialize_with(
+        field: &Vec<(Range<K>, V)>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        serialize_range_map(field, serializer)
+    }
+}
+
+impl<K, V, D> DeserializeWith<ArchivedRangeMap<K::Archived, V::Archived>, Vec<(Range<K>, V)>, D>
+    for RangeMap
+where
+    K: Archive,
+    V: Archive,
+    K::Archived: Deserialize<K, D>,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedRangeMap<K::Archived, V::Archived>,
+        deserializer: &mut D,
+    ) -> Result<Vec<(Range<K>, V)>, D::Error> {
+        let mut result = Vec::with_capacity(field.len());
+        for (range, value) in field.iter() {
+            result.push((
+                range.start.deserialize(deserializer)?..range.end.deserialize(deserializer)?,
+                value.deserialize(deserializer)?,
+            ));
+        }
+        Ok(result)
+    }
+}
+
+impl<K: Archive, V: Archive> ArchiveWith<Vec<(RangeInclusive<K>, V)>> for RangeMap {
+    type Archived = ArchivedRangeInclusiveMap<K::Archived, V::Archived>;
+    type Resolver = RangeMapResolver;
+
+    unsafe fn resolve_with(
+        field: &Vec<(RangeInclusive<K>, V)>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedRangeMap::resolve_from_len(field.len(), pos, resolver, out);
+    }
+}
+
+impl<K, V, S> SerializeWith<Vec<(RangeInclusive<K>, V)>, S> for RangeMap
+where
+    K: Serialize<S> + Ord,
+    V: Serialize<S>,
+    S: ScratchSpace + Serializer + ?Sized,
+    S::Error: From<RangeMapError>,
+{
+    fn serialize_with(
+        field: &Vec<(RangeInclusive<K>, V)>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        serialize_range_map(field, serializer)
+    }
+}
+
+impl<K, V, D>
+    DeserializeWith<
+        ArchivedRangeInclusiveMap<K::Archived, V::Archived>,
+        Vec<(RangeInclusive<K>, V)>,
+        D,
+    > for RangeMap
+where
+    K: Archive,
+    V: Archive,
+    K::Archived: Deserialize<K, D>,
+    V::Archived: Deserialize<V, D>,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedRangeInclusiveMap<K::Archived, V::Archived>,
+        deserializer: &mut D,
+    ) -> Result<Vec<(RangeInclusive<K>, V)>, D::Error> {
+        let mut result = Vec::with_capacity(field.len());
+        for (range, value) in field.iter() {
+            result.push((
+                range.start.deserialize(deserializer)?..=range.end.deserialize(deserializer)?,
+                value.deserialize(deserializer)?,
+            ));
+        }
+        Ok(result)
+    }
+}
@@ -8,15 +8,17 @@ use crate::{
     },
     option::ArchivedOption,
     with::{
-        ArchiveWith, AsBox, DeserializeWith, Inline, Map, Niche, RefAsBox, SerializeWith, Skip,
-        Unsafe,
+        ArchiveWith, AsBox, AsInner, AsInnerError, Converter, DeserializeWith, Inline, Map,
+        MapWith, Niche, Pod, RefAsBox, SerializeWith, Skip, Unsafe,
     },
     Archive, ArchiveUnsized, Deserialize, Fallible, Serialize, SerializeUnsized,
 };
 use ::core::{
-    cell::{Cell, UnsafeCell},
+    cell::{Cell, RefCell, UnsafeCell},
     convert::TryInto,
     hint::unreachable_unchecked,
+    marker::PhantomData,
+    mem::{align_of, size_of},
     num::{
         NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
         NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
@@ -104,6 +106,52 @@ struct ArchivedOptionVariantNone(ArchivedOptionTag);
 #[repr(C)]
 struct ArchivedOptionVariantSome<T>(ArchivedOptionTag, T);
 
+// MapWith
+
+impl<F, C: Converter<F>> ArchiveWith<F> for MapWith<C> {
+    type Archived = <C::Target as Archive>::Archived;
+    type Resolver = (C::Target, <C::Target as Archive>::Resolver);
+
+    #[inline]
+    unsafe fn resolve_with(
+        _: &F,
+        pos: usize,
+        (target, resolver): Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        target.resolve(pos, resolver, out);
+    }
+}
+
+impl<F, C, S> SerializeWith<F, S> for MapWith<C>
+where
+    C: Converter<F>,
+    C::Target: Serialize<S>,
+    S: Fallible + ?Sized,
+{
+    #[inline]
+    fn serialize_with(field: &F, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        let target = C::to_target(field);
+        let resolver = target.serialize(serializer)?;
+        Ok((target, resolver))
+    }
+}
+
+impl<F, C, D> DeserializeWith<<C::Target as Archive>::Archived, F, D> for MapWith<C>
+where
+    C: Converter<F>,
+    <C::Target as Archive>::Archived: Deserialize<C::Target, D>,
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize_with(
+        field: &<C::Target as Archive>::Archived,
+        deserializer: &mut D,
+    ) -> Result<F, D::Error> {
+        Ok(C::from_target(field.deserialize(deserializer)?))
+    }
+}
+
 // Inline
 
 impl<F: Archive> ArchiveWith<&F> for Inline {
@@ -328,6 +376,43 @@ impl<D: Fallible + ?Sized> DeserializeWith<ArchivedOptionNonZeroUsize, Option<No
     }
 }
 
+// Pod
+
+struct AssertSameLayout<T, U>(PhantomData<(T, U)>);
+
+impl<T, U> AssertSameLayout<T, U> {
+    const ASSERT: () = assert!(
+        size_of::<T>() == size_of::<U>() && align_of::<T>() == align_of::<U>(),
+        "Pod requires a type to have the same layout as its archived type",
+    );
+}
+
+impl<F: Archive + Copy + 'static> ArchiveWith<F> for Pod {
+    type Archived = F::Archived;
+    type Resolver = ();
+
+    #[inline]
+    unsafe fn resolve_with(field: &F, _: usize, _: Self::Resolver, out: *mut Self::Archived) {
+        let () = AssertSameLayout::<F, F::Archived>::ASSERT;
+        out.cast::<F>().write(*field);
+    }
+}
+
+impl<F: Archive + Copy + 'static, S: Fallible + ?Sized> SerializeWith<F, S> for Pod {
+    #[inline]
+    fn serialize_with(_: &F, _: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+impl<F: Archive + Copy + 'static, D: Fallible + ?Sized> DeserializeWith<F::Archived, F, D> for Pod {
+    #[inline]
+    fn deserialize_with(field: &F::Archived, _: &mut D) -> Result<F, D::Error> {
+        let () = AssertSameLayout::<F, F::Archived>::ASSERT;
+        Ok(unsafe { ptr::read((field as *const F::Archived).cast::<F>()) })
+    }
+}
+
 // Unsafe
 
 impl<F: Archive> ArchiveWith<UnsafeCell<F>> for Unsafe {
@@ -412,6 +497,82 @@ where
     }
 }
 
+// AsInner
+
+impl<F: Archive + Copy> ArchiveWith<Cell<F>> for AsInner {
+    type Archived = F::Archived;
+    type Resolver = F::Resolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &Cell<F>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        field.get().resolve(pos, resolver, out);
+    }
+}
+
+impl<F: Serialize<S> + Copy, S: Fallible + ?Sized> SerializeWith<Cell<F>, S> for AsInner {
+    #[inline]
+    fn serialize_with(field: &Cell<F>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        field.get().serialize(serializer)
+    }
+}
+
+impl<F, T, D> DeserializeWith<F, Cell<T>, D> for AsInner
+where
+    F: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize_with(field: &F, deserializer: &mut D) -> Result<Cell<T>, D::Error> {
+        Ok(Cell::new(field.deserialize(deserializer)?))
+    }
+}
+
+impl<F: Archive> ArchiveWith<RefCell<F>> for AsInner {
+    type Archived = F::Archived;
+    type Resolver = F::Resolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &RefCell<F>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        // Resolve must be infallible, so this panics if the cell was mutably borrowed after it was
+        // serialized.
+        field.borrow().resolve(pos, resolver, out);
+    }
+}
+
+impl<F: Serialize<S>, S: Fallible + ?Sized> SerializeWith<RefCell<F>, S> for AsInner
+where
+    S::Error: From<AsInnerError>,
+{
+    #[inline]
+    fn serialize_with(field: &RefCell<F>, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        field
+            .try_borrow()
+            .map_err(|_| AsInnerError::MutablyBorrowed)?
+            .serialize(serializer)
+    }
+}
+
+impl<F, T, D> DeserializeWith<F, RefCell<T>, D> for AsInner
+where
+    F: Deserialize<T, D>,
+    D: Fallible + ?Sized,
+{
+    #[inline]
+    fn deserialize_with(field: &F, deserializer: &mut D) -> Result<RefCell<T>, D::Error> {
+        Ok(RefCell::new(field.deserialize(deserializer)?))
+    }
+}
+
 // Skip
 
 impl<F> ArchiveWith<F> for Skip {
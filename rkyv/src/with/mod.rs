@@ -8,11 +8,15 @@ mod alloc;
 #[cfg(has_atomics)]
 mod atomic;
 mod core;
+#[cfg(all(feature = "alloc", feature = "serde"))]
+mod serde;
 #[cfg(feature = "std")]
 mod std;
 
 #[cfg(feature = "alloc")]
 pub use self::alloc::*;
+#[cfg(all(feature = "alloc", feature = "serde"))]
+pub use self::serde::*;
 #[cfg(feature = "std")]
 pub use self::std::*;
 
@@ -92,11 +96,11 @@ impl<F: ?Sized, W> AsRef<F> for With<F, W> {
 ///
 /// ```
 /// use rkyv::{
-///     archived_root,
 ///     ser::{
 ///         serializers::AllocSerializer,
 ///         Serializer,
 ///     },
+///     unchecked::archived_root,
 ///     with::{
 ///         ArchiveWith,
 ///         DeserializeWith,
@@ -294,6 +298,66 @@ pub struct Map<Archivable> {
     _type: PhantomData<Archivable>,
 }
 
+/// A generic wrapper that converts a field to a different archivable type and back.
+///
+/// The conversion is provided by a [`Converter`], which can be implemented on any type. This is
+/// useful for one-off conversions where writing out a full wrapper would be overkill.
+///
+/// The field is converted once while serializing it, and the converted value is kept in the
+/// resolver until the field is resolved.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{Archive, Deserialize, Serialize, with::{Converter, MapWith}};
+///
+/// struct Rgb {
+///     r: u8,
+///     g: u8,
+///     b: u8,
+/// }
+///
+/// struct Packed;
+///
+/// impl Converter<Rgb> for Packed {
+///     type Target = u32;
+///
+///     fn to_target(field: &Rgb) -> u32 {
+///         (field.r as u32) << 16 | (field.g as u32) << 8 | field.b as u32
+///     }
+///
+///     fn from_target(target: u32) -> Rgb {
+///         Rgb {
+///             r: (target >> 16) as u8,
+///             g: (target >> 8) as u8,
+///             b: target as u8,
+///         }
+///     }
+/// }
+///
+/// #[derive(Archive, Deserialize, Serialize)]
+/// struct Example {
+///     #[with(MapWith<Packed>)]
+///     color: Rgb,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct MapWith<C> {
+    _converter: PhantomData<C>,
+}
+
+/// A conversion from a type to an archivable type and back, for use with [`MapWith`].
+pub trait Converter<T> {
+    /// The archivable type to convert to.
+    type Target: Archive;
+
+    /// Converts a field to the target type.
+    fn to_target(field: &T) -> Self::Target;
+
+    /// Converts a deserialized target back to the field type.
+    fn from_target(target: Self::Target) -> T;
+}
+
 /// A wrapper that archives an atomic with an underlying atomic.
 ///
 /// By default, atomics are archived with an underlying integer.
@@ -321,8 +385,9 @@ pub struct Atomic;
 
 /// A wrapper that serializes a reference inline.
 ///
-/// References serialized with `Inline` cannot be deserialized because the struct cannot own the
-/// deserialized value.
+/// References serialized with `Inline` can't be deserialized back into the same struct because it
+/// can't own the deserialized value. Instead, [`DeserializeWith`] can deserialize them into a
+/// `Box` that owns a copy of the value.
 ///
 /// # Example
 ///
@@ -383,8 +448,9 @@ pub struct AsBox;
 ///
 /// Unlike [`Inline`], unsized references can be serialized with `RefAsBox`.
 ///
-/// References serialized with `RefAsBox` cannot be deserialized because the struct cannot own the
-/// deserialized value.
+/// References serialized with `RefAsBox` can't be deserialized back into the same struct because
+/// it can't own the deserialized value. Instead, [`DeserializeWith`] can deserialize them into a
+/// `Box` that owns a copy of the value.
 ///
 /// # Example
 ///
@@ -444,6 +510,39 @@ impl fmt::Display for AsStringError {
 #[cfg(feature = "std")]
 impl ::std::error::Error for AsStringError {}
 
+/// A wrapper that converts a type to UTF-8, replacing any invalid sequences.
+///
+/// This works like [`AsString`], but never fails to serialize. Any part of an `OsString` or
+/// `PathBuf` that isn't valid UTF-8 is replaced with `U+FFFD REPLACEMENT CHARACTER`, the same way
+/// `OsStr::to_string_lossy` does. This makes archives portable and readable at the cost of not
+/// always deserializing to the original value.
+///
+/// # Example
+///
+/// ```
+/// use std::{ffi::OsString, path::PathBuf};
+/// use rkyv::{unchecked::archived_root, Archive, Serialize, with::AsStringLossy};
+///
+/// #[derive(Archive, Serialize)]
+/// struct Example {
+///     #[with(AsStringLossy)]
+///     os_string: OsString,
+///     #[with(AsStringLossy)]
+///     path: PathBuf,
+/// }
+///
+/// let value = Example {
+///     os_string: OsString::from("hello world"),
+///     path: PathBuf::from("/tmp/archive.bin"),
+/// };
+/// let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+/// let archived = unsafe { archived_root::<Example>(&bytes) };
+/// assert_eq!(archived.os_string, "hello world");
+/// assert_eq!(archived.path, "/tmp/archive.bin");
+/// ```
+#[derive(Debug)]
+pub struct AsStringLossy;
+
 /// A wrapper that locks a lock and serializes the value immutably.
 ///
 /// This wrapper can panic under very specific circumstances when:
@@ -663,6 +762,380 @@ impl ::std::error::Error for UnixTimestampError {}
 #[derive(Debug)]
 pub struct Raw;
 
+/// A wrapper that archives a plain-old-data field by copying its bytes.
+///
+/// Fields archived with `Pod` skip the usual serialize and resolve logic. Instead, the bytes of
+/// the field are copied directly into its archived type, which is checked at compile time to have
+/// the same size and alignment as the unarchived type. This is intended for hot-path data like
+/// matrices and fixed arrays of IDs where even the generated resolve code is noticeable.
+///
+/// Unlike [`Raw`], the field is still validated as its regular archived type.
+///
+/// # Safety
+///
+/// The field type must be trivially copyable, meaning that it has the same archived and unarchived
+/// representations. This is not true of multibyte primitives when archiving with a non-native
+/// endianness, or of any type containing pointers or padding bytes. Using this wrapper with types
+/// that aren't trivially copyable may result in undefined behavior.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{Archive, with::Pod};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[with(Pod)]
+///     transform: [[f32; 4]; 4],
+///     #[with(Pod)]
+///     ids: [u8; 16],
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Pod;
+
+/// A wrapper that stores up to `N` bytes of elements inline instead of behind a relative pointer.
+///
+/// Vecs whose elements fit in `N` bytes are archived directly inside the
+/// [`ArchivedInlineVec`](crate::vec::ArchivedInlineVec), which avoids an out-of-line allocation and
+/// a pointer chase. This works well for vecs that usually only hold a handful of elements. Longer
+/// vecs are stored out of line as usual.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{Archive, with::InlineVec};
+///
+/// #[derive(Archive)]
+/// struct Node {
+///     // Up to four `u32`s are stored inline
+///     #[with(InlineVec<16>)]
+///     children: Vec<u32>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct InlineVec<const N: usize>;
+
+/// A wrapper that archives a `Vec` of boxes as [`VarintRelPtrs`](crate::rel_ptr::VarintRelPtrs).
+///
+/// Offsets are stored as variable-length integers, so the pointers usually take one or two bytes
+/// each instead of a full [`RelPtr`](crate::RelPtr). In exchange, the boxed values can only be
+/// accessed in order.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{Archive, with::VarintOffsets};
+///
+/// #[derive(Archive)]
+/// struct Tree {
+///     #[with(VarintOffsets)]
+///     children: Vec<Box<u64>>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct VarintOffsets;
+
+/// A wrapper that archives a `Vec` of primitives with the same alignment and padding as an
+/// [Apache Arrow](https://arrow.apache.org) buffer.
+///
+/// The elements are aligned to [`ALIGNMENT`](ArrowBuffer::ALIGNMENT) bytes relative to the start of
+/// the archive, and the end of the elements is padded to a multiple of the same. When the archive is
+/// loaded at an address with at least that alignment, the archived elements can be handed to Arrow
+/// as a buffer without copying them. Arrow requires little-endian values, so archives read this way
+/// should be written with native endianness on a little-endian target or with `archive_le`.
+///
+/// Only elements that don't serialize any dependencies (i.e. have a resolver of `()`) can be
+/// archived this way.
+///
+/// With the `arrow` feature, archived elements can be shared with Arrow as a `ScalarBuffer` with
+/// `arrow::scalar_buffer`, and Arrow buffers and primitive arrays can be archived directly with the
+/// same layout.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{Archive, with::ArrowBuffer};
+///
+/// #[derive(Archive)]
+/// struct Column {
+///     #[with(ArrowBuffer)]
+///     values: Vec<f64>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ArrowBuffer;
+
+impl ArrowBuffer {
+    /// The alignment and padding of archived buffers in bytes, as recommended by the Arrow
+    /// columnar format.
+    pub const ALIGNMENT: usize = 64;
+}
+
+/// A wrapper that archives strings with the same contents only once.
+///
+/// Interned strings are archived like an `Arc<str>`, so every field with the same contents points
+/// to a single archived string. Deserializing them as `Arc<str>` shares one allocation between all
+/// of those fields, while deserializing them as `String` makes a copy for each field.
+///
+/// The serializer must implement [`InternSerializeRegistry`](crate::ser::InternSerializeRegistry),
+/// which is provided by [`SharedSerializeMap`](crate::ser::serializers::SharedSerializeMap).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{Archive, with::Intern};
+/// use std::sync::Arc;
+///
+/// #[derive(Archive)]
+/// struct Token {
+///     #[with(Intern)]
+///     kind: Arc<str>,
+///     #[with(Intern)]
+///     module: String,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Intern;
+
+/// A wrapper that archives identical slices only once.
+///
+/// Deduplicated slices are archived like an `Rc<[T]>`, so fields with the same archived bytes can
+/// point to a single archived slice and still pass validation. Deserializing them makes a copy for
+/// each field.
+///
+/// The elements of a deduplicated `Vec` or boxed slice are archived into scratch space first, and
+/// if the same bytes have already been written by another deduplicated field, the field points to
+/// that copy instead of writing a new one. This works even when the values are not behind shared
+/// pointers in memory. Elements with dependencies are compared by what they point to, so nested
+/// subtrees are shared when their own slices are deduplicated as well. See
+/// [`serialize_deduped`](crate::ser::DedupSerializeRegistry::serialize_deduped) for details.
+///
+/// The serializer must implement [`DedupSerializeRegistry`](crate::ser::DedupSerializeRegistry),
+/// which is provided by [`SharedSerializeMap`](crate::ser::serializers::SharedSerializeMap).
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{Archive, with::Dedup};
+///
+/// #[derive(Archive)]
+/// struct Revision {
+///     #[with(Dedup)]
+///     content: Vec<u8>,
+///     #[with(Dedup)]
+///     checksums: Box<[u32]>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Dedup;
+
+/// A wrapper that archives a `Vec` with run-length encoding.
+///
+/// Runs of consecutive equal elements are archived as a single element, and the archived
+/// [`ArchivedRleVec`](crate::vec::ArchivedRleVec) looks elements up by index with a binary search
+/// over the runs. Deserializing requires the elements to be `Clone`.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{Archive, with::RleVec};
+///
+/// #[derive(Archive)]
+/// struct Chunk {
+///     #[with(RleVec)]
+///     voxels: Vec<u16>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct RleVec;
+
+/// A wrapper that archives a `Vec` by only storing the elements that aren't the default value.
+///
+/// The archived [`ArchivedSparseVec`](crate::vec::ArchivedSparseVec) stores the other elements
+/// along with their indices, and returns a reference to the archived default value for every
+/// element that isn't stored. Deserializing requires the elements to be `Clone`.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{Archive, with::SparseVec};
+///
+/// #[derive(Archive)]
+/// struct TileMap {
+///     #[with(SparseVec)]
+///     tiles: Vec<Option<u32>>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct SparseVec;
+
+/// A wrapper that archives a map from keys to `Vec`s of values as an
+/// [`ArchivedMultiMap`](crate::collections::ArchivedMultiMap).
+///
+/// All of the values are archived in a single vector with the values for each key next to each
+/// other, so looking up a key returns its values as a slice without going through a second
+/// relative pointer. This works with `HashMap<K, Vec<V>>` and `BTreeMap<K, Vec<V>>`.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use rkyv::{unchecked::archived_root, Archive, Serialize, with::MultiMap};
+///
+/// #[derive(Archive, Serialize)]
+/// struct InvertedIndex {
+///     #[with(MultiMap)]
+///     postings: HashMap<String, Vec<u32>>,
+/// }
+///
+/// let mut postings = HashMap::new();
+/// postings.insert("archive".to_string(), vec![1, 4, 9]);
+/// postings.insert("zero".to_string(), vec![4]);
+/// let value = InvertedIndex { postings };
+///
+/// let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+/// let archived = unsafe { archived_root::<InvertedIndex>(&bytes) };
+/// assert_eq!(archived.postings.get("archive").unwrap(), &[1, 4, 9]);
+/// assert_eq!(archived.postings.get("zero").unwrap(), &[4]);
+/// assert!(archived.postings.get("copy").is_none());
+/// ```
+#[derive(Debug)]
+pub struct MultiMap;
+
+/// A wrapper that archives a `HashMap` as an [`ArchivedBiMap`](crate::collections::ArchivedBiMap),
+/// which can be queried from either side.
+///
+/// The keys of the map become the left values of the bimap and the values become the right values.
+/// Hash indexes are built for both sides during serialization, so the archived map can look up a
+/// value by its key and a key by its value. Because of this, the values must be unique as well as
+/// the keys.
+///
+/// Regular serializers don't support the custom error handling needed for this type by default. To
+/// use this wrapper, a custom serializer with an error type satisfying
+/// `<S as Fallible>::Error: From<BiMapValueError>` must be provided.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use rkyv::{Archive, with::BiMap};
+///
+/// #[derive(Archive)]
+/// struct Users {
+///     #[with(BiMap)]
+///     names: HashMap<u32, String>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct BiMap;
+
+/// Errors that can occur while serializing a [`BiMap`] wrapper.
+#[derive(Debug)]
+pub enum BiMapValueError {
+    /// The same value was associated with more than one key
+    Duplicate,
+}
+
+impl fmt::Display for BiMapValueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "duplicate value in bimap")
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for BiMapValueError {}
+
+/// A wrapper that builds the hash index of a `HashMap` or `HashSet` on multiple threads with
+/// [rayon](https://docs.rs/rayon).
+///
+/// The field is archived as the same hash map or set as it would be without the wrapper, but its
+/// keys are hashed and assigned buckets in parallel. The built index doesn't depend on the number
+/// of threads. The keys and values of the field must be `Sync`.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::HashMap;
+/// use rkyv::{Archive, with::ParallelIndex};
+///
+/// #[derive(Archive)]
+/// struct Dictionary {
+///     #[with(ParallelIndex)]
+///     words: HashMap<String, u32>,
+/// }
+/// ```
+#[cfg(feature = "rayon")]
+#[derive(Debug)]
+pub struct ParallelIndex;
+
+/// A wrapper that archives a `Vec` of ranges and values as an
+/// [`ArchivedRangeMap`](crate::collections::ArchivedRangeMap), which finds the range containing a
+/// point with a binary search.
+///
+/// The ranges may be `Range`s or `RangeInclusive`s, which are archived as an
+/// [`ArchivedRangeInclusiveMap`](crate::collections::ArchivedRangeInclusiveMap). They are sorted
+/// during serialization and must not overlap. Deserializing produces the ranges in sorted order.
+///
+/// Regular serializers don't support the custom error handling needed for this type by default. To
+/// use this wrapper, a custom serializer with an error type satisfying
+/// `<S as Fallible>::Error: From<RangeMapError>` must be provided.
+///
+/// # Example
+///
+/// ```
+/// use core::ops::Range;
+/// use rkyv::{Archive, with::RangeMap};
+///
+/// #[derive(Archive)]
+/// struct Geolocation {
+///     #[with(RangeMap)]
+///     countries: Vec<(Range<u32>, String)>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct RangeMap;
+
+/// A wrapper that serializes a `BTreeMap` or `BTreeSet` using the serializer's scratch space.
+///
+/// The field is archived as the same B-tree map or set as it would be without the wrapper, but the
+/// nodes and resolvers kept while serializing it are allocated from the serializer's
+/// [`ScratchSpace`](crate::ser::ScratchSpace) instead of the global allocator. This lets B-tree
+/// fields be serialized with fixed-buffer serializers and without hidden allocations.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::{BTreeMap, BTreeSet};
+/// use rkyv::{Archive, with::InScratch};
+///
+/// #[derive(Archive)]
+/// struct Index {
+///     #[with(InScratch)]
+///     offsets: BTreeMap<String, u32>,
+///     #[with(InScratch)]
+///     tags: BTreeSet<String>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct InScratch;
+
+/// Errors that can occur while serializing a [`RangeMap`] wrapper.
+#[derive(Debug)]
+pub enum RangeMapError {
+    /// Two of the ranges overlapped
+    OverlappingRanges,
+}
+
+impl fmt::Display for RangeMapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "overlapping ranges in range map")
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for RangeMapError {}
+
 /// A wrapper that allows serialize-unsafe types to be serialized.
 ///
 /// Types like `Cell` and `UnsafeCell` may contain serializable types, but have unsafe access
@@ -695,6 +1168,53 @@ pub struct Raw;
 #[derive(Debug)]
 pub struct Unsafe;
 
+/// A wrapper that archives the value inside of a `Cell` or `RefCell`.
+///
+/// Unlike [`Unsafe`], this wrapper only reads the value through the cell's safe API, so the
+/// archived type is just the archived inner value. `Cell` values must be `Copy` so they can be
+/// read with `Cell::get`. `RefCell` values are borrowed while they are serialized and resolved.
+/// Deserializing builds a new cell around the deserialized value.
+///
+/// `UnsafeCell` values can't be read safely, so they have to be serialized with [`Unsafe`]
+/// instead.
+///
+/// Regular serializers don't support the custom error handling needed for `RefCell` by default.
+/// To use this wrapper with a `RefCell`, a custom serializer with an error type satisfying
+/// `<S as Fallible>::Error: From<AsInnerError>` must be provided.
+///
+/// # Example
+///
+/// ```
+/// use core::cell::{Cell, RefCell};
+/// use rkyv::{Archive, with::AsInner};
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[with(AsInner)]
+///     count: Cell<u32>,
+///     #[with(AsInner)]
+///     names: RefCell<Vec<String>>,
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsInner;
+
+/// Errors that can occur while serializing an [`AsInner`] wrapper
+#[derive(Debug)]
+pub enum AsInnerError {
+    /// The `RefCell` was already mutably borrowed
+    MutablyBorrowed,
+}
+
+impl fmt::Display for AsInnerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cell already mutably borrowed")
+    }
+}
+
+#[cfg(feature = "std")]
+impl ::std::error::Error for AsInnerError {}
+
 /// A wrapper that skips serializing a field.
 ///
 /// Skipped fields must implement `Default` to be deserialized.
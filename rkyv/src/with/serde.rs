@@ -0,0 +1,157 @@
+use crate::{
+    ser::Serializer,
+    vec::{ArchivedVec, VecResolver},
+    with::{ArchiveWith, DeserializeWith, SerializeWith},
+    Fallible,
+};
+#[cfg(not(feature = "std"))]
+use ::alloc::vec::Vec;
+use ::core::marker::PhantomData;
+use ::serde::{de::DeserializeOwned, Serialize};
+
+/// A serde data format that [`AsSerde`] can use to encode values.
+pub trait SerdeFormat {
+    /// The error produced when encoding or decoding fails.
+    type Error;
+
+    /// Encodes a value to bytes.
+    fn to_bytes<T: Serialize>(value: &T) -> Result<Vec<u8>, Self::Error>;
+
+    /// Decodes a value from bytes.
+    fn from_bytes<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Self::Error>;
+}
+
+/// A wrapper that archives a type with serde by embedding its encoding as a byte blob.
+///
+/// This is an escape hatch for third-party types that implement `serde::Serialize` and
+/// `serde::Deserialize` but not rkyv's traits. The field is archived as an
+/// [`ArchivedVec<u8>`](ArchivedVec) of bytes encoded with the format `F`, so accessing it requires
+/// decoding those bytes. The rest of the struct is still zero-copy.
+///
+/// Regular serializers and deserializers don't support the custom error handling needed for this
+/// type by default. To use this wrapper, a custom serializer or deserializer with an error type
+/// satisfying `<S as Fallible>::Error: From<F::Error>` must be provided.
+///
+/// # Example
+///
+/// ```
+/// # #[cfg(feature = "ciborium")]
+/// # {
+/// use rkyv::{Archive, with::{AsSerde, Cbor}};
+/// use std::net::SocketAddr;
+///
+/// #[derive(Archive)]
+/// struct Example {
+///     #[with(AsSerde<Cbor>)]
+///     addr: SocketAddr,
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct AsSerde<F> {
+    _format: PhantomData<F>,
+}
+
+impl<F> AsSerde<F> {
+    /// Decodes a value from the archived bytes of an `AsSerde` field.
+    #[inline]
+    pub fn decode<T: DeserializeOwned>(field: &ArchivedVec<u8>) -> Result<T, F::Error>
+    where
+        F: SerdeFormat,
+    {
+        F::from_bytes(field.as_slice())
+    }
+}
+
+/// The resolver for an [`AsSerde`] field.
+pub struct AsSerdeResolver {
+    len: usize,
+    inner: VecResolver,
+}
+
+impl<T, F> ArchiveWith<T> for AsSerde<F> {
+    type Archived = ArchivedVec<u8>;
+    type Resolver = AsSerdeResolver;
+
+    #[inline]
+    unsafe fn resolve_with(_: &T, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+        ArchivedVec::resolve_from_len(resolver.len, pos, resolver.inner, out);
+    }
+}
+
+impl<T, F, S> SerializeWith<T, S> for AsSerde<F>
+where
+    T: Serialize,
+    F: SerdeFormat,
+    S: Serializer + ?Sized,
+    S::Error: From<F::Error>,
+{
+    fn serialize_with(field: &T, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        let bytes = F::to_bytes(field)?;
+        // Bytes are copy-safe
+        let inner =
+            unsafe { ArchivedVec::serialize_copy_from_slice(bytes.as_slice(), serializer)? };
+        Ok(AsSerdeResolver {
+            len: bytes.len(),
+            inner,
+        })
+    }
+}
+
+impl<T, F, D> DeserializeWith<ArchivedVec<u8>, T, D> for AsSerde<F>
+where
+    T: DeserializeOwned,
+    F: SerdeFormat,
+    D: Fallible + ?Sized,
+    D::Error: From<F::Error>,
+{
+    #[inline]
+    fn deserialize_with(field: &ArchivedVec<u8>, _: &mut D) -> Result<T, D::Error> {
+        Ok(Self::decode(field)?)
+    }
+}
+
+/// The [CBOR](https://cbor.io) data format, as implemented by `ciborium`.
+#[cfg(feature = "ciborium")]
+#[derive(Debug)]
+pub struct Cbor;
+
+/// An error encoding or decoding a value with the [`Cbor`] format.
+#[cfg(feature = "ciborium")]
+#[derive(Debug)]
+pub enum CborError {
+    /// The value could not be encoded.
+    Encode(::ciborium::ser::Error<<Vec<u8> as ::ciborium_io::Write>::Error>),
+    /// The bytes could not be decoded.
+    Decode(::ciborium::de::Error<<&'static [u8] as ::ciborium_io::Read>::Error>),
+}
+
+#[cfg(feature = "ciborium")]
+impl ::core::fmt::Display for CborError {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        match self {
+            CborError::Encode(e) => write!(f, "failed to encode CBOR: {}", e),
+            CborError::Decode(e) => write!(f, "failed to decode CBOR: {}", e),
+        }
+    }
+}
+
+#[cfg(all(feature = "ciborium", feature = "std"))]
+impl ::std::error::Error for CborError {}
+
+#[cfg(feature = "ciborium")]
+impl SerdeFormat for Cbor {
+    type Error = CborError;
+
+    #[inline]
+    fn to_bytes<T: Serialize>(value: &T) -> Result<Vec<u8>, Self::Error> {
+        let mut bytes = Vec::new();
+        ::ciborium::ser::into_writer(value, &mut bytes).map_err(CborError::Encode)?;
+        Ok(bytes)
+    }
+
+    #[inline]
+    fn from_bytes<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, Self::Error> {
+        ::ciborium::de::from_reader(bytes).map_err(CborError::Decode)
+    }
+}
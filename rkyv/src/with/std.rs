@@ -1,16 +1,24 @@
 use crate::{
-    collections::util::Entry,
+    collections::{
+        bi_map::{ArchivedBiMap, BiMapResolver},
+        multi_map::{ArchivedMultiMap, MultiMapResolver},
+        util::Entry,
+    },
     ser::{ScratchSpace, Serializer},
     string::{ArchivedString, StringResolver},
     time::ArchivedDuration,
     vec::{ArchivedVec, VecResolver},
     with::{
-        ArchiveWith, AsString, AsStringError, AsVec, DeserializeWith, Immutable, Lock, LockError,
-        SerializeWith, UnixTimestamp, UnixTimestampError,
+        ArchiveWith, AsString, AsStringError, AsStringLossy, AsVec, BiMap, BiMapValueError,
+        DeserializeWith, Immutable, Lock, LockError, MultiMap, SerializeWith, UnixTimestamp,
+        UnixTimestampError,
     },
     Archive, Deserialize, Fallible, Serialize, SerializeUnsized,
 };
-use core::{hash::Hash, str::FromStr};
+use core::{
+    hash::{BuildHasher, Hash},
+    str::FromStr,
+};
 use std::{
     collections::{HashMap, HashSet},
     ffi::OsString,
@@ -97,6 +105,72 @@ impl<D: Fallible + ?Sized> DeserializeWith<ArchivedString, PathBuf, D> for AsStr
     }
 }
 
+// AsStringLossy
+
+impl ArchiveWith<OsString> for AsStringLossy {
+    type Archived = ArchivedString;
+    type Resolver = StringResolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &OsString,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedString::resolve_from_str(&field.to_string_lossy(), pos, resolver, out);
+    }
+}
+
+impl<S: Fallible + ?Sized> SerializeWith<OsString, S> for AsStringLossy
+where
+    str: SerializeUnsized<S>,
+{
+    #[inline]
+    fn serialize_with(field: &OsString, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedString::serialize_from_str(&field.to_string_lossy(), serializer)
+    }
+}
+
+impl<D: Fallible + ?Sized> DeserializeWith<ArchivedString, OsString, D> for AsStringLossy {
+    #[inline]
+    fn deserialize_with(field: &ArchivedString, _: &mut D) -> Result<OsString, D::Error> {
+        Ok(OsString::from(field.as_str()))
+    }
+}
+
+impl ArchiveWith<PathBuf> for AsStringLossy {
+    type Archived = ArchivedString;
+    type Resolver = StringResolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        field: &PathBuf,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedString::resolve_from_str(&field.to_string_lossy(), pos, resolver, out);
+    }
+}
+
+impl<S: Fallible + ?Sized> SerializeWith<PathBuf, S> for AsStringLossy
+where
+    str: SerializeUnsized<S>,
+{
+    #[inline]
+    fn serialize_with(field: &PathBuf, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedString::serialize_from_str(&field.to_string_lossy(), serializer)
+    }
+}
+
+impl<D: Fallible + ?Sized> DeserializeWith<ArchivedString, PathBuf, D> for AsStringLossy {
+    #[inline]
+    fn deserialize_with(field: &ArchivedString, _: &mut D) -> Result<PathBuf, D::Error> {
+        Ok(PathBuf::from(field.as_str()))
+    }
+}
+
 // Lock
 
 impl<F: Archive> ArchiveWith<Mutex<F>> for Lock {
@@ -290,6 +364,237 @@ where
     }
 }
 
+// MultiMap
+
+impl<K: Archive, V: Archive, H> ArchiveWith<HashMap<K, Vec<V>, H>> for MultiMap {
+    type Archived = ArchivedMultiMap<K::Archived, V::Archived>;
+    type Resolver = MultiMapResolver;
+
+    unsafe fn resolve_with(
+        field: &HashMap<K, Vec<V>, H>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        let values_len = field.values().map(|values| values.len()).sum();
+        ArchivedMultiMap::resolve_from_len(field.len(), values_len, pos, resolver, out);
+    }
+}
+
+impl<K, V, H, S> SerializeWith<HashMap<K, Vec<V>, H>, S> for MultiMap
+where
+    K: Serialize<S> + Hash + Eq,
+    V: Serialize<S>,
+    S: ScratchSpace + Serializer + ?Sized,
+{
+    fn serialize_with(
+        field: &HashMap<K, Vec<V>, H>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        unsafe {
+            ArchivedMultiMap::serialize_from_iter(
+                field.iter().map(|(key, values)| (key, values.as_slice())),
+                serializer,
+            )
+        }
+    }
+}
+
+impl<K, V, H, D>
+    DeserializeWith<ArchivedMultiMap<K::Archived, V::Archived>, HashMap<K, Vec<V>, H>, D>
+    for MultiMap
+where
+    K: Archive + Hash + Eq,
+    V: Archive,
+    K::Archived: Deserialize<K, D>,
+    V::Archived: Deserialize<V, D>,
+    H: Default + BuildHasher,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedMultiMap<K::Archived, V::Archived>,
+        deserializer: &mut D,
+    ) -> Result<HashMap<K, Vec<V>, H>, D::Error> {
+        let mut result = HashMap::with_capacity_and_hasher(field.len(), H::default());
+        for (key, values) in field.iter() {
+            let mut deserialized = Vec::with_capacity(values.len());
+            for value in values {
+                deserialized.push(value.deserialize(deserializer)?);
+            }
+            result.insert(key.deserialize(deserializer)?, deserialized);
+        }
+        Ok(result)
+    }
+}
+
+// BiMap
+
+impl<K: Archive, V: Archive, H> ArchiveWith<HashMap<K, V, H>> for BiMap {
+    type Archived = ArchivedBiMap<K::Archived, V::Archived>;
+    type Resolver = BiMapResolver;
+
+    unsafe fn resolve_with(
+        field: &HashMap<K, V, H>,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedBiMap::resolve_from_len(field.len(), pos, resolver, out);
+    }
+}
+
+impl<K, V, H, S> SerializeWith<HashMap<K, V, H>, S> for BiMap
+where
+    K: Serialize<S> + Hash + Eq,
+    V: Serialize<S> + Hash + Eq,
+    S: ScratchSpace + Serializer + ?Sized,
+    S::Error: From<BiMapValueError>,
+{
+    fn serialize_with(
+        field: &HashMap<K, V, H>,
+        serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        let mut values = HashSet::with_capacity(field.len());
+        if !field.values().all(|value| values.insert(value)) {
+            return Err(BiMapValueError::Duplicate.into());
+        }
+
+        unsafe { ArchivedBiMap::serialize_from_iter(field.iter(), serializer) }
+    }
+}
+
+impl<K, V, H, D> DeserializeWith<ArchivedBiMap<K::Archived, V::Archived>, HashMap<K, V, H>, D>
+    for BiMap
+where
+    K: Archive + Hash + Eq,
+    V: Archive,
+    K::Archived: Deserialize<K, D>,
+    V::Archived: Deserialize<V, D>,
+    H: Default + BuildHasher,
+    D: Fallible + ?Sized,
+{
+    fn deserialize_with(
+        field: &ArchivedBiMap<K::Archived, V::Archived>,
+        deserializer: &mut D,
+    ) -> Result<HashMap<K, V, H>, D::Error> {
+        let mut result = HashMap::with_capacity_and_hasher(field.len(), H::default());
+        for (key, value) in field.iter() {
+            result.insert(
+                key.deserialize(deserializer)?,
+                value.deserialize(deserializer)?,
+            );
+        }
+        Ok(result)
+    }
+}
+
+// ParallelIndex
+
+#[cfg(feature = "rayon")]
+const _: () = {
+    use crate::{
+        collections::{
+            hash_map::{ArchivedHashMap, HashMapResolver},
+            hash_set::{ArchivedHashSet, HashSetResolver},
+        },
+        with::ParallelIndex,
+    };
+
+    impl<K: Archive, V: Archive, H> ArchiveWith<HashMap<K, V, H>> for ParallelIndex {
+        type Archived = ArchivedHashMap<K::Archived, V::Archived>;
+        type Resolver = HashMapResolver;
+
+        #[inline]
+        unsafe fn resolve_with(
+            field: &HashMap<K, V, H>,
+            pos: usize,
+            resolver: Self::Resolver,
+            out: *mut Self::Archived,
+        ) {
+            ArchivedHashMap::resolve_from_len(field.len(), pos, resolver, out);
+        }
+    }
+
+    impl<K, V, H, S> SerializeWith<HashMap<K, V, H>, S> for ParallelIndex
+    where
+        K: Serialize<S> + Hash + Eq + Sync,
+        V: Serialize<S> + Sync,
+        S: ScratchSpace + Serializer + ?Sized,
+    {
+        #[inline]
+        fn serialize_with(
+            field: &HashMap<K, V, H>,
+            serializer: &mut S,
+        ) -> Result<Self::Resolver, S::Error> {
+            unsafe { ArchivedHashMap::par_serialize_from_iter(field.iter(), serializer) }
+        }
+    }
+
+    impl<K, V, H, D> DeserializeWith<ArchivedHashMap<K::Archived, V::Archived>, HashMap<K, V, H>, D>
+        for ParallelIndex
+    where
+        K: Archive + Hash + Eq,
+        V: Archive,
+        K::Archived: Deserialize<K, D> + Hash + Eq,
+        V::Archived: Deserialize<V, D>,
+        H: Default + BuildHasher,
+        D: Fallible + ?Sized,
+    {
+        #[inline]
+        fn deserialize_with(
+            field: &ArchivedHashMap<K::Archived, V::Archived>,
+            deserializer: &mut D,
+        ) -> Result<HashMap<K, V, H>, D::Error> {
+            field.deserialize(deserializer)
+        }
+    }
+
+    impl<K: Archive, H> ArchiveWith<HashSet<K, H>> for ParallelIndex {
+        type Archived = ArchivedHashSet<K::Archived>;
+        type Resolver = HashSetResolver;
+
+        #[inline]
+        unsafe fn resolve_with(
+            field: &HashSet<K, H>,
+            pos: usize,
+            resolver: Self::Resolver,
+            out: *mut Self::Archived,
+        ) {
+            ArchivedHashSet::resolve_from_len(field.len(), pos, resolver, out);
+        }
+    }
+
+    impl<K, H, S> SerializeWith<HashSet<K, H>, S> for ParallelIndex
+    where
+        K: Serialize<S> + Hash + Eq + Sync,
+        S: ScratchSpace + Serializer + ?Sized,
+    {
+        #[inline]
+        fn serialize_with(
+            field: &HashSet<K, H>,
+            serializer: &mut S,
+        ) -> Result<Self::Resolver, S::Error> {
+            unsafe { ArchivedHashSet::par_serialize_from_iter(field.iter(), serializer) }
+        }
+    }
+
+    impl<K, H, D> DeserializeWith<ArchivedHashSet<K::Archived>, HashSet<K, H>, D> for ParallelIndex
+    where
+        K: Archive + Hash + Eq,
+        K::Archived: Deserialize<K, D> + Hash + Eq,
+        H: Default + BuildHasher,
+        D: Fallible + ?Sized,
+    {
+        #[inline]
+        fn deserialize_with(
+            field: &ArchivedHashSet<K::Archived>,
+            deserializer: &mut D,
+        ) -> Result<HashSet<K, H>, D::Error> {
+            field.deserialize(deserializer)
+        }
+    }
+};
+
 // UnixTimestamp
 
 impl ArchiveWith<SystemTime> for UnixTimestamp {
@@ -5,9 +5,10 @@ use rand_pcg::Lcg64Xsh32;
 use rkyv::{
     archived_root, check_archived_root,
     ser::{
-        serializers::{AlignedSerializer, BufferScratch, CompositeSerializer},
+        serializers::{AlignedSerializer, AllocScratch, BufferScratch, CompositeSerializer},
         Serializer,
     },
+    with::VarintOffsets,
     AlignedVec, Archive, Deserialize, Infallible, Serialize,
 };
 use std::collections::HashMap;
@@ -452,5 +453,104 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, criterion_benchmark);
+#[derive(Archive, Serialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct BoxedItems {
+    items: Vec<Box<Item>>,
+}
+
+#[derive(Archive, Serialize)]
+#[archive_attr(derive(CheckBytes))]
+pub struct VarintItems {
+    #[with(VarintOffsets)]
+    items: Vec<Box<Item>>,
+}
+
+fn serialize_to_vec<T: Serialize<CompositeSerializer<AlignedSerializer<AlignedVec>, AllocScratch>>>(
+    value: &T,
+) -> AlignedVec {
+    let mut serializer = CompositeSerializer::new(
+        AlignedSerializer::new(AlignedVec::new()),
+        AllocScratch::default(),
+        Infallible,
+    );
+    serializer.serialize_value(value).unwrap();
+    serializer.into_serializer().into_inner()
+}
+
+pub fn rel_ptr_offsets_benchmark(c: &mut Criterion) {
+    const ITEMS: usize = 10_000;
+    const STATE: u64 = 2718281828;
+    const STREAM: u64 = 4590452353;
+
+    let mut rng = Lcg64Xsh32::new(STATE, STREAM);
+    let items = (0..ITEMS)
+        .map(|_| Box::new(Item::generate(&mut rng)))
+        .collect::<Vec<_>>();
+
+    let boxed = serialize_to_vec(&BoxedItems {
+        items: items
+            .iter()
+            .map(|item| {
+                Box::new(Item {
+                    count: item.count,
+                    slot: item.slot,
+                    id: item.id.clone(),
+                })
+            })
+            .collect(),
+    });
+    let varint = serialize_to_vec(&VarintItems { items });
+
+    // Varint offsets trade random access for density, so report the size difference alongside
+    // the timings.
+    println!(
+        "rel_ptr_offsets: boxed archive is {} bytes, varint archive is {} bytes",
+        boxed.len(),
+        varint.len(),
+    );
+
+    let mut group = c.benchmark_group("rel_ptr_offsets");
+    group.bench_function("boxed iterate", |b| {
+        b.iter(|| {
+            let value = unsafe { archived_root::<BoxedItems>(black_box(boxed.as_ref())) };
+            for item in value.items.iter() {
+                black_box(item.count);
+            }
+        })
+    });
+    group.bench_function("varint iterate", |b| {
+        b.iter(|| {
+            let value = unsafe { archived_root::<VarintItems>(black_box(varint.as_ref())) };
+            for item in value.items.iter() {
+                black_box(item.count);
+            }
+        })
+    });
+    group.bench_function("boxed index", |b| {
+        b.iter(|| {
+            let value = unsafe { archived_root::<BoxedItems>(black_box(boxed.as_ref())) };
+            black_box(value.items[black_box(ITEMS / 2)].count);
+        })
+    });
+    group.bench_function("varint index", |b| {
+        b.iter(|| {
+            let value = unsafe { archived_root::<VarintItems>(black_box(varint.as_ref())) };
+            black_box(value.items.get(black_box(ITEMS / 2)).unwrap().count);
+        })
+    });
+    group.bench_function("boxed validate", |b| {
+        b.iter(|| {
+            check_archived_root::<BoxedItems>(black_box(boxed.as_ref())).unwrap();
+        })
+    });
+    group.bench_function("varint validate", |b| {
+        b.iter(|| {
+            check_archived_root::<VarintItems>(black_box(varint.as_ref())).unwrap();
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark, rel_ptr_offsets_benchmark);
 criterion_main!(benches);
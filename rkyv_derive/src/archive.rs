@@ -1,14 +1,16 @@
 use crate::{
     attributes::{parse_attributes, Attributes},
+    c_header::derive_c_type_impl,
     repr::{BaseRepr, IntRepr, Repr},
     util::{add_bounds, strip_raw},
+    visit::derive_visit_impl,
     with::{make_with_cast, make_with_ty},
 };
 use proc_macro2::{Span, TokenStream};
-use quote::quote;
+use quote::{quote, ToTokens};
 use syn::{
     parse_quote, spanned::Spanned, Attribute, Data, DeriveInput, Error, Field, Fields, Ident,
-    Index, Meta, NestedMeta, Type,
+    Index, Meta, NestedMeta, Type, WhereClause,
 };
 
 pub fn derive(input: DeriveInput) -> Result<TokenStream, Error> {
@@ -34,6 +36,36 @@ fn field_archive_attrs(field: &Field) -> impl '_ + Iterator<Item = NestedMeta> {
         .flatten()
 }
 
+/// Generates a hidden associated constant on the archived type that asserts its layout, and a
+/// statement that evaluates it from `resolve`.
+///
+/// Generic archived types can only be checked once they're instantiated, so the assertion is
+/// evaluated whenever the type is archived. Non-generic archived types are checked immediately.
+fn layout_assertion(
+    impl_generics: &impl ToTokens,
+    archived_type: &Type,
+    where_clause: &WhereClause,
+    is_generic: bool,
+    assertion: TokenStream,
+) -> (Option<TokenStream>, Option<TokenStream>) {
+    let eager_check = if is_generic {
+        None
+    } else {
+        Some(quote! { const _: () = <#archived_type>::__RKYV_ASSERT_LAYOUT; })
+    };
+
+    (
+        Some(quote! {
+            impl #impl_generics #archived_type #where_clause {
+                const __RKYV_ASSERT_LAYOUT: () = #assertion;
+            }
+
+            #eager_check
+        }),
+        Some(quote! { let () = <#archived_type>::__RKYV_ASSERT_LAYOUT; }),
+    )
+}
+
 fn derive_archive_impl(
     mut input: DeriveInput,
     attributes: &Attributes,
@@ -115,6 +147,11 @@ fn derive_archive_impl(
     );
     let resolver_doc = format!("The resolver for an archived [`{}`]", name);
 
+    let assert_layout = cfg!(feature = "strict")
+        && attributes.archive_as.is_none()
+        && attributes.archived_repr.modifier.is_none();
+    let is_generic = !input.generics.params.is_empty();
+
     let (archive_types, archive_impls) = match input.data {
         Data::Struct(ref data) => {
             let base_repr = if cfg!(feature = "strict") {
@@ -197,6 +234,23 @@ fn derive_archive_impl(
                         }
                     });
 
+                    let (layout_impl, layout_check) = if assert_layout {
+                        let fields = fields.named.iter().map(|f| {
+                            let name = &f.ident;
+                            let ty = with_ty(f).unwrap();
+                            quote! { #name: #rkyv_path::Archived<#ty> }
+                        });
+                        layout_assertion(
+                            &impl_generics,
+                            &archived_type,
+                            &archive_where,
+                            is_generic,
+                            quote! { #rkyv_path::assert_repr_c!(Self { #(#fields,)* }) },
+                        )
+                    } else {
+                        (None, None)
+                    };
+
                     let mut partial_eq_impl = None;
                     let mut partial_ord_impl = None;
                     if let Some((_, ref compares)) = attributes.compares {
@@ -314,6 +368,7 @@ fn derive_archive_impl(
                                 #[allow(clippy::unit_arg)]
                                 #[inline]
                                 unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+                                    #layout_check
                                     #(#resolve_fields)*
                                 }
                             }
@@ -321,6 +376,7 @@ fn derive_archive_impl(
                             #partial_eq_impl
                             #partial_ord_impl
                             #copy_safe_impl
+                            #layout_impl
                         },
                     )
                 }
@@ -376,6 +432,23 @@ fn derive_archive_impl(
                         }
                     });
 
+                    let (layout_impl, layout_check) = if assert_layout {
+                        let fields = fields.unnamed.iter().enumerate().map(|(i, f)| {
+                            let index = Index::from(i);
+                            let ty = with_ty(f).unwrap();
+                            quote! { #index: #rkyv_path::Archived<#ty> }
+                        });
+                        layout_assertion(
+                            &impl_generics,
+                            &archived_type,
+                            &archive_where,
+                            is_generic,
+                            quote! { #rkyv_path::assert_repr_c!(Self { #(#fields,)* }) },
+                        )
+                    } else {
+                        (None, None)
+                    };
+
                     let mut partial_eq_impl = None;
                     let mut partial_ord_impl = None;
                     if let Some((_, ref compares)) = attributes.compares {
@@ -496,6 +569,7 @@ fn derive_archive_impl(
                                 #[allow(clippy::unit_arg)]
                                 #[inline]
                                 unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+                                    #layout_check
                                     #(#resolve_fields)*
                                 }
                             }
@@ -503,6 +577,7 @@ fn derive_archive_impl(
                             #partial_eq_impl
                             #partial_ord_impl
                             #copy_safe_impl
+                            #layout_impl
                         },
                     )
                 }
@@ -520,6 +595,18 @@ fn derive_archive_impl(
                         None
                     };
 
+                    let (layout_impl, layout_check) = if assert_layout {
+                        layout_assertion(
+                            &impl_generics,
+                            &archived_type,
+                            where_clause,
+                            is_generic,
+                            quote! { #rkyv_path::assert_repr_c!(Self {}) },
+                        )
+                    } else {
+                        (None, None)
+                    };
+
                     let mut partial_eq_impl = None;
                     let mut partial_ord_impl = None;
                     if let Some((_, ref compares)) = attributes.compares {
@@ -589,12 +676,15 @@ fn derive_archive_impl(
                                 type Resolver = #resolver #ty_generics;
 
                                 #[inline]
-                                unsafe fn resolve(&self, _: usize, _: Self::Resolver, _: *mut Self::Archived) {}
+                                unsafe fn resolve(&self, _: usize, _: Self::Resolver, _: *mut Self::Archived) {
+                                    #layout_check
+                                }
                             }
 
                             #partial_eq_impl
                             #partial_ord_impl
                             #copy_safe_impl
+                            #layout_impl
                         },
                     )
                 }
@@ -777,16 +867,10 @@ fn derive_archive_impl(
                 Some((BaseRepr::Int(int_repr), span)) => (int_repr, span),
                 // If unspecified, the base repr is set to u* with the smallest unsigned integer
                 // that can represent the number of variants
-                None => {
-                    let int_repr = match data.variants.len() {
-                        0..=255 => IntRepr::U8,
-                        256..=65_535 => IntRepr::U16,
-                        65_536..=4_294_967_295 => IntRepr::U32,
-                        4_294_967_296..=18_446_744_073_709_551_615 => IntRepr::U64,
-                        _ => IntRepr::U128,
-                    };
-                    (int_repr, Span::call_site())
-                }
+                None => (
+                    IntRepr::for_variant_count(data.variants.len()),
+                    Span::call_site(),
+                ),
             };
             let repr = Repr {
                 base_repr: Some((BaseRepr::Int(int_repr), int_repr_span)),
@@ -936,6 +1020,42 @@ fn derive_archive_impl(
                 }
             });
 
+            let (layout_impl, layout_check) = if assert_layout {
+                let variant_layouts = data.variants.iter().map(|v| {
+                    let fields = v.fields.iter().map(|f| {
+                        let ty = with_ty(f).unwrap();
+                        quote! { .field::<#rkyv_path::Archived<#ty>>() }
+                    });
+                    quote! {
+                        let layout = layout.union(
+                            #rkyv_path::layout::ReprC::new().field::<ArchivedTag>() #(#fields)*
+                        );
+                    }
+                });
+                let message = format!(
+                    "the size and alignment of {} do not match its repr({}) layout",
+                    archived_name,
+                    quote! { #int_repr },
+                );
+                layout_assertion(
+                    &impl_generics,
+                    &archived_type,
+                    &archive_where,
+                    is_generic,
+                    quote! {{
+                        let layout = #rkyv_path::layout::ReprC::new();
+                        #(#variant_layouts)*
+                        ::core::assert!(
+                            ::core::mem::size_of::<Self>() == layout.size()
+                                && ::core::mem::align_of::<Self>() == layout.align(),
+                            #message,
+                        );
+                    }},
+                )
+            } else {
+                (None, None)
+            };
+
             let mut partial_eq_impl = None;
             let mut partial_ord_impl = None;
             if let Some((_, ref compares)) = attributes.compares {
@@ -1260,6 +1380,7 @@ fn derive_archive_impl(
                         #[allow(clippy::unit_arg)]
                         #[inline]
                         unsafe fn resolve(&self, pos: usize, resolver: Self::Resolver, out: *mut Self::Archived) {
+                            #layout_check
                             match resolver {
                                 #(#resolve_arms,)*
                             }
@@ -1269,6 +1390,7 @@ fn derive_archive_impl(
                     #partial_eq_impl
                     #partial_ord_impl
                     #copy_safe_impl
+                    #layout_impl
                 },
             )
         }
@@ -1280,6 +1402,23 @@ fn derive_archive_impl(
         }
     };
 
+    let visit_impl = if attributes.serde.is_some() {
+        Some(derive_visit_impl(&input, &archived_type, rkyv_path)?)
+    } else {
+        None
+    };
+
+    let c_type_impl = if attributes.c_header.is_some() {
+        Some(derive_c_type_impl(
+            &input,
+            attributes,
+            &archived_name,
+            rkyv_path,
+        )?)
+    } else {
+        None
+    };
+
     Ok(quote! {
         #archive_types
 
@@ -1289,6 +1428,8 @@ fn derive_archive_impl(
             use #rkyv_path::{out_field, Archive, Archived};
 
             #archive_impls
+            #visit_impl
+            #c_type_impl
         };
     })
 }
@@ -15,6 +15,8 @@ pub struct Attributes {
     pub deserialize_bound: Option<LitStr>,
     pub copy_safe: Option<Path>,
     pub rkyv_path: Option<Path>,
+    pub serde: Option<Path>,
+    pub c_header: Option<Path>,
 }
 
 fn try_set_attribute<T: ToTokens>(
@@ -38,6 +40,10 @@ fn parse_archive_attributes(attributes: &mut Attributes, meta: &Meta) -> Result<
         Meta::Path(path) => {
             if path.is_ident("copy_safe") {
                 try_set_attribute(&mut attributes.copy_safe, path.clone(), "copy_safe")
+            } else if path.is_ident("serde") {
+                try_set_attribute(&mut attributes.serde, path.clone(), "serde")
+            } else if path.is_ident("c_header") {
+                try_set_attribute(&mut attributes.c_header, path.clone(), "c_header")
             } else {
                 Err(Error::new_spanned(meta, "unrecognized archive argument"))
             }
@@ -0,0 +1,129 @@
+use crate::{
+    attributes::Attributes,
+    repr::{BaseRepr, IntRepr},
+    util::strip_raw,
+    with::make_with_ty,
+};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, Data, DeriveInput, Error, Fields, Ident, Path};
+
+fn c_fields(fields: &Fields, rkyv_path: &Path) -> Result<Vec<TokenStream>, Error> {
+    let with_ty = make_with_ty(rkyv_path);
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| {
+            let ty = with_ty(f)?;
+            let name = f
+                .ident
+                .as_ref()
+                .map_or_else(|| format!("_{}", i), strip_raw);
+            Ok(quote! {
+                #rkyv_path::c_header::CField::new::<#rkyv_path::Archived<#ty>>(#name)
+            })
+        })
+        .collect()
+}
+
+pub fn derive_c_type_impl(
+    input: &DeriveInput,
+    attributes: &Attributes,
+    archived_name: &Ident,
+    rkyv_path: &Path,
+) -> Result<TokenStream, Error> {
+    let c_header: Path = parse_quote! { #rkyv_path::c_header };
+
+    if let Some(ref archive_as) = attributes.archive_as {
+        return Err(Error::new_spanned(
+            archive_as,
+            "c_header may not be used with as = \"...\"",
+        ));
+    }
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "c_header may not be used with generic types",
+        ));
+    }
+
+    let ident = strip_raw(archived_name);
+    let (tag, add_definitions) = match input.data {
+        Data::Struct(ref data) => {
+            let is_repr_c = cfg!(feature = "strict")
+                || matches!(attributes.archived_repr.base_repr, Some((BaseRepr::C, _)));
+            if !is_repr_c {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "c_header requires the archived type to be repr(C)\n\
+                    add #[archive_attr(repr(C))] or enable the `strict` feature",
+                ));
+            }
+            if data.fields.is_empty() {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "c_header may not be used with structs that have no fields",
+                ));
+            }
+
+            let fields = c_fields(&data.fields, rkyv_path)?;
+            (
+                "struct",
+                quote! {
+                    header.add_struct::<Self>(#ident, &[#(#fields,)*]);
+                },
+            )
+        }
+        Data::Enum(ref data) => {
+            let int_repr = match attributes.archived_repr.base_repr {
+                Some((BaseRepr::Int(int_repr), _)) => int_repr,
+                _ => IntRepr::for_variant_count(data.variants.len()),
+            };
+
+            let variants = data
+                .variants
+                .iter()
+                .map(|v| {
+                    let name = strip_raw(&v.ident);
+                    let fields = c_fields(&v.fields, rkyv_path)?;
+                    Ok(quote! {
+                        #c_header::CVariant::new(#name, &[#(#fields,)*])
+                    })
+                })
+                .collect::<Result<Vec<_>, Error>>()?;
+            (
+                "union",
+                quote! {
+                    header.add_enum::<Self, #int_repr>(#ident, &[#(#variants,)*]);
+                },
+            )
+        }
+        Data::Union(_) => {
+            return Err(Error::new_spanned(
+                input,
+                "C headers cannot be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #c_header::CType for #archived_name {
+            #[inline]
+            fn write_declaration<W: ::core::fmt::Write + ?Sized>(
+                name: &str,
+                out: &mut W,
+            ) -> ::core::fmt::Result {
+                #c_header::write_tagged(out, #tag, #ident, name)
+            }
+
+            #[inline]
+            fn write_ident<W: ::core::fmt::Write + ?Sized>(out: &mut W) -> ::core::fmt::Result {
+                out.write_str(#ident)
+            }
+
+            fn add_definitions(header: &mut #c_header::CHeader) {
+                #add_definitions
+            }
+        }
+    })
+}
@@ -8,11 +8,13 @@
 
 mod archive;
 mod attributes;
+mod c_header;
 mod deserialize;
 mod repr;
 mod serde;
 mod serialize;
 mod util;
+mod visit;
 mod with;
 
 extern crate proc_macro;
@@ -45,6 +47,10 @@ use syn::{parse_macro_input, DeriveInput};
 ///   `Deserialize` bounds.
 /// - `copy_safe`: States that the archived type is tightly packed with no padding bytes. This
 ///   qualifies it for copy optimizations. (requires nightly)
+/// - `serde`: Implements `VisitArchived` for the archived type so it can be deserialized with
+///   serde through `rkyv::de::serde`. Requires the `serde` feature of rkyv.
+/// - `c_header`: Implements `CType` for the archived type so it can be added to C headers with
+///   `rkyv::c_header`. Archived structs must be `repr(C)`.
 /// - `as = "..."`: Instead of generating a separate archived type, this type will archive as the
 ///   named type. This is useful for types which are generic over their parameters.
 /// - `crate = "..."`: Chooses an alternative crate path to import rkyv from.
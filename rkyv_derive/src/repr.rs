@@ -34,6 +34,17 @@ impl ToTokens for IntRepr {
 }
 
 impl IntRepr {
+    #[inline]
+    pub fn for_variant_count(count: usize) -> Self {
+        match count {
+            0..=255 => IntRepr::U8,
+            256..=65_535 => IntRepr::U16,
+            65_536..=4_294_967_295 => IntRepr::U32,
+            4_294_967_296..=18_446_744_073_709_551_615 => IntRepr::U64,
+            _ => IntRepr::U128,
+        }
+    }
+
     #[inline]
     #[cfg(not(feature = "arbitrary_enum_discriminant"))]
     pub fn enum_discriminant(&self, _: usize) -> Option<EnumDiscriminant> {
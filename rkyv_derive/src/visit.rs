@@ -0,0 +1,216 @@
+use crate::{util::strip_raw, with::make_with_ty};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, Data, DeriveInput, Error, Field, Fields, Index, Path, Type};
+
+fn field_names(fields: &Fields) -> Vec<String> {
+    match fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|f| strip_raw(f.ident.as_ref().unwrap()))
+            .collect(),
+        Fields::Unnamed(_) | Fields::Unit => Vec::new(),
+    }
+}
+
+fn bounded_fields(fields: &Fields) -> impl '_ + Iterator<Item = &'_ Field> {
+    fields
+        .iter()
+        .filter(|f| !f.attrs.iter().any(|a| a.path.is_ident("omit_bounds")))
+}
+
+pub fn derive_visit_impl(
+    input: &DeriveInput,
+    archived_type: &Type,
+    rkyv_path: &Path,
+) -> Result<TokenStream, Error> {
+    let with_ty = make_with_ty(rkyv_path);
+    let serde_path: Path = parse_quote! { #rkyv_path::de::serde };
+
+    let (impl_generics, _, where_clause) = input.generics.split_for_impl();
+    let mut visit_where = where_clause.unwrap().clone();
+
+    let (visit_fields, visit) = match input.data {
+        Data::Struct(ref data) => {
+            for field in bounded_fields(&data.fields) {
+                let ty = with_ty(field)?;
+                visit_where
+                    .predicates
+                    .push(parse_quote! { #ty: #rkyv_path::Archive });
+                visit_where
+                    .predicates
+                    .push(parse_quote! { Archived<#ty>: #serde_path::VisitArchived });
+            }
+
+            let names = field_names(&data.fields);
+            let count = data.fields.len();
+            let visit_arms = data.fields.iter().enumerate().map(|(i, f)| {
+                let member = match f.ident {
+                    Some(ref ident) => quote! { #ident },
+                    None => {
+                        let index = Index::from(i);
+                        quote! { #index }
+                    }
+                };
+                quote! {
+                    #i => seed.deserialize(#serde_path::ArchiveDeserializer::new(&self.#member))
+                }
+            });
+
+            (
+                quote! {
+                    #[inline]
+                    fn field_names(&self) -> &'static [&'static str] {
+                        &[#(#names,)*]
+                    }
+
+                    #[inline]
+                    fn field_count(&self) -> usize {
+                        #count
+                    }
+
+                    #[inline]
+                    fn visit_field<'de, S, E>(
+                        &'de self,
+                        index: usize,
+                        seed: S,
+                    ) -> ::core::result::Result<S::Value, E>
+                    where
+                        S: #rkyv_path::serde::de::DeserializeSeed<'de>,
+                        E: #rkyv_path::serde::de::Error,
+                    {
+                        match index {
+                            #(#visit_arms,)*
+                            _ => Err(E::invalid_length(index, &"a field index")),
+                        }
+                    }
+                },
+                quote! { #serde_path::visit_struct(self, visitor) },
+            )
+        }
+        Data::Enum(ref data) => {
+            for variant in data.variants.iter() {
+                for field in bounded_fields(&variant.fields) {
+                    let ty = with_ty(field)?;
+                    visit_where
+                        .predicates
+                        .push(parse_quote! { #ty: #rkyv_path::Archive });
+                    visit_where
+                        .predicates
+                        .push(parse_quote! { Archived<#ty>: #serde_path::VisitArchived });
+                }
+            }
+
+            let patterns = data
+                .variants
+                .iter()
+                .map(|v| {
+                    let variant = &v.ident;
+                    match v.fields {
+                        Fields::Named(_) => quote! { Self::#variant { .. } },
+                        Fields::Unnamed(_) => quote! { Self::#variant(..) },
+                        Fields::Unit => quote! { Self::#variant },
+                    }
+                })
+                .collect::<Vec<_>>();
+            let variant_names = data.variants.iter().map(|v| strip_raw(&v.ident));
+            let names = data.variants.iter().map(|v| {
+                let names = field_names(&v.fields);
+                quote! { &[#(#names,)*] }
+            });
+            let counts = data.variants.iter().map(|v| v.fields.len());
+            let visit_arms =
+                data.variants.iter().flat_map(|v| {
+                    let variant = &v.ident;
+                    let serde_path = &serde_path;
+                    v.fields.iter().enumerate().map(move |(i, f)| match f.ident {
+                    Some(ref ident) => quote! {
+                        (Self::#variant { #ident, .. }, #i) => {
+                            seed.deserialize(#serde_path::ArchiveDeserializer::new(#ident))
+                        }
+                    },
+                    None => {
+                        let bindings = (0..v.fields.len()).map(|j| {
+                            if i == j {
+                                quote! { value }
+                            } else {
+                                quote! { _ }
+                            }
+                        });
+                        quote! {
+                            (Self::#variant(#(#bindings,)*), #i) => {
+                                seed.deserialize(#serde_path::ArchiveDeserializer::new(value))
+                            }
+                        }
+                    }
+                })
+                });
+
+            (
+                quote! {
+                    #[inline]
+                    fn variant(&self) -> ::core::option::Option<&'static str> {
+                        Some(match self {
+                            #(#patterns => #variant_names,)*
+                        })
+                    }
+
+                    #[inline]
+                    fn field_names(&self) -> &'static [&'static str] {
+                        match self {
+                            #(#patterns => #names,)*
+                        }
+                    }
+
+                    #[inline]
+                    fn field_count(&self) -> usize {
+                        match self {
+                            #(#patterns => #counts,)*
+                        }
+                    }
+
+                    #[inline]
+                    fn visit_field<'de, S, E>(
+                        &'de self,
+                        index: usize,
+                        seed: S,
+                    ) -> ::core::result::Result<S::Value, E>
+                    where
+                        S: #rkyv_path::serde::de::DeserializeSeed<'de>,
+                        E: #rkyv_path::serde::de::Error,
+                    {
+                        match (self, index) {
+                            #(#visit_arms,)*
+                            _ => Err(E::invalid_length(index, &"a field index")),
+                        }
+                    }
+                },
+                quote! { #serde_path::visit_enum(self, visitor) },
+            )
+        }
+        Data::Union(_) => {
+            return Err(Error::new_spanned(
+                input,
+                "serde visitors cannot be derived for unions",
+            ))
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics #serde_path::VisitFields for #archived_type #visit_where {
+            #visit_fields
+        }
+
+        impl #impl_generics #serde_path::VisitArchived for #archived_type #visit_where {
+            #[inline]
+            fn visit<'de, V, E>(&'de self, visitor: V) -> ::core::result::Result<V::Value, E>
+            where
+                V: #rkyv_path::serde::de::Visitor<'de>,
+                E: #rkyv_path::serde::de::Error,
+            {
+                #visit
+            }
+        }
+    })
+}
@@ -0,0 +1,598 @@
+//! Archiving boxed `Any` values for a registry of known types.
+//!
+//! A `Box<dyn Any + Send + Sync>` can hold a value of any type, so it can't be archived without
+//! knowing ahead of time which types it may contain. An [`AnyRegistry`] lists those types and
+//! assigns each of them a stable ID, which is written to the archive alongside the archived value.
+//! Boxed values are archived with the [`Registered`] wrapper as an [`ArchivedAny`], which can be
+//! downcast to the archived version of its concrete type or deserialized back into a box.
+//!
+//! # Example
+//!
+//! ```
+//! use core::any::Any;
+//! use rkyv::{archived_root, ser::Serializer, Archive, Deserialize, Fallible, Serialize};
+//! use rkyv_dyn::any::{AnyError, AnyRegistry, Registered};
+//!
+//! #[derive(Archive, Serialize, Deserialize)]
+//! struct Position {
+//!     x: f32,
+//!     y: f32,
+//! }
+//!
+//! #[derive(Archive, Serialize, Deserialize)]
+//! struct Properties {
+//!     #[with(Registered)]
+//!     name: Box<dyn Any + Send + Sync>,
+//!     #[with(Registered)]
+//!     position: Box<dyn Any + Send + Sync>,
+//! }
+//!
+//! let mut registry = AnyRegistry::new();
+//! registry.add::<String>(1).add::<Position>(2);
+//! assert!(registry.install().is_ok());
+//!
+//! let value = Properties {
+//!     name: Box::new("player".to_string()),
+//!     position: Box::new(Position { x: 1.0, y: 2.0 }),
+//! };
+//!
+//! // The error type of `MySerializer` and `MyDeserializer` implements `From<AnyError>`
+//! # use rkyv::{ser::serializers::{AlignedSerializer, AllocScratch}, AlignedVec};
+//! # #[derive(Debug)]
+//! # struct MyError;
+//! # impl From<AnyError> for MyError {
+//! #     fn from(_: AnyError) -> Self {
+//! #         MyError
+//! #     }
+//! # }
+//! # #[derive(Default)]
+//! # struct MySerializer {
+//! #     inner: AlignedSerializer<AlignedVec>,
+//! #     scratch: AllocScratch,
+//! # }
+//! # impl Fallible for MySerializer {
+//! #     type Error = MyError;
+//! # }
+//! # impl Serializer for MySerializer {
+//! #     fn pos(&self) -> usize {
+//! #         self.inner.pos()
+//! #     }
+//! #     fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+//! #         self.inner.write(bytes).map_err(|_| MyError)
+//! #     }
+//! # }
+//! # impl rkyv::ser::ScratchSpace for MySerializer {
+//! #     unsafe fn push_scratch(
+//! #         &mut self,
+//! #         layout: core::alloc::Layout,
+//! #     ) -> Result<core::ptr::NonNull<[u8]>, Self::Error> {
+//! #         self.scratch.push_scratch(layout).map_err(|_| MyError)
+//! #     }
+//! #     unsafe fn pop_scratch(
+//! #         &mut self,
+//! #         ptr: core::ptr::NonNull<u8>,
+//! #         layout: core::alloc::Layout,
+//! #     ) -> Result<(), Self::Error> {
+//! #         self.scratch.pop_scratch(ptr, layout).map_err(|_| MyError)
+//! #     }
+//! # }
+//! # struct MyDeserializer;
+//! # impl Fallible for MyDeserializer {
+//! #     type Error = MyError;
+//! # }
+//! let mut serializer = MySerializer::default();
+//! serializer.serialize_value(&value).unwrap();
+//! let buf = serializer.inner.into_inner();
+//! let archived = unsafe { archived_root::<Properties>(buf.as_ref()) };
+//!
+//! assert_eq!(archived.name.id(), 1);
+//! assert_eq!(archived.name.downcast_ref::<String>().unwrap(), "player");
+//! assert!(archived.position.is::<Position>());
+//! assert!(archived.position.downcast_ref::<String>().is_none());
+//!
+//! let deserialized: Properties = archived.deserialize(&mut MyDeserializer).unwrap();
+//! let position = deserialized.position.downcast_ref::<Position>().unwrap();
+//! assert_eq!(position.y, 2.0);
+//! ```
+
+use crate::{DynDeserializer, DynError, DynSerializer};
+use alloc::boxed::Box;
+use core::{
+    any::{type_name, Any, TypeId},
+    fmt, ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+use hashbrown::HashMap;
+use rkyv::{
+    from_archived, out_field,
+    ser::{ScratchSpace, Serializer},
+    to_archived,
+    with::{ArchiveWith, DeserializeWith, SerializeWith},
+    Archive, Archived, Deserialize, Fallible, RawRelPtr, Serialize,
+};
+
+/// A boxed value of any type that can be shared between threads.
+pub type BoxedAny = Box<dyn Any + Send + Sync>;
+
+type SerializeAny = fn(&(dyn Any + Send + Sync), &mut dyn DynSerializer) -> Result<usize, DynError>;
+
+type DeserializeAny = unsafe fn(*const (), &mut dyn DynDeserializer) -> Result<BoxedAny, DynError>;
+
+fn serialize_any<T>(
+    value: &(dyn Any + Send + Sync),
+    serializer: &mut dyn DynSerializer,
+) -> Result<usize, DynError>
+where
+    T: Any + for<'a> Serialize<dyn DynSerializer + 'a>,
+{
+    serializer.serialize_value(value.downcast_ref::<T>().unwrap())
+}
+
+unsafe fn deserialize_any<T>(
+    archived: *const (),
+    deserializer: &mut dyn DynDeserializer,
+) -> Result<BoxedAny, DynError>
+where
+    T: Archive + Any + Send + Sync,
+    T::Archived: for<'a> Deserialize<T, dyn DynDeserializer + 'a>,
+{
+    let value = (*archived.cast::<T::Archived>()).deserialize(deserializer)?;
+    Ok(Box::new(value))
+}
+
+#[derive(Clone, Copy)]
+struct AnyEntry {
+    id: u64,
+    type_name: &'static str,
+    serialize: SerializeAny,
+    deserialize: DeserializeAny,
+    #[cfg(feature = "validation")]
+    check_bytes: validation::CheckAny,
+}
+
+/// An error that occurs when a type conflicts with a type that was already added to an
+/// [`AnyRegistry`].
+///
+/// Archived values are identified by the ID stored in the archive, so every type must be added
+/// once and with an ID that no other type uses.
+#[derive(Debug)]
+pub struct AnyConflictError {
+    id: u64,
+    existing: &'static str,
+    new: &'static str,
+}
+
+impl AnyConflictError {
+    /// Returns `true` if the same type was added more than once, or `false` if two different types
+    /// were added with the same ID.
+    pub fn is_duplicate(&self) -> bool {
+        self.existing == self.new
+    }
+
+    /// Returns the conflicting ID.
+    ///
+    /// If the same type was added more than once, this is the ID it was first added with.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns the name of the type that was already added.
+    pub fn existing_type_name(&self) -> &'static str {
+        self.existing
+    }
+
+    /// Returns the name of the type that conflicted with it.
+    pub fn new_type_name(&self) -> &'static str {
+        self.new
+    }
+}
+
+impl fmt::Display for AnyConflictError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_duplicate() {
+            write!(f, "`{}` was added more than once", self.new)
+        } else {
+            write!(
+                f,
+                "`{}` and `{}` were both added with id {}",
+                self.existing, self.new, self.id,
+            )
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AnyConflictError {}
+
+/// A registry of the types that boxed `Any` values can be archived as.
+///
+/// Each type is added with an ID that identifies it in archives. IDs must be unique and should
+/// never change, since archives written with an ID can only be read while a type is registered
+/// under it. Once all of the types have been added, [`install`](AnyRegistry::install) the
+/// registry before serializing or accessing any boxed `Any` values.
+///
+/// See the [module docs](self) for an example.
+pub struct AnyRegistry {
+    by_type: HashMap<TypeId, AnyEntry>,
+    by_id: HashMap<u64, AnyEntry>,
+}
+
+impl Default for AnyRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AnyRegistry {
+    /// Creates a new, empty registry.
+    pub fn new() -> Self {
+        Self {
+            by_type: HashMap::new(),
+            by_id: HashMap::new(),
+        }
+    }
+
+    /// Adds the type `T` to the registry with the given ID.
+    ///
+    #[cfg_attr(
+        feature = "validation",
+        doc = "With the `validation` feature, archived values of types added this way fail",
+        doc = "validation. Use [`add_checked`](AnyRegistry::add_checked) to add types that can be",
+        doc = "validated.",
+        doc = ""
+    )]
+    /// # Panics
+    ///
+    /// Panics if the type or ID conflicts with one that was already added. See
+    /// [`try_add`](AnyRegistry::try_add) for a non-panicking version.
+    pub fn add<T>(&mut self, id: u64) -> &mut Self
+    where
+        T: Archive + Any + Send + Sync + for<'a> Serialize<dyn DynSerializer + 'a>,
+        T::Archived: for<'a> Deserialize<T, dyn DynDeserializer + 'a>,
+    {
+        if let Err(e) = self.try_add::<T>(id) {
+            panic!("{}", e);
+        }
+        self
+    }
+
+    /// Adds the type `T` to the registry with the given ID, returning an error if it conflicts
+    /// with a type or ID that was already added.
+    pub fn try_add<T>(&mut self, id: u64) -> Result<(), AnyConflictError>
+    where
+        T: Archive + Any + Send + Sync + for<'a> Serialize<dyn DynSerializer + 'a>,
+        T::Archived: for<'a> Deserialize<T, dyn DynDeserializer + 'a>,
+    {
+        self.add_entry::<T>(AnyEntry {
+            id,
+            type_name: type_name::<T>(),
+            serialize: serialize_any::<T>,
+            deserialize: deserialize_any::<T>,
+            #[cfg(feature = "validation")]
+            check_bytes: validation::check_bytes_unimplemented,
+        })
+    }
+
+    fn add_entry<T: Any>(&mut self, entry: AnyEntry) -> Result<(), AnyConflictError> {
+        let type_id = TypeId::of::<T>();
+        if let Some(existing) = self
+            .by_type
+            .get(&type_id)
+            .or_else(|| self.by_id.get(&entry.id))
+        {
+            return Err(AnyConflictError {
+                id: existing.id,
+                existing: existing.type_name,
+                new: entry.type_name,
+            });
+        }
+
+        self.by_type.insert(type_id, entry);
+        self.by_id.insert(entry.id, entry);
+        Ok(())
+    }
+
+    /// Installs this registry as the global registry for boxed `Any` values.
+    ///
+    /// If a registry has already been installed, the registry is returned as an error.
+    #[allow(clippy::result_large_err)]
+    pub fn install(self) -> Result<(), Self> {
+        let registry = Box::into_raw(Box::new(self));
+        match INSTALLED_REGISTRY.compare_exchange(
+            ptr::null_mut(),
+            registry,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(*unsafe { Box::from_raw(registry) }),
+        }
+    }
+
+    fn installed() -> Option<&'static Self> {
+        unsafe { INSTALLED_REGISTRY.load(Ordering::Acquire).as_ref() }
+    }
+
+    fn get_type(type_id: TypeId) -> Option<&'static AnyEntry> {
+        Self::installed()?.by_type.get(&type_id)
+    }
+
+    fn get_id(id: u64) -> Option<&'static AnyEntry> {
+        Self::installed()?.by_id.get(&id)
+    }
+}
+
+static INSTALLED_REGISTRY: AtomicPtr<AnyRegistry> = AtomicPtr::new(ptr::null_mut());
+
+/// Errors that can occur while serializing or deserializing a boxed `Any` value.
+#[derive(Debug)]
+pub enum AnyError {
+    /// The type of the boxed value was not added to the installed registry
+    UnregisteredType,
+    /// The ID of the archived value was not added to the installed registry
+    UnregisteredId(u64),
+}
+
+impl fmt::Display for AnyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnyError::UnregisteredType => write!(f, "type of boxed value is not registered"),
+            AnyError::UnregisteredId(id) => write!(f, "id {} is not registered", id),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AnyError {}
+
+/// An archived `Box<dyn Any + Send + Sync>`.
+///
+/// The archived value can be accessed by downcasting it to the type it was serialized as.
+#[cfg_attr(feature = "strict", repr(C))]
+pub struct ArchivedAny {
+    id: Archived<u64>,
+    ptr: RawRelPtr,
+}
+
+impl ArchivedAny {
+    /// Returns the ID of the type of the archived value.
+    #[inline]
+    pub fn id(&self) -> u64 {
+        from_archived!(self.id)
+    }
+
+    /// Returns `true` if the archived value was serialized as type `T`.
+    pub fn is<T: Any>(&self) -> bool {
+        matches!(
+            AnyRegistry::get_type(TypeId::of::<T>()),
+            Some(entry) if entry.id == self.id()
+        )
+    }
+
+    /// Returns a reference to the archived value if it was serialized as type `T`, or `None` if it
+    /// wasn't.
+    pub fn downcast_ref<T: Archive + Any>(&self) -> Option<&T::Archived> {
+        if self.is::<T>() {
+            Some(unsafe { &*self.ptr.as_ptr().cast::<T::Archived>() })
+        } else {
+            None
+        }
+    }
+
+    /// Resolves an archived boxed `Any` from the given ID and position.
+    ///
+    /// # Safety
+    ///
+    /// - `pos` must be the position of `out` within the archive
+    /// - `resolver` must be the result of serializing a boxed `Any`
+    #[inline]
+    pub unsafe fn resolve_from_resolver(pos: usize, resolver: AnyResolver, out: *mut Self) {
+        let (_, fo) = out_field!(out.id);
+        fo.write(to_archived!(resolver.id));
+        let (fp, fo) = out_field!(out.ptr);
+        RawRelPtr::emplace(pos + fp, resolver.pos, fo);
+    }
+}
+
+impl fmt::Debug for ArchivedAny {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArchivedAny")
+            .field("id", &self.id())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The resolver for an archived boxed `Any`.
+pub struct AnyResolver {
+    id: u64,
+    pos: usize,
+}
+
+/// A wrapper that archives a `Box<dyn Any + Send + Sync>` holding a value of a type added to the
+/// installed [`AnyRegistry`].
+///
+/// Regular serializers and deserializers don't support the custom error handling needed for this
+/// type by default. To use this wrapper, a custom serializer and deserializer with error types
+/// satisfying `<S as Fallible>::Error: From<AnyError>` must be provided.
+///
+/// See the [module docs](self) for an example.
+#[derive(Debug)]
+pub struct Registered;
+
+impl ArchiveWith<BoxedAny> for Registered {
+    type Archived = ArchivedAny;
+    type Resolver = AnyResolver;
+
+    #[inline]
+    unsafe fn resolve_with(
+        _: &BoxedAny,
+        pos: usize,
+        resolver: Self::Resolver,
+        out: *mut Self::Archived,
+    ) {
+        ArchivedAny::resolve_from_resolver(pos, resolver, out);
+    }
+}
+
+impl<S> SerializeWith<BoxedAny, S> for Registered
+where
+    S: ScratchSpace + Serializer + ?Sized,
+    S::Error: From<AnyError>,
+{
+    fn serialize_with(
+        field: &BoxedAny,
+        mut serializer: &mut S,
+    ) -> Result<Self::Resolver, S::Error> {
+        let value = &**field;
+        let entry = AnyRegistry::get_type(value.type_id()).ok_or(AnyError::UnregisteredType)?;
+        let pos = (entry.serialize)(value, &mut serializer)
+            .map_err(|e| *e.downcast::<S::Error>().unwrap())?;
+        Ok(AnyResolver { id: entry.id, pos })
+    }
+}
+
+impl<D> DeserializeWith<ArchivedAny, BoxedAny, D> for Registered
+where
+    D: Fallible + ?Sized,
+    D::Error: From<AnyError>,
+{
+    fn deserialize_with(
+        field: &ArchivedAny,
+        mut deserializer: &mut D,
+    ) -> Result<BoxedAny, D::Error> {
+        let id = field.id();
+        let entry = AnyRegistry::get_id(id).ok_or(AnyError::UnregisteredId(id))?;
+        unsafe {
+            (entry.deserialize)(field.ptr.as_ptr(), &mut deserializer)
+                .map_err(|e| *e.downcast::<D::Error>().unwrap())
+        }
+    }
+}
+
+#[cfg(feature = "validation")]
+mod validation {
+    use super::{
+        deserialize_any, serialize_any, AnyConflictError, AnyEntry, AnyRegistry, ArchivedAny,
+    };
+    use crate::{
+        validation::{CheckDynError, DynContext},
+        DynDeserializer, DynSerializer,
+    };
+    use alloc::boxed::Box;
+    use bytecheck::CheckBytes;
+    #[cfg(not(feature = "std"))]
+    use bytecheck::Error;
+    use core::{
+        any::{type_name, Any},
+        fmt, ptr,
+    };
+    use rkyv::{from_archived, validation::ArchiveContext, Archive, Deserialize, Serialize};
+    #[cfg(feature = "std")]
+    use std::error::Error;
+
+    pub type CheckAny =
+        unsafe fn(*const u8, isize, &mut dyn DynContext) -> Result<(), Box<dyn Error>>;
+
+    #[derive(Debug)]
+    struct CheckBytesUnimplemented;
+
+    impl fmt::Display for CheckBytesUnimplemented {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "type was registered without check bytes")
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl Error for CheckBytesUnimplemented {}
+
+    pub unsafe fn check_bytes_unimplemented(
+        _: *const u8,
+        _: isize,
+        _: &mut dyn DynContext,
+    ) -> Result<(), Box<dyn Error>> {
+        Err(Box::new(CheckBytesUnimplemented))
+    }
+
+    unsafe fn check_bytes_any<T>(
+        base: *const u8,
+        offset: isize,
+        context: &mut dyn DynContext,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        T: Archive,
+        T::Archived: for<'a> CheckBytes<dyn DynContext + 'a>,
+    {
+        let ptr = context.check_subtree_ptr::<T::Archived>(base, offset, ())?;
+        let range = context.push_prefix_subtree(ptr)?;
+        T::Archived::check_bytes(ptr, context).map_err(|e| Box::new(e) as Box<dyn Error>)?;
+        context.pop_prefix_range(range)?;
+        Ok(())
+    }
+
+    impl AnyRegistry {
+        /// Adds the type `T` to the registry with the given ID, along with support for validating
+        /// its archived values.
+        ///
+        /// # Panics
+        ///
+        /// Panics if the type or ID conflicts with one that was already added. See
+        /// [`try_add_checked`](AnyRegistry::try_add_checked) for a non-panicking version.
+        pub fn add_checked<T>(&mut self, id: u64) -> &mut Self
+        where
+            T: Archive + Any + Send + Sync + for<'a> Serialize<dyn DynSerializer + 'a>,
+            T::Archived: for<'a> Deserialize<T, dyn DynDeserializer + 'a>
+                + for<'a> CheckBytes<dyn DynContext + 'a>,
+        {
+            if let Err(e) = self.try_add_checked::<T>(id) {
+                panic!("{}", e);
+            }
+            self
+        }
+
+        /// Adds the type `T` to the registry with the given ID, along with support for validating
+        /// its archived values. Returns an error if it conflicts with a type or ID that was
+        /// already added.
+        pub fn try_add_checked<T>(&mut self, id: u64) -> Result<(), AnyConflictError>
+        where
+            T: Archive + Any + Send + Sync + for<'a> Serialize<dyn DynSerializer + 'a>,
+            T::Archived: for<'a> Deserialize<T, dyn DynDeserializer + 'a>
+                + for<'a> CheckBytes<dyn DynContext + 'a>,
+        {
+            self.add_entry::<T>(AnyEntry {
+                id,
+                type_name: type_name::<T>(),
+                serialize: serialize_any::<T>,
+                deserialize: deserialize_any::<T>,
+                check_bytes: check_bytes_any::<T>,
+            })
+        }
+    }
+
+    impl CheckBytes<dyn DynContext + '_> for ArchivedAny {
+        type Error = CheckDynError;
+
+        unsafe fn check_bytes<'a>(
+            value: *const Self,
+            context: &mut (dyn DynContext + '_),
+        ) -> Result<&'a Self, Self::Error> {
+            // Every bit pattern is a valid ID and offset
+            let id = from_archived!(*ptr::addr_of!((*value).id));
+            let rel_ptr = &*ptr::addr_of!((*value).ptr);
+            let entry = AnyRegistry::get_id(id).ok_or(CheckDynError::InvalidMetadata(id))?;
+            (entry.check_bytes)(rel_ptr.base(), rel_ptr.offset(), context)?;
+            Ok(&*value)
+        }
+    }
+
+    impl<C: DynContext> CheckBytes<C> for ArchivedAny {
+        type Error = CheckDynError;
+
+        #[inline]
+        unsafe fn check_bytes<'a>(
+            value: *const Self,
+            context: &mut C,
+        ) -> Result<&'a Self, Self::Error> {
+            Self::check_bytes(value, context as &mut dyn DynContext)
+        }
+    }
+}
@@ -5,9 +5,24 @@
 //!
 //! See [`SerializeDyn`] for an example of how to use rkyv_dyn.
 //!
+//! Boxed `Any` values can also be archived for a registry of known types, see the [`any`] module.
+//!
+//! ## Impl IDs
+//!
+//! Archived trait objects store the impl ID of their concrete type, which is used to find the impl
+//! when the archive is read. Unless an impl is assigned an explicit ID with [`register_impl!`], its
+//! impl ID is the FNV-1a hash of the name of its archived type (see [`type_name_id`]).
+//!
+//! *Format break*: earlier versions of rkyv_dyn hashed type names with the standard library's
+//! `DefaultHasher`, whose algorithm is unspecified and can change between Rust releases. The impl
+//! IDs written by those versions don't match the ones computed now, so archives containing trait
+//! objects have to be rewritten with this version before they can be read.
+//!
 //! ## Features
 //!
-//! - `nightly`: Enables some nightly features, such as [`likely`](std::intrinsics::likely).
+//! - `nightly`: Enables some nightly features, such as [`likely`](core::intrinsics::likely).
+//! - `std`: Enables standard library support. Enabled by default. Without `std`, implementations
+//!   are not collected automatically and an [`ImplRegistry`] must be installed at startup.
 //! - `strict`: Guarantees that types will have the same representations across platforms and
 //!   compilations. This is already the case in practice, but this feature provides a guarantee.
 //! - `validation`: Enables validation support through `bytecheck`.
@@ -17,34 +32,43 @@
 #![deny(rustdoc::broken_intra_doc_links)]
 #![deny(missing_docs)]
 #![deny(rustdoc::missing_crate_level_docs)]
+#![cfg_attr(not(feature = "std"), no_std)]
 #![cfg_attr(feature = "nightly", feature(core_intrinsics))]
 
+extern crate alloc;
+
+pub mod any;
 #[cfg(feature = "validation")]
 pub mod validation;
 
+use alloc::{boxed::Box, string::String, vec::Vec};
 #[cfg(feature = "vtable_cache")]
-use core::sync::atomic::{AtomicU64, Ordering};
+use core::sync::atomic::AtomicU64;
 use core::{
     alloc::Layout,
     any::Any,
-    hash::{Hash, Hasher},
+    fmt,
     marker::PhantomData,
+    pin::Pin,
     ptr,
+    sync::atomic::{AtomicPtr, Ordering},
 };
+use hashbrown::HashMap;
 use ptr_meta::{DynMetadata, Pointee};
 #[cfg(feature = "vtable_cache")]
 use rkyv::with::{Atomic, With};
 use rkyv::{
+    boxed::ArchivedBox,
     from_archived,
     ser::{ScratchSpace, Serializer},
-    to_archived, Archived, Fallible, Serialize,
+    to_archived, ArchivePointee, Archived, Fallible, Serialize,
 };
 pub use rkyv_dyn_derive::archive_dyn;
 use rkyv_typename::TypeName;
-use std::collections::{hash_map::DefaultHasher, HashMap};
 #[cfg(feature = "validation")]
 pub use validation::{CheckDynError, DynContext};
 
+#[cfg(feature = "std")]
 #[doc(hidden)]
 pub use inventory;
 
@@ -143,10 +167,40 @@ impl<S: ScratchSpace + Serializer + ?Sized> DynSerializer for &mut S {
     }
 }
 
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+const fn hash_bytes(mut hash: u64, bytes: &[u8]) -> u64 {
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+        i += 1;
+    }
+    hash
+}
+
+/// Returns the impl type ID for a type with the given name.
+///
+/// Impls that aren't assigned an explicit ID are identified by the hash of the name of their
+/// archived type. This can be used to keep archives loadable after renaming or moving a type by
+/// adding its old name as an alias (see [`register_impl!`]).
+///
+/// # Example
+///
+/// ```
+/// use rkyv_dyn::type_name_id;
+///
+/// const OLD_ID: u64 = type_name_id("my_crate::ArchivedMyType");
+/// ```
+pub const fn type_name_id(name: &str) -> u64 {
+    hash_bytes(FNV_OFFSET_BASIS, name.as_bytes())
+}
+
 fn hash_type<T: TypeName + ?Sized>() -> u64 {
-    let mut hasher = DefaultHasher::new();
-    T::build_type_name(|piece| piece.hash(&mut hasher));
-    hasher.finish()
+    let mut hash = FNV_OFFSET_BASIS;
+    T::build_type_name(|piece| hash = hash_bytes(hash, piece.as_bytes()));
+    hash
 }
 
 /// A trait object that can be archived.
@@ -323,6 +377,10 @@ pub struct ArchivedDynMetadata<T: ?Sized> {
 impl<T: TypeName + ?Sized> ArchivedDynMetadata<T> {
     /// Creates a new `ArchivedDynMetadata` for the given type.
     ///
+    /// `type_id` is the impl ID of the concrete type. This is the explicit ID of the impl if it was
+    /// registered with one, or the type ID returned by [`SerializeDyn::archived_type_id`]
+    /// otherwise.
+    ///
     /// # Safety
     ///
     /// `out` must point to a valid location for an `ArchivedDynMetadata<T>`.
@@ -338,7 +396,7 @@ impl<T: TypeName + ?Sized> ArchivedDynMetadata<T> {
     }
 
     fn lookup_vtable(&self) -> usize {
-        IMPL_REGISTRY
+        ImplRegistry::installed()
             .get::<T>(from_archived!(self.type_id))
             .expect("attempted to get vtable for an unregistered impl")
             .vtable
@@ -371,6 +429,132 @@ impl<T: TypeName + ?Sized> ArchivedDynMetadata<T> {
     }
 }
 
+/// Type inspection and downcasting for archived trait objects.
+///
+/// This is implemented for all archived boxed trait objects and mirrors the API of `dyn Any`. Only
+/// types that are registered as impls of the trait object can be downcast to. The concrete type is
+/// checked by comparing the impl stored with the trait object against the impl registered for the
+/// requested type.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     archived_root,
+///     ser::{serializers::AllocSerializer, Serializer},
+///     Archive,
+///     Archived,
+///     Serialize,
+/// };
+/// use rkyv_dyn::{archive_dyn, ArchivedDyn};
+/// use rkyv_typename::TypeName;
+///
+/// #[archive_dyn]
+/// trait Shape {
+///     fn area(&self) -> f32;
+/// }
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive_attr(derive(TypeName))]
+/// struct Square(f32);
+///
+/// #[archive_dyn]
+/// impl Shape for Square {
+///     fn area(&self) -> f32 {
+///         self.0 * self.0
+///     }
+/// }
+///
+/// impl Shape for Archived<Square> {
+///     fn area(&self) -> f32 {
+///         self.0 * self.0
+///     }
+/// }
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive_attr(derive(TypeName))]
+/// struct Circle(f32);
+///
+/// #[archive_dyn]
+/// impl Shape for Circle {
+///     fn area(&self) -> f32 {
+///         3.14 * self.0 * self.0
+///     }
+/// }
+///
+/// impl Shape for Archived<Circle> {
+///     fn area(&self) -> f32 {
+///         3.14 * self.0 * self.0
+///     }
+/// }
+///
+/// let value = Box::new(Square(2.0)) as Box<dyn SerializeShape>;
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serializer.serialize_value(&value).unwrap();
+/// let buf = serializer.into_serializer().into_inner();
+/// let archived = unsafe { archived_root::<Box<dyn SerializeShape>>(buf.as_ref()) };
+///
+/// assert!(archived.is::<Archived<Square>>());
+/// assert!(!archived.is::<Archived<Circle>>());
+/// let square = archived.downcast_ref::<Archived<Square>>().unwrap();
+/// assert_eq!(square.0, 2.0);
+/// ```
+pub trait ArchivedDyn {
+    /// The trait object type.
+    type Trait: ?Sized;
+
+    /// Returns `true` if the concrete type of the trait object is `U`.
+    fn is<U: TypeName + RegisteredImpl<Self::Trait>>(&self) -> bool;
+
+    /// Returns a reference to the concrete value if it is of type `U`, or `None` if it isn't.
+    fn downcast_ref<U: TypeName + RegisteredImpl<Self::Trait>>(&self) -> Option<&U>;
+
+    /// Returns a pinned mutable reference to the concrete value if it is of type `U`, or `None` if
+    /// it isn't.
+    fn downcast_pin<U: TypeName + RegisteredImpl<Self::Trait>>(
+        self: Pin<&mut Self>,
+    ) -> Option<Pin<&mut U>>;
+}
+
+impl<T> ArchivedDyn for ArchivedBox<T>
+where
+    T: ArchivePointee<ArchivedMetadata = ArchivedDynMetadata<T>> + TypeName + ?Sized,
+{
+    type Trait = T;
+
+    fn is<U: TypeName + RegisteredImpl<T>>(&self) -> bool {
+        // Aliases can be arbitrary values, so looking up the ID of `U` could find a different impl.
+        // Instead, compare the impl found for the archived ID against the impl registered for `U`.
+        let type_id = from_archived!(self.metadata().type_id);
+        match ImplRegistry::installed().get::<T>(type_id) {
+            Some(archived) => {
+                archived.vtable == <U as RegisteredImpl<T>>::vtable()
+                    && archived.id == <U as RegisteredImpl<T>>::ID.unwrap_or_else(hash_type::<U>)
+            }
+            None => false,
+        }
+    }
+
+    fn downcast_ref<U: TypeName + RegisteredImpl<T>>(&self) -> Option<&U> {
+        if self.is::<U>() {
+            Some(unsafe { &*(self.get() as *const T).cast::<U>() })
+        } else {
+            None
+        }
+    }
+
+    fn downcast_pin<U: TypeName + RegisteredImpl<T>>(self: Pin<&mut Self>) -> Option<Pin<&mut U>> {
+        if self.is::<U>() {
+            Some(unsafe {
+                self.get_pin_mut()
+                    .map_unchecked_mut(|value| &mut *(value as *mut T).cast::<U>())
+            })
+        } else {
+            None
+        }
+    }
+}
+
 #[cfg(debug_assertions)]
 #[doc(hidden)]
 #[derive(Copy, Clone)]
@@ -380,6 +564,13 @@ pub struct ImplDebugInfo {
     pub column: u32,
 }
 
+#[cfg(debug_assertions)]
+impl fmt::Display for ImplDebugInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
+
 #[cfg(debug_assertions)]
 #[doc(hidden)]
 #[macro_export]
@@ -398,6 +589,13 @@ macro_rules! debug_info {
 #[derive(Copy, Clone)]
 pub struct ImplDebugInfo;
 
+#[cfg(not(debug_assertions))]
+impl fmt::Display for ImplDebugInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<unknown location, enable debug assertions to locate>")
+    }
+}
+
 #[cfg(not(debug_assertions))]
 #[doc(hidden)]
 #[macro_export]
@@ -412,6 +610,17 @@ macro_rules! debug_info {
 pub struct ImplData {
     pub vtable: usize,
     pub debug_info: ImplDebugInfo,
+    id: u64,
+    type_name: fn() -> String,
+    trait_name: fn() -> String,
+    #[cfg(feature = "validation")]
+    validation: validation::ImplValidation,
+}
+
+fn type_name<T: TypeName + ?Sized>() -> String {
+    let mut result = String::new();
+    T::build_type_name(|piece| result.push_str(piece));
+    result
 }
 
 #[derive(Clone, Copy, Hash, Eq, PartialEq)]
@@ -421,94 +630,361 @@ struct ImplId {
 }
 
 impl ImplId {
-    fn new<TY: TypeName, TR: TypeName + ?Sized>() -> Self {
-        Self::from_type_id::<TR>(hash_type::<TY>())
-    }
-
     fn from_type_id<TR: TypeName + ?Sized>(type_id: u64) -> Self {
         Self {
             trait_id: hash_type::<TR>(),
-            // The last bit of the type ID is set to 1 to make sure we can differentiate between
-            // cached and uncached vtables when the feature is turned on
-            type_id: type_id | 1,
+            type_id,
         }
     }
 }
 
 #[doc(hidden)]
 pub struct ImplEntry {
-    impl_id: ImplId,
+    trait_id: u64,
+    type_hash: u64,
+    aliases: &'static [u64],
     data: ImplData,
 }
 
 impl ImplEntry {
     #[doc(hidden)]
     pub fn new<TY: TypeName + RegisteredImpl<TR>, TR: TypeName + ?Sized>() -> Self {
+        let type_hash = hash_type::<TY>();
         Self {
-            impl_id: ImplId::new::<TY, TR>(),
+            trait_id: hash_type::<TR>(),
+            type_hash,
+            aliases: <TY as RegisteredImpl<TR>>::ALIASES,
             data: ImplData {
                 vtable: <TY as RegisteredImpl<TR>>::vtable(),
                 debug_info: <TY as RegisteredImpl<TR>>::debug_info(),
+                id: <TY as RegisteredImpl<TR>>::ID.unwrap_or(type_hash),
+                type_name: type_name::<TY>,
+                trait_name: type_name::<TR>,
+                #[cfg(feature = "validation")]
+                validation: validation::ImplValidation {
+                    layout: Layout::new::<TY>(),
+                    check_bytes_dyn: <TY as RegisteredImpl<TR>>::check_bytes_dyn(),
+                },
             },
         }
     }
 }
 
+/// An error that occurs when an impl conflicts with an impl that was already registered.
+///
+/// Archived trait objects are dispatched by the impl ID stored in the archive, so two impls that
+/// share an impl ID could cause an archived value to be used as the wrong type. Conflicts are
+/// always reported instead of replacing the existing impl.
+pub struct ImplConflictError {
+    existing: ImplData,
+    new: ImplData,
+}
+
+impl ImplConflictError {
+    /// Returns `true` if the same impl was registered more than once, or `false` if two different
+    /// impls have the same impl ID because their type names hash to the same values.
+    pub fn is_duplicate(&self) -> bool {
+        self.existing_type_name() == self.new_type_name()
+            && self.existing_trait_name() == self.new_trait_name()
+    }
+
+    /// Returns the name of the archived type of the existing impl.
+    pub fn existing_type_name(&self) -> String {
+        (self.existing.type_name)()
+    }
+
+    /// Returns the name of the trait object of the existing impl.
+    pub fn existing_trait_name(&self) -> String {
+        (self.existing.trait_name)()
+    }
+
+    /// Returns the name of the archived type of the new impl.
+    pub fn new_type_name(&self) -> String {
+        (self.new.type_name)()
+    }
+
+    /// Returns the name of the trait object of the new impl.
+    pub fn new_trait_name(&self) -> String {
+        (self.new.trait_name)()
+    }
+}
+
+impl fmt::Debug for ImplConflictError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ImplConflictError")
+            .field("existing_type_name", &self.existing_type_name())
+            .field("existing_trait_name", &self.existing_trait_name())
+            .field("new_type_name", &self.new_type_name())
+            .field("new_trait_name", &self.new_trait_name())
+            .finish()
+    }
+}
+
+impl fmt::Display for ImplConflictError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_duplicate() {
+            write!(
+                f,
+                "`{}` was registered as `{}` more than once (at {} and {})",
+                self.new_type_name(),
+                self.new_trait_name(),
+                self.existing.debug_info,
+                self.new.debug_info,
+            )
+        } else {
+            write!(
+                f,
+                "impl id collision between `{}` as `{}` (at {}) and `{}` as `{}` (at {}), rename \
+                one of the types or assign it a different id to resolve it",
+                self.existing_type_name(),
+                self.existing_trait_name(),
+                self.existing.debug_info,
+                self.new_type_name(),
+                self.new_trait_name(),
+                self.new.debug_info,
+            )
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ImplConflictError {}
+
+#[cfg(feature = "std")]
 inventory::collect!(ImplEntry);
 
-struct ImplRegistry {
+/// A registry of the trait object implementations that archived trait objects can dispatch to.
+///
+/// With the `std` feature enabled, every impl registered with [`register_impl!`] or
+/// [`archive_dyn`](macro@archive_dyn) is collected automatically the first time an archived trait
+/// object is used, and there is no need to build a registry by hand. To handle conflicting impls
+/// without panicking, [`collect`](ImplRegistry::collect) and install the registry at startup.
+///
+/// Without `std`, impls cannot be collected automatically. Instead, build a registry at startup
+/// with [`add`](ImplRegistry::add) and [`install`](ImplRegistry::install) it before using any
+/// archived trait objects. Installing a registry replaces the automatically-collected one, so it
+/// must list every impl that archived trait objects may point to.
+///
+/// # Example
+///
+/// ```
+/// use rkyv::{
+///     archived_root,
+///     ser::{serializers::AllocSerializer, Serializer},
+///     Archive,
+///     Archived,
+///     Serialize,
+/// };
+/// use rkyv_dyn::{archive_dyn, ImplRegistry};
+/// use rkyv_typename::TypeName;
+///
+/// #[archive_dyn]
+/// trait Value {
+///     fn value(&self) -> i32;
+/// }
+///
+/// #[derive(Archive, Serialize)]
+/// #[archive_attr(derive(TypeName))]
+/// struct Int(i32);
+///
+/// #[archive_dyn]
+/// impl Value for Int {
+///     fn value(&self) -> i32 {
+///         self.0
+///     }
+/// }
+///
+/// impl Value for Archived<Int> {
+///     fn value(&self) -> i32 {
+///         self.0
+///     }
+/// }
+///
+/// let mut registry = ImplRegistry::new();
+/// registry.add::<Archived<Int>, dyn Value>();
+/// assert!(registry.install().is_ok());
+///
+/// let value = Box::new(Int(42)) as Box<dyn SerializeValue>;
+/// let mut serializer = AllocSerializer::<256>::default();
+/// serializer.serialize_value(&value).unwrap();
+/// let buf = serializer.into_serializer().into_inner();
+/// let archived = unsafe { archived_root::<Box<dyn SerializeValue>>(buf.as_ref()) };
+/// assert_eq!(archived.value(), 42);
+/// ```
+pub struct ImplRegistry {
     id_to_data: HashMap<ImplId, ImplData>,
+    #[cfg(feature = "validation")]
+    vtable_to_validation: HashMap<usize, validation::ImplValidation>,
+}
+
+impl Default for ImplRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ImplRegistry {
-    fn new() -> Self {
+    /// Creates a new, empty registry.
+    pub fn new() -> Self {
         Self {
             id_to_data: HashMap::new(),
+            #[cfg(feature = "validation")]
+            vtable_to_validation: HashMap::new(),
+        }
+    }
+
+    /// Adds the impl of the trait object `TR` for the archived type `TY` to the registry.
+    ///
+    /// `TY` and `TR` are the same types that are passed to [`register_impl!`] as
+    /// `register_impl!(TY as TR)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the impl conflicts with one that was already added. See
+    /// [`try_add`](ImplRegistry::try_add) for a non-panicking version.
+    pub fn add<TY, TR>(&mut self) -> &mut Self
+    where
+        TY: TypeName + RegisteredImpl<TR>,
+        TR: TypeName + ?Sized,
+    {
+        if let Err(e) = self.try_add::<TY, TR>() {
+            panic!("{}", e);
         }
+        self
     }
 
-    fn add_entry(&mut self, entry: &ImplEntry) {
-        let old_value = self.id_to_data.insert(entry.impl_id, entry.data);
-
-        #[cfg(debug_assertions)]
-        if let Some(old_data) = old_value {
-            eprintln!("impl id conflict, a trait implementation was likely added twice (but it's possible there was a hash collision)");
-            eprintln!(
-                "existing impl registered at {}:{}:{}",
-                old_data.debug_info.file, old_data.debug_info.line, old_data.debug_info.column
-            );
-            eprintln!(
-                "new impl registered at {}:{}:{}",
-                entry.data.debug_info.file,
-                entry.data.debug_info.line,
-                entry.data.debug_info.column
-            );
-            panic!();
+    /// Adds the impl of the trait object `TR` for the archived type `TY` to the registry, returning
+    /// an error if it conflicts with an impl that was already added.
+    #[allow(clippy::result_large_err)]
+    pub fn try_add<TY, TR>(&mut self) -> Result<(), ImplConflictError>
+    where
+        TY: TypeName + RegisteredImpl<TR>,
+        TR: TypeName + ?Sized,
+    {
+        self.add_entry(&ImplEntry::new::<TY, TR>())
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn add_entry(&mut self, entry: &ImplEntry) -> Result<(), ImplConflictError> {
+        // Impls with an explicit ID are also registered under the hash of their type name so that
+        // archives written before the ID was assigned can still be loaded.
+        let mut type_ids = Vec::with_capacity(entry.aliases.len() + 2);
+        type_ids.push(entry.data.id);
+        type_ids.push(entry.type_hash);
+        type_ids.extend_from_slice(entry.aliases);
+        type_ids.sort_unstable();
+        type_ids.dedup();
+
+        for &type_id in type_ids.iter() {
+            let impl_id = ImplId {
+                trait_id: entry.trait_id,
+                type_id,
+            };
+            if let Some(existing) = self.id_to_data.get(&impl_id) {
+                return Err(ImplConflictError {
+                    existing: *existing,
+                    new: entry.data,
+                });
+            }
+        }
+
+        for type_id in type_ids {
+            let impl_id = ImplId {
+                trait_id: entry.trait_id,
+                type_id,
+            };
+            self.id_to_data.insert(impl_id, entry.data);
         }
+        #[cfg(feature = "validation")]
+        self.vtable_to_validation
+            .insert(entry.data.vtable, entry.data.validation);
 
-        debug_assert!(old_value.is_none(), "impl id conflict, a trait implementation was likely added twice (but it's possible there was a hash collision)");
+        Ok(())
     }
 
     fn get<T: TypeName + ?Sized>(&self, type_id: u64) -> Option<&ImplData> {
         self.id_to_data.get(&ImplId::from_type_id::<T>(type_id))
     }
-}
 
-lazy_static::lazy_static! {
-    static ref IMPL_REGISTRY: ImplRegistry = {
-        let mut result = ImplRegistry::new();
+    #[cfg(feature = "validation")]
+    fn get_validation(&self, vtable: usize) -> Option<&validation::ImplValidation> {
+        self.vtable_to_validation.get(&vtable)
+    }
+
+    /// Installs this registry as the global impl registry.
+    ///
+    /// This must be called before any archived trait objects are used. If a registry has already
+    /// been installed (or collected automatically with the `std` feature), the registry is
+    /// returned as an error.
+    #[allow(clippy::result_large_err)]
+    pub fn install(self) -> Result<(), Self> {
+        let registry = Box::into_raw(Box::new(self));
+        match INSTALLED_REGISTRY.compare_exchange(
+            ptr::null_mut(),
+            registry,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(*unsafe { Box::from_raw(registry) }),
+        }
+    }
+
+    /// Collects every impl registered with [`register_impl!`] or [`archive_dyn`](macro@archive_dyn)
+    /// into a new registry, returning an error if any of them conflict.
+    ///
+    /// This is the registry that is collected automatically the first time an archived trait object
+    /// is used, which panics if there is a conflict. Collecting and
+    /// [installing](ImplRegistry::install) it at startup reports conflicts as errors instead.
+    #[cfg(feature = "std")]
+    pub fn collect() -> Result<Self, ImplConflictError> {
+        let mut result = Self::new();
         for entry in inventory::iter::<ImplEntry> {
-            result.add_entry(entry);
+            result.add_entry(entry)?;
         }
-        result
-    };
+        Ok(result)
+    }
+
+    /// Returns the installed registry, collecting registered impls into a new registry if none
+    /// has been installed yet.
+    #[cfg(feature = "std")]
+    fn installed() -> &'static Self {
+        let installed = INSTALLED_REGISTRY.load(Ordering::Acquire);
+        if !installed.is_null() {
+            return unsafe { &*installed };
+        }
+
+        let result = Self::collect().unwrap_or_else(|e| panic!("{}", e));
+        // Another thread may have installed a registry since we checked, in which case we use that
+        // one instead.
+        let _ = result.install();
+        unsafe { &*INSTALLED_REGISTRY.load(Ordering::Acquire) }
+    }
+
+    /// Returns the installed registry.
+    #[cfg(not(feature = "std"))]
+    fn installed() -> &'static Self {
+        let installed = INSTALLED_REGISTRY.load(Ordering::Acquire);
+        assert!(
+            !installed.is_null(),
+            "attempted to use an archived trait object before installing an impl registry"
+        );
+        unsafe { &*installed }
+    }
 }
 
+static INSTALLED_REGISTRY: AtomicPtr<ImplRegistry> = AtomicPtr::new(ptr::null_mut());
+
 /// Guarantees that an impl has been registered for the type as the given trait object.
 #[doc(hidden)]
 pub unsafe trait RegisteredImpl<T: ?Sized> {
+    const ID: Option<u64> = None;
+    const ALIASES: &'static [u64] = &[];
+
     fn vtable() -> usize;
     fn debug_info() -> ImplDebugInfo;
+    #[cfg(feature = "validation")]
+    fn check_bytes_dyn() -> validation::CheckBytesDyn;
 }
 
 #[doc(hidden)]
@@ -518,6 +994,25 @@ macro_rules! register_validation {
     ($type:ty as $trait:ty) => {};
 }
 
+#[doc(hidden)]
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! submit_impl {
+    ($type:ty as $trait:ty) => {
+        rkyv_dyn::inventory::submit! {
+            #![crate = rkyv_dyn]
+            rkyv_dyn::ImplEntry::new::<$type, $trait>()
+        }
+    };
+}
+
+#[doc(hidden)]
+#[cfg(not(feature = "std"))]
+#[macro_export]
+macro_rules! submit_impl {
+    ($type:ty as $trait:ty) => {};
+}
+
 /// Registers a new impl with the trait object system.
 ///
 /// This is called by `#[archive_dyn]` when attached to a trait implementation. You might need to
@@ -525,16 +1020,45 @@ macro_rules! register_validation {
 /// to be individually registered.
 ///
 /// Call it like `register_impl!(MyType as dyn MyTrait)`.
+///
+/// Without the `std` feature, registered impls must also be added to an [`ImplRegistry`] which is
+/// installed at startup.
+///
+/// # Explicit IDs
+///
+/// By default, an impl is identified in archives by the hash of the name of its archived type.
+/// Renaming the type or moving it to another module or crate changes that hash, and archives
+/// written before the change can no longer be loaded. To avoid this, assign the impl a stable ID
+/// with `register_impl!(MyType as dyn MyTrait, id = 42)` or `#[archive_dyn(id = 42)]`.
+///
+/// Archives are always written with the explicit ID, but impls with an explicit ID can still be
+/// loaded from archives that were written with the hash of their current type name. Additional
+/// IDs that should resolve to the impl can be listed as aliases:
+///
+/// ```ignore
+/// register_impl!(
+///     ArchivedMyType as dyn MyTrait,
+///     id = 42,
+///     aliases = [rkyv_dyn::type_name_id("old_crate::ArchivedMyType")],
+/// );
+/// ```
+///
+/// Explicit IDs also make it possible to version impls. When the layout of a type changes, keep the
+/// old archived type registered under its original ID and register the new type under a new ID.
+/// Archives written with either version can then be loaded side by side.
 #[macro_export]
 macro_rules! register_impl {
-    ($type:ty as $trait:ty) => {
+    (@impl $type:ty as $trait:ty, $id:expr, [$($alias:expr),*]) => {
         const _: () = {
             use rkyv_dyn::{
-                debug_info, inventory, register_validation, ImplData, ImplDebugInfo, ImplEntry,
+                debug_info, register_validation, submit_impl, ImplData, ImplDebugInfo, ImplEntry,
                 RegisteredImpl,
             };
 
             unsafe impl RegisteredImpl<$trait> for $type {
+                const ID: Option<u64> = $id;
+                const ALIASES: &'static [u64] = &[$($alias),*];
+
                 fn vtable() -> usize {
                     unsafe {
                         core::mem::transmute(ptr_meta::metadata(
@@ -546,10 +1070,20 @@ macro_rules! register_impl {
                 fn debug_info() -> ImplDebugInfo {
                     debug_info!()
                 }
+
+                register_validation!($type as $trait);
             }
 
-            inventory::submit! { ImplEntry::new::<$type, $trait>() }
-            register_validation!($type as $trait);
+            submit_impl!($type as $trait);
         };
     };
+    ($type:ty as $trait:ty $(,)?) => {
+        rkyv_dyn::register_impl!(@impl $type as $trait, None, []);
+    };
+    ($type:ty as $trait:ty, id = $id:expr $(,)?) => {
+        rkyv_dyn::register_impl!(@impl $type as $trait, Some($id), []);
+    };
+    ($type:ty as $trait:ty, id = $id:expr, aliases = [$($alias:expr),* $(,)?] $(,)?) => {
+        rkyv_dyn::register_impl!(@impl $type as $trait, Some($id), [$($alias),*]);
+    };
 }
@@ -1,7 +1,10 @@
 //! Validation implementations and helper types.
 
-use crate::{ArchivedDynMetadata, RegisteredImpl, IMPL_REGISTRY};
+use crate::{ArchivedDynMetadata, ImplRegistry};
+use alloc::boxed::Box;
 use bytecheck::CheckBytes;
+#[cfg(not(feature = "std"))]
+use bytecheck::Error;
 #[cfg(feature = "vtable_cache")]
 use core::sync::atomic::Ordering;
 use core::{
@@ -18,7 +21,8 @@ use rkyv::{
     Archived, Fallible,
 };
 use rkyv_typename::TypeName;
-use std::{collections::HashMap, error::Error};
+#[cfg(feature = "std")]
+use std::error::Error;
 
 /// A context that's object safe and suitable for checking most types.
 pub trait DynContext {
@@ -289,9 +293,11 @@ impl fmt::Display for CheckBytesUnimplemented {
     }
 }
 
+#[cfg(feature = "std")]
 impl Error for CheckBytesUnimplemented {}
 
-type CheckBytesDyn = unsafe fn(*const u8, &mut dyn DynContext) -> Result<(), Box<dyn Error>>;
+#[doc(hidden)]
+pub type CheckBytesDyn = unsafe fn(*const u8, &mut dyn DynContext) -> Result<(), Box<dyn Error>>;
 
 // This is the fallback function that gets called if the archived type doesn't implement CheckBytes.
 #[inline]
@@ -299,7 +305,7 @@ unsafe fn check_bytes_dyn_unimplemented(
     _bytes: *const u8,
     _context: &mut dyn DynContext,
 ) -> Result<(), Box<dyn Error>> {
-    Err(Box::new(CheckBytesUnimplemented).into())
+    Err(Box::new(CheckBytesUnimplemented))
 }
 
 #[doc(hidden)]
@@ -321,7 +327,7 @@ impl<T: for<'a> CheckBytes<dyn DynContext + 'a>> IsCheckBytesDyn<T> {
         bytes: *const u8,
         context: &mut dyn DynContext,
     ) -> Result<(), Box<dyn Error>> {
-        T::check_bytes(bytes.cast(), context)?;
+        T::check_bytes(bytes.cast(), context).map_err(|e| Box::new(e) as Box<dyn Error>)?;
         Ok(())
     }
 }
@@ -378,6 +384,7 @@ impl fmt::Display for DynMetadataError {
     }
 }
 
+#[cfg(feature = "std")]
 impl Error for DynMetadataError {}
 
 impl From<Infallible> for DynMetadataError {
@@ -398,7 +405,7 @@ impl<T: TypeName + ?Sized, C: ?Sized> CheckBytes<C> for ArchivedDynMetadata<T> {
             context,
         )?);
         PhantomData::<T>::check_bytes(ptr::addr_of!((*value).phantom), context)?;
-        if let Some(impl_data) = IMPL_REGISTRY.get::<T>(type_id) {
+        if let Some(impl_data) = ImplRegistry::installed().get::<T>(type_id) {
             let cached_vtable_ptr = ptr::addr_of!((*value).cached_vtable);
             #[cfg(feature = "vtable_cache")]
             let cached_vtable =
@@ -439,6 +446,7 @@ impl fmt::Display for CheckDynError {
     }
 }
 
+#[cfg(feature = "std")]
 impl Error for CheckDynError {
     fn source(&self) -> Option<&(dyn Error + 'static)> {
         match self {
@@ -454,70 +462,20 @@ impl From<Box<dyn Error>> for CheckDynError {
     }
 }
 
+/// Returns the validation data for the impl with the given vtable from the installed registry.
 #[doc(hidden)]
-pub struct CheckBytesEntry {
-    vtable: usize,
-    validation: ImplValidation,
-}
-
-impl CheckBytesEntry {
-    #[doc(hidden)]
-    pub fn new<TY: RegisteredImpl<TR>, TR: ?Sized>(check_bytes_dyn: CheckBytesDyn) -> Self {
-        Self {
-            vtable: <TY as RegisteredImpl<TR>>::vtable(),
-            validation: ImplValidation {
-                layout: Layout::new::<TY>(),
-                check_bytes_dyn,
-            },
-        }
-    }
-}
-
-inventory::collect!(CheckBytesEntry);
-
-#[doc(hidden)]
-pub struct CheckBytesRegistry {
-    vtable_to_check_bytes: HashMap<usize, ImplValidation>,
-}
-
-impl CheckBytesRegistry {
-    fn new() -> Self {
-        Self {
-            vtable_to_check_bytes: HashMap::new(),
-        }
-    }
-
-    fn add_entry(&mut self, entry: &CheckBytesEntry) {
-        let old_value = self
-            .vtable_to_check_bytes
-            .insert(entry.vtable, entry.validation);
-
-        debug_assert!(old_value.is_none(), "vtable conflict, a trait implementation was likely added twice (but it's possible there was a hash collision)");
-    }
-
-    #[doc(hidden)]
-    pub fn get(&self, vtable: usize) -> Option<&ImplValidation> {
-        self.vtable_to_check_bytes.get(&vtable)
-    }
-}
-
-lazy_static::lazy_static! {
-    #[doc(hidden)]
-    pub static ref CHECK_BYTES_REGISTRY: CheckBytesRegistry =  {
-        let mut result = CheckBytesRegistry::new();
-        for entry in inventory::iter::<CheckBytesEntry> {
-            result.add_entry(entry);
-        }
-        result
-    };
+pub fn impl_validation(vtable: usize) -> Option<&'static ImplValidation> {
+    ImplRegistry::installed().get_validation(vtable)
 }
 
 #[doc(hidden)]
 #[macro_export]
 macro_rules! register_validation {
     ($type:ty as $trait:ty) => {
-        use rkyv_dyn::validation::{CheckBytesEntry, IsCheckBytesDyn, NotCheckBytesDyn};
+        fn check_bytes_dyn() -> rkyv_dyn::validation::CheckBytesDyn {
+            use rkyv_dyn::validation::{IsCheckBytesDyn, NotCheckBytesDyn};
 
-        inventory::submit! { CheckBytesEntry::new::<$type, $trait>(IsCheckBytesDyn::<$type>::CHECK_BYTES_DYN) }
-    }
+            IsCheckBytesDyn::<$type>::CHECK_BYTES_DYN
+        }
+    };
 }
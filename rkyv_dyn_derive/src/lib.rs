@@ -13,7 +13,7 @@ use syn::{
     parse::{Parse, ParseStream, Result},
     parse_macro_input,
     spanned::Spanned,
-    Attribute, Error, Ident, ItemImpl, ItemTrait, LitStr, Token, Visibility,
+    Attribute, Error, Expr, ExprArray, Ident, ItemImpl, ItemTrait, LitStr, Token, Visibility,
 };
 
 enum Input {
@@ -55,6 +55,8 @@ impl Parse for Input {
 struct Args {
     serialize: Option<LitStr>,
     deserialize: Option<Option<LitStr>>,
+    id: Option<Expr>,
+    aliases: Option<ExprArray>,
 }
 
 impl Parse for Args {
@@ -62,10 +64,14 @@ impl Parse for Args {
         mod kw {
             syn::custom_keyword!(serialize);
             syn::custom_keyword!(deserialize);
+            syn::custom_keyword!(id);
+            syn::custom_keyword!(aliases);
         }
 
         let mut serialize = None;
         let mut deserialize = None;
+        let mut id = None;
+        let mut aliases = None;
 
         let mut needs_punct = false;
         while !input.is_empty() {
@@ -93,18 +99,40 @@ impl Parse for Args {
                 } else {
                     deserialize = Some(None);
                 }
+            } else if input.peek(kw::id) {
+                if id.is_some() {
+                    return Err(input.error("duplicate id argument"));
+                }
+
+                input.parse::<kw::id>()?;
+                input.parse::<Token![=]>()?;
+                id = Some(input.parse::<Expr>()?);
+            } else if input.peek(kw::aliases) {
+                if aliases.is_some() {
+                    return Err(input.error("duplicate aliases argument"));
+                }
+
+                input.parse::<kw::aliases>()?;
+                input.parse::<Token![=]>()?;
+                aliases = Some(input.parse::<ExprArray>()?);
             } else {
-                return Err(
-                    input.error("expected serialize = \"...\" or deserialize = \"...\" parameters")
-                );
+                return Err(input.error(
+                    "expected serialize = \"...\", deserialize = \"...\", id = ..., or aliases = [...] parameters",
+                ));
             }
 
             needs_punct = true;
         }
 
+        if aliases.is_some() && id.is_none() {
+            return Err(input.error("aliases can only be used with an explicit id"));
+        }
+
         Ok(Args {
             serialize,
             deserialize,
+            id,
+            aliases,
         })
     }
 }
@@ -123,6 +151,11 @@ impl Parse for Args {
 /// - `deserialize`, `deserialize = "..."`: Adds deserialization support to the archived trait.
 ///   Similarly to the `name` parameter, you can choose the name of the deserialize trait and by
 ///   default it will be named "Deserialize" + your trait name.
+/// - `id = ...`: Only valid on trait implementations. Assigns the impl an explicit ID instead of
+///   identifying it by the hash of its archived type name. See `register_impl` for more
+///   information.
+/// - `aliases = [...]`: Only valid on trait implementations with an explicit `id`. Additional IDs
+///   that archived trait objects can use to refer to the impl.
 #[proc_macro_attribute]
 pub fn archive_dyn(
     attr: proc_macro::TokenStream,
@@ -187,6 +220,12 @@ pub fn archive_dyn(
                     (trait_.clone(), quote! {})
                 };
 
+                let id_args = match (args.id, args.aliases) {
+                    (Some(id), Some(aliases)) => quote! { , id = #id, aliases = #aliases },
+                    (Some(id), None) => quote! { , id = #id },
+                    (None, _) => quote! {},
+                };
+
                 quote! {
                     #input
 
@@ -202,7 +241,7 @@ pub fn archive_dyn(
                             DynError,
                         };
 
-                        rkyv_dyn::register_impl!(Archived<#ty> as dyn #deserialize_trait);
+                        rkyv_dyn::register_impl!(Archived<#ty> as dyn #deserialize_trait #id_args);
 
                         #deserialize_impl
                     };
@@ -216,6 +255,15 @@ pub fn archive_dyn(
             }
         }
         Input::Trait(input) => {
+            if let Some(id) = args.id {
+                return Error::new(
+                    id.span(),
+                    "ids can only be assigned to trait implementations",
+                )
+                .to_compile_error()
+                .into();
+            }
+
             let vis = &input.vis;
 
             let generic_params = input.generics.params.iter().map(|p| quote! { #p });
@@ -298,7 +346,7 @@ pub fn archive_dyn(
             let validation_impl = quote! {
                 use bytecheck::CheckBytes;
                 use rkyv::validation::LayoutRaw;
-                use rkyv_dyn::validation::{CHECK_BYTES_REGISTRY, CheckDynError, DynContext};
+                use rkyv_dyn::validation::{impl_validation, CheckDynError, DynContext};
 
                 impl<#generic_params> LayoutRaw for (dyn #deserialize_trait<#generic_args> + '_) {
                     fn layout_raw(value: *const Self) -> Layout {
@@ -313,7 +361,7 @@ pub fn archive_dyn(
                     #[inline]
                     unsafe fn check_bytes<'a>(value: *const Self, context: &mut (dyn DynContext + '_)) -> Result<&'a Self, Self::Error> {
                         let vtable = core::mem::transmute(ptr_meta::metadata(value));
-                        if let Some(validation) = CHECK_BYTES_REGISTRY.get(vtable) {
+                        if let Some(validation) = impl_validation(vtable) {
                             (validation.check_bytes_dyn)(value.cast(), context)?;
                             Ok(&*value)
                         } else {
@@ -340,7 +388,10 @@ pub fn archive_dyn(
                 #input
 
                 #[ptr_meta::pointee]
-                #vis trait #serialize_trait<#generic_params>: #name<#generic_args> + rkyv_dyn::SerializeDyn {}
+                #vis trait #serialize_trait<#generic_params>: #name<#generic_args> + rkyv_dyn::SerializeDyn {
+                    #[doc(hidden)]
+                    fn archived_impl_id(&self) -> u64;
+                }
 
                 #deserialize_trait_def
 
@@ -370,7 +421,12 @@ pub fn archive_dyn(
                     impl<__T: Archive + SerializeDyn + #name<#generic_args>, #generic_params> #serialize_trait<#generic_args> for __T
                     where
                         __T::Archived: RegisteredImpl<dyn #deserialize_trait<#generic_args>>
-                    {}
+                    {
+                        fn archived_impl_id(&self) -> u64 {
+                            <__T::Archived as RegisteredImpl<dyn #deserialize_trait<#generic_args>>>::ID
+                                .unwrap_or_else(|| self.archived_type_id())
+                        }
+                    }
 
                     #deserialize_trait_impl
 
@@ -388,7 +444,7 @@ pub fn archive_dyn(
                         type MetadataResolver = ();
 
                         unsafe fn resolve_metadata(&self, _: usize, _: Self::MetadataResolver, out: *mut ArchivedMetadata<Self>) {
-                            ArchivedDynMetadata::emplace(self.archived_type_id(), out);
+                            ArchivedDynMetadata::emplace(self.archived_impl_id(), out);
                         }
                     }
 
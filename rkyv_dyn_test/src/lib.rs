@@ -413,4 +413,332 @@ mod tests {
         value.as_mut().get_pin_mut().set_value(64);
         assert_eq!(value.value(), 64);
     }
+
+    #[test]
+    #[cfg(not(feature = "wasm"))]
+    fn downcast_dyn() {
+        use rkyv_dyn::{archive_dyn, ArchivedDyn};
+        use rkyv_typename::TypeName;
+
+        #[archive_dyn]
+        trait TestTrait {
+            fn value(&self) -> i32;
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(TypeName))]
+        struct Foo(i32);
+
+        #[archive_dyn]
+        impl TestTrait for Foo {
+            fn value(&self) -> i32 {
+                self.0
+            }
+        }
+
+        impl TestTrait for Archived<Foo> {
+            fn value(&self) -> i32 {
+                self.0.into()
+            }
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(TypeName))]
+        struct Bar(i32);
+
+        #[archive_dyn]
+        impl TestTrait for Bar {
+            fn value(&self) -> i32 {
+                -self.0
+            }
+        }
+
+        impl TestTrait for Archived<Bar> {
+            fn value(&self) -> i32 {
+                -i32::from(self.0)
+            }
+        }
+
+        let value = Box::new(Foo(42)) as Box<dyn SerializeTestTrait>;
+
+        let mut serializer = AllocSerializer::<256>::default();
+        serializer.serialize_value(&value).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+
+        let archived = unsafe { archived_root::<Box<dyn SerializeTestTrait>>(buf.as_ref()) };
+        assert!(archived.is::<Archived<Foo>>());
+        assert!(!archived.is::<Archived<Bar>>());
+        assert_eq!(archived.downcast_ref::<Archived<Foo>>().unwrap().0, 42);
+        assert!(archived.downcast_ref::<Archived<Bar>>().is_none());
+
+        let mut archived =
+            unsafe { archived_root_mut::<Box<dyn SerializeTestTrait>>(Pin::new(buf.as_mut())) };
+        assert!(archived.as_mut().downcast_pin::<Archived<Bar>>().is_none());
+        let foo = archived.as_mut().downcast_pin::<Archived<Foo>>().unwrap();
+        unsafe {
+            foo.get_unchecked_mut().0 = 24.into();
+        }
+        assert_eq!(archived.value(), 24);
+    }
+
+    #[test]
+    #[cfg(not(feature = "wasm"))]
+    fn impl_conflict() {
+        use rkyv_dyn::{archive_dyn, ImplRegistry};
+        use rkyv_typename::TypeName;
+
+        #[archive_dyn]
+        trait ConflictTrait {}
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(TypeName))]
+        struct ConflictTest;
+
+        #[archive_dyn]
+        impl ConflictTrait for ConflictTest {}
+
+        impl ConflictTrait for Archived<ConflictTest> {}
+
+        let mut registry = ImplRegistry::new();
+        registry
+            .try_add::<Archived<ConflictTest>, dyn ConflictTrait>()
+            .unwrap();
+        let error = registry
+            .try_add::<Archived<ConflictTest>, dyn ConflictTrait>()
+            .unwrap_err();
+        assert!(error.is_duplicate());
+        assert_eq!(
+            error.new_type_name(),
+            "rkyv_dyn_test::tests::ArchivedConflictTest"
+        );
+        assert_eq!(error.new_trait_name(), "dyn ConflictTrait");
+    }
+
+    #[test]
+    #[cfg(not(feature = "wasm"))]
+    fn explicit_impl_id() {
+        use rkyv_dyn::{archive_dyn, type_name_id};
+        use rkyv_typename::TypeName;
+
+        const ID: u64 = 0x0123_4567_89ab_cdef;
+        const ALIAS: u64 = type_name_id("old_crate::ArchivedIdTest");
+
+        #[archive_dyn]
+        trait IdTrait {
+            fn value(&self) -> i32;
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(TypeName))]
+        struct IdTest(i32);
+
+        #[archive_dyn(id = ID, aliases = [ALIAS])]
+        impl IdTrait for IdTest {
+            fn value(&self) -> i32 {
+                self.0
+            }
+        }
+
+        impl IdTrait for Archived<IdTest> {
+            fn value(&self) -> i32 {
+                self.0.into()
+            }
+        }
+
+        let value = Box::new(IdTest(42)) as Box<dyn SerializeIdTrait>;
+
+        let mut serializer = AllocSerializer::<256>::default();
+        serializer.serialize_value(&value).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+
+        // The explicit ID is written instead of the hash of the type name
+        let id_bytes = Archived::<u64>::from(ID);
+        let id_bytes = unsafe {
+            core::slice::from_raw_parts(
+                (&id_bytes as *const Archived<u64>).cast::<u8>(),
+                core::mem::size_of::<Archived<u64>>(),
+            )
+        };
+        let id_pos = buf
+            .windows(id_bytes.len())
+            .position(|window| window == id_bytes)
+            .expect("explicit id was not written to the archive");
+
+        let archived = unsafe { archived_root::<Box<dyn SerializeIdTrait>>(buf.as_ref()) };
+        assert_eq!(archived.value(), 42);
+
+        // Archives that refer to the impl by its type name hash or an alias can still be loaded
+        let mut type_name = String::new();
+        Archived::<IdTest>::build_type_name(|piece| type_name.push_str(piece));
+        for other_id in [type_name_id(&type_name), ALIAS] {
+            let other_id = Archived::<u64>::from(other_id);
+            unsafe {
+                buf.as_mut_ptr()
+                    .add(id_pos)
+                    .cast::<Archived<u64>>()
+                    .write_unaligned(other_id);
+            }
+            let archived = unsafe { archived_root::<Box<dyn SerializeIdTrait>>(buf.as_ref()) };
+            assert_eq!(archived.value(), 42);
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "wasm"))]
+    fn archive_any() {
+        use core::{alloc::Layout, any::Any, ptr::NonNull};
+        use rkyv::{
+            ser::{
+                serializers::{AlignedSerializer, AllocScratch},
+                ScratchSpace,
+            },
+            with::Map,
+            AlignedVec, Fallible,
+        };
+        use rkyv_dyn::any::{AnyError, AnyRegistry, Registered};
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[cfg_attr(feature = "validation", archive_attr(derive(bytecheck::CheckBytes)))]
+        struct Point {
+            x: i32,
+            y: i32,
+        }
+
+        struct Unregistered;
+
+        #[derive(Archive, Serialize, Deserialize)]
+        #[cfg_attr(feature = "validation", archive_attr(derive(bytecheck::CheckBytes)))]
+        struct Properties {
+            #[with(Map<Registered>)]
+            values: Vec<Box<dyn Any + Send + Sync>>,
+        }
+
+        #[derive(Debug)]
+        enum TestError {
+            Serializer,
+            Any(AnyError),
+        }
+
+        impl From<AnyError> for TestError {
+            fn from(e: AnyError) -> Self {
+                TestError::Any(e)
+            }
+        }
+
+        #[derive(Default)]
+        struct TestSerializer {
+            inner: AlignedSerializer<AlignedVec>,
+            scratch: AllocScratch,
+        }
+
+        impl Fallible for TestSerializer {
+            type Error = TestError;
+        }
+
+        impl Serializer for TestSerializer {
+            fn pos(&self) -> usize {
+                self.inner.pos()
+            }
+
+            fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+                self.inner.write(bytes).map_err(|_| TestError::Serializer)
+            }
+        }
+
+        impl ScratchSpace for TestSerializer {
+            unsafe fn push_scratch(
+                &mut self,
+                layout: Layout,
+            ) -> Result<NonNull<[u8]>, Self::Error> {
+                self.scratch
+                    .push_scratch(layout)
+                    .map_err(|_| TestError::Serializer)
+            }
+
+            unsafe fn pop_scratch(
+                &mut self,
+                ptr: NonNull<u8>,
+                layout: Layout,
+            ) -> Result<(), Self::Error> {
+                self.scratch
+                    .pop_scratch(ptr, layout)
+                    .map_err(|_| TestError::Serializer)
+            }
+        }
+
+        struct TestDeserializer;
+
+        impl Fallible for TestDeserializer {
+            type Error = TestError;
+        }
+
+        fn serialize(values: Vec<Box<dyn Any + Send + Sync>>) -> Result<AlignedVec, TestError> {
+            let mut serializer = TestSerializer::default();
+            serializer.serialize_value(&Properties { values })?;
+            Ok(serializer.inner.into_inner())
+        }
+
+        let mut registry = AnyRegistry::new();
+        registry.add::<String>(1);
+        #[cfg(not(feature = "validation"))]
+        registry.add::<Point>(2);
+        #[cfg(feature = "validation")]
+        registry.add_checked::<Point>(2);
+
+        let duplicate = registry.try_add::<String>(3).unwrap_err();
+        assert!(duplicate.is_duplicate());
+        assert_eq!(duplicate.id(), 1);
+        let collision = registry.try_add::<i32>(2).unwrap_err();
+        assert!(!collision.is_duplicate());
+        assert_eq!(
+            collision.existing_type_name(),
+            core::any::type_name::<Point>()
+        );
+        assert!(registry.install().is_ok());
+        assert!(AnyRegistry::new().install().is_err());
+
+        let buf = serialize(vec![
+            Box::new("hello world".to_string()),
+            Box::new(Point { x: 1, y: 2 }),
+        ])
+        .unwrap();
+        let archived = unsafe { archived_root::<Properties>(buf.as_ref()) };
+        assert_eq!(archived.values[0].id(), 1);
+        assert!(archived.values[0].is::<String>());
+        assert!(!archived.values[0].is::<Point>());
+        assert_eq!(
+            archived.values[0].downcast_ref::<String>().unwrap(),
+            "hello world"
+        );
+        assert_eq!(archived.values[1].id(), 2);
+        assert!(archived.values[1].downcast_ref::<String>().is_none());
+        let point = archived.values[1].downcast_ref::<Point>().unwrap();
+        assert_eq!((point.x, point.y), (1, 2));
+
+        let deserialized: Properties = archived.deserialize(&mut TestDeserializer).unwrap();
+        assert_eq!(
+            deserialized.values[0].downcast_ref::<String>().unwrap(),
+            "hello world"
+        );
+        assert_eq!(
+            deserialized.values[1].downcast_ref::<Point>().unwrap(),
+            &Point { x: 1, y: 2 }
+        );
+
+        assert!(matches!(
+            serialize(vec![Box::new(Unregistered)]),
+            Err(TestError::Any(AnyError::UnregisteredType)),
+        ));
+
+        #[cfg(feature = "validation")]
+        {
+            use rkyv::check_archived_root;
+
+            assert!(check_archived_root::<Properties>(buf.as_ref()).is_err());
+            let buf = serialize(vec![Box::new(Point { x: 3, y: 4 })]).unwrap();
+            let archived = check_archived_root::<Properties>(buf.as_ref()).unwrap();
+            let point = archived.values[0].downcast_ref::<Point>().unwrap();
+            assert_eq!((point.x, point.y), (3, 4));
+        }
+    }
 }
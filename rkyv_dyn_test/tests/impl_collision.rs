@@ -0,0 +1,61 @@
+//! Impls are registered for the whole test binary, so colliding impls are tested separately from
+//! the rest of the trait object tests.
+
+#![cfg(not(feature = "wasm"))]
+
+use rkyv::{Archive, Archived, Serialize};
+use rkyv_dyn::{archive_dyn, ImplRegistry};
+use rkyv_typename::TypeName;
+
+const ID: u64 = 0x0123_4567_89ab_cdef;
+
+#[archive_dyn]
+trait CollisionTrait {}
+
+#[derive(Archive, Serialize)]
+#[archive_attr(derive(TypeName))]
+struct First;
+
+#[archive_dyn(id = ID)]
+impl CollisionTrait for First {}
+
+impl CollisionTrait for Archived<First> {}
+
+#[derive(Archive, Serialize)]
+#[archive_attr(derive(TypeName))]
+struct Second;
+
+#[archive_dyn(id = ID)]
+impl CollisionTrait for Second {}
+
+impl CollisionTrait for Archived<Second> {}
+
+#[test]
+fn explicit_id_collision() {
+    let mut registry = ImplRegistry::new();
+    registry
+        .try_add::<Archived<First>, dyn CollisionTrait>()
+        .unwrap();
+    let error = registry
+        .try_add::<Archived<Second>, dyn CollisionTrait>()
+        .unwrap_err();
+    assert!(!error.is_duplicate());
+    assert_eq!(error.existing_type_name(), "impl_collision::ArchivedFirst");
+    assert_eq!(error.new_type_name(), "impl_collision::ArchivedSecond");
+    assert_eq!(error.new_trait_name(), "dyn CollisionTrait");
+
+    // The automatically-collected impls conflict in the same way
+    let error = ImplRegistry::collect()
+        .err()
+        .expect("colliding impls were not reported");
+    assert!(!error.is_duplicate());
+    let mut type_names = [error.existing_type_name(), error.new_type_name()];
+    type_names.sort();
+    assert_eq!(
+        type_names,
+        [
+            "impl_collision::ArchivedFirst",
+            "impl_collision::ArchivedSecond"
+        ]
+    );
+}
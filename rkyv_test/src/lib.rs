@@ -50,6 +50,53 @@ mod tests {
         test_archive(&Some(42));
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    #[cfg(not(feature = "strict"))]
+    fn archive_large_tuples() {
+        use rkyv::{archived_root, ser::Serializer, Deserialize, Infallible};
+
+        type Tuple16 = (
+            u8,
+            bool,
+            u8,
+            u8,
+            u8,
+            u8,
+            u8,
+            u8,
+            u8,
+            u8,
+            u8,
+            u8,
+            u8,
+            u8,
+            u8,
+            u8,
+        );
+
+        let value: Tuple16 = (0, true, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15);
+
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(&value)
+            .expect("failed to archive value");
+        let len = serializer.pos();
+        let buffer = serializer.into_serializer().into_inner();
+
+        let archived = unsafe { archived_root::<Tuple16>(&buffer[0..len]) };
+        assert_eq!(archived.0, 0);
+        assert!(archived.1);
+        assert_eq!(archived.12, 12);
+        assert_eq!(archived.15, 15);
+
+        let deserialized: Tuple16 = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(&deserialized.0, &value.0);
+        assert_eq!(&deserialized.1, &value.1);
+        assert_eq!(&deserialized.12, &value.12);
+        assert_eq!(&deserialized.15, &value.15);
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_refs() {
@@ -139,4 +186,48 @@ mod tests {
         test_archive::<r#virtual>(&r#virtual { r#virtual: 42 });
         test_archive::<r#try>(&r#try::r#try { r#try: 42 });
     }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    #[cfg(feature = "strict")]
+    fn archive_strict_layout() {
+        use core::mem::{align_of, size_of};
+        use rkyv::{layout::ReprC, Archive, Archived, Deserialize, Serialize};
+
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        struct Test {
+            a: u8,
+            b: u32,
+            c: u16,
+        }
+
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
+        enum TestEnum {
+            A,
+            B(u8, u64),
+        }
+
+        const STRUCT: ReprC = ReprC::new()
+            .field::<Archived<u8>>()
+            .field::<Archived<u32>>()
+            .field::<Archived<u16>>();
+        assert_eq!(size_of::<ArchivedTest>(), STRUCT.size());
+        assert_eq!(align_of::<ArchivedTest>(), STRUCT.align());
+
+        const ENUM: ReprC = ReprC::new().field::<u8>().union(
+            ReprC::new()
+                .field::<u8>()
+                .field::<Archived<u8>>()
+                .field::<Archived<u64>>(),
+        );
+        assert_eq!(size_of::<ArchivedTestEnum>(), ENUM.size());
+        assert_eq!(align_of::<ArchivedTestEnum>(), ENUM.align());
+
+        test_archive(&Test { a: 1, b: 2, c: 3 });
+        test_archive(&TestEnum::B(4, 5));
+    }
 }
@@ -45,6 +45,103 @@ mod tests {
         test_archive_container(&vec![1, 2, 3, 4]);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archived_string_comparisons() {
+        let value = vec![
+            "pear".to_string(),
+            "apple".to_string(),
+            "a string longer than the inline capacity".to_string(),
+        ];
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<String>>(buf.as_ref()) };
+
+        let mut sorted = archived.iter().collect::<Vec<_>>();
+        sorted.sort();
+        assert_eq!(sorted[0], "a string longer than the inline capacity");
+        assert_eq!(sorted[1], "apple");
+        assert_eq!(sorted[2], "pear");
+
+        let pear = &archived[0];
+        let pear_string = "pear".to_string();
+        let peach_string = "peach".to_string();
+        let pear_cow: Cow<'_, str> = Cow::Owned("pear".to_string());
+        assert_eq!(*pear, *"pear");
+        assert_eq!(*"pear", *pear);
+        assert_eq!(*pear, pear_string);
+        assert_eq!(pear_string, *pear);
+        assert_eq!(*pear, Cow::Borrowed("pear"));
+        assert_eq!(Cow::Borrowed("pear"), *pear);
+        assert_eq!(*pear, pear_cow);
+        assert_eq!(pear_cow, *pear);
+
+        assert!(*pear > "apple");
+        assert!("apple" < *pear);
+        assert!(*pear < *"plum");
+        assert!(*"plum" > *pear);
+        assert!(*pear > peach_string);
+        assert!(peach_string < *pear);
+        assert!(*pear < Cow::Borrowed("quince"));
+        assert!(Cow::Borrowed("quince") > *pear);
+        assert!(archived[1] < archived[0]);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_copy_optimized_vecs() {
+        assert!(u8::COPY_OPTIMIZATION.is_enabled());
+        assert!(<[bool; 4]>::COPY_OPTIMIZATION.is_enabled());
+        assert!(!String::COPY_OPTIMIZATION.is_enabled());
+        assert!(!<Vec<u8>>::COPY_OPTIMIZATION.is_enabled());
+
+        test_archive(&Vec::<u8>::new());
+        test_archive(&(0..=255).collect::<Vec<u8>>());
+        test_archive(&vec![true, false, true]);
+        test_archive(&vec![-1i16, 0, 1, i16::MAX]);
+        test_archive(&(0..1000).collect::<Vec<u32>>());
+        test_archive(&vec![u64::MAX, 0, 42]);
+        test_archive(&vec![1.0f32, -0.5, f32::INFINITY]);
+        test_archive(&vec!['a', 'ß', '💖']);
+        test_archive(&vec![[1u32, 2, 3], [4, 5, 6]]);
+        test_archive(&vec![vec![1u16, 2], vec![], vec![3]]);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn serialize_slices() {
+        use core::slice;
+
+        let strings = vec!["a".to_string(), "hello world".repeat(4), String::new()];
+        let numbers = (0..100).collect::<Vec<u32>>();
+
+        let mut serializer = DefaultSerializer::default();
+        let strings_pos = serializer.serialize_slice(&strings).unwrap();
+        let numbers_pos = serializer.serialize_slice(&numbers).unwrap();
+        let iter_pos = serializer
+            .serialize_iter::<String, _, _>(strings.iter().rev())
+            .unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        unsafe {
+            let archived = |pos: usize, len: usize| {
+                slice::from_raw_parts(buf.as_ptr().add(pos).cast::<Archived<String>>(), len)
+            };
+            assert_eq!(archived(strings_pos, strings.len()), strings.as_slice());
+            assert!(archived(iter_pos, strings.len())
+                .iter()
+                .eq(strings.iter().rev()));
+
+            let numbers_ptr = buf.as_ptr().add(numbers_pos).cast::<Archived<u32>>();
+            let archived_numbers = slice::from_raw_parts(numbers_ptr, numbers.len());
+            assert!(archived_numbers
+                .iter()
+                .zip(numbers.iter())
+                .all(|(a, b)| a == b));
+        }
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_option() {
@@ -1543,10 +1640,91 @@ mod tests {
         assert_eq!(archived.value.load(Ordering::Relaxed), 42);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_map_with() {
+        #[cfg(not(feature = "std"))]
+        use alloc::format;
+        use rkyv::with::{Converter, Map, MapWith};
+
+        #[derive(Debug, PartialEq)]
+        struct Version {
+            major: u16,
+            minor: u16,
+            patch: u16,
+        }
+
+        struct AsText;
+
+        impl Converter<Version> for AsText {
+            type Target = String;
+
+            fn to_target(field: &Version) -> String {
+                format!("{}.{}.{}", field.major, field.minor, field.patch)
+            }
+
+            fn from_target(target: String) -> Version {
+                let mut parts = target.split('.').map(|part| part.parse().unwrap());
+                Version {
+                    major: parts.next().unwrap(),
+                    minor: parts.next().unwrap(),
+                    patch: parts.next().unwrap(),
+                }
+            }
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[cfg_attr(feature = "validation", archive_attr(derive(bytecheck::CheckBytes)))]
+        struct Test {
+            #[with(MapWith<AsText>)]
+            version: Version,
+            #[with(Map<MapWith<AsText>>)]
+            previous: Vec<Version>,
+        }
+
+        let value = Test {
+            version: Version {
+                major: 1,
+                minor: 12,
+                patch: 3,
+            },
+            previous: vec![
+                Version {
+                    major: 0,
+                    minor: 9,
+                    patch: 0,
+                },
+                Version {
+                    major: 1,
+                    minor: 0,
+                    patch: 0,
+                },
+            ],
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(result.as_slice()) };
+
+        assert_eq!(archived.version, "1.12.3");
+        assert_eq!(archived.previous.len(), 2);
+        assert_eq!(archived.previous[0], "0.9.0");
+        assert_eq!(archived.previous[1], "1.0.0");
+
+        #[cfg(feature = "validation")]
+        rkyv::check_archived_root::<Test>(result.as_slice()).unwrap();
+
+        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn with_inline() {
-        use rkyv::with::Inline;
+        use rkyv::{
+            with::{DeserializeWith, Inline},
+            Infallible,
+        };
 
         #[derive(Archive, Serialize, Deserialize)]
         struct Test<'a> {
@@ -1562,6 +1740,53 @@ mod tests {
         let archived = unsafe { archived_root::<Test>(result.as_slice()) };
 
         assert_eq!(archived.value, 42);
+
+        let deserialized: Box<i32> =
+            Inline::deserialize_with(&archived.value, &mut Infallible).unwrap();
+        assert_eq!(*deserialized, 42);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    // Multibyte primitives aren't trivially copyable with non-native endianness
+    #[cfg(not(any(feature = "archive_le", feature = "archive_be")))]
+    fn with_pod() {
+        use rkyv::with::Pod;
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[cfg_attr(feature = "validation", archive_attr(derive(bytecheck::CheckBytes)))]
+        struct Test {
+            #[with(Pod)]
+            transform: [[f32; 4]; 4],
+            #[with(Pod)]
+            ids: [u64; 4],
+            name: String,
+        }
+
+        let value = Test {
+            transform: [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [2.0, 3.0, 4.0, 1.0],
+            ],
+            ids: [1, 2, 3, u64::MAX],
+            name: "camera".to_string(),
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(result.as_slice()) };
+
+        assert_eq!(archived.transform, value.transform);
+        assert_eq!(archived.ids, value.ids);
+        assert_eq!(archived.name, "camera");
+
+        #[cfg(feature = "validation")]
+        rkyv::check_archived_root::<Test>(result.as_slice()).unwrap();
+
+        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
     }
 
     #[test]
@@ -1587,7 +1812,10 @@ mod tests {
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn with_ref_as_box() {
-        use rkyv::with::RefAsBox;
+        use rkyv::{
+            with::{DeserializeWith, RefAsBox},
+            Infallible,
+        };
 
         #[derive(Archive, Serialize, Deserialize)]
         struct Test<'a> {
@@ -1603,6 +1831,10 @@ mod tests {
         let archived = unsafe { archived_root::<Test>(result.as_slice()) };
 
         assert_eq!(archived.value.as_ref(), "hello world");
+
+        let deserialized: Box<str> =
+            RefAsBox::deserialize_with(&archived.value, &mut Infallible).unwrap();
+        assert_eq!(&*deserialized, "hello world");
     }
 
     #[test]
@@ -1910,171 +2142,1042 @@ mod tests {
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn with_unsafe() {
-        use core::cell::UnsafeCell;
-        use rkyv::{to_archived, with::Unsafe};
+    fn with_inline_vec() {
+        use rkyv::with::InlineVec;
 
-        #[derive(Archive, Serialize, Deserialize)]
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Node {
+            #[with(InlineVec<12>)]
+            children: Vec<u32>,
+            #[with(InlineVec<16>)]
+            names: Vec<String>,
+        }
+
+        for len in 0..6 {
+            let value = Node {
+                children: (0..len).collect(),
+                names: (0..len).map(|i| i.to_string().repeat(20)).collect(),
+            };
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&value).unwrap();
+            let result = serializer.into_serializer().into_inner();
+            let archived = unsafe { archived_root::<Node>(result.as_slice()) };
+
+            assert_eq!(archived.children.is_inline(), len <= 3);
+            assert_eq!(archived.children.as_slice(), value.children.as_slice());
+            assert_eq!(archived.names.len(), value.names.len());
+            for (archived, name) in archived.names.iter().zip(value.names.iter()) {
+                assert_eq!(archived, name);
+            }
+
+            let deserialized: Node = archived
+                .deserialize(&mut DefaultDeserializer::default())
+                .unwrap();
+            assert_eq!(deserialized, value);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_varint_offsets() {
+        use rkyv::with::VarintOffsets;
+
+        #[allow(clippy::vec_box)]
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
         struct Test {
-            #[with(Unsafe)]
-            inner: UnsafeCell<u32>,
+            #[with(VarintOffsets)]
+            values: Vec<Box<u32>>,
+            #[with(VarintOffsets)]
+            strings: Vec<Box<String>>,
         }
 
         let value = Test {
-            inner: UnsafeCell::new(100),
+            values: (0..100).map(Box::new).collect(),
+            strings: (0..100)
+                .map(|i| Box::new(i.to_string().repeat(i)))
+                .collect(),
         };
         let mut serializer = DefaultSerializer::default();
         serializer.serialize_value(&value).unwrap();
         let result = serializer.into_serializer().into_inner();
         let archived = unsafe { archived_root::<Test>(result.as_slice()) };
 
-        unsafe {
-            assert_eq!(*archived.inner.get(), 100);
-            *archived.inner.get() = to_archived!(42u32);
-            assert_eq!(*archived.inner.get(), 42);
+        assert_eq!(archived.values.len(), 100);
+        assert!(archived.values.offsets().len() < 2 * archived.values.len());
+        assert!(archived.values.iter().eq(value.values.iter().map(|v| &**v)));
+        assert!(archived.values.get(42).unwrap() == &42);
+        assert_eq!(archived.values.get(100), None);
+        for (archived, string) in archived.strings.iter().zip(value.strings.iter()) {
+            assert_eq!(archived, &**string);
         }
 
         let deserialized: Test = archived
             .deserialize(&mut DefaultDeserializer::default())
             .unwrap();
-        unsafe {
-            assert_eq!(*deserialized.inner.get(), 42);
-            *deserialized.inner.get() = 88;
-            assert_eq!(*deserialized.inner.get(), 88);
-        }
+        assert_eq!(deserialized, value);
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn archive_crate_path() {
-        use ::rkyv as alt_path;
+    fn with_arrow_buffer() {
+        use rkyv::with::ArrowBuffer;
 
-        #[derive(Archive, Deserialize, Serialize)]
-        #[archive(crate = "alt_path")]
-        struct Test<'a> {
-            #[with(alt_path::with::AsBox)]
-            value: &'a str,
-            other: i32,
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Columns {
+            name: String,
+            #[with(ArrowBuffer)]
+            ints: Vec<u8>,
+            #[with(ArrowBuffer)]
+            floats: Vec<f64>,
+        }
+
+        for len in [0, 1, 7, 100] {
+            let value = Columns {
+                name: "a column name that is stored out of line".to_string(),
+                ints: (0..len).map(|i| i as u8).collect(),
+                floats: (0..len).map(|i| i as f64 / 2.0).collect(),
+            };
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&value).unwrap();
+            let result = serializer.into_serializer().into_inner();
+            let archived = unsafe { archived_root::<Columns>(result.as_slice()) };
+
+            let start = result.as_ptr() as usize;
+            let ints = archived.ints.as_ptr() as usize - start;
+            let floats = archived.floats.as_ptr() as usize - start;
+            assert_eq!(ints % ArrowBuffer::ALIGNMENT, 0);
+            assert_eq!(floats % ArrowBuffer::ALIGNMENT, 0);
+            // The ints are padded out to the next aligned position
+            if len > 0 {
+                assert!(floats >= ints + ArrowBuffer::ALIGNMENT);
+            }
+            assert_eq!(archived.ints.as_slice(), value.ints.as_slice());
+            assert_eq!(archived.floats.as_slice(), value.floats.as_slice());
+
+            let deserialized: Columns = archived
+                .deserialize(&mut DefaultDeserializer::default())
+                .unwrap();
+            assert_eq!(deserialized, value);
         }
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn archive_btree_map() {
-        let mut value = BTreeMap::new();
-        value.insert("foo".to_string(), 10);
-        value.insert("bar".to_string(), 20);
-        value.insert("baz".to_string(), 40);
-        value.insert("bat".to_string(), 80);
+    fn iter_vec() {
+        use rkyv::vec::IterVec;
 
-        let mut serializer = AlignedSerializer::new(AlignedVec::new());
-        serializer.serialize_value(&value).unwrap();
-        let result = serializer.into_inner();
-        let archived = unsafe { archived_root::<BTreeMap<String, i32>>(result.as_slice()) };
+        let generate = |i: u32| -> String { "element ".repeat(i as usize % 5) + &i.to_string() };
+        let expected = (0..100).map(generate).collect::<Vec<_>>();
 
-        assert_eq!(archived.len(), 4);
-        for (k, v) in value.iter() {
-            let (ak, av) = archived
-                .get_key_value(k.as_str())
-                .expect("failed to find key in archived B-tree map");
-            assert_eq!(k, ak);
-            assert_eq!(v, av);
-        }
-        assert!(archived.get_key_value("wrong!").is_none());
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(&IterVec((0..100).map(generate)))
+            .unwrap();
+        let result = serializer.into_serializer().into_inner();
 
-        let deserialized: BTreeMap<_, _> = archived.deserialize(&mut Infallible).unwrap();
-        assert_eq!(value, deserialized);
+        // The layout matches the layout of the equivalent vec
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&expected).unwrap();
+        assert_eq!(
+            result.as_slice(),
+            serializer.into_serializer().into_inner().as_slice()
+        );
+
+        let archived = unsafe { archived_root::<Vec<String>>(result.as_slice()) };
+        assert_eq!(archived.as_slice(), expected.as_slice());
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn archive_empty_btree_map() {
-        let value: BTreeMap<String, i32> = BTreeMap::new();
+    fn with_intern() {
+        #[cfg(not(feature = "std"))]
+        use alloc::sync::Arc;
+        use rkyv::with::{Intern, Map};
+        #[cfg(feature = "std")]
+        use std::sync::Arc;
 
-        let mut serializer = AlignedSerializer::new(AlignedVec::new());
-        serializer.serialize_value(&value).unwrap();
-        let result = serializer.into_inner();
-        let archived = unsafe { archived_root::<BTreeMap<String, i32>>(result.as_slice()) };
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Token {
+            #[with(Intern)]
+            kind: Arc<str>,
+            #[with(Intern)]
+            module: String,
+            #[with(Map<Intern>)]
+            tags: Vec<String>,
+        }
+
+        let name = "a repeated identifier";
+        let value = vec![
+            Token {
+                kind: name.into(),
+                module: name.to_string(),
+                tags: vec!["short".to_string(), name.to_string()],
+            },
+            Token {
+                kind: "short".into(),
+                module: "another module".to_string(),
+                tags: vec![name.to_string()],
+            },
+        ];
 
-        assert_eq!(archived.len(), 0);
-        for _ in archived.iter() {
-            panic!("there should be no values in the archived empty btree");
-        }
-        assert!(archived.get_key_value("wrong!").is_none());
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<Token>>(buf.as_ref()) };
+
+        assert_eq!(&*archived[0].kind, name);
+        assert_eq!(&*archived[1].module, "another module");
+        let interned = archived[0].kind.get().as_ptr();
+        assert_eq!(archived[0].module.get().as_ptr(), interned);
+        assert_eq!(archived[0].tags[1].get().as_ptr(), interned);
+        assert_eq!(archived[1].tags[0].get().as_ptr(), interned);
+        assert_eq!(
+            archived[0].tags[0].get().as_ptr(),
+            archived[1].kind.get().as_ptr()
+        );
+        // The repeated identifier is only written once
+        let occurrences = buf
+            .as_slice()
+            .windows(name.len())
+            .filter(|w| *w == name.as_bytes())
+            .count();
+        assert_eq!(occurrences, 1);
 
-        let deserialized: BTreeMap<_, _> = archived.deserialize(&mut Infallible).unwrap();
-        assert_eq!(value, deserialized);
+        let mut deserializer = DefaultDeserializer::default();
+        let deserialized: Vec<Token> = archived.deserialize(&mut deserializer).unwrap();
+        assert_eq!(deserialized, value);
+        let other: Vec<Token> = archived.deserialize(&mut deserializer).unwrap();
+        assert!(Arc::ptr_eq(&deserialized[0].kind, &other[0].kind));
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn archive_btree_set() {
-        let mut value = BTreeSet::new();
-        value.insert("foo".to_string());
-        value.insert("bar".to_string());
-        value.insert("baz".to_string());
-        value.insert("bat".to_string());
+    fn with_dedup() {
+        use rkyv::with::Dedup;
 
-        let mut serializer = AlignedSerializer::new(AlignedVec::new());
-        serializer.serialize_value(&value).unwrap();
-        let result = serializer.into_inner();
-        let archived = unsafe { archived_root::<BTreeSet<String>>(result.as_slice()) };
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Revision {
+            #[with(Dedup)]
+            content: Vec<u8>,
+            #[with(Dedup)]
+            checksums: Box<[u32]>,
+        }
+
+        let content = b"the same content in every revision".to_vec();
+        let checksums = vec![1u32, 2, 3, 4].into_boxed_slice();
+        let value = vec![
+            Revision {
+                content: content.clone(),
+                checksums: checksums.clone(),
+            },
+            Revision {
+                content: b"some new content".to_vec(),
+                checksums: checksums.clone(),
+            },
+            Revision {
+                content: content.clone(),
+                checksums: Box::new([]),
+            },
+        ];
 
-        assert_eq!(archived.len(), 4);
-        for k in value.iter() {
-            let ak = archived
-                .get(k.as_str())
-                .expect("failed to find key in archived B-tree map");
-            assert_eq!(k, ak);
-        }
-        assert!(archived.get("wrong!").is_none());
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<Revision>>(buf.as_ref()) };
 
-        let deserialized: BTreeSet<_> = archived.deserialize(&mut Infallible).unwrap();
-        assert_eq!(value, deserialized);
+        assert_eq!(archived[0].content.get(), content.as_slice());
+        assert_eq!(archived[1].content.get(), b"some new content");
+        assert_eq!(archived[0].content.as_ptr(), archived[2].content.as_ptr());
+        assert_ne!(archived[0].content.as_ptr(), archived[1].content.as_ptr());
+        assert_eq!(
+            archived[0].checksums.as_ptr(),
+            archived[1].checksums.as_ptr()
+        );
+        assert!(archived[2].checksums.is_empty());
+        let occurrences = buf
+            .as_slice()
+            .windows(content.len())
+            .filter(|w| *w == content.as_slice())
+            .count();
+        assert_eq!(occurrences, 1);
+
+        let deserialized: Vec<Revision> = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
     }
 
     #[test]
-    // This test is unfortunately too slow to run through miri
-    #[cfg_attr(miri, ignore)]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    // This test creates structures too big to fit in 16-bit offsets
-    #[cfg(not(feature = "size_16"))]
-    fn archive_btree_map_large() {
-        let mut value = BTreeMap::new();
-        for i in 0..100_000 {
-            value.insert(i.to_string(), i);
-        }
+    fn with_rle_vec() {
+        use rkyv::with::RleVec;
 
-        let mut serializer = AlignedSerializer::new(AlignedVec::new());
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Chunk {
+            #[with(RleVec)]
+            voxels: Vec<u16>,
+            #[with(RleVec)]
+            names: Vec<String>,
+        }
+
+        let mut voxels = vec![0u16; 1000];
+        voxels.resize(1024, 7);
+        voxels.push(3);
+        voxels.resize(1525, 0);
+        let value = Chunk {
+            voxels,
+            names: vec!["stone".to_string(), "stone".to_string(), "dirt".to_string()],
+        };
+
+        let mut serializer = DefaultSerializer::default();
         serializer.serialize_value(&value).unwrap();
-        let result = serializer.into_inner();
-        let archived = unsafe { archived_root::<BTreeMap<String, i32>>(result.as_slice()) };
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Chunk>(buf.as_ref()) };
+
+        assert_eq!(archived.voxels.len(), value.voxels.len());
+        assert_eq!(archived.voxels.run_count(), 4);
+        for (i, voxel) in value.voxels.iter().enumerate() {
+            assert_eq!(*archived.voxels.get(i).unwrap(), *voxel);
+        }
+        assert!(archived.voxels.get(value.voxels.len()).is_none());
+        let runs = [(0u16, 1000), (7, 24), (3, 1), (0, 500)];
+        assert_eq!(archived.voxels.runs().count(), runs.len());
+        for ((value, len), (expected_value, expected_len)) in archived.voxels.runs().zip(runs) {
+            assert_eq!(*value, expected_value);
+            assert_eq!(len, expected_len);
+        }
+        assert_eq!(&archived.voxels, value.voxels.as_slice());
+        assert_eq!(archived.names.run_count(), 2);
+        assert_eq!(&archived.names, value.names.as_slice());
+
+        let deserialized: Chunk = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
+
+        let empty = Chunk {
+            voxels: Vec::new(),
+            names: Vec::new(),
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&empty).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Chunk>(buf.as_ref()) };
+        assert!(archived.voxels.is_empty());
+        assert!(archived.voxels.get(0).is_none());
+        assert_eq!(archived.voxels.iter().count(), 0);
+        assert_eq!(archived.voxels.run_count(), 0);
+        assert_eq!(archived.names.run_count(), 0);
+    }
 
-        assert_eq!(archived.len(), 100_000);
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_sparse_vec() {
+        use rkyv::with::SparseVec;
 
-        for ((k, v), (ak, av)) in value.iter().zip(archived.iter()) {
-            assert_eq!(k, ak);
-            assert_eq!(v, av);
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct TileMap {
+            #[with(SparseVec)]
+            tiles: Vec<Option<u32>>,
         }
 
-        for (k, v) in value.iter() {
-            let av = archived
-                .get(k.as_str())
-                .expect("failed to find key in archived B-tree map");
-            assert_eq!(v, av);
+        let mut tiles = vec![None; 4096];
+        tiles[0] = Some(1);
+        tiles[100] = Some(2);
+        tiles[4095] = Some(3);
+        let value = TileMap { tiles };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        assert!(buf.len() < 128);
+        let archived = unsafe { archived_root::<TileMap>(buf.as_ref()) };
+
+        assert_eq!(archived.tiles.len(), 4096);
+        assert_eq!(archived.tiles.stored_len(), 3);
+        assert!(archived.tiles.default_value().is_none());
+        for (i, tile) in value.tiles.iter().enumerate() {
+            assert_eq!(archived.tiles.get(i).unwrap(), tile);
+        }
+        assert_eq!(archived.tiles.get(4096), None);
+        let entries = [(0, Some(1)), (100, Some(2)), (4095, Some(3))];
+        assert_eq!(archived.tiles.entries().count(), entries.len());
+        for ((index, tile), (expected_index, expected_tile)) in
+            archived.tiles.entries().zip(entries)
+        {
+            assert_eq!(index, expected_index);
+            assert_eq!(tile, &expected_tile);
         }
-        assert!(archived.get("wrong!").is_none());
+        assert_eq!(archived.tiles.iter().len(), 4096);
+        assert_eq!(&archived.tiles, value.tiles.as_slice());
 
-        let deserialized: BTreeMap<_, _> = archived.deserialize(&mut Infallible).unwrap();
-        assert_eq!(value, deserialized);
+        let deserialized: TileMap = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
     }
 
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
-    fn archive_zst_containers() {
-        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
-        #[archive(compare(PartialEq))]
-        #[archive_attr(derive(Debug))]
+    fn with_multi_map() {
+        use rkyv::with::MultiMap;
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct InvertedIndex {
+            #[with(MultiMap)]
+            postings: BTreeMap<String, Vec<u32>>,
+        }
+
+        let mut postings = BTreeMap::new();
+        postings.insert("archive".to_string(), vec![1, 4, 9]);
+        postings.insert("bytes".to_string(), Vec::new());
+        postings.insert("zero".to_string(), vec![4]);
+        postings.insert("copy".to_string(), vec![2, 3]);
+        let value = InvertedIndex { postings };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<InvertedIndex>(buf.as_ref()) };
+
+        assert_eq!(archived.postings.len(), 4);
+        assert_eq!(archived.postings.values_len(), 6);
+        for (key, values) in value.postings.iter() {
+            assert_eq!(
+                archived.postings.get(key.as_str()).unwrap(),
+                values.as_slice()
+            );
+            assert_eq!(&archived.postings[key.as_str()], values.as_slice());
+        }
+        assert!(archived.postings.get("missing").is_none());
+        assert!(!archived.postings.contains_key("missing"));
+        assert_eq!(archived.postings.iter().len(), 4);
+        for (key, values) in archived.postings.iter() {
+            assert_eq!(value.postings[key.as_str()].as_slice(), values);
+        }
+
+        let deserialized: InvertedIndex = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_range_map() {
+        use core::{
+            alloc::Layout,
+            ops::{Range, RangeInclusive},
+            ptr::NonNull,
+        };
+        use rkyv::{
+            ser::{serializers::AllocScratch, ScratchSpace},
+            with::{RangeMap, RangeMapError},
+        };
+
+        #[derive(Debug)]
+        enum TestError {
+            Serializer,
+            OverlappingRanges,
+        }
+
+        impl From<RangeMapError> for TestError {
+            fn from(_: RangeMapError) -> Self {
+                TestError::OverlappingRanges
+            }
+        }
+
+        #[derive(Default)]
+        struct TestSerializer {
+            inner: AlignedSerializer<AlignedVec>,
+            scratch: AllocScratch,
+        }
+
+        impl Fallible for TestSerializer {
+            type Error = TestError;
+        }
+
+        impl Serializer for TestSerializer {
+            fn pos(&self) -> usize {
+                self.inner.pos()
+            }
+
+            fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+                self.inner.write(bytes).map_err(|_| TestError::Serializer)
+            }
+        }
+
+        impl ScratchSpace for TestSerializer {
+            unsafe fn push_scratch(
+                &mut self,
+                layout: Layout,
+            ) -> Result<NonNull<[u8]>, Self::Error> {
+                self.scratch
+                    .push_scratch(layout)
+                    .map_err(|_| TestError::Serializer)
+            }
+
+            unsafe fn pop_scratch(
+                &mut self,
+                ptr: NonNull<u8>,
+                layout: Layout,
+            ) -> Result<(), Self::Error> {
+                self.scratch
+                    .pop_scratch(ptr, layout)
+                    .map_err(|_| TestError::Serializer)
+            }
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[cfg_attr(feature = "validation", archive_attr(derive(bytecheck::CheckBytes)))]
+        struct Geolocation {
+            #[with(RangeMap)]
+            countries: Vec<(Range<u32>, String)>,
+            #[with(RangeMap)]
+            regions: Vec<(RangeInclusive<u32>, String)>,
+        }
+
+        let value = Geolocation {
+            countries: vec![
+                (100..200, "b".to_string()),
+                (10..20, "a".to_string()),
+                (20..20, "empty".to_string()),
+                (200..250, "c".to_string()),
+            ],
+            regions: vec![(11..=20, "y".to_string()), (0..=10, "x".to_string())],
+        };
+
+        let mut serializer = TestSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.inner.into_inner();
+        let archived = unsafe { archived_root::<Geolocation>(buf.as_ref()) };
+
+        assert_eq!(archived.countries.len(), 4);
+        assert_eq!(archived.countries.get(&5), None);
+        assert_eq!(archived.countries.get(&10).unwrap(), "a");
+        assert_eq!(archived.countries.get(&19).unwrap(), "a");
+        assert_eq!(archived.countries.get(&20), None);
+        assert_eq!(archived.countries.get(&99), None);
+        assert_eq!(archived.countries.get(&150).unwrap(), "b");
+        assert_eq!(archived.countries.get(&200).unwrap(), "c");
+        assert_eq!(archived.countries.get(&250), None);
+        let (range, _) = archived.countries.get_key_value(&120).unwrap();
+        assert_eq!(range, &(100..200));
+        assert!(archived
+            .countries
+            .iter()
+            .zip(archived.countries.iter().skip(1))
+            .all(|((a, _), (b, _))| a.start <= b.start));
+        assert_eq!(archived.regions.get(&10).unwrap(), "x");
+        assert_eq!(archived.regions.get(&11).unwrap(), "y");
+        assert_eq!(archived.regions.get(&20).unwrap(), "y");
+        assert_eq!(archived.regions.get(&21), None);
+
+        #[cfg(feature = "validation")]
+        rkyv::check_archived_root::<Geolocation>(buf.as_ref()).unwrap();
+
+        let deserialized: Geolocation = archived.deserialize(&mut Infallible).unwrap();
+        let mut sorted = value.countries;
+        sorted.sort_by_key(|(range, _)| range.start);
+        assert_eq!(deserialized.countries, sorted);
+        let mut sorted = value.regions;
+        sorted.sort_by_key(|(range, _)| *range.start());
+        assert_eq!(deserialized.regions, sorted);
+
+        let overlapping = Geolocation {
+            countries: vec![(0..10, "a".to_string()), (5..15, "b".to_string())],
+            regions: Vec::new(),
+        };
+        let mut serializer = TestSerializer::default();
+        assert!(matches!(
+            serializer.serialize_value(&overlapping),
+            Err(TestError::OverlappingRanges),
+        ));
+
+        let overlapping = Geolocation {
+            countries: Vec::new(),
+            regions: vec![(0..=10, "x".to_string()), (10..=20, "y".to_string())],
+        };
+        let mut serializer = TestSerializer::default();
+        assert!(matches!(
+            serializer.serialize_value(&overlapping),
+            Err(TestError::OverlappingRanges),
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "rangemap")]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_rangemap() {
+        use rangemap::{RangeInclusiveMap, RangeMap};
+
+        let mut value = RangeMap::new();
+        value.insert(10..20, "a".to_string());
+        value.insert(30..40, "b".to_string());
+        value.insert(15..35, "c".to_string());
+
+        let buf = rkyv::to_bytes::<_, 256>(&value).unwrap();
+        let archived = unsafe { archived_root::<RangeMap<u32, String>>(buf.as_ref()) };
+        assert_eq!(archived, &value);
+        assert_eq!(archived.get(&14).unwrap(), "a");
+        assert_eq!(archived.get(&15).unwrap(), "c");
+        assert_eq!(archived.get(&35).unwrap(), "b");
+        assert_eq!(archived.get(&40), None);
+
+        let deserialized: RangeMap<u32, String> = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
+
+        let mut value = RangeInclusiveMap::new();
+        value.insert(10..=20, "a".to_string());
+        value.insert(21..=30, "b".to_string());
+
+        let buf = rkyv::to_bytes::<_, 256>(&value).unwrap();
+        let archived = unsafe { archived_root::<RangeInclusiveMap<u32, String>>(buf.as_ref()) };
+        assert_eq!(archived, &value);
+        assert_eq!(archived.get(&20).unwrap(), "a");
+        assert_eq!(archived.get(&30).unwrap(), "b");
+        assert_eq!(archived.get(&31), None);
+
+        let deserialized: RangeInclusiveMap<u32, String> =
+            archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_unsafe() {
+        use core::cell::UnsafeCell;
+        use rkyv::{to_archived, with::Unsafe};
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(Unsafe)]
+            inner: UnsafeCell<u32>,
+        }
+
+        let value = Test {
+            inner: UnsafeCell::new(100),
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(result.as_slice()) };
+
+        unsafe {
+            assert_eq!(*archived.inner.get(), 100);
+            *archived.inner.get() = to_archived!(42u32);
+            assert_eq!(*archived.inner.get(), 42);
+        }
+
+        let deserialized: Test = archived
+            .deserialize(&mut DefaultDeserializer::default())
+            .unwrap();
+        unsafe {
+            assert_eq!(*deserialized.inner.get(), 42);
+            *deserialized.inner.get() = 88;
+            assert_eq!(*deserialized.inner.get(), 88);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_as_inner() {
+        use core::{
+            alloc::Layout,
+            cell::{Cell, RefCell},
+            ptr::NonNull,
+        };
+        use rkyv::{
+            ser::{serializers::AllocScratch, ScratchSpace},
+            with::{AsInner, AsInnerError},
+        };
+
+        #[derive(Debug)]
+        enum TestError {
+            Serializer,
+            MutablyBorrowed,
+        }
+
+        impl From<AsInnerError> for TestError {
+            fn from(_: AsInnerError) -> Self {
+                TestError::MutablyBorrowed
+            }
+        }
+
+        #[derive(Default)]
+        struct TestSerializer {
+            inner: AlignedSerializer<AlignedVec>,
+            scratch: AllocScratch,
+        }
+
+        impl Fallible for TestSerializer {
+            type Error = TestError;
+        }
+
+        impl Serializer for TestSerializer {
+            fn pos(&self) -> usize {
+                self.inner.pos()
+            }
+
+            fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+                self.inner.write(bytes).map_err(|_| TestError::Serializer)
+            }
+        }
+
+        impl ScratchSpace for TestSerializer {
+            unsafe fn push_scratch(
+                &mut self,
+                layout: Layout,
+            ) -> Result<NonNull<[u8]>, Self::Error> {
+                self.scratch
+                    .push_scratch(layout)
+                    .map_err(|_| TestError::Serializer)
+            }
+
+            unsafe fn pop_scratch(
+                &mut self,
+                ptr: NonNull<u8>,
+                layout: Layout,
+            ) -> Result<(), Self::Error> {
+                self.scratch
+                    .pop_scratch(ptr, layout)
+                    .map_err(|_| TestError::Serializer)
+            }
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[cfg_attr(feature = "validation", archive_attr(derive(bytecheck::CheckBytes)))]
+        struct Test {
+            #[with(AsInner)]
+            count: Cell<u32>,
+            #[with(AsInner)]
+            names: RefCell<Vec<String>>,
+        }
+
+        let value = Test {
+            count: Cell::new(42),
+            names: RefCell::new(vec!["alice".to_string(), "bob".to_string()]),
+        };
+        let mut serializer = TestSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.inner.into_inner();
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+
+        assert_eq!(archived.count, 42);
+        assert_eq!(archived.names.len(), 2);
+        assert_eq!(archived.names[0], "alice");
+        assert_eq!(archived.names[1], "bob");
+
+        #[cfg(feature = "validation")]
+        rkyv::check_archived_root::<Test>(buf.as_ref()).unwrap();
+
+        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
+
+        let _guard = value.names.borrow_mut();
+        let mut serializer = TestSerializer::default();
+        assert!(matches!(
+            serializer.serialize_value(&value),
+            Err(TestError::MutablyBorrowed),
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_crate_path() {
+        use ::rkyv as alt_path;
+
+        #[derive(Archive, Deserialize, Serialize)]
+        #[archive(crate = "alt_path")]
+        struct Test<'a> {
+            #[with(alt_path::with::AsBox)]
+            value: &'a str,
+            other: i32,
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_btree_map() {
+        let mut value = BTreeMap::new();
+        value.insert("foo".to_string(), 10);
+        value.insert("bar".to_string(), 20);
+        value.insert("baz".to_string(), 40);
+        value.insert("bat".to_string(), 80);
+
+        let mut serializer = AlignedSerializer::new(AlignedVec::new());
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_inner();
+        let archived = unsafe { archived_root::<BTreeMap<String, i32>>(result.as_slice()) };
+
+        assert_eq!(archived.len(), 4);
+        for (k, v) in value.iter() {
+            let (ak, av) = archived
+                .get_key_value(k.as_str())
+                .expect("failed to find key in archived B-tree map");
+            assert_eq!(k, ak);
+            assert_eq!(v, av);
+        }
+        assert!(archived.get_key_value("wrong!").is_none());
+
+        let deserialized: BTreeMap<_, _> = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(value, deserialized);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_empty_btree_map() {
+        let value: BTreeMap<String, i32> = BTreeMap::new();
+
+        let mut serializer = AlignedSerializer::new(AlignedVec::new());
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_inner();
+        let archived = unsafe { archived_root::<BTreeMap<String, i32>>(result.as_slice()) };
+
+        assert_eq!(archived.len(), 0);
+        for _ in archived.iter() {
+            panic!("there should be no values in the archived empty btree");
+        }
+        assert!(archived.get_key_value("wrong!").is_none());
+
+        let deserialized: BTreeMap<_, _> = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(value, deserialized);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_btree_set() {
+        let mut value = BTreeSet::new();
+        value.insert("foo".to_string());
+        value.insert("bar".to_string());
+        value.insert("baz".to_string());
+        value.insert("bat".to_string());
+
+        let mut serializer = AlignedSerializer::new(AlignedVec::new());
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_inner();
+        let archived = unsafe { archived_root::<BTreeSet<String>>(result.as_slice()) };
+
+        assert_eq!(archived.len(), 4);
+        for k in value.iter() {
+            let ak = archived
+                .get(k.as_str())
+                .expect("failed to find key in archived B-tree map");
+            assert_eq!(k, ak);
+        }
+        assert!(archived.get("wrong!").is_none());
+
+        let deserialized: BTreeSet<_> = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(value, deserialized);
+    }
+
+    #[test]
+    // This test is unfortunately too slow to run through miri
+    #[cfg_attr(miri, ignore)]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    // This test creates structures too big to fit in 16-bit offsets
+    #[cfg(not(feature = "size_16"))]
+    fn archive_btree_map_large() {
+        let mut value = BTreeMap::new();
+        for i in 0..100_000 {
+            value.insert(i.to_string(), i);
+        }
+
+        let mut serializer = AlignedSerializer::new(AlignedVec::new());
+        serializer.serialize_value(&value).unwrap();
+        let result = serializer.into_inner();
+        let archived = unsafe { archived_root::<BTreeMap<String, i32>>(result.as_slice()) };
+
+        assert_eq!(archived.len(), 100_000);
+
+        for ((k, v), (ak, av)) in value.iter().zip(archived.iter()) {
+            assert_eq!(k, ak);
+            assert_eq!(v, av);
+        }
+
+        for (k, v) in value.iter() {
+            let av = archived
+                .get(k.as_str())
+                .expect("failed to find key in archived B-tree map");
+            assert_eq!(v, av);
+        }
+        assert!(archived.get("wrong!").is_none());
+
+        let deserialized: BTreeMap<_, _> = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(value, deserialized);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_btree_fixed_buffers() {
+        #[cfg(not(feature = "std"))]
+        use alloc::format;
+        use rkyv::{
+            archived_value,
+            collections::{btree_map::ArchivedBTreeMap, btree_set::ArchivedBTreeSet},
+            ser::serializers::{BufferScratch, CompositeSerializer},
+        };
+
+        const LEN: usize = 700;
+        const KEY_LEN: usize = 32;
+        // Each entry needs an archived string and value, its key bytes, and a share of the node
+        // overhead, all of which scale with the size of an archived usize
+        const BUFFER_LEN: usize = LEN
+            * (core::mem::size_of::<Archived<String>>()
+                + core::mem::size_of::<Archived<usize>>()
+                + KEY_LEN
+                + 8);
+
+        // Long keys leave room for fewer entries in each leaf, so the node list has to grow
+        let mut map = BTreeMap::new();
+        let mut set = BTreeSet::new();
+        for i in 0..LEN as u32 {
+            let key = format!("{:0width$}", i, width = KEY_LEN);
+            map.insert(key.clone(), i);
+            set.insert(key);
+        }
+
+        let mut scratch = vec![0u8; 1 << 16].into_boxed_slice();
+        let mut serializer = CompositeSerializer::new(
+            BufferSerializer::new(AlignedBytes([0u8; BUFFER_LEN])),
+            BufferScratch::new(&mut scratch),
+            Infallible,
+        );
+        let pos = unsafe {
+            let resolver = ArchivedBTreeMap::serialize_from_reverse_iter_in_scratch(
+                map.iter().rev(),
+                &mut serializer,
+            )
+            .expect("failed to archive B-tree map");
+            serializer
+                .resolve_aligned(&map, resolver)
+                .expect("failed to archive B-tree map")
+        };
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_value::<BTreeMap<String, u32>>(buf.as_ref(), pos) };
+        assert_eq!(archived.len(), map.len());
+        for ((k, v), (ak, av)) in map.iter().zip(archived.iter()) {
+            assert_eq!(k, ak);
+            assert_eq!(v, av);
+        }
+
+        let mut serializer = CompositeSerializer::new(
+            BufferSerializer::new(AlignedBytes([0u8; BUFFER_LEN])),
+            BufferScratch::new(&mut scratch),
+            Infallible,
+        );
+        let pos = unsafe {
+            let resolver = ArchivedBTreeSet::serialize_from_reverse_iter_in_scratch(
+                set.iter().rev(),
+                &mut serializer,
+            )
+            .expect("failed to archive B-tree set");
+            serializer
+                .resolve_aligned(&set, resolver)
+                .expect("failed to archive B-tree set")
+        };
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_value::<BTreeSet<String>>(buf.as_ref(), pos) };
+        assert_eq!(archived.len(), set.len());
+        assert!(set.iter().all(|k| archived.contains_key(k.as_str())));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_in_scratch() {
+        use rkyv::{
+            archived_value,
+            ser::serializers::{BufferScratch, CompositeSerializer},
+            with::InScratch,
+        };
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Index {
+            #[with(InScratch)]
+            offsets: BTreeMap<u32, u32>,
+            #[with(InScratch)]
+            tags: BTreeSet<u32>,
+        }
+
+        let value = Index {
+            offsets: (0..100).map(|i| (i, i * 4)).collect(),
+            tags: (0..100).filter(|i| i % 3 == 0).collect(),
+        };
+
+        // Neither the serializer nor its scratch space can allocate
+        let mut scratch = [0u8; 4096];
+        let mut serializer = CompositeSerializer::new(
+            BufferSerializer::new(AlignedBytes([0u8; 4096])),
+            BufferScratch::new(&mut scratch),
+            Infallible,
+        );
+        let pos = serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_value::<Index>(buf.as_ref(), pos) };
+        assert_eq!(archived.offsets.get(&50), Some(&200));
+        assert!(archived.tags.contains_key(&99));
+
+        let deserialized: Index = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_dedup_subtrees() {
+        use rkyv::with::Dedup;
+
+        #[derive(Archive, Serialize, Deserialize, Clone, Debug, PartialEq)]
+        struct Node {
+            name: u32,
+            #[with(Dedup)]
+            children: Vec<u32>,
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Trie {
+            #[with(Dedup)]
+            first: Box<[Node]>,
+            #[with(Dedup)]
+            second: Box<[Node]>,
+            #[with(Dedup)]
+            third: Box<[Node]>,
+        }
+
+        let subtree = vec![
+            Node {
+                name: 1,
+                children: vec![10, 11, 12],
+            },
+            Node {
+                name: 2,
+                children: vec![20, 21],
+            },
+        ]
+        .into_boxed_slice();
+        let mut other = subtree.clone();
+        other[1].children.push(22);
+        let value = Trie {
+            first: subtree.clone(),
+            second: subtree.clone(),
+            third: other,
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Trie>(buf.as_ref()) };
+
+        assert_eq!(archived.first.as_ptr(), archived.second.as_ptr());
+        assert_ne!(archived.first.as_ptr(), archived.third.as_ptr());
+        assert_eq!(
+            archived.first[0].children.as_ptr(),
+            archived.third[0].children.as_ptr()
+        );
+        assert_eq!(archived.second[1].children.get(), &[20, 21]);
+        assert_eq!(archived.third[1].children.get(), &[20, 21, 22]);
+
+        let deserialized: Trie = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_zst_containers() {
+        #[derive(Archive, Deserialize, Serialize, Debug, PartialEq)]
+        #[archive(compare(PartialEq))]
+        #[archive_attr(derive(Debug))]
         struct MyZST;
 
         test_archive(&Box::new(MyZST));
@@ -2117,6 +3220,57 @@ mod tests {
         });
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn serializer_tracker() {
+        use rkyv::ser::serializers::{
+            AlignedSerializer, AllocScratch, CompositeSerializer, SerializerTracker,
+        };
+
+        type TrackerSerializer = CompositeSerializer<
+            SerializerTracker<AlignedSerializer<AlignedVec>>,
+            AllocScratch,
+            Infallible,
+        >;
+        fn track_serialize<T>(value: &T) -> SerializerTracker<AlignedSerializer<AlignedVec>>
+        where
+            T: Serialize<TrackerSerializer>,
+        {
+            let mut serializer = CompositeSerializer::new(
+                SerializerTracker::new(AlignedSerializer::<AlignedVec>::default()),
+                AllocScratch::default(),
+                Infallible,
+            );
+            serializer
+                .serialize_value(value)
+                .expect("failed to serialize value");
+            serializer.into_components().0
+        }
+
+        let tracker = track_serialize(&42u8);
+        assert_eq!(tracker.bytes_written(), 1);
+        assert_eq!(tracker.padding_bytes(), 0);
+        assert_eq!(tracker.max_padding(), 0);
+        assert_eq!(tracker.max_alignment(), 1);
+
+        let tracker = track_serialize(&[1u32, 2u32]);
+        assert_eq!(tracker.bytes_written(), 8);
+        assert_eq!(tracker.bytes_written(), tracker.inner().pos());
+        assert_eq!(tracker.max_alignment(), 4);
+
+        // The u8 elements leave the buffer misaligned for the relative pointer of the vec
+        let align = core::mem::align_of::<Archived<Vec<u8>>>();
+        let padding = (align - 3 % align) % align;
+        let tracker = track_serialize(&vec![1u8, 2, 3]);
+        assert_eq!(tracker.padding_bytes(), padding);
+        assert_eq!(tracker.max_padding(), padding);
+        assert_eq!(
+            tracker.bytes_written(),
+            3 + padding + core::mem::size_of::<Archived<Vec<u8>>>()
+        );
+        assert_eq!(tracker.bytes_written(), tracker.inner().pos());
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn scratch_tracker() {
@@ -2163,4 +3317,575 @@ mod tests {
         assert_eq!(tracker.max_allocations(), 1);
         assert_ne!(tracker.min_buffer_size(), 0);
     }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn xxh3_hasher() {
+        use core::hash::Hasher;
+        use rkyv::collections::hash_index::Xxh3Hasher;
+
+        const PRIME32: u64 = 2654435761;
+        const PRIME64: u64 = 11400714785074694797;
+
+        // The sanity check buffer and test vectors from the reference implementation
+        let mut buffer = Vec::with_capacity(2367);
+        let mut byte_gen = PRIME32;
+        for _ in 0..2367 {
+            buffer.push((byte_gen >> 56) as u8);
+            byte_gen = byte_gen.wrapping_mul(PRIME64);
+        }
+
+        let vectors = [
+            (0, 0, 0x2d06800538d394c2),
+            (0, PRIME64, 0xa8a6b918b2f0364a),
+            (1, 0, 0xc44bdff4074eecdb),
+            (1, PRIME64, 0x032be332dd766ef8),
+            (6, 0, 0x27b56a84cd2d7325),
+            (6, PRIME64, 0x84589c116ab59ab9),
+            (12, 0, 0xa713daf0dfbb77e7),
+            (12, PRIME64, 0xe7303e1b2336de0e),
+            (24, 0, 0xa3fe70bf9d3510eb),
+            (24, PRIME64, 0x850e80fc35bdd690),
+            (48, 0, 0x397da259ecba1f11),
+            (48, PRIME64, 0xadc2cbaa44acc616),
+            (80, 0, 0xbcdefbbb2c47c90a),
+            (80, PRIME64, 0xc6dd0cb699532e73),
+            (195, 0, 0xcd94217ee362ec3a),
+            (195, PRIME64, 0xba68003d370cb3d9),
+            (403, 0, 0xcdeb804d65c6dea4),
+            (2048, 0, 0xdd59e2c3a5f038e0),
+            (2048, PRIME64, 0x66f81670669ababc),
+            (2367, 0, 0xcb37aeb9e5d361ed),
+            (2367, PRIME64, 0xd2db3415b942b42a),
+        ];
+        for &(len, seed, expected) in vectors.iter() {
+            let mut hasher = Xxh3Hasher::with_seed(seed);
+            hasher.write(&buffer[..len]);
+            assert_eq!(hasher.finish(), expected, "len {} seed {:#x}", len, seed);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn hash_index_algorithms() {
+        use rkyv::{
+            collections::{
+                hash_index::{HashAlgorithm, HashIndexResolver},
+                ArchivedHashIndex,
+            },
+            ser::ScratchSpace,
+            ScratchVec,
+        };
+
+        struct Keys(HashAlgorithm, Vec<String>);
+
+        impl Archive for Keys {
+            type Archived = ArchivedHashIndex;
+            type Resolver = HashIndexResolver;
+
+            unsafe fn resolve(
+                &self,
+                pos: usize,
+                resolver: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                ArchivedHashIndex::resolve_from_len(self.1.len(), pos, resolver, out);
+            }
+        }
+
+        impl<S: ScratchSpace + Serializer + ?Sized> Serialize<S> for Keys {
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                unsafe {
+                    let mut entries = ScratchVec::new(serializer, self.1.len())?;
+                    entries.set_len(self.1.len());
+                    let resolver = ArchivedHashIndex::build_and_serialize_with(
+                        self.0,
+                        self.1.iter().map(|key| (key, &())),
+                        serializer,
+                        &mut entries,
+                    )?;
+                    entries.free(serializer)?;
+                    Ok(resolver)
+                }
+            }
+        }
+
+        for &algorithm in [
+            HashAlgorithm::SeaHash,
+            HashAlgorithm::WyHash,
+            HashAlgorithm::Xxh3,
+        ]
+        .iter()
+        {
+            let keys = Keys(algorithm, (0..1000).map(|i| i.to_string()).collect());
+
+            let mut serializer = DefaultSerializer::default();
+            serializer
+                .serialize_value(&keys)
+                .expect("failed to archive value");
+            let buf = serializer.into_serializer().into_inner();
+            let archived = unsafe { archived_root::<Keys>(buf.as_ref()) };
+
+            assert_eq!(archived.algorithm(), algorithm);
+            assert_eq!(archived.len(), keys.1.len());
+
+            for (i, key) in keys.1.iter().enumerate() {
+                assert_eq!(archived.find(key, |j| keys.1[j] == *key), Some(i));
+            }
+            let missing = "missing".to_string();
+            assert_eq!(archived.find(&missing, |j| keys.1[j] == missing), None);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn par_hash_index() {
+        use rkyv::{
+            collections::{
+                hash_index::{HashAlgorithm, HashIndexResolver},
+                ArchivedHashIndex,
+            },
+            ser::ScratchSpace,
+            ScratchVec,
+        };
+
+        struct Keys(HashAlgorithm, Vec<String>);
+
+        impl Archive for Keys {
+            type Archived = ArchivedHashIndex;
+            type Resolver = HashIndexResolver;
+
+            unsafe fn resolve(
+                &self,
+                pos: usize,
+                resolver: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                ArchivedHashIndex::resolve_from_len(self.1.len(), pos, resolver, out);
+            }
+        }
+
+        impl<S: ScratchSpace + Serializer + ?Sized> Serialize<S> for Keys {
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                unsafe {
+                    let mut entries = ScratchVec::new(serializer, self.1.len())?;
+                    entries.set_len(self.1.len());
+                    let resolver = ArchivedHashIndex::par_build_and_serialize_with(
+                        self.0,
+                        self.1.iter().map(|key| (key, &())),
+                        serializer,
+                        &mut entries,
+                    )?;
+                    entries.free(serializer)?;
+                    Ok(resolver)
+                }
+            }
+        }
+
+        for &algorithm in [
+            HashAlgorithm::SeaHash,
+            HashAlgorithm::WyHash,
+            HashAlgorithm::Xxh3,
+        ]
+        .iter()
+        {
+            let keys = Keys(algorithm, (0..100_000).map(|i| i.to_string()).collect());
+
+            let mut serializer = DefaultSerializer::default();
+            serializer
+                .serialize_value(&keys)
+                .expect("failed to archive value");
+            let buf = serializer.into_serializer().into_inner();
+            let archived = unsafe { archived_root::<Keys>(buf.as_ref()) };
+
+            assert_eq!(archived.algorithm(), algorithm);
+            assert_eq!(archived.len(), keys.1.len());
+
+            for (i, key) in keys.1.iter().enumerate() {
+                assert_eq!(archived.find(key, |j| keys.1[j] == *key), Some(i));
+            }
+            let missing = "missing".to_string();
+            assert_eq!(archived.find(&missing, |j| keys.1[j] == missing), None);
+
+            // Building the index again gives the same bytes
+            let mut serializer = DefaultSerializer::default();
+            serializer
+                .serialize_value(&keys)
+                .expect("failed to archive value");
+            assert_eq!(
+                serializer.into_serializer().into_inner().as_slice(),
+                buf.as_slice()
+            );
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_index_map() {
+        use rkyv::{
+            collections::index_map::{ArchivedIndexMap, IndexMapResolver},
+            ser::ScratchSpace,
+            string::ArchivedString,
+        };
+
+        struct Pairs(Vec<(String, u32)>);
+
+        impl Archive for Pairs {
+            type Archived = ArchivedIndexMap<ArchivedString, Archived<u32>>;
+            type Resolver = IndexMapResolver;
+
+            unsafe fn resolve(
+                &self,
+                pos: usize,
+                resolver: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                ArchivedIndexMap::resolve_from_len(self.0.len(), pos, resolver, out);
+            }
+        }
+
+        impl<S: ScratchSpace + Serializer + ?Sized> Serialize<S> for Pairs {
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                unsafe {
+                    ArchivedIndexMap::serialize_from_iter(
+                        self.0.iter().map(|(k, v)| (k, v)),
+                        serializer,
+                    )
+                }
+            }
+        }
+
+        let pairs = Pairs((0..200).rev().map(|i| (i.to_string(), i)).collect());
+
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(&pairs)
+            .expect("failed to archive value");
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Pairs>(buf.as_ref()) };
+
+        assert_eq!(archived.len(), pairs.0.len());
+        for (i, ((key, value), (archived_key, archived_value))) in
+            pairs.0.iter().zip(archived.iter()).enumerate()
+        {
+            assert_eq!(archived_key, key);
+            assert_eq!(archived_value, value);
+            assert_eq!(archived.get_index_of(key.as_str()), Some(i));
+            assert_eq!(archived.get(key.as_str()), Some(archived_value));
+        }
+        assert_eq!(archived.get("missing"), None);
+
+        let all = archived.as_slice();
+        assert_eq!(all.len(), pairs.0.len());
+        assert!(all.iter().eq(archived.iter()));
+        assert!(archived.get_range(..=pairs.0.len()).is_none());
+        let (start, end) = (10, 5);
+        assert!(archived.get_range(start..end).is_none());
+
+        let window = archived.get_range(50..100).unwrap();
+        assert_eq!(window.len(), 50);
+        assert_eq!(window.first().unwrap().0, &pairs.0[50].0);
+        assert_eq!(window.last().unwrap().0, &pairs.0[99].0);
+        assert_eq!(window.get_index(10).unwrap().0, &pairs.0[60].0);
+        assert_eq!(window[10], pairs.0[60].1);
+        assert!(window.get_index(50).is_none());
+        assert!(window.keys().eq(archived.keys().skip(50).take(50)));
+        assert!(window.values().eq(archived.values().skip(50).take(50)));
+
+        let sub = window.get_range(5..=7).unwrap();
+        assert_eq!(sub.len(), 3);
+        assert_eq!(sub.first().unwrap().0, &pairs.0[55].0);
+        let (left, right) = window.split_at(20);
+        assert_eq!((left.len(), right.len()), (20, 30));
+        assert_eq!(right.first().unwrap().0, &pairs.0[70].0);
+        assert!(window.get_range(50..).unwrap().is_empty());
+
+        #[cfg(feature = "validation")]
+        rkyv::check_archived_root::<Pairs>(buf.as_ref()).unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn frame_decoder() {
+        use rkyv::util::{encode_frame, FrameDecoder, FrameError, FRAME_HEADER_SIZE};
+
+        let values = (0..20).map(|i| i.to_string().repeat(i)).collect::<Vec<_>>();
+        let mut stream = Vec::new();
+        for value in values.iter() {
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(value).unwrap();
+            let bytes = serializer.into_serializer().into_inner();
+            encode_frame(&bytes, &mut stream).unwrap();
+        }
+
+        for chunk_size in [1, 7, 64, stream.len()] {
+            let mut decoder = FrameDecoder::new();
+            let mut received = Vec::new();
+            for chunk in stream.chunks(chunk_size) {
+                decoder.extend_from_slice(chunk);
+                while let Some(frame) = decoder.decode().unwrap() {
+                    assert_eq!(frame.as_ptr() as usize % 16, 0);
+                    let archived = unsafe { archived_root::<String>(&frame) };
+                    received.push(archived.to_string());
+                }
+            }
+            assert_eq!(received, values);
+            assert_eq!(decoder.buffered_len(), 0);
+        }
+
+        // Frames that are too long are rejected as soon as their header arrives
+        let mut decoder = FrameDecoder::with_max_frame_len(16);
+        let mut stream = Vec::new();
+        encode_frame(&[0; 17], &mut stream).unwrap();
+        decoder.extend_from_slice(&stream[..FRAME_HEADER_SIZE]);
+        assert!(matches!(
+            decoder.decode(),
+            Err(FrameError::FrameTooLong { len: 17, max: 16 }),
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archived_vec_overlay() {
+        use rkyv::vec::ArchivedVecOverlay;
+
+        let values = (0..10).map(|i| i.to_string()).collect::<Vec<_>>();
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&values).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<String>>(&buf) };
+
+        let mut overlay = ArchivedVecOverlay::<String>::new(archived);
+        assert!(!overlay.is_modified());
+        assert_eq!(overlay.len(), 10);
+        assert!(overlay.get(10).is_none());
+
+        overlay.set(3, &"three".to_string()).unwrap();
+        overlay.set(3, &"THREE".to_string()).unwrap();
+        overlay.push(&"10".to_string()).unwrap();
+        overlay.push(&"11".to_string()).unwrap();
+        overlay.set(11, &"eleven".to_string()).unwrap();
+        assert!(overlay.is_modified());
+        assert_eq!(overlay.len(), 12);
+        assert_eq!(overlay.get(3).unwrap(), "THREE");
+        assert_eq!(overlay.get(4).unwrap(), "4");
+        assert_eq!(overlay.get(10).unwrap(), "10");
+        assert_eq!(overlay.get(11).unwrap(), "eleven");
+        assert_eq!(overlay.iter().len(), 12);
+
+        // The base archive is untouched
+        assert_eq!(archived[3], "3");
+        assert_eq!(archived.len(), 10);
+
+        let mut expected = values.clone();
+        expected[3] = "THREE".to_string();
+        expected.push("10".to_string());
+        expected.push("eleven".to_string());
+        assert!(overlay.iter().eq(expected.iter()));
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&overlay).unwrap();
+        let result = serializer.into_serializer().into_inner();
+        let reserialized = unsafe { archived_root::<Vec<String>>(&result) };
+        assert_eq!(reserialized.as_slice(), expected.as_slice());
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&expected).unwrap();
+        assert_eq!(
+            result.as_slice(),
+            serializer.into_serializer().into_inner().as_slice()
+        );
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_loader() {
+        use rkyv::util::{ArchiveLoader, LoadError};
+
+        let value = (0..20).map(|i| i.to_string().repeat(i)).collect::<Vec<_>>();
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let bytes = serializer.into_serializer().into_inner();
+
+        for chunk_size in [1, 7, 64, bytes.len()] {
+            let mut loader = ArchiveLoader::new(bytes.len());
+            for chunk in bytes.chunks(chunk_size) {
+                assert!(!loader.is_complete());
+                let unfilled = loader.unfilled_mut();
+                unfilled[..chunk.len()].copy_from_slice(chunk);
+                loader.advance(chunk.len());
+            }
+            assert!(loader.is_complete());
+            assert!(loader.unfilled_mut().is_empty());
+
+            let buf = loader.finish().unwrap();
+            assert_eq!(buf.as_ptr() as usize % 16, 0);
+            let archived = unsafe { archived_root::<Vec<String>>(&buf) };
+            assert_eq!(archived.as_slice(), value.as_slice());
+        }
+
+        // Extra bytes are left for the caller
+        let mut stream = bytes.to_vec();
+        stream.extend_from_slice(b"next");
+        let mut loader = ArchiveLoader::new(bytes.len());
+        assert_eq!(loader.extend_from_slice(&stream[..10]), 10);
+        assert_eq!(loader.received_bytes(), &bytes[..10]);
+        assert_eq!(loader.extend_from_slice(&stream[10..]), bytes.len() - 10);
+        assert_eq!(loader.finish().unwrap().as_slice(), bytes.as_slice());
+
+        let mut loader = ArchiveLoader::new(bytes.len());
+        loader.extend_from_slice(&bytes[..10]);
+        assert!(matches!(
+            loader.finish(),
+            Err(LoadError::Incomplete { received: 10, len }) if len == bytes.len(),
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn unchecked_access() {
+        use rkyv::{
+            unchecked::{archived_root, archived_value, from_bytes_unchecked},
+            util::{encode_frame, ArchiveLoader, FrameDecoder},
+        };
+
+        let value = vec!["hello".to_string(), "world".to_string()];
+        let mut serializer = DefaultSerializer::default();
+        let pos = serializer.serialize_value(&value).unwrap();
+        let bytes = serializer.into_serializer().into_inner();
+
+        let archived = unsafe { archived_root::<Vec<String>>(&bytes) };
+        assert_eq!(archived.as_slice(), value.as_slice());
+        let archived = unsafe { archived_value::<Vec<String>>(&bytes, pos) };
+        assert_eq!(archived.as_slice(), value.as_slice());
+        let deserialized = unsafe { from_bytes_unchecked::<Vec<String>>(&bytes).unwrap() };
+        assert_eq!(deserialized, value);
+
+        let mut stream = Vec::new();
+        encode_frame(&bytes, &mut stream).unwrap();
+        let mut decoder = FrameDecoder::new();
+        decoder.extend_from_slice(&stream);
+        let frame = decoder.decode_unchecked().unwrap().unwrap();
+        assert_eq!(frame.as_slice(), bytes.as_slice());
+
+        let mut loader = ArchiveLoader::new(bytes.len());
+        loader.extend_from_slice(&bytes);
+        let buf = loader.finish_unchecked().unwrap();
+        assert_eq!(buf.as_slice(), bytes.as_slice());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    #[allow(deprecated)]
+    fn unchecked_root_access() {
+        use rkyv::util::{encode_frame, ArchiveLoader, FrameDecoder};
+
+        // The root functions and plain methods are kept (and deprecated) with `safe_api`
+        let value = vec!["hello".to_string(), "world".to_string()];
+        let mut serializer = DefaultSerializer::default();
+        let pos = serializer.serialize_value(&value).unwrap();
+        let bytes = serializer.into_serializer().into_inner();
+
+        let archived = unsafe { rkyv::archived_root::<Vec<String>>(&bytes) };
+        assert_eq!(archived.as_slice(), value.as_slice());
+        let archived = unsafe { rkyv::archived_value::<Vec<String>>(&bytes, pos) };
+        assert_eq!(archived.as_slice(), value.as_slice());
+        let deserialized = unsafe { rkyv::from_bytes_unchecked::<Vec<String>>(&bytes).unwrap() };
+        assert_eq!(deserialized, value);
+
+        let mut stream = Vec::new();
+        encode_frame(&bytes, &mut stream).unwrap();
+        let mut decoder = FrameDecoder::new();
+        decoder.extend_from_slice(&stream);
+        let frame = decoder.decode().unwrap().unwrap();
+        assert_eq!(frame.as_slice(), bytes.as_slice());
+
+        let mut loader = ArchiveLoader::new(bytes.len());
+        loader.extend_from_slice(&bytes);
+        let buf = loader.finish().unwrap();
+        assert_eq!(buf.as_slice(), bytes.as_slice());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn c_header() {
+        #[cfg(not(feature = "std"))]
+        use alloc::format;
+        use core::mem::size_of;
+        use rkyv::c_header::CHeader;
+
+        #[derive(Archive)]
+        #[archive(c_header)]
+        #[archive_attr(repr(C))]
+        struct Point {
+            x: f32,
+            y: f32,
+        }
+
+        #[derive(Archive)]
+        #[archive(c_header)]
+        #[allow(dead_code)]
+        enum Shape {
+            Empty,
+            Circle(Point, f32),
+            Polygon { points: Vec<Point> },
+        }
+
+        #[derive(Archive)]
+        #[archive(c_header)]
+        #[archive_attr(repr(C))]
+        struct Scene {
+            name: String,
+            origin: Box<Point>,
+            shapes: Vec<Shape>,
+            tags: Box<[u32]>,
+            parent: Option<u32>,
+            matrix: [[f32; 4]; 4],
+        }
+
+        let header = CHeader::new("SCENE_H").add::<Archived<Scene>>().to_string();
+
+        assert!(header.starts_with("#ifndef SCENE_H\n#define SCENE_H\n"));
+        assert!(header.ends_with("#endif\n"));
+        assert!(header.contains("struct ArchivedPoint {\n    float x;\n    float y;\n};\n"));
+        assert!(header.contains(&format!(
+            "RKYV_STATIC_ASSERT(sizeof(struct ArchivedPoint) == {}, ",
+            size_of::<ArchivedPoint>(),
+        )));
+        assert!(header.contains("#define ArchivedShape_TAG_Empty 0\n"));
+        assert!(header.contains("#define ArchivedShape_TAG_Polygon 2\n"));
+        assert!(header.contains(
+            "struct ArchivedShape_Circle {\n    uint8_t tag;\n    struct ArchivedPoint _0;\n    float _1;\n};\n"
+        ));
+        assert!(header.contains(
+            "union ArchivedShape {\n    uint8_t tag;\n    struct ArchivedShape_Circle Circle;\n    struct ArchivedShape_Polygon Polygon;\n};\n"
+        ));
+        assert!(header.contains(
+            "struct rkyv_option_uint32_t {\n    uint8_t tag;\n    uint32_t value;\n};\n"
+        ));
+        assert!(header.contains(
+            "struct ArchivedScene {\n    struct rkyv_string name;\n    rkyv_rel_ptr origin;\n    struct rkyv_vec shapes;\n    struct rkyv_vec tags;\n    struct rkyv_option_uint32_t parent;\n    float matrix[4][4];\n};\n"
+        ));
+        assert!(header.contains(
+            "static inline const char *ArchivedScene_name(const struct ArchivedScene *value) {"
+        ));
+        assert!(header.contains(
+            "static inline const struct ArchivedPoint *ArchivedScene_origin(const struct ArchivedScene *value) {"
+        ));
+        assert!(header.contains(
+            "static inline const union ArchivedShape *ArchivedScene_shapes(const struct ArchivedScene *value) {"
+        ));
+        assert!(header.contains(
+            "static inline rkyv_size ArchivedScene_tags_len(const struct ArchivedScene *value) {"
+        ));
+        assert!(header.contains(
+            "static inline const struct ArchivedPoint *ArchivedShape_Polygon_points(const union ArchivedShape *value) {"
+        ));
+
+        // Definitions must come before their uses
+        let point = header.find("struct ArchivedPoint {").unwrap();
+        let shape = header.find("union ArchivedShape {").unwrap();
+        let scene = header.find("struct ArchivedScene {").unwrap();
+        assert!(point < shape && shape < scene);
+    }
 }
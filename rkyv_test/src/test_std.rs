@@ -27,6 +27,138 @@ mod tests {
             .expect_err("serialized to an undersized buffer must fail");
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn buffer_pool() {
+        use rkyv::ser::serializers::BufferPool;
+        use std::sync::Arc;
+
+        let pool = BufferPool::with_limit(2);
+        assert!(pool.is_empty());
+
+        let values = vec!["hello world".to_string(), "goodbye world".to_string()];
+        let bytes = {
+            let mut serializer = pool.serializer(256);
+            serializer.serialize_value(&values).unwrap();
+            let buf = serializer.into_serializer().into_inner();
+            let archived = unsafe { archived_root::<Vec<String>>(buf.as_ref()) };
+            assert_eq!(archived, &values);
+            buf.into_inner()
+        };
+        // The output buffer was kept, only the scratch space was returned
+        assert_eq!(pool.len(), 1);
+
+        let pool = Arc::new(pool);
+        let threads = (0..4)
+            .map(|_| {
+                let pool = pool.clone();
+                let values = values.clone();
+                let bytes = bytes.clone();
+                std::thread::spawn(move || {
+                    let mut serializer = pool.serializer(256);
+                    serializer.serialize_value(&values).unwrap();
+                    let buf = serializer.into_serializer().into_inner();
+                    assert_eq!(buf.as_slice(), bytes.as_slice());
+                })
+            })
+            .collect::<Vec<_>>();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        assert_eq!(pool.len(), 2);
+
+        let buf = pool.checkout();
+        assert!(buf.is_empty());
+        let scratch = pool.checkout_scratch(64);
+        assert_eq!(scratch.len(), 64);
+        assert!(pool.is_empty());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn concurrent_serializer() {
+        use rkyv::{archived_value, ser::serializers::ConcurrentSerializer};
+        use std::sync::Arc;
+
+        let serializer = Arc::new(ConcurrentSerializer::<256>::new());
+        assert!(serializer.is_empty());
+
+        let threads = (0..8u8)
+            .map(|i| {
+                let serializer = serializer.clone();
+                std::thread::spawn(move || {
+                    // Odd-length values make sure regions get padded
+                    let value = (0..i).map(|j| vec![j; j as usize]).collect::<Vec<_>>();
+                    let id = serializer.serialize_value(&value).unwrap();
+                    (id, value)
+                })
+            })
+            .collect::<Vec<_>>();
+        let values = threads
+            .into_iter()
+            .map(|thread| thread.join().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(serializer.len(), 8);
+
+        let serializer = Arc::try_unwrap(serializer).unwrap();
+        let (buf, roots) = serializer.finish();
+        assert_eq!(roots.len(), 8);
+
+        for (id, value) in values.iter() {
+            let archived = unsafe { archived_value::<Vec<Vec<u8>>>(buf.as_ref(), roots[*id]) };
+            assert_eq!(archived, value);
+
+            #[cfg(feature = "validation")]
+            rkyv::check_archived_value::<Vec<Vec<u8>>>(buf.as_ref(), roots[*id]).unwrap();
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_loader_read_from() {
+        use rkyv::util::ArchiveLoader;
+        use std::io::{self, Read};
+
+        // Returns at most 5 bytes per read
+        struct Trickle<'a>(&'a [u8]);
+
+        impl Read for Trickle<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                let len = buf.len().min(self.0.len()).min(5);
+                buf[..len].copy_from_slice(&self.0[..len]);
+                self.0 = &self.0[len..];
+                Ok(len)
+            }
+        }
+
+        let value = vec!["hello world".to_string(), "goodbye world".to_string()];
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let bytes = serializer.into_serializer().into_inner();
+
+        let mut reader = Trickle(&bytes);
+        let mut loader = ArchiveLoader::new(bytes.len());
+        let mut reads = 0;
+        while !loader.is_complete() {
+            assert!(loader.read_from(&mut reader).unwrap() <= 5);
+            reads += 1;
+        }
+        assert!(reads > 1);
+        assert_eq!(loader.read_from(&mut reader).unwrap(), 0);
+        let buf = loader.finish().unwrap();
+        let archived = unsafe { archived_root::<Vec<String>>(&buf) };
+        assert_eq!(archived, &value);
+
+        let mut reader = Trickle(&bytes[..10]);
+        let mut loader = ArchiveLoader::new(bytes.len());
+        loader.read_from(&mut reader).unwrap();
+        loader.read_from(&mut reader).unwrap();
+        assert_eq!(
+            loader.read_from(&mut reader).unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_hash_map() {
@@ -66,6 +198,123 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_hash_map_seed() {
+        use core::hash::{Hash, Hasher};
+        use rkyv::ser::serializers::SeededSerializer;
+
+        let mut hash_map = HashMap::new();
+        for i in 0..100 {
+            hash_map.insert(i.to_string(), format!("value {}", i));
+        }
+
+        let serialize = |mut serializer: SeededSerializer<DefaultSerializer>| {
+            serializer.serialize_value(&hash_map).unwrap();
+            serializer.into_inner().into_serializer().into_inner()
+        };
+        let fixed = serialize(SeededSerializer::new(DefaultSerializer::default(), 0));
+        let seeded = serialize(SeededSerializer::new(DefaultSerializer::default(), 0x1234));
+        let random = serialize(SeededSerializer::random(DefaultSerializer::default()));
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&hash_map).unwrap();
+        assert_eq!(
+            fixed.as_slice(),
+            serializer.into_serializer().into_inner().as_slice()
+        );
+        assert_ne!(fixed.as_slice(), seeded.as_slice());
+
+        let mut hashes = Vec::new();
+        for buf in [&fixed, &seeded, &random] {
+            let archived = unsafe { archived_root::<HashMap<String, String>>(buf.as_ref()) };
+            assert_eq!(archived.len(), hash_map.len());
+            for (key, value) in hash_map.iter() {
+                assert_eq!(&archived[key.as_str()], value);
+            }
+            assert!(!archived.contains_key("missing"));
+
+            let mut hasher = archived.hasher();
+            "key".hash(&mut hasher);
+            hashes.push(hasher.finish());
+        }
+        assert_ne!(hashes[0], hashes[1]);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_hash_map_algorithm() {
+        use rkyv::{
+            collections::hash_index::{HashAlgorithm, HashBuilder},
+            ser::serializers::SeededSerializer,
+        };
+
+        let mut hash_map = HashMap::new();
+        for i in 0..100 {
+            hash_map.insert(i.to_string(), format!("value {}", i));
+        }
+
+        for &algorithm in [
+            HashAlgorithm::SeaHash,
+            HashAlgorithm::WyHash,
+            HashAlgorithm::Xxh3,
+        ]
+        .iter()
+        {
+            let mut serializer = SeededSerializer::new(DefaultSerializer::default(), 0x1234)
+                .with_algorithm(algorithm);
+            serializer.serialize_value(&hash_map).unwrap();
+            let buf = serializer.into_inner().into_serializer().into_inner();
+            let archived = unsafe { archived_root::<HashMap<String, String>>(buf.as_ref()) };
+
+            assert!(matches!(
+                (algorithm, archived.hasher()),
+                (HashAlgorithm::SeaHash, HashBuilder::SeaHash(_))
+                    | (HashAlgorithm::WyHash, HashBuilder::WyHash(_))
+                    | (HashAlgorithm::Xxh3, HashBuilder::Xxh3(_))
+            ));
+            for (key, value) in hash_map.iter() {
+                assert_eq!(&archived[key.as_str()], value);
+            }
+            assert!(!archived.contains_key("missing"));
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn seeded_serializer_intern() {
+        use rkyv::{ser::serializers::SeededSerializer, with::Intern};
+
+        #[derive(Archive, Serialize)]
+        struct Entry {
+            #[with(Intern)]
+            name: String,
+            counts: HashMap<String, String>,
+        }
+
+        let name = "a repeated identifier";
+        let value = (0..2)
+            .map(|i| Entry {
+                name: name.to_string(),
+                counts: Some((name.to_string(), i.to_string()))
+                    .into_iter()
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+
+        let mut serializer = SeededSerializer::new(DefaultSerializer::default(), 0x1234);
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_inner().into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Vec<Entry>>(buf.as_ref()) };
+
+        assert_eq!(&*archived[0].name, name);
+        assert_eq!(
+            archived[0].name.get().as_ptr(),
+            archived[1].name.get().as_ptr()
+        );
+        assert_eq!(archived[1].counts[name], "1");
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     #[allow(deprecated)]
@@ -106,6 +355,151 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn archive_loader_read_from_async() {
+        use core::{
+            future::Future,
+            pin::Pin,
+            task::{Context, Poll},
+        };
+        use rkyv::util::ArchiveLoader;
+        use std::{
+            io,
+            sync::Arc,
+            task::{Wake, Waker},
+        };
+        use tokio::io::{AsyncRead, ReadBuf};
+
+        struct NoopWaker;
+
+        impl Wake for NoopWaker {
+            fn wake(self: Arc<Self>) {}
+        }
+
+        // Returns at most 5 bytes per read and is only ready every other poll
+        struct Trickle<'a> {
+            bytes: &'a [u8],
+            ready: bool,
+        }
+
+        impl AsyncRead for Trickle<'_> {
+            fn poll_read(
+                mut self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+                buf: &mut ReadBuf<'_>,
+            ) -> Poll<io::Result<()>> {
+                self.ready = !self.ready;
+                if !self.ready {
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+                let len = buf.remaining().min(self.bytes.len()).min(5);
+                buf.put_slice(&self.bytes[..len]);
+                self.bytes = &self.bytes[len..];
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        fn block_on<F: Future>(future: F) -> F::Output {
+            let waker = Waker::from(Arc::new(NoopWaker));
+            let mut cx = Context::from_waker(&waker);
+            let mut future = Box::pin(future);
+            loop {
+                if let Poll::Ready(output) = Pin::as_mut(&mut future).poll(&mut cx) {
+                    return output;
+                }
+            }
+        }
+
+        let value = vec!["hello world".to_string(), "goodbye world".to_string()];
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let bytes = serializer.into_serializer().into_inner();
+
+        let mut reader = Trickle {
+            bytes: &bytes,
+            ready: false,
+        };
+        let mut loader = ArchiveLoader::new(bytes.len());
+        let mut reads = 0;
+        while !loader.is_complete() {
+            assert!(block_on(loader.read_from_async(&mut reader)).unwrap() <= 5);
+            reads += 1;
+        }
+        assert!(reads > 1);
+        assert_eq!(block_on(loader.read_from_async(&mut reader)).unwrap(), 0);
+        let buf = loader.finish().unwrap();
+        let archived = unsafe { archived_root::<Vec<String>>(&buf) };
+        assert_eq!(archived, &value);
+
+        let mut reader = Trickle {
+            bytes: &bytes[..10],
+            ready: false,
+        };
+        let mut loader = ArchiveLoader::new(bytes.len());
+        block_on(loader.read_from_async(&mut reader)).unwrap();
+        block_on(loader.read_from_async(&mut reader)).unwrap();
+        assert_eq!(
+            block_on(loader.read_from_async(&mut reader))
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn frame_codec() {
+        use bytes::{Buf, BytesMut};
+        use rkyv::util::{encode_frame, FrameCodec, FrameError};
+        use tokio_util::codec::{Decoder, Encoder};
+
+        let values = ["hello", "a somewhat longer string", ""];
+        let archives = values
+            .iter()
+            .map(|value| {
+                let mut serializer = DefaultSerializer::default();
+                serializer.serialize_value(&value.to_string()).unwrap();
+                serializer.into_serializer().into_inner()
+            })
+            .collect::<Vec<_>>();
+
+        // The codec writes the same frames as encode_frame
+        let mut codec = FrameCodec::new();
+        let mut stream = BytesMut::new();
+        let mut expected = Vec::new();
+        for archive in archives.iter() {
+            codec.encode(archive.as_slice(), &mut stream).unwrap();
+            encode_frame(archive, &mut expected).unwrap();
+        }
+        assert_eq!(stream.as_ref(), expected.as_slice());
+
+        // Start the receive buffer one byte off so the frames in it are misaligned
+        let mut buf = BytesMut::from(&[0u8][..]);
+        buf.advance(1);
+        let mut received = Vec::new();
+        for chunk in stream.chunks(3) {
+            buf.extend_from_slice(chunk);
+            while let Some(frame) = codec.decode(&mut buf).unwrap() {
+                let archived = unsafe { archived_root::<String>(&frame) };
+                received.push(archived.to_string());
+            }
+        }
+        assert_eq!(received, values);
+        assert!(buf.is_empty());
+
+        let mut codec = FrameCodec::with_max_frame_len(4);
+        assert!(matches!(
+            codec.encode(archives[0].as_slice(), &mut BytesMut::new()),
+            Err(FrameError::FrameTooLong { max: 4, .. }),
+        ));
+        assert!(matches!(
+            codec.decode(&mut BytesMut::from(expected.as_slice())),
+            Err(FrameError::FrameTooLong { max: 4, .. }),
+        ));
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn archive_hash_set() {
@@ -222,6 +616,38 @@ mod tests {
         test_archive(&value);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    #[cfg(unix)]
+    fn with_as_string_lossy() {
+        use rkyv::{with::AsStringLossy, Infallible};
+        use std::{ffi::OsString, os::unix::ffi::OsStringExt, path::PathBuf};
+
+        #[derive(Archive, Serialize, Deserialize)]
+        struct Test {
+            #[with(AsStringLossy)]
+            os_string: OsString,
+            #[with(AsStringLossy)]
+            path: PathBuf,
+        }
+
+        let value = Test {
+            os_string: OsString::from_vec(b"hello \xffworld".to_vec()),
+            path: PathBuf::from("/tmp/archive.bin"),
+        };
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(buf.as_ref()) };
+
+        assert_eq!(archived.os_string, "hello \u{fffd}world");
+        assert_eq!(archived.path, "/tmp/archive.bin");
+
+        let deserialized: Test = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized.os_string, "hello \u{fffd}world");
+        assert_eq!(deserialized.path, value.path);
+    }
+
     // TODO: figure out errors
 
     // #[test]
@@ -364,4 +790,719 @@ mod tests {
         value.insert(());
         test_archive(&value);
     }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    // Archived primitives don't implement serde::Serialize with non-native endianness
+    #[cfg(all(
+        feature = "serde",
+        not(any(feature = "archive_le", feature = "archive_be"))
+    ))]
+    fn serde_serialize_archived() {
+        use std::collections::BTreeMap;
+
+        #[derive(Archive, Serialize)]
+        struct Example {
+            name: String,
+            values: Vec<u32>,
+            nickname: Option<String>,
+            missing: Option<String>,
+            scores: BTreeMap<String, u32>,
+            tags: HashMap<String, Vec<String>>,
+            boxed: Box<[i64]>,
+        }
+
+        let mut scores = BTreeMap::new();
+        scores.insert("alice".to_string(), 10);
+        scores.insert("bob".to_string(), 7);
+        let mut tags = HashMap::new();
+        tags.insert(
+            "colors".to_string(),
+            vec!["red".to_string(), "blue".to_string()],
+        );
+        tags.insert("empty".to_string(), Vec::new());
+
+        let value = Example {
+            name: "example".to_string(),
+            values: vec![1, 2, 3],
+            nickname: Some("ex".to_string()),
+            missing: None,
+            scores,
+            tags,
+            boxed: vec![-1, 0, 1].into_boxed_slice(),
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(&value)
+            .expect("failed to archive value");
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Example>(buf.as_ref()) };
+
+        macro_rules! assert_json_eq {
+            ($($field:ident),*) => {
+                $(
+                    assert_eq!(
+                        serde_json::to_value(&archived.$field).unwrap(),
+                        serde_json::to_value(&value.$field).unwrap(),
+                    );
+                )*
+            };
+        }
+
+        assert_json_eq!(name, values, nickname, missing, scores, tags, boxed);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    // Only archived types without multibyte primitives implement serde::Serialize with non-native
+    // endianness
+    #[cfg(all(feature = "serde", any(feature = "archive_le", feature = "archive_be")))]
+    fn serde_serialize_archived_endian() {
+        use std::collections::BTreeMap;
+
+        #[derive(Archive, Serialize)]
+        struct Example {
+            name: String,
+            bytes: Vec<u8>,
+            flags: Vec<bool>,
+            nickname: Option<String>,
+            missing: Option<String>,
+            aliases: BTreeMap<String, String>,
+            tags: HashMap<String, Vec<String>>,
+        }
+
+        let mut aliases = BTreeMap::new();
+        aliases.insert("alice".to_string(), "al".to_string());
+        aliases.insert("bob".to_string(), "rob".to_string());
+        let mut tags = HashMap::new();
+        tags.insert(
+            "colors".to_string(),
+            vec!["red".to_string(), "blue".to_string()],
+        );
+        tags.insert("empty".to_string(), Vec::new());
+
+        let value = Example {
+            name: "example".to_string(),
+            bytes: vec![1, 2, 3],
+            flags: vec![true, false],
+            nickname: Some("ex".to_string()),
+            missing: None,
+            aliases,
+            tags,
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(&value)
+            .expect("failed to archive value");
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Example>(buf.as_ref()) };
+
+        macro_rules! assert_json_eq {
+            ($($field:ident),*) => {
+                $(
+                    assert_eq!(
+                        serde_json::to_value(&archived.$field).unwrap(),
+                        serde_json::to_value(&value.$field).unwrap(),
+                    );
+                )*
+            };
+        }
+
+        assert_json_eq!(name, bytes, flags, nickname, missing, aliases, tags);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    #[cfg(feature = "serde")]
+    fn serde_deserializer() {
+        use rkyv::de::serde::{from_archived, ArchiveDeserializer};
+        use std::collections::BTreeMap;
+
+        #[derive(Archive, Serialize)]
+        #[archive(serde)]
+        enum Shape {
+            Point,
+            Circle(f32),
+            Rect { width: u32, height: u32 },
+            Line(i16, i16),
+        }
+
+        #[derive(Archive, Serialize)]
+        #[archive(serde)]
+        struct Meters(u64);
+
+        #[derive(Archive, Serialize)]
+        #[archive(serde)]
+        struct Drawing {
+            name: String,
+            shapes: Vec<Shape>,
+            tags: BTreeMap<String, Option<u8>>,
+            size: Meters,
+            origin: (i32, i32),
+            result: Result<u32, String>,
+        }
+
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        enum ShapeView {
+            Point,
+            Circle(f32),
+            Rect { width: u32, height: u32 },
+            Line(i16, i16),
+        }
+
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct MetersView(u64);
+
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct DrawingView<'a> {
+            name: &'a str,
+            shapes: Vec<ShapeView>,
+            tags: BTreeMap<&'a str, Option<u8>>,
+            size: MetersView,
+            origin: (i32, i32),
+            result: Result<u32, String>,
+        }
+
+        let mut tags = BTreeMap::new();
+        tags.insert("color".to_string(), Some(3));
+        tags.insert("hidden".to_string(), None);
+
+        let value = Drawing {
+            name: "sketch".to_string(),
+            shapes: vec![
+                Shape::Point,
+                Shape::Circle(1.5),
+                Shape::Rect {
+                    width: 4,
+                    height: 2,
+                },
+                Shape::Line(-1, 1),
+            ],
+            tags,
+            size: Meters(100),
+            origin: (-5, 5),
+            result: Err("failed".to_string()),
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(&value)
+            .expect("failed to archive value");
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Drawing>(buf.as_ref()) };
+
+        let view: DrawingView = from_archived(archived).unwrap();
+        let mut expected_tags = BTreeMap::new();
+        expected_tags.insert("color", Some(3));
+        expected_tags.insert("hidden", None);
+        assert_eq!(
+            view,
+            DrawingView {
+                name: "sketch",
+                shapes: vec![
+                    ShapeView::Point,
+                    ShapeView::Circle(1.5),
+                    ShapeView::Rect {
+                        width: 4,
+                        height: 2,
+                    },
+                    ShapeView::Line(-1, 1),
+                ],
+                tags: expected_tags,
+                size: MetersView(100),
+                origin: (-5, 5),
+                result: Err("failed".to_string()),
+            }
+        );
+        // Strings are borrowed from the archive
+        assert_eq!(view.name.as_ptr(), archived.name.as_str().as_ptr());
+
+        // Transcode part of the archive to JSON
+        let json = <serde_json::Value as serde::Deserialize>::deserialize(ArchiveDeserializer::<
+            _,
+            serde_json::Error,
+        >::new(
+            &archived.tags
+        ))
+        .unwrap();
+        assert_eq!(json.to_string(), r#"{"color":3,"hidden":null}"#);
+    }
+
+    #[test]
+    #[cfg(feature = "arrow")]
+    fn archive_arrow_arrays() {
+        use arrow_array::{
+            types::{Float64Type, Int32Type},
+            Array, PrimitiveArray,
+        };
+        use arrow_buffer::ScalarBuffer;
+        use rkyv::{arrow::scalar_buffer, with::ArrowBuffer};
+        use std::{panic::AssertUnwindSafe, sync::Arc};
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Batch {
+            name: String,
+            ids: PrimitiveArray<Int32Type>,
+            scores: PrimitiveArray<Float64Type>,
+            offsets: ScalarBuffer<u64>,
+        }
+
+        let ids = (0..20)
+            .map(|i| if i % 3 == 0 { None } else { Some(i) })
+            .collect::<PrimitiveArray<Int32Type>>();
+        let value = Batch {
+            name: "a batch".to_string(),
+            // Slicing moves the validity bitmap off of a byte boundary
+            ids: ids.slice(3, 13),
+            scores: (0..7).map(|i| i as f64 / 2.0).collect(),
+            offsets: ScalarBuffer::from(vec![0u64, 4, 8, 15]),
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = Arc::new(serializer.into_serializer().into_inner());
+        let archived = unsafe { archived_root::<Batch>(&buf) };
+
+        let start = buf.as_ptr() as usize;
+        for ptr in [
+            archived.ids.values().as_ptr() as usize,
+            archived.ids.null_bitmap().unwrap().as_ptr() as usize,
+            archived.scores.values().as_ptr() as usize,
+            archived.offsets.as_ptr() as usize,
+        ] {
+            assert_eq!((ptr - start) % ArrowBuffer::ALIGNMENT, 0);
+        }
+        assert!(archived.scores.null_bitmap().is_none());
+        for i in 0..13 {
+            assert_eq!(archived.ids.is_null(i), value.ids.is_null(i));
+            if value.ids.is_valid(i) {
+                assert_eq!(archived.ids.get(i), Some(&value.ids.value(i)));
+            }
+        }
+        assert_eq!(archived.ids.get(13), None);
+
+        // The views share their values and validity bitmaps with the archive
+        let ids = archived.ids.to_array::<Int32Type>(&buf);
+        assert_eq!(ids, value.ids);
+        assert_eq!(ids.values().as_ptr(), archived.ids.values().as_ptr());
+        assert_eq!(
+            ids.nulls().unwrap().buffer().as_ptr(),
+            archived.ids.null_bitmap().unwrap().as_ptr()
+        );
+        assert_eq!(archived.scores.to_array::<Float64Type>(&buf), value.scores);
+        let offsets = scalar_buffer(&buf, &archived.offsets);
+        assert_eq!(offsets, value.offsets);
+        assert_eq!(offsets.as_ptr(), archived.offsets.as_ptr());
+
+        // Buffers can't be shared from a different archive
+        let other = Arc::new(rkyv::AlignedVec::new());
+        assert!(std::panic::catch_unwind(AssertUnwindSafe(|| {
+            scalar_buffer(&other, &archived.offsets)
+        }))
+        .is_err());
+
+        let deserialized: Batch = archived.deserialize(&mut rkyv::Infallible).unwrap();
+        assert_eq!(deserialized, value);
+
+        // The views keep the archive alive
+        drop(buf);
+        assert_eq!(ids, value.ids);
+        assert_eq!(offsets, value.offsets);
+    }
+
+    #[test]
+    #[cfg(feature = "arrow")]
+    fn archive_arrow_empty_arrays() {
+        use arrow_array::{types::Int64Type, Array, PrimitiveArray};
+        use arrow_buffer::ScalarBuffer;
+        use rkyv::arrow::scalar_buffer;
+        use std::sync::Arc;
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Empty {
+            values: PrimitiveArray<Int64Type>,
+            nullable: PrimitiveArray<Int64Type>,
+            offsets: ScalarBuffer<u64>,
+        }
+
+        let value = Empty {
+            values: PrimitiveArray::from(Vec::<i64>::new()),
+            nullable: PrimitiveArray::from(vec![None, Some(1i64)]).slice(0, 0),
+            offsets: ScalarBuffer::from(Vec::<u64>::new()),
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = Arc::new(serializer.into_serializer().into_inner());
+        let archived = unsafe { archived_root::<Empty>(&buf) };
+
+        let values = archived.values.to_array::<Int64Type>(&buf);
+        assert!(values.is_empty());
+        assert_eq!(values, value.values);
+        let nullable = archived.nullable.to_array::<Int64Type>(&buf);
+        assert!(nullable.is_empty());
+        assert_eq!(nullable, value.nullable);
+        assert_eq!(scalar_buffer(&buf, &archived.offsets), value.offsets);
+
+        let deserialized: Empty = archived.deserialize(&mut rkyv::Infallible).unwrap();
+        assert_eq!(deserialized, value);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    #[cfg(feature = "serde")]
+    fn with_as_serde() {
+        use rkyv::{
+            ser::serializers::AlignedSerializer,
+            with::{AsSerde, SerdeFormat},
+            AlignedVec, Fallible,
+        };
+        use std::collections::BTreeMap;
+
+        #[derive(Debug)]
+        enum TestError {
+            Serializer,
+            Format,
+        }
+
+        impl From<serde_json::Error> for TestError {
+            fn from(_: serde_json::Error) -> Self {
+                TestError::Format
+            }
+        }
+
+        #[cfg(feature = "ciborium")]
+        impl From<rkyv::with::CborError> for TestError {
+            fn from(_: rkyv::with::CborError) -> Self {
+                TestError::Format
+            }
+        }
+
+        #[derive(Default)]
+        struct TestSerializer(AlignedSerializer<AlignedVec>);
+
+        impl Fallible for TestSerializer {
+            type Error = TestError;
+        }
+
+        impl Serializer for TestSerializer {
+            fn pos(&self) -> usize {
+                self.0.pos()
+            }
+
+            fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+                self.0.write(bytes).map_err(|_| TestError::Serializer)
+            }
+        }
+
+        struct TestDeserializer;
+
+        impl Fallible for TestDeserializer {
+            type Error = TestError;
+        }
+
+        struct Json;
+
+        impl SerdeFormat for Json {
+            type Error = serde_json::Error;
+
+            fn to_bytes<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, Self::Error> {
+                serde_json::to_vec(value)
+            }
+
+            fn from_bytes<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T, Self::Error> {
+                serde_json::from_slice(bytes)
+            }
+        }
+
+        // A third-party type that only implements serde's traits
+        #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+        struct Foreign {
+            name: String,
+            weights: Vec<f32>,
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Example {
+            id: u32,
+            #[with(AsSerde<Json>)]
+            foreign: Foreign,
+        }
+
+        let value = Example {
+            id: 42,
+            foreign: Foreign {
+                name: "foreign".to_string(),
+                weights: vec![0.5, 1.5, 2.5],
+            },
+        };
+        let mut serializer = TestSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.0.into_inner();
+        let archived = unsafe { archived_root::<Example>(buf.as_ref()) };
+
+        assert_eq!(archived.id, 42);
+        assert_eq!(
+            archived.foreign.as_slice(),
+            serde_json::to_vec(&value.foreign).unwrap().as_slice(),
+        );
+        let foreign: Foreign = AsSerde::<Json>::decode(&archived.foreign).unwrap();
+        assert_eq!(foreign, value.foreign);
+
+        let deserialized: Example = archived.deserialize(&mut TestDeserializer).unwrap();
+        assert_eq!(deserialized, value);
+
+        // Encoding errors are passed through to the serializer
+        #[derive(Archive, Serialize)]
+        struct Unencodable {
+            #[with(AsSerde<Json>)]
+            map: BTreeMap<Vec<u8>, u8>,
+        }
+
+        let mut map = BTreeMap::new();
+        map.insert(vec![1, 2, 3], 4);
+        let mut serializer = TestSerializer::default();
+        assert!(matches!(
+            serializer.serialize_value(&Unencodable { map }),
+            Err(TestError::Format),
+        ));
+
+        #[cfg(feature = "ciborium")]
+        {
+            use rkyv::with::Cbor;
+
+            #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+            struct CborExample {
+                #[with(AsSerde<Cbor>)]
+                foreign: Foreign,
+            }
+
+            let value = CborExample {
+                foreign: value.foreign,
+            };
+            let mut serializer = TestSerializer::default();
+            serializer.serialize_value(&value).unwrap();
+            let buf = serializer.0.into_inner();
+            let archived = unsafe { archived_root::<CborExample>(buf.as_ref()) };
+
+            let mut expected = Vec::new();
+            ciborium::ser::into_writer(&value.foreign, &mut expected).unwrap();
+            assert_eq!(archived.foreign.as_slice(), expected.as_slice());
+            let deserialized: CborExample = archived.deserialize(&mut TestDeserializer).unwrap();
+            assert_eq!(deserialized, value);
+        }
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn with_bi_map() {
+        use core::{alloc::Layout, ptr::NonNull};
+        use rkyv::{
+            ser::{
+                serializers::{AlignedSerializer, AllocScratch},
+                ScratchSpace,
+            },
+            to_archived,
+            with::{BiMap, BiMapValueError},
+            AlignedVec, Archived, Fallible, Infallible,
+        };
+
+        #[derive(Debug)]
+        enum TestError {
+            Serializer,
+            DuplicateValue,
+        }
+
+        impl From<BiMapValueError> for TestError {
+            fn from(_: BiMapValueError) -> Self {
+                TestError::DuplicateValue
+            }
+        }
+
+        #[derive(Default)]
+        struct TestSerializer {
+            inner: AlignedSerializer<AlignedVec>,
+            scratch: AllocScratch,
+        }
+
+        impl Fallible for TestSerializer {
+            type Error = TestError;
+        }
+
+        impl Serializer for TestSerializer {
+            fn pos(&self) -> usize {
+                self.inner.pos()
+            }
+
+            fn write(&mut self, bytes: &[u8]) -> Result<(), Self::Error> {
+                self.inner.write(bytes).map_err(|_| TestError::Serializer)
+            }
+        }
+
+        impl ScratchSpace for TestSerializer {
+            unsafe fn push_scratch(
+                &mut self,
+                layout: Layout,
+            ) -> Result<NonNull<[u8]>, Self::Error> {
+                self.scratch
+                    .push_scratch(layout)
+                    .map_err(|_| TestError::Serializer)
+            }
+
+            unsafe fn pop_scratch(
+                &mut self,
+                ptr: NonNull<u8>,
+                layout: Layout,
+            ) -> Result<(), Self::Error> {
+                self.scratch
+                    .pop_scratch(ptr, layout)
+                    .map_err(|_| TestError::Serializer)
+            }
+        }
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[cfg_attr(feature = "validation", archive_attr(derive(bytecheck::CheckBytes)))]
+        struct Users {
+            #[with(BiMap)]
+            ids: HashMap<String, u32>,
+        }
+
+        let mut ids = HashMap::new();
+        ids.insert("alice".to_string(), 1);
+        ids.insert("bob".to_string(), 2);
+        ids.insert("carol".to_string(), 30);
+        let value = Users { ids };
+
+        let mut serializer = TestSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.inner.into_inner();
+        let archived = unsafe { archived_root::<Users>(buf.as_ref()) };
+
+        assert_eq!(archived.ids.len(), 3);
+        for (name, id) in value.ids.iter() {
+            assert_eq!(*archived.ids.get_by_left(name.as_str()).unwrap(), *id);
+            let archived_id: Archived<u32> = to_archived!(*id);
+            assert_eq!(archived.ids.get_by_right(&archived_id).unwrap(), name);
+        }
+        assert!(archived.ids.get_by_left("dave").is_none());
+        assert!(!archived.ids.contains_right(&to_archived!(3u32)));
+        assert_eq!(archived.ids.iter().len(), 3);
+        assert_eq!(archived.ids.left_values().count(), 3);
+
+        #[cfg(feature = "validation")]
+        rkyv::check_archived_root::<Users>(buf.as_ref()).unwrap();
+
+        let deserialized: Users = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
+
+        // Values have to be unique too
+        let mut ids = HashMap::new();
+        ids.insert("alice".to_string(), 1);
+        ids.insert("bob".to_string(), 1);
+        let mut serializer = TestSerializer::default();
+        assert!(matches!(
+            serializer.serialize_value(&Users { ids }),
+            Err(TestError::DuplicateValue),
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "rayon")]
+    fn with_parallel_index() {
+        use rkyv::{with::ParallelIndex, Infallible};
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        struct Dictionary {
+            #[with(ParallelIndex)]
+            words: HashMap<String, u32>,
+            #[with(ParallelIndex)]
+            stop_words: HashSet<String>,
+        }
+
+        let value = Dictionary {
+            words: (0..10_000).map(|i| (i.to_string(), i)).collect(),
+            stop_words: (0..100).map(|i| format!("stop {}", i)).collect(),
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Dictionary>(buf.as_ref()) };
+
+        assert_eq!(archived.words.len(), value.words.len());
+        for (word, count) in value.words.iter() {
+            assert_eq!(archived.words[word.as_str()], *count);
+        }
+        assert!(!archived.words.contains_key("missing"));
+        for word in value.stop_words.iter() {
+            assert!(archived.stop_words.contains(word.as_str()));
+        }
+
+        let deserialized: Dictionary = archived.deserialize(&mut Infallible).unwrap();
+        assert_eq!(deserialized, value);
+    }
+
+    #[test]
+    #[cfg(all(feature = "profile", feature = "validation"))]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn profile_hook() {
+        use bytecheck::CheckBytes;
+        use rkyv::profile::{self, Event, Operation};
+        use std::sync::Mutex;
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive_attr(derive(CheckBytes))]
+        struct NestedProfiled(u32);
+
+        #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+        #[archive_attr(derive(CheckBytes))]
+        struct Profiled {
+            name: String,
+            // Boxed values are serialized with `serialize_value` but aren't reported on their own
+            nested: Box<NestedProfiled>,
+        }
+
+        static EVENTS: Mutex<Vec<(Operation, usize, bool)>> = Mutex::new(Vec::new());
+
+        fn record(event: &Event) {
+            if event.type_name().ends_with("Profiled") {
+                EVENTS
+                    .lock()
+                    .unwrap()
+                    .push((event.operation(), event.len(), event.success()));
+            }
+        }
+
+        let value = Profiled {
+            name: "a name longer than the inline capacity".to_string(),
+            nested: Box::new(NestedProfiled(42)),
+        };
+
+        profile::set_hook(record);
+        let bytes = rkyv::to_bytes::<_, 256>(&value).unwrap();
+        let deserialized = rkyv::from_bytes::<Profiled>(&bytes).unwrap();
+        let truncated = rkyv::check_archived_root::<Profiled>(&bytes[..4]);
+        // Serializers report events without going through `to_bytes`
+        let mut serializer = DefaultSerializer::default();
+        serializer.pad(3).unwrap();
+        serializer.serialize_value(&value).unwrap();
+        let written = serializer.pos() - 3;
+        assert!(profile::take_hook().is_some());
+        rkyv::to_bytes::<_, 256>(&value).unwrap();
+
+        assert_eq!(deserialized, value);
+        assert!(truncated.is_err());
+        assert_eq!(
+            *EVENTS.lock().unwrap(),
+            [
+                (Operation::Serialize, bytes.len(), true),
+                (Operation::Validate, bytes.len(), true),
+                (Operation::Deserialize, bytes.len(), true),
+                (Operation::Validate, 0, false),
+                (Operation::Serialize, written, true),
+            ],
+        );
+    }
 }
@@ -5,6 +5,7 @@ mod tests {
     use alloc::{
         boxed::Box,
         collections::{BTreeMap, BTreeSet},
+        format,
         rc::Rc,
         string::{String, ToString},
         vec,
@@ -338,6 +339,27 @@ mod tests {
         ));
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    #[cfg(not(feature = "strict"))]
+    fn check_tuples() {
+        // bytecheck validates tuples with up to 12 elements
+        serialize_and_check(&(
+            1u8,
+            2u16,
+            3u32,
+            4u64,
+            true,
+            'a',
+            "b".to_string(),
+            Some(8u32),
+            9i8,
+            10i16,
+            11i32,
+            vec![12u32],
+        ));
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn derive_enum() {
@@ -523,6 +545,80 @@ mod tests {
         assert_eq!(buf.as_ref(), &ARCHIVED_BYTES);
     }
 
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_inline_vec() {
+        use rkyv::with::InlineVec;
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(CheckBytes))]
+        struct Test {
+            #[with(InlineVec<8>)]
+            chars: Vec<char>,
+            #[with(InlineVec<16>)]
+            strings: Vec<String>,
+        }
+
+        for len in 0..4 {
+            serialize_and_check(&Test {
+                chars: "abc".chars().take(len).collect(),
+                strings: (0..len).map(|i| i.to_string().repeat(20)).collect(),
+            });
+        }
+
+        // An inline char that is out of range
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(&Test {
+                chars: vec!['a'],
+                strings: Vec::new(),
+            })
+            .unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        let pos = buf
+            .windows(4)
+            .position(|w| w == [b'a', 0, 0, 0] || w == [0, 0, 0, b'a'])
+            .unwrap();
+        buf[pos..pos + 4].copy_from_slice(&[0xff; 4]);
+        assert!(check_archived_root::<Test>(buf.as_ref()).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_varint_offsets() {
+        use rkyv::with::VarintOffsets;
+
+        #[allow(clippy::vec_box)]
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(CheckBytes))]
+        struct Test {
+            #[with(VarintOffsets)]
+            strings: Vec<Box<String>>,
+        }
+
+        serialize_and_check(&Test {
+            strings: Vec::new(),
+        });
+
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(&Test {
+                strings: (0..10)
+                    .map(|i| Box::new(i.to_string().repeat(20)))
+                    .collect(),
+            })
+            .unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        check_archived_root::<Test>(buf.as_ref()).unwrap();
+
+        // Point the last string at the one before it
+        let archived = unsafe { rkyv::archived_root::<Test>(buf.as_ref()) };
+        let offsets_pos = archived.strings.offsets().as_ptr() as usize - buf.as_ptr() as usize;
+        let last = offsets_pos + archived.strings.offsets().len() - 1;
+        buf[last] = 0;
+        assert!(check_archived_root::<Test>(buf.as_ref()).is_err());
+    }
+
     #[test]
     #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
     fn check_valid_durations() {
@@ -555,4 +651,683 @@ mod tests {
             )
         ));
     }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_duplicate_hash_index_entry() {
+        use core::mem::size_of;
+        use rkyv::{
+            collections::hash_index::{
+                validation::HashIndexError, ArchivedHashIndex, HashIndexResolver,
+            },
+            ser::ScratchSpace,
+            validation::CheckArchiveError,
+            Archived, ScratchVec,
+        };
+
+        struct Keys([u32; 2]);
+
+        impl Archive for Keys {
+            type Archived = ArchivedHashIndex;
+            type Resolver = HashIndexResolver;
+
+            unsafe fn resolve(
+                &self,
+                pos: usize,
+                resolver: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                ArchivedHashIndex::resolve_from_len(self.0.len(), pos, resolver, out);
+            }
+        }
+
+        impl<S: Serializer + ScratchSpace + ?Sized> Serialize<S> for Keys {
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                unsafe {
+                    let mut entries = ScratchVec::new(serializer, self.0.len())?;
+                    entries.set_len(self.0.len());
+                    let resolver = ArchivedHashIndex::build_and_serialize(
+                        self.0.iter().map(|key| (key, &())),
+                        serializer,
+                        &mut entries,
+                    )?;
+                    entries.free(serializer)?;
+                    Ok(resolver)
+                }
+            }
+        }
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&Keys([1, 2])).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        check_archived_root::<Keys>(buf.as_ref()).unwrap();
+
+        // The single group of 16 slots is written first, so point both keys at the same entry
+        let index_size = size_of::<Archived<usize>>();
+        for slot in 0..16 {
+            if buf[slot] != 0xff {
+                let start = 16 + slot * index_size;
+                buf[start..start + index_size].fill(0);
+            }
+        }
+
+        let e = check_archived_root::<Keys>(buf.as_ref()).unwrap_err();
+        assert!(matches!(
+            e,
+            CheckArchiveError::CheckBytesError(HashIndexError::DuplicateEntryIndex {
+                value: 0,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn frame_decoder_checked() {
+        use rkyv::util::{encode_frame, FrameDecoder, FrameError};
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&"valid".to_string()).unwrap();
+        let valid = serializer.into_serializer().into_inner();
+
+        let mut stream = Vec::new();
+        encode_frame(&valid, &mut stream).unwrap();
+        // A string pointing out of bounds
+        encode_frame(&[0, 0, 0, 0, 0xf0, 0xff, 0xff, 0xff], &mut stream).unwrap();
+        encode_frame(&valid, &mut stream).unwrap();
+
+        let mut decoder = FrameDecoder::new();
+        decoder.extend_from_slice(&stream);
+        let frame = decoder.decode_checked::<String>().unwrap().unwrap();
+        assert_eq!(frame.as_slice(), valid.as_slice());
+        assert!(matches!(
+            decoder.decode_checked::<String>(),
+            Err(FrameError::InvalidArchive(_)),
+        ));
+        let frame = decoder.decode_checked::<String>().unwrap().unwrap();
+        assert_eq!(frame.as_slice(), valid.as_slice());
+        assert!(decoder.decode_checked::<String>().unwrap().is_none());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archived_extents() {
+        use rkyv::{archived_root, validation::extent::archived_extents};
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(CheckBytes))]
+        struct Test {
+            id: u32,
+            names: Vec<String>,
+            shared: Rc<String>,
+            also_shared: Rc<String>,
+            tags: Vec<String>,
+        }
+
+        let shared = Rc::new("a shared string that is stored out of line".to_string());
+        let value = Test {
+            id: 42,
+            names: (0..4)
+                .map(|i| format!("a long name number {}", i))
+                .collect(),
+            shared: shared.clone(),
+            also_shared: shared,
+            tags: vec!["a tag that is stored out of line".to_string()],
+        };
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        let archived = unsafe { archived_root::<Test>(&buf) };
+
+        // The whole archive
+        let extents = archived_extents(&buf, archived).unwrap();
+        assert_eq!(extents.root().end, buf.len());
+        assert_eq!(extents.span().end, buf.len());
+
+        // A nested value and its out-of-line data
+        let names = archived_extents(&buf, &archived.names).unwrap();
+        let tags = archived_extents(&buf, &archived.tags).unwrap();
+        assert_eq!(names.root().len(), core::mem::size_of_val(&archived.names));
+        assert!(names.total_len() > 4 * "a long name number 0".len());
+        assert!(!names.is_contiguous());
+        for name_range in names.ranges() {
+            assert!(extents
+                .ranges()
+                .iter()
+                .any(|range| range.start <= name_range.start && name_range.end <= range.end));
+            for tag_range in tags.ranges() {
+                assert!(name_range.end <= tag_range.start || tag_range.end <= name_range.start);
+            }
+        }
+
+        let names_buf = names.to_archive();
+        let copied = check_archived_root::<Vec<String>>(&names_buf).unwrap();
+        assert_eq!(copied.as_slice(), archived.names.as_slice());
+
+        // Shared data is owned by every pointer to it
+        let shared = archived_extents(&buf, &archived.shared).unwrap();
+        let also_shared = archived_extents(&buf, &archived.also_shared).unwrap();
+        assert_eq!(shared.total_len(), also_shared.total_len());
+        assert_eq!(shared.ranges()[0], also_shared.ranges()[0]);
+
+        // Values with no out-of-line data are just their own bytes
+        let id = archived_extents(&buf, &archived.id).unwrap();
+        assert!(id.is_contiguous());
+        assert_eq!(id.ranges(), &[id.root()]);
+
+        // Values outside of the buffer are rejected
+        let other = 42u32;
+        assert!(archived_extents(&buf, &other).is_err());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_loader_checked() {
+        use rkyv::util::{ArchiveLoader, LoadError};
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&"valid".to_string()).unwrap();
+        let valid = serializer.into_serializer().into_inner();
+
+        let mut loader = ArchiveLoader::new(valid.len());
+        loader.extend_from_slice(&valid);
+        let buf = loader.finish_checked::<String>().unwrap();
+        assert_eq!(buf.as_slice(), valid.as_slice());
+
+        // A string pointing out of bounds
+        let mut loader = ArchiveLoader::new(8);
+        loader.extend_from_slice(&[0, 0, 0, 0, 0xf0, 0xff, 0xff, 0xff]);
+        assert!(matches!(
+            loader.finish_checked::<String>(),
+            Err(LoadError::InvalidArchive(_)),
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn archive_loader_check_received() {
+        use rkyv::util::{ArchiveLoader, LoadError};
+
+        // A stream of records followed by a root that lists them
+        let records = ["first record", "second record", "third record"];
+        let mut serializer = DefaultSerializer::default();
+        let positions = records
+            .iter()
+            .map(|record| serializer.serialize_value(&record.to_string()).unwrap())
+            .collect::<Vec<_>>();
+        serializer
+            .serialize_value(&records.iter().map(|r| r.to_string()).collect::<Vec<_>>())
+            .unwrap();
+        let bytes = serializer.into_serializer().into_inner();
+
+        let mut loader = ArchiveLoader::new(bytes.len());
+        let mut checked = 0;
+        for chunk in bytes.chunks(7) {
+            loader.extend_from_slice(chunk);
+            while checked < positions.len() {
+                match loader.check_received::<String>(positions[checked]).unwrap() {
+                    Some(archived) => {
+                        assert_eq!(archived, records[checked]);
+                        checked += 1;
+                    }
+                    None => break,
+                }
+            }
+            if checked < positions.len() {
+                assert!(!loader.is_complete());
+            }
+        }
+        assert_eq!(checked, positions.len());
+        let buf = loader.finish_checked::<Vec<String>>().unwrap();
+        assert_eq!(buf.as_slice(), bytes.as_slice());
+
+        // A record with invalid UTF-8
+        let mut corrupted = bytes.to_vec();
+        let text = corrupted
+            .windows(records[1].len())
+            .position(|w| w == records[1].as_bytes())
+            .unwrap();
+        corrupted[text] = 0xff;
+        let mut loader = ArchiveLoader::new(corrupted.len());
+        loader.extend_from_slice(&corrupted[..positions[1]]);
+        assert!(matches!(loader.check_received::<String>(positions[1]), Ok(None)));
+        loader.extend_from_slice(&corrupted[positions[1]..]);
+        assert!(loader.check_received::<String>(positions[0]).unwrap().is_some());
+        assert!(matches!(
+            loader.check_received::<String>(positions[1]),
+            Err(LoadError::InvalidArchive(_)),
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_interned_strings() {
+        use rkyv::with::Intern;
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(CheckBytes))]
+        struct Pair {
+            #[with(Intern)]
+            a: String,
+            #[with(Intern)]
+            b: String,
+        }
+
+        let value = Pair {
+            a: "an interned string".to_string(),
+            b: "an interned string".to_string(),
+        };
+        serialize_and_check(&value);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_deduped_slices() {
+        use rkyv::with::Dedup;
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(CheckBytes))]
+        struct Revision {
+            #[with(Dedup)]
+            content: Vec<u8>,
+            #[with(Dedup)]
+            checksums: Box<[u32]>,
+        }
+
+        let content = b"the same content in every revision".to_vec();
+        let value = vec![
+            Revision {
+                content: content.clone(),
+                checksums: vec![1, 2, 3].into_boxed_slice(),
+            },
+            Revision {
+                content: content.clone(),
+                checksums: vec![1, 2, 3].into_boxed_slice(),
+            },
+            Revision {
+                content: Vec::new(),
+                checksums: Box::new([]),
+            },
+            Revision {
+                content,
+                checksums: Box::new([]),
+            },
+        ];
+        serialize_and_check(&value);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_compressed_vecs() {
+        use rkyv::with::{RleVec, SparseVec};
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(CheckBytes))]
+        struct Compressed {
+            #[with(RleVec)]
+            runs: Vec<String>,
+            #[with(SparseVec)]
+            sparse: Vec<u32>,
+        }
+
+        let value = Compressed {
+            runs: vec!["a".to_string(), "a".to_string(), "b".to_string()],
+            sparse: vec![0, 0, 0, 4, 0],
+        };
+        serialize_and_check(&value);
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_invalid_compressed_vecs() {
+        use rkyv::{
+            ser::ScratchSpace,
+            validation::CheckArchiveError,
+            vec::{
+                ArchivedRleVec, ArchivedSparseVec, RleVecError, RleVecResolver, SparseVecError,
+                SparseVecResolver,
+            },
+            Archived,
+        };
+
+        struct Runs(Vec<u32>);
+
+        impl Archive for Runs {
+            type Archived = ArchivedRleVec<Archived<u32>>;
+            type Resolver = RleVecResolver;
+
+            unsafe fn resolve(
+                &self,
+                pos: usize,
+                resolver: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                ArchivedRleVec::resolve_from_slice(&self.0, pos, resolver, out);
+            }
+        }
+
+        impl<S: Serializer + ScratchSpace + ?Sized> Serialize<S> for Runs {
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                ArchivedRleVec::serialize_from_slice(&self.0, serializer)
+            }
+        }
+
+        struct Sparse(Vec<u32>);
+
+        impl Archive for Sparse {
+            type Archived = ArchivedSparseVec<Archived<u32>>;
+            type Resolver = SparseVecResolver<()>;
+
+            unsafe fn resolve(
+                &self,
+                pos: usize,
+                resolver: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                ArchivedSparseVec::resolve_from_slice(&self.0, &0, pos, resolver, out);
+            }
+        }
+
+        impl<S: Serializer + ScratchSpace + ?Sized> Serialize<S> for Sparse {
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                ArchivedSparseVec::serialize_from_slice(&self.0, &0, serializer)
+            }
+        }
+
+        // Overwrites the archived positions `from` in `buf` with `to`
+        fn replace_positions(buf: &mut [u8], from: [usize; 2], to: [usize; 2]) {
+            let from = rkyv::to_bytes::<_, 256>(&from).unwrap();
+            let to = rkyv::to_bytes::<_, 256>(&to).unwrap();
+            let start = buf
+                .windows(from.len())
+                .position(|w| w == from.as_slice())
+                .unwrap();
+            buf[start..start + to.len()].copy_from_slice(&to);
+        }
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&Runs(vec![7, 7, 7, 9])).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+        replace_positions(&mut buf, [3, 4], [3, 3]);
+        let e = check_archived_root::<Runs>(buf.as_ref()).unwrap_err();
+        assert!(matches!(
+            e,
+            CheckArchiveError::CheckBytesError(RleVecError::InvalidRunEnd {
+                run: 1,
+                start: 3,
+                end: 3,
+            })
+        ));
+
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(&Sparse(vec![0, 0, 5, 0, 6]))
+            .unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        let mut unsorted = buf.clone();
+        replace_positions(&mut unsorted, [2, 4], [4, 2]);
+        let e = check_archived_root::<Sparse>(unsorted.as_ref()).unwrap_err();
+        assert!(matches!(
+            e,
+            CheckArchiveError::CheckBytesError(SparseVecError::InvalidIndex {
+                index: 2,
+                next: 5,
+                len: 5,
+            })
+        ));
+
+        let mut out_of_bounds = buf;
+        replace_positions(&mut out_of_bounds, [2, 4], [2, 5]);
+        let e = check_archived_root::<Sparse>(out_of_bounds.as_ref()).unwrap_err();
+        assert!(matches!(
+            e,
+            CheckArchiveError::CheckBytesError(SparseVecError::InvalidIndex {
+                index: 5,
+                next: 3,
+                len: 5,
+            })
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_invalid_multi_map_range() {
+        use rkyv::{
+            collections::multi_map::{
+                validation::MultiMapError, ArchivedMultiMap, MultiMapResolver,
+            },
+            ser::ScratchSpace,
+            validation::CheckArchiveError,
+            Archived,
+        };
+
+        // Archives fewer values than the ranges of its keys refer to
+        struct Truncated(BTreeMap<u32, Vec<u32>>);
+
+        impl Archive for Truncated {
+            type Archived = ArchivedMultiMap<Archived<u32>, Archived<u32>>;
+            type Resolver = MultiMapResolver;
+
+            unsafe fn resolve(
+                &self,
+                pos: usize,
+                resolver: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                ArchivedMultiMap::resolve_from_len(self.0.len(), 1, pos, resolver, out);
+            }
+        }
+
+        impl<S: Serializer + ScratchSpace + ?Sized> Serialize<S> for Truncated {
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                unsafe {
+                    ArchivedMultiMap::serialize_from_iter(
+                        self.0.iter().map(|(k, v)| (k, v.as_slice())),
+                        serializer,
+                    )
+                }
+            }
+        }
+
+        let mut map = BTreeMap::new();
+        map.insert(1, vec![10, 11, 12]);
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&Truncated(map)).unwrap();
+        let buf = serializer.into_serializer().into_inner();
+
+        let e = check_archived_root::<Truncated>(buf.as_ref()).unwrap_err();
+        assert!(matches!(
+            e,
+            CheckArchiveError::CheckBytesError(MultiMapError::InvalidRange {
+                start: 0,
+                end: 3,
+                len: 1,
+            })
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_invalid_range_map() {
+        use core::ops::Range;
+        use rkyv::{
+            collections::range_map::{
+                validation::ArchivedRangeMapError, ArchivedRangeMap, RangeMapResolver,
+            },
+            ser::ScratchSpace,
+            validation::CheckArchiveError,
+            Archived,
+        };
+
+        // Archives its ranges in the given order without sorting or checking them
+        struct Unchecked(Vec<(Range<u32>, u32)>);
+
+        impl Archive for Unchecked {
+            type Archived = ArchivedRangeMap<Archived<u32>, Archived<u32>>;
+            type Resolver = RangeMapResolver;
+
+            unsafe fn resolve(
+                &self,
+                pos: usize,
+                resolver: Self::Resolver,
+                out: *mut Self::Archived,
+            ) {
+                ArchivedRangeMap::resolve_from_len(self.0.len(), pos, resolver, out);
+            }
+        }
+
+        impl<S: Serializer + ScratchSpace + ?Sized> Serialize<S> for Unchecked {
+            fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+                unsafe {
+                    ArchivedRangeMap::<Archived<u32>, Archived<u32>>::serialize_from_iter(
+                        self.0.iter().map(|(range, value)| (range, value)),
+                        serializer,
+                    )
+                }
+            }
+        }
+
+        fn serialize(ranges: Vec<(Range<u32>, u32)>) -> rkyv::AlignedVec {
+            let mut serializer = DefaultSerializer::default();
+            serializer.serialize_value(&Unchecked(ranges)).unwrap();
+            serializer.into_serializer().into_inner()
+        }
+
+        let buf = serialize(vec![(0..10, 0), (10..10, 1), (10..20, 2)]);
+        check_archived_root::<Unchecked>(buf.as_ref()).unwrap();
+
+        let buf = serialize(vec![(10..20, 0), (0..10, 1)]);
+        assert!(matches!(
+            check_archived_root::<Unchecked>(buf.as_ref()),
+            Err(CheckArchiveError::CheckBytesError(
+                ArchivedRangeMapError::UnsortedRanges { index: 1 }
+            ))
+        ));
+
+        let buf = serialize(vec![(0..10, 0), (5..15, 1)]);
+        assert!(matches!(
+            check_archived_root::<Unchecked>(buf.as_ref()),
+            Err(CheckArchiveError::CheckBytesError(
+                ArchivedRangeMapError::OverlappingRanges { index: 1 }
+            ))
+        ));
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_multi_map() {
+        use rkyv::with::MultiMap;
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(CheckBytes))]
+        struct InvertedIndex {
+            #[with(MultiMap)]
+            postings: BTreeMap<String, Vec<String>>,
+        }
+
+        let mut postings = BTreeMap::new();
+        postings.insert(
+            "archive".to_string(),
+            vec!["first".to_string(), "second".to_string()],
+        );
+        postings.insert("empty".to_string(), Vec::new());
+        postings.insert("zero".to_string(), vec!["third".to_string()]);
+        serialize_and_check(&InvertedIndex { postings });
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn check_niche() {
+        use core::num::{NonZeroU32, NonZeroU64};
+        use rkyv::with::Niche;
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(CheckBytes))]
+        struct Test {
+            #[with(Niche)]
+            boxed: Option<Box<str>>,
+            #[with(Niche)]
+            slice: Option<Box<[u32]>>,
+            #[with(Niche)]
+            id: Option<NonZeroU64>,
+            #[with(Niche)]
+            count: Option<NonZeroU32>,
+        }
+
+        serialize_and_check(&Test {
+            boxed: Some("hello world".into()),
+            slice: Some(vec![1, 2, 3].into_boxed_slice()),
+            id: NonZeroU64::new(42),
+            count: None,
+        });
+        serialize_and_check(&Test {
+            boxed: None,
+            slice: None,
+            id: None,
+            count: NonZeroU32::new(7),
+        });
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn chunked_validator() {
+        use bytecheck::SliceCheckError;
+        use rkyv::validation::{
+            chunked::ChunkedValidator, owned::OwnedPointerError, CheckArchiveError,
+        };
+
+        let value = (0..100)
+            .map(|i| format!("element number {}", i))
+            .collect::<Vec<_>>();
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&value).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+
+        let mut validator = ChunkedValidator::<String, _>::new(&buf).unwrap();
+        assert_eq!(validator.len(), 100);
+        let mut chunks = 1;
+        while !validator.check_next(7).unwrap() {
+            assert_eq!(validator.checked(), 7 * chunks);
+            chunks += 1;
+        }
+        assert_eq!(chunks, 15);
+        assert_eq!(validator.checked(), 100);
+        let archived = validator.finish().unwrap();
+        assert_eq!(archived, check_archived_root::<Vec<String>>(&buf).unwrap());
+
+        // Unfinished elements are validated by finish
+        let archived = ChunkedValidator::<String, _>::new(&buf)
+            .unwrap()
+            .finish()
+            .unwrap();
+        assert_eq!(archived.as_slice(), value.as_slice());
+
+        // Break the UTF-8 of one of the strings
+        let pos = buf
+            .as_slice()
+            .windows(17)
+            .position(|w| w == b"element number 42")
+            .unwrap();
+        buf.as_mut_slice()[pos] = 0xff;
+        assert!(check_archived_root::<Vec<String>>(&buf).is_err());
+
+        let mut validator = ChunkedValidator::<String, _>::new(&buf).unwrap();
+        assert!(!validator.check_next(40).unwrap());
+        assert!(matches!(
+            validator.check_next(40),
+            Err(CheckArchiveError::CheckBytesError(
+                OwnedPointerError::ValueCheckBytesError(SliceCheckError::CheckBytes {
+                    index: 42,
+                    ..
+                })
+            )),
+        ));
+        assert_eq!(validator.checked(), 42);
+        assert!(validator.finish().is_err());
+
+        // Archives that are too short fail immediately
+        assert!(ChunkedValidator::<String, _>::new(&buf[..4]).is_err());
+    }
 }
@@ -25,4 +25,164 @@ mod tests {
         set.insert("baz".to_string());
         serialize_and_check(&set);
     }
+
+    #[test]
+    #[cfg(feature = "arrow")]
+    fn check_arrow_arrays() {
+        use crate::util::alloc::*;
+        use arrow_array::{
+            types::{Int32Type, UInt8Type},
+            PrimitiveArray,
+        };
+        use arrow_buffer::ScalarBuffer;
+        use bytecheck::CheckBytes;
+        use rkyv::{
+            arrow::PrimitiveArrayError, check_archived_root, ser::Serializer,
+            validation::CheckArchiveError, Archive, Serialize,
+        };
+
+        #[derive(Archive, Serialize)]
+        #[archive_attr(derive(CheckBytes))]
+        struct Batch {
+            ids: PrimitiveArray<Int32Type>,
+            flags: PrimitiveArray<UInt8Type>,
+            offsets: ScalarBuffer<u64>,
+        }
+
+        serialize_and_check(&Batch {
+            ids: PrimitiveArray::from(vec![Some(1), None, Some(3), None]),
+            flags: PrimitiveArray::from(vec![1, 2, 3]),
+            offsets: ScalarBuffer::from(vec![0u64, 4, 8]),
+        });
+
+        // Has the same layout as an archived primitive array, but with a validity bitmap that is
+        // too long for its values
+        #[derive(Archive, Serialize)]
+        struct Parts {
+            values: Vec<i32>,
+            nulls: Vec<u8>,
+        }
+
+        let mut serializer = DefaultSerializer::default();
+        serializer
+            .serialize_value(&Parts {
+                values: vec![1, 2, 3],
+                nulls: vec![0xff, 0xff],
+            })
+            .unwrap();
+        let buf = serializer.into_serializer().into_inner();
+        assert!(matches!(
+            check_archived_root::<PrimitiveArray<Int32Type>>(&buf),
+            Err(CheckArchiveError::CheckBytesError(
+                PrimitiveArrayError::InvalidNullsLength {
+                    nulls_len: 2,
+                    len: 3
+                }
+            )),
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn frame_codec_checked() {
+        use crate::util::alloc::*;
+        use bytes::BytesMut;
+        use rkyv::{
+            ser::Serializer,
+            util::{FrameCodec, FrameError},
+        };
+        use tokio_util::codec::{Decoder, Encoder};
+
+        let mut serializer = DefaultSerializer::default();
+        serializer.serialize_value(&"valid".to_string()).unwrap();
+        let valid = serializer.into_serializer().into_inner();
+
+        let mut codec = FrameCodec::new().checked::<String>();
+        let mut buf = BytesMut::new();
+        codec.encode(valid.as_slice(), &mut buf).unwrap();
+        // A string pointing out of bounds
+        codec
+            .encode(&[0, 0, 0, 0, 0xf0, 0xff, 0xff, 0xff][..], &mut buf)
+            .unwrap();
+        codec.encode(valid.as_slice(), &mut buf).unwrap();
+
+        let frame = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(frame.as_slice(), valid.as_slice());
+        assert!(matches!(
+            codec.decode(&mut buf),
+            Err(FrameError::InvalidArchive(_)),
+        ));
+        let frame = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(frame.as_slice(), valid.as_slice());
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+    }
+
+    #[test]
+    #[cfg_attr(feature = "wasm", wasm_bindgen_test)]
+    fn chunked_validator_async() {
+        use core::{
+            future::Future,
+            pin::Pin,
+            task::{Context, Poll},
+        };
+        use rkyv::{
+            ser::{serializers::AllocSerializer, Serializer},
+            validation::chunked::ChunkedValidator,
+        };
+        use std::{
+            sync::Arc,
+            task::{Wake, Waker},
+        };
+
+        struct NoopWaker;
+
+        impl Wake for NoopWaker {
+            fn wake(self: Arc<Self>) {}
+        }
+
+        fn assert_send<T: Send>(_: &T) {}
+
+        // Polls a future to completion and returns its output and how many times it yielded
+        fn block_on<F: Future>(future: F) -> (F::Output, usize) {
+            let waker = Waker::from(Arc::new(NoopWaker));
+            let mut cx = Context::from_waker(&waker);
+            let mut future = Box::pin(future);
+            let mut yields = 0;
+            loop {
+                match Pin::as_mut(&mut future).poll(&mut cx) {
+                    Poll::Ready(output) => return (output, yields),
+                    Poll::Pending => yields += 1,
+                }
+            }
+        }
+
+        let value = (0..100)
+            .map(|i| format!("element number {}", i))
+            .collect::<Vec<_>>();
+        let mut serializer = AllocSerializer::<256>::default();
+        serializer.serialize_value(&value).unwrap();
+        let mut buf = serializer.into_serializer().into_inner();
+
+        let future = ChunkedValidator::<String, _>::new(&buf)
+            .unwrap()
+            .check_async(7);
+        assert_send(&future);
+        let (archived, yields) = block_on(future);
+        assert_eq!(archived.unwrap().as_slice(), value.as_slice());
+        assert_eq!(yields, 14);
+
+        let pos = buf
+            .as_slice()
+            .windows(17)
+            .position(|w| w == b"element number 42")
+            .unwrap();
+        buf.as_mut_slice()[pos] = 0xff;
+
+        let future = ChunkedValidator::<String, _>::new(&buf)
+            .unwrap()
+            .check_async(10);
+        let (result, yields) = block_on(future);
+        assert!(result.is_err());
+        assert_eq!(yields, 4);
+    }
 }